@@ -0,0 +1,687 @@
+//! Readability-style extraction of the main article of a page.
+//!
+//! [`extract_article`] works on a copy of the document. Paragraph-like
+//! elements are scored by their text length and commas, their scores flow up
+//! to their ancestors, which are weighted by tag and by `class`/`id` keywords
+//! and penalized for link density. The best candidate and the siblings that
+//! look like part of it become the article, which is then cleaned of
+//! boilerplate. When the result is too short, extraction runs again with the
+//! heuristics relaxed, as Mozilla's Readability does.
+
+use markup5ever_rcdom::{Handle, NodeData};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::metadata::extract_metadata;
+use crate::node::{get_parent, DomNode};
+
+/// `class`/`id` keywords of page parts that are unlikely to be the article.
+const UNLIKELY_CANDIDATES: &[&str] = &[
+  "-ad-",
+  "ai2html",
+  "banner",
+  "breadcrumbs",
+  "combx",
+  "comment",
+  "community",
+  "cover-wrap",
+  "disqus",
+  "extra",
+  "footer",
+  "gdpr",
+  "header",
+  "legends",
+  "menu",
+  "related",
+  "remark",
+  "replies",
+  "rss",
+  "shoutbox",
+  "sidebar",
+  "skyscraper",
+  "social",
+  "sponsor",
+  "supplemental",
+  "ad-break",
+  "agegate",
+  "pagination",
+  "pager",
+  "popup",
+  "yom-remote",
+];
+
+/// Keywords that keep an element matching [`UNLIKELY_CANDIDATES`].
+const MAYBE_CANDIDATES: &[&str] = &[
+  "and", "article", "body", "column", "content", "main", "shadow",
+];
+
+const POSITIVE_KEYWORDS: &[&str] = &[
+  "article",
+  "body",
+  "content",
+  "entry",
+  "hentry",
+  "h-entry",
+  "main",
+  "page",
+  "pagination",
+  "post",
+  "text",
+  "blog",
+  "story",
+];
+
+const NEGATIVE_KEYWORDS: &[&str] = &[
+  "-ad-",
+  "hidden",
+  "banner",
+  "combx",
+  "comment",
+  "com-",
+  "contact",
+  "footer",
+  "gdpr",
+  "masthead",
+  "media",
+  "meta",
+  "outbrain",
+  "promo",
+  "related",
+  "scroll",
+  "share",
+  "shoutbox",
+  "sidebar",
+  "skyscraper",
+  "sponsor",
+  "shopping",
+  "tags",
+  "widget",
+];
+
+const BYLINE_KEYWORDS: &[&str] = &["byline", "author", "dateline", "writtenby", "p-author"];
+
+const UNLIKELY_ROLES: &[&str] = &[
+  "menu",
+  "menubar",
+  "complementary",
+  "navigation",
+  "alert",
+  "alertdialog",
+  "dialog",
+];
+
+/// Elements that never hold article text.
+const REMOVED_ELEMENTS: &[&str] = &[
+  "script", "style", "noscript", "template", "link", "meta", "object", "embed", "iframe", "button",
+  "input", "select", "textarea", "aside", "nav", "footer",
+];
+
+const TAGS_TO_SCORE: &[&str] = &["section", "h2", "h3", "h4", "h5", "h6", "p", "td", "pre"];
+
+/// Elements that stop a `<div>` from being scored like a paragraph.
+const BLOCK_ELEMENTS: &[&str] = &[
+  "blockquote",
+  "dl",
+  "div",
+  "img",
+  "ol",
+  "p",
+  "pre",
+  "table",
+  "ul",
+];
+
+/// Presentational attributes stripped from the article.
+const PRESENTATIONAL_ATTRIBUTES: &[&str] = &[
+  "style",
+  "align",
+  "background",
+  "bgcolor",
+  "border",
+  "cellpadding",
+  "cellspacing",
+  "frame",
+  "hspace",
+  "rules",
+  "valign",
+  "vspace",
+];
+
+const TITLE_SEPARATORS: &[char] = &['|', '-', '–', '—', '\\', '/', '>', '»'];
+
+/// Options for [`extract_article`].
+#[derive(Clone, Debug)]
+pub struct ArticleOptions {
+  /// The number of characters an article needs before the heuristics are relaxed. Defaults to 500.
+  pub char_threshold: usize,
+  /// Keep `class` attributes in the article. Defaults to false.
+  pub keep_classes: bool,
+}
+
+impl Default for ArticleOptions {
+  fn default() -> Self {
+    ArticleOptions {
+      char_threshold: 500,
+      keep_classes: false,
+    }
+  }
+}
+
+/// The main article of a page, returned by [`extract_article`].
+#[derive(Clone, Debug)]
+pub struct Article {
+  pub title: Option<String>,
+  pub byline: Option<String>,
+  /// The page description, or the first paragraph of the article.
+  pub excerpt: Option<String>,
+  pub site_name: Option<String>,
+  pub language: Option<String>,
+  /// A detached `<div>` holding the cleaned article, with absolute URLs.
+  pub content: DomNode,
+  /// The text of the article with white space collapsed.
+  pub text_content: String,
+  /// The length of `text_content` in characters.
+  pub length: usize,
+}
+
+#[derive(Clone, Copy)]
+struct Flags {
+  strip_unlikely: bool,
+  weight_classes: bool,
+  clean_conditionally: bool,
+}
+
+fn local_name(handle: &Handle) -> Option<&str> {
+  match &handle.data {
+    NodeData::Element { name, .. } => Some(name.local.as_ref()),
+    _ => None,
+  }
+}
+
+fn attribute(handle: &Handle, name: &str) -> Option<String> {
+  DomNode(handle.clone()).get_attribute(name.to_string())
+}
+
+fn key(handle: &Handle) -> usize {
+  Rc::as_ptr(handle) as usize
+}
+
+fn element_children(handle: &Handle) -> Vec<Handle> {
+  handle
+    .children
+    .borrow()
+    .iter()
+    .filter(|child| local_name(child).is_some())
+    .cloned()
+    .collect()
+}
+
+fn descendants(handle: &Handle, names: &[&str]) -> Vec<Handle> {
+  let mut found = vec![];
+  let mut stack: Vec<Handle> = handle.children.borrow().iter().rev().cloned().collect();
+  while let Some(node) = stack.pop() {
+    if local_name(&node).is_some_and(|name| names.contains(&name)) {
+      found.push(node.clone());
+    }
+    stack.extend(node.children.borrow().iter().rev().cloned());
+  }
+  found
+}
+
+/// The text of a node with white space collapsed.
+fn inner_text(handle: &Handle) -> String {
+  DomNode(handle.clone())
+    .text()
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+fn text_length(handle: &Handle) -> usize {
+  inner_text(handle).chars().count()
+}
+
+/// The share of a node's text inside links, counting `#fragment` links less.
+fn link_density(handle: &Handle) -> f64 {
+  let length = text_length(handle);
+  if length == 0 {
+    return 0.0;
+  }
+  let links: f64 = descendants(handle, &["a"])
+    .iter()
+    .map(|link| {
+      let coefficient = if attribute(link, "href").is_some_and(|href| href.starts_with('#')) {
+        0.3
+      } else {
+        1.0
+      };
+      text_length(link) as f64 * coefficient
+    })
+    .sum();
+  links / length as f64
+}
+
+fn class_and_id(handle: &Handle) -> String {
+  format!(
+    "{} {}",
+    attribute(handle, "class").unwrap_or_default(),
+    attribute(handle, "id").unwrap_or_default()
+  )
+  .to_ascii_lowercase()
+}
+
+fn matches_any(value: &str, keywords: &[&str]) -> bool {
+  keywords.iter().any(|keyword| value.contains(keyword))
+}
+
+fn class_weight(handle: &Handle, flags: Flags) -> f64 {
+  if !flags.weight_classes {
+    return 0.0;
+  }
+  let mut weight = 0.0;
+  for value in [attribute(handle, "class"), attribute(handle, "id")]
+    .into_iter()
+    .flatten()
+  {
+    let value = value.to_ascii_lowercase();
+    if matches_any(&value, NEGATIVE_KEYWORDS) {
+      weight -= 25.0;
+    }
+    if matches_any(&value, POSITIVE_KEYWORDS) {
+      weight += 25.0;
+    }
+  }
+  weight
+}
+
+fn is_hidden(handle: &Handle) -> bool {
+  let style = attribute(handle, "style")
+    .unwrap_or_default()
+    .to_ascii_lowercase()
+    .replace(char::is_whitespace, "");
+  style.contains("display:none")
+    || style.contains("visibility:hidden")
+    || attribute(handle, "hidden").is_some()
+    || attribute(handle, "aria-hidden").is_some_and(|value| value == "true")
+}
+
+fn has_ancestor(handle: &Handle, names: &[&str]) -> bool {
+  let mut current = get_parent(handle);
+  while let Some(node) = current {
+    if local_name(&node).is_some_and(|name| names.contains(&name)) {
+      return true;
+    }
+    current = get_parent(&node);
+  }
+  false
+}
+
+fn is_byline(handle: &Handle) -> bool {
+  let rel_author = attribute(handle, "rel").is_some_and(|rel| rel == "author");
+  let itemprop_author = attribute(handle, "itemprop").is_some_and(|prop| prop.contains("author"));
+  (rel_author || itemprop_author || matches_any(&class_and_id(handle), BYLINE_KEYWORDS))
+    && (1..100).contains(&text_length(handle))
+}
+
+/// The starting score of a candidate, from its tag and `class`/`id`.
+fn initial_score(handle: &Handle, flags: Flags) -> f64 {
+  let tag_score = match local_name(handle).unwrap_or_default() {
+    "div" => 5.0,
+    "pre" | "td" | "blockquote" => 3.0,
+    "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+    "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+    _ => 0.0,
+  };
+  tag_score + class_weight(handle, flags)
+}
+
+/// Removes noise and unlikely candidates from the body, returning the byline
+/// found on the way and the elements to score, in tree order.
+fn prepare(body: &Handle, flags: Flags) -> (Option<String>, Vec<Handle>) {
+  let mut byline = None;
+  let mut to_score = vec![];
+  let mut stack: Vec<Handle> = body.children.borrow().iter().rev().cloned().collect();
+  while let Some(node) = stack.pop() {
+    let Some(name) = local_name(&node) else {
+      if matches!(node.data, NodeData::Comment { .. }) {
+        DomNode(node).remove();
+      }
+      continue;
+    };
+    let name = name.to_string();
+    if REMOVED_ELEMENTS.contains(&name.as_str()) || is_hidden(&node) {
+      DomNode(node).remove();
+      continue;
+    }
+    if byline.is_none() && is_byline(&node) {
+      byline = Some(inner_text(&node));
+      DomNode(node).remove();
+      continue;
+    }
+    if flags.strip_unlikely {
+      let match_string = class_and_id(&node);
+      let unlikely = matches_any(&match_string, UNLIKELY_CANDIDATES)
+        && !matches_any(&match_string, MAYBE_CANDIDATES)
+        && name != "a"
+        && !has_ancestor(&node, &["table", "code"]);
+      let role = attribute(&node, "role").unwrap_or_default();
+      if unlikely || UNLIKELY_ROLES.contains(&role.as_str()) {
+        DomNode(node).remove();
+        continue;
+      }
+    }
+    if TAGS_TO_SCORE.contains(&name.as_str())
+      || (name == "div" && descendants(&node, BLOCK_ELEMENTS).is_empty())
+    {
+      to_score.push(node.clone());
+    }
+    stack.extend(node.children.borrow().iter().rev().cloned());
+  }
+  (byline, to_score)
+}
+
+/// Whether a container should be dropped from the article as boilerplate.
+fn is_boilerplate(handle: &Handle, scores: &HashMap<usize, f64>, flags: Flags) -> bool {
+  let weight = class_weight(handle, flags);
+  let score = scores.get(&key(handle)).copied().unwrap_or(0.0);
+  if weight + score < 0.0 {
+    return true;
+  }
+  let text = inner_text(handle);
+  if text.matches(',').count() >= 10 {
+    return false;
+  }
+  let name = local_name(handle).unwrap_or_default();
+  let is_list = name == "ul" || name == "ol";
+  let paragraphs = descendants(handle, &["p"]).len() as f64;
+  let images = descendants(handle, &["img"]).len() as f64;
+  let items = descendants(handle, &["li"]).len() as f64 - 100.0;
+  let inputs = descendants(handle, &["input"]).len() as f64;
+  let embeds = descendants(handle, &["object", "embed", "iframe", "video"]).len();
+  let headings = descendants(handle, &["h1", "h2", "h3", "h4", "h5", "h6"]).len();
+  let density = link_density(handle);
+  let length = text.chars().count();
+  (images > 1.0 && paragraphs / images < 0.5 && !has_ancestor(handle, &["figure"]))
+    || (!is_list && items > paragraphs)
+    || inputs > (paragraphs / 3.0).floor()
+    || (!is_list && length < 25 && (images == 0.0 || images > 2.0) && headings == 0)
+    || (!is_list && weight < 25.0 && density > 0.2)
+    || (weight >= 25.0 && density > 0.5)
+    || (embeds == 1 && length < 75)
+    || embeds > 1
+}
+
+/// Cleans the article: boilerplate containers, headings repeating the title,
+/// empty paragraphs and presentational attributes.
+/// The share of `text` made of words that also appear in `reference`, by length.
+fn text_similarity(reference: &str, text: &str) -> f64 {
+  let tokens = |value: &str| {
+    value
+      .split(|c: char| !c.is_alphanumeric() && c != '_')
+      .filter(|token| !token.is_empty())
+      .map(str::to_lowercase)
+      .collect::<Vec<_>>()
+  };
+  let reference = tokens(reference);
+  let text = tokens(text);
+  if text.is_empty() {
+    return 0.0;
+  }
+  let unique: Vec<&str> = text
+    .iter()
+    .filter(|token| !reference.contains(token))
+    .map(String::as_str)
+    .collect();
+  1.0 - unique.join(" ").len() as f64 / text.join(" ").len() as f64
+}
+
+fn clean(
+  article: &Handle,
+  title: Option<&str>,
+  scores: &HashMap<usize, f64>,
+  flags: Flags,
+  options: &ArticleOptions,
+) {
+  for heading in descendants(article, &["h1", "h2"]) {
+    let text = inner_text(&heading);
+    if class_weight(&heading, flags) < 0.0
+      || title.is_some_and(|title| text_similarity(title, &text) > 0.75)
+    {
+      DomNode(heading).remove();
+    }
+  }
+  if flags.clean_conditionally {
+    // Inner containers first, so an outer one is judged on what is left of it.
+    for container in descendants(article, &["form", "fieldset", "table", "ul", "div"])
+      .into_iter()
+      .rev()
+    {
+      if is_boilerplate(&container, scores, flags) {
+        DomNode(container).remove();
+      }
+    }
+  }
+  for paragraph in descendants(article, &["p"]) {
+    let has_media =
+      !descendants(&paragraph, &["img", "picture", "video", "audio", "svg"]).is_empty();
+    if !has_media && inner_text(&paragraph).is_empty() {
+      DomNode(paragraph).remove();
+    }
+  }
+  let mut stack = vec![article.clone()];
+  while let Some(node) = stack.pop() {
+    if let NodeData::Element { attrs, .. } = &node.data {
+      attrs.borrow_mut().retain(|attr| {
+        let name = attr.name.local.as_ref();
+        !(PRESENTATIONAL_ATTRIBUTES.contains(&name)
+          || name.starts_with("on")
+          || (name == "class" && !options.keep_classes))
+      });
+    }
+    stack.extend(node.children.borrow().iter().cloned());
+  }
+}
+
+/// Runs one extraction attempt on a fresh copy of the document.
+fn grab_article(
+  document: &DomNode,
+  title: Option<&str>,
+  flags: Flags,
+  options: &ArticleOptions,
+) -> Option<(DomNode, Option<String>)> {
+  let document = document.clone_recursive();
+  let body = document.body().or_else(|| document.document_element())?.0;
+  let (byline, to_score) = prepare(&body, flags);
+
+  let mut scores: HashMap<usize, f64> = HashMap::new();
+  let mut candidates: Vec<Handle> = vec![];
+  for element in &to_score {
+    let text = inner_text(element);
+    let length = text.chars().count();
+    if length < 25 || get_parent(element).is_none() {
+      continue;
+    }
+    let score = 1.0 + text.matches(',').count() as f64 + (length / 100).min(3) as f64;
+    let mut ancestor = get_parent(element);
+    for level in 0..5 {
+      let Some(node) = ancestor.filter(|node| local_name(node).is_some()) else {
+        break;
+      };
+      let entry = scores.entry(key(&node)).or_insert_with(|| {
+        candidates.push(node.clone());
+        initial_score(&node, flags)
+      });
+      let divider = match level {
+        0 => 1.0,
+        1 => 2.0,
+        _ => level as f64 * 3.0,
+      };
+      *entry += score / divider;
+      ancestor = get_parent(&node);
+    }
+  }
+  for candidate in &candidates {
+    if let Some(score) = scores.get_mut(&key(candidate)) {
+      *score *= 1.0 - link_density(candidate);
+    }
+  }
+
+  let top = candidates
+    .iter()
+    .max_by(|a, b| scores[&key(a)].total_cmp(&scores[&key(b)]))
+    .cloned();
+  let article = document.create_element("div".to_string());
+  match top.filter(|top| !Rc::ptr_eq(top, &body)) {
+    Some(top) => {
+      let top_score = scores[&key(&top)];
+      let threshold = (top_score * 0.2).max(10.0);
+      let top_class = attribute(&top, "class").unwrap_or_default();
+      let siblings = match get_parent(&top) {
+        Some(parent) => element_children(&parent),
+        None => vec![top.clone()],
+      };
+      for sibling in siblings {
+        let include = Rc::ptr_eq(&sibling, &top) || {
+          let mut bonus = 0.0;
+          if !top_class.is_empty() && attribute(&sibling, "class").as_ref() == Some(&top_class) {
+            bonus = top_score * 0.2;
+          }
+          let score = scores.get(&key(&sibling)).copied();
+          if score.is_some_and(|score| score + bonus >= threshold) {
+            true
+          } else if local_name(&sibling) == Some("p") {
+            let text = inner_text(&sibling);
+            let length = text.chars().count();
+            let density = link_density(&sibling);
+            (length > 80 && density < 0.25)
+              || (length > 0 && length <= 80 && density == 0.0 && text.ends_with('.'))
+          } else {
+            false
+          }
+        };
+        if include {
+          article.append_child(&DomNode(sibling));
+        }
+      }
+    }
+    None => {
+      let children = body.children.borrow().clone();
+      for child in children {
+        article.append_child(&DomNode(child));
+      }
+    }
+  }
+  clean(&article.0, title, &scores, flags, options);
+  Some((article, byline))
+}
+
+/// The document title without the site name, as Readability computes it.
+fn article_title(document_title: &str) -> Option<String> {
+  let original = document_title
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ");
+  if original.is_empty() {
+    return None;
+  }
+  let word_count = |value: &str| value.split_whitespace().count();
+  let is_separator =
+    |word: &str| word.chars().count() == 1 && word.chars().all(|c| TITLE_SEPARATORS.contains(&c));
+  let words: Vec<&str> = original.split(' ').collect();
+  let Some(last) = words.iter().rposition(|word| is_separator(word)) else {
+    return Some(original);
+  };
+  let mut title = words[..last].join(" ");
+  if word_count(&title) < 3 {
+    let first = words
+      .iter()
+      .position(|word| is_separator(word))
+      .unwrap_or(last);
+    title = words[first + 1..].join(" ");
+  }
+  // Titles like "Docs / Section / Page" keep a short last segment.
+  let hierarchical = words
+    .iter()
+    .any(|word| matches!(*word, "\\" | "/" | ">" | "»"));
+  let without_separators = words.iter().filter(|word| !is_separator(word)).count();
+  if word_count(&title) <= 4 && (!hierarchical || word_count(&title) != without_separators - 1) {
+    return Some(original);
+  }
+  Some(title)
+}
+
+/// Extracts the main article of `document`, or `None` if it has no body or no text.
+/// The document itself is left unchanged.
+pub fn extract_article(document: &DomNode, options: &ArticleOptions) -> Option<Article> {
+  let metadata = extract_metadata(document);
+  let first = |map: &indexmap::IndexMap<String, Vec<String>>, key: &str| {
+    map
+      .get(key)
+      .and_then(|values| values.first())
+      .map(|value| value.trim().to_string())
+      .filter(|value| !value.is_empty())
+  };
+  let title = first(&metadata.open_graph, "og:title")
+    .or_else(|| first(&metadata.twitter, "twitter:title"))
+    .or_else(|| article_title(&metadata.title.clone().unwrap_or_default()));
+  let meta_author = document
+    .query_selector_all("meta[name][content]".to_string())
+    .into_iter()
+    .find(|meta| {
+      meta
+        .get_attribute("name".to_string())
+        .is_some_and(|name| name.trim().eq_ignore_ascii_case("author"))
+    })
+    .and_then(|meta| meta.get_attribute("content".to_string()))
+    .map(|author| author.trim().to_string())
+    .filter(|author| !author.is_empty())
+    .or_else(|| first(&metadata.open_graph, "article:author"));
+
+  let mut best: Option<(DomNode, Option<String>, usize)> = None;
+  let attempts = [
+    (true, true, true),
+    (false, true, true),
+    (false, false, true),
+    (false, false, false),
+  ];
+  for (strip_unlikely, weight_classes, clean_conditionally) in attempts {
+    let flags = Flags {
+      strip_unlikely,
+      weight_classes,
+      clean_conditionally,
+    };
+    let (content, byline) = grab_article(document, title.as_deref(), flags, options)?;
+    let length = text_length(&content.0);
+    let done = length >= options.char_threshold;
+    if best.as_ref().is_none_or(|(_, _, best)| length > *best) {
+      best = Some((content, byline, length));
+    }
+    if done {
+      break;
+    }
+  }
+  let (content, byline, length) = best.filter(|(_, _, length)| *length > 0)?;
+  content.absolutize_urls(document.base_url().as_ref());
+  let text_content = inner_text(&content.0);
+  let excerpt = metadata
+    .description
+    .clone()
+    .or_else(|| first(&metadata.open_graph, "og:description"))
+    .or_else(|| first(&metadata.twitter, "twitter:description"))
+    .or_else(|| {
+      descendants(&content.0, &["p"])
+        .iter()
+        .map(inner_text)
+        .find(|text| !text.is_empty())
+    });
+  Some(Article {
+    title,
+    byline: meta_author.or(byline),
+    excerpt,
+    site_name: first(&metadata.open_graph, "og:site_name"),
+    language: metadata.language,
+    content,
+    text_content,
+    length,
+  })
+}
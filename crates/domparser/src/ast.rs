@@ -0,0 +1,437 @@
+//! A plain-data representation of DOM trees.
+//!
+//! [`AstNode`] mirrors a `DomNode` tree without sharing any of its nodes, so it
+//! can be cached, compared or sent elsewhere and turned back into an equivalent
+//! tree with [`DomNode::from_ast`]. With the `serde` feature it (de)serializes
+//! to a JSON format where each node is an object tagged by `type`:
+//!
+//! ```json
+//! { "type": "element", "name": "p", "namespace": "http://www.w3.org/1999/xhtml",
+//!   "attributes": [{ "name": "class", "value": "note" }],
+//!   "children": [{ "type": "text", "value": "Hello" }] }
+//! ```
+
+use html5ever::{ns, Attribute, LocalName, Namespace, Prefix, QualName};
+use markup5ever_rcdom::{Handle, Node, NodeData};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::node::{
+  attached_shadow_root, is_shadow_root, is_valid_attribute_local_name, is_valid_element_local_name,
+  is_valid_namespace_prefix, new_document_of_type, template_contents, DomNode, ShadowRootInit,
+};
+
+/// A node of the AST.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+  feature = "serde",
+  serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+  )
+)]
+pub enum AstNode {
+  Document {
+    /// The MIME type, such as `text/html` or `application/xml`.
+    content_type: String,
+    /// Whether `<noscript>` holds raw text, as it does in parsed documents.
+    #[cfg_attr(feature = "serde", serde(default = "default_true"))]
+    scripting_enabled: bool,
+    children: Vec<AstNode>,
+  },
+  DocumentType {
+    name: String,
+    public_id: String,
+    system_id: String,
+  },
+  Element(AstElement),
+  Text {
+    value: String,
+  },
+  Comment {
+    value: String,
+  },
+  ProcessingInstruction {
+    target: String,
+    data: String,
+  },
+  DocumentFragment {
+    children: Vec<AstNode>,
+  },
+  ShadowRoot(AstShadowRoot),
+}
+
+/// An element node of the AST.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct AstElement {
+  /// The local name.
+  pub name: String,
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub namespace: Option<String>,
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub prefix: Option<String>,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub attributes: Vec<AstAttribute>,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub children: Vec<AstNode>,
+  /// The children of a `<template>` element's contents.
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub content: Option<Vec<AstNode>>,
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub shadow_root: Option<AstShadowRoot>,
+  /// Set by the parser on `<annotation-xml>` elements that are HTML integration points.
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, skip_serializing_if = "std::ops::Not::not")
+  )]
+  pub mathml_annotation_xml_integration_point: bool,
+  /// Where the element came from in the input, if it was parsed with source
+  /// locations. It describes the parsed tree only, so it is never read back.
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")
+  )]
+  pub location: Option<Box<AstLocation>>,
+}
+
+/// The range of the input an [`AstElement`] was parsed from, with 0-based byte
+/// offsets and 1-based lines and columns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct AstLocation {
+  pub start_offset: usize,
+  pub end_offset: usize,
+  pub start_line: usize,
+  pub start_column: usize,
+  pub end_line: usize,
+  pub end_column: usize,
+}
+
+/// An attribute of an [`AstElement`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AstAttribute {
+  /// The local name.
+  pub name: String,
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub namespace: Option<String>,
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub prefix: Option<String>,
+  pub value: String,
+}
+
+/// A shadow root, either attached to an [`AstElement`] or converted on its own.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct AstShadowRoot {
+  /// `"open"` or `"closed"`.
+  pub mode: String,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub delegates_focus: bool,
+  /// `"named"` or `"manual"`.
+  #[cfg_attr(feature = "serde", serde(default = "default_slot_assignment"))]
+  pub slot_assignment: String,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub clonable: bool,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub serializable: bool,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub children: Vec<AstNode>,
+}
+
+#[cfg(feature = "serde")]
+fn default_true() -> bool {
+  true
+}
+
+#[cfg(feature = "serde")]
+fn default_slot_assignment() -> String {
+  "named".to_string()
+}
+
+fn optional(value: &str) -> Option<String> {
+  (!value.is_empty()).then(|| value.to_string())
+}
+
+fn children_to_ast(handle: &Handle) -> Vec<AstNode> {
+  handle.children.borrow().iter().map(node_to_ast).collect()
+}
+
+fn shadow_root_to_ast(root: &Handle) -> AstShadowRoot {
+  let root = DomNode(root.clone());
+  AstShadowRoot {
+    mode: root.mode().unwrap_or_default(),
+    delegates_focus: root.delegates_focus(),
+    slot_assignment: root
+      .slot_assignment()
+      .unwrap_or_else(|| "named".to_string()),
+    clonable: root.clonable(),
+    serializable: root.serializable(),
+    children: children_to_ast(&root.0),
+  }
+}
+
+fn node_to_ast(handle: &Handle) -> AstNode {
+  match &handle.data {
+    NodeData::Document => {
+      let document = DomNode(handle.clone());
+      AstNode::Document {
+        content_type: document.content_type().unwrap_or_default(),
+        scripting_enabled: document.scripting_enabled(),
+        children: children_to_ast(handle),
+      }
+    }
+    NodeData::Doctype {
+      name,
+      public_id,
+      system_id,
+    } => AstNode::DocumentType {
+      name: name.to_string(),
+      public_id: public_id.to_string(),
+      system_id: system_id.to_string(),
+    },
+    NodeData::Text { contents } => AstNode::Text {
+      value: contents.borrow().to_string(),
+    },
+    NodeData::Comment { contents } => AstNode::Comment {
+      value: contents.to_string(),
+    },
+    NodeData::ProcessingInstruction { target, contents } => AstNode::ProcessingInstruction {
+      target: target.to_string(),
+      data: contents.to_string(),
+    },
+    NodeData::Element { .. } if is_shadow_root(handle) => {
+      AstNode::ShadowRoot(shadow_root_to_ast(handle))
+    }
+    NodeData::Element { name, .. }
+      if name.ns == ns!() && name.local.as_ref() == "#document-fragment" =>
+    {
+      AstNode::DocumentFragment {
+        children: children_to_ast(handle),
+      }
+    }
+    NodeData::Element {
+      name,
+      attrs,
+      mathml_annotation_xml_integration_point,
+      ..
+    } => {
+      let content = template_contents(handle).map(|fragment| children_to_ast(&fragment));
+      let shadow_root = attached_shadow_root(handle).map(|root| shadow_root_to_ast(&root));
+      AstNode::Element(AstElement {
+        name: name.local.to_string(),
+        namespace: optional(&name.ns),
+        prefix: name.prefix.as_ref().and_then(|prefix| optional(prefix)),
+        attributes: attrs
+          .borrow()
+          .iter()
+          .map(|attr| AstAttribute {
+            name: attr.name.local.to_string(),
+            namespace: optional(&attr.name.ns),
+            prefix: attr
+              .name
+              .prefix
+              .as_ref()
+              .and_then(|prefix| optional(prefix)),
+            value: attr.value.to_string(),
+          })
+          .collect(),
+        children: children_to_ast(handle),
+        content,
+        shadow_root,
+        mathml_annotation_xml_integration_point: *mathml_annotation_xml_integration_point,
+        location: DomNode(handle.clone()).source_location().map(|location| {
+          Box::new(AstLocation {
+            start_offset: location.start.offset,
+            end_offset: location.end.offset,
+            start_line: location.start.line,
+            start_column: location.start.column,
+            end_line: location.end.line,
+            end_column: location.end.column,
+          })
+        }),
+      })
+    }
+  }
+}
+
+fn qual_name(prefix: &Option<String>, namespace: &Option<String>, local: &str) -> QualName {
+  QualName::new(
+    prefix.as_deref().map(Prefix::from),
+    namespace.as_deref().map(Namespace::from).unwrap_or(ns!()),
+    LocalName::from(local),
+  )
+}
+
+fn append_children(parent: &Handle, children: &[AstNode]) -> Result<(), String> {
+  for child in children {
+    let child = node_from_ast(child)?;
+    if matches!(child.data, NodeData::Document) {
+      return Err("A document cannot be the child of another node.".to_string());
+    }
+    child.parent.set(Some(Rc::downgrade(parent)));
+    parent.children.borrow_mut().push(child);
+  }
+  Ok(())
+}
+
+fn new_fragment() -> Handle {
+  Node::new(NodeData::Element {
+    name: QualName::new(None, ns!(), LocalName::from("#document-fragment")),
+    attrs: RefCell::new(vec![]),
+    template_contents: RefCell::new(None),
+    mathml_annotation_xml_integration_point: false,
+  })
+}
+
+fn node_from_ast(ast: &AstNode) -> Result<Handle, String> {
+  let node = match ast {
+    AstNode::Document {
+      content_type,
+      scripting_enabled,
+      children,
+    } => {
+      let document = new_document_of_type(content_type, *scripting_enabled)?;
+      append_children(&document, children)?;
+      document
+    }
+    AstNode::DocumentType {
+      name,
+      public_id,
+      system_id,
+    } => Node::new(NodeData::Doctype {
+      name: name.as_str().into(),
+      public_id: public_id.as_str().into(),
+      system_id: system_id.as_str().into(),
+    }),
+    AstNode::Text { value } => Node::new(NodeData::Text {
+      contents: RefCell::new(value.as_str().into()),
+    }),
+    AstNode::Comment { value } => Node::new(NodeData::Comment {
+      contents: value.as_str().into(),
+    }),
+    AstNode::ProcessingInstruction { target, data } => Node::new(NodeData::ProcessingInstruction {
+      target: target.as_str().into(),
+      contents: data.as_str().into(),
+    }),
+    AstNode::DocumentFragment { children } => {
+      let fragment = new_fragment();
+      append_children(&fragment, children)?;
+      fragment
+    }
+    AstNode::ShadowRoot(_) => {
+      return Err("A shadow root can only be created along with its host element.".to_string())
+    }
+    AstNode::Element(element) => {
+      // Names are serialized as they are, so they must not be able to end the tag or the attribute.
+      if !is_valid_element_local_name(&element.name)
+        || !element
+          .prefix
+          .as_deref()
+          .is_none_or(is_valid_namespace_prefix)
+      {
+        return Err(format!("'{}' is not a valid element name.", element.name));
+      }
+      if let Some(attr) = element.attributes.iter().find(|attr| {
+        !is_valid_attribute_local_name(&attr.name)
+          || !attr.prefix.as_deref().is_none_or(is_valid_namespace_prefix)
+      }) {
+        return Err(format!("'{}' is not a valid attribute name.", attr.name));
+      }
+      let node = Node::new(NodeData::Element {
+        name: qual_name(&element.prefix, &element.namespace, &element.name),
+        attrs: RefCell::new(
+          element
+            .attributes
+            .iter()
+            .map(|attr| Attribute {
+              name: qual_name(&attr.prefix, &attr.namespace, &attr.name),
+              value: attr.value.as_str().into(),
+            })
+            .collect(),
+        ),
+        template_contents: RefCell::new(None),
+        mathml_annotation_xml_integration_point: element.mathml_annotation_xml_integration_point,
+      });
+      append_children(&node, &element.children)?;
+
+      if let Some(content) = &element.content {
+        let fragment = template_contents(&node).ok_or_else(|| {
+          format!(
+            "The <{}> element cannot have template contents.",
+            element.name
+          )
+        })?;
+        append_children(&fragment, content)?;
+      }
+      if let Some(shadow_root) = &element.shadow_root {
+        if element.content.is_some() {
+          return Err("A template element cannot host a shadow root.".to_string());
+        }
+        let root = DomNode(node.clone()).attach_shadow(ShadowRootInit {
+          mode: shadow_root.mode.clone(),
+          delegates_focus: shadow_root.delegates_focus,
+          slot_assignment: Some(shadow_root.slot_assignment.clone()),
+          clonable: shadow_root.clonable,
+          serializable: shadow_root.serializable,
+        })?;
+        append_children(&root.0, &shadow_root.children)?;
+      }
+      node
+    }
+  };
+  Ok(node)
+}
+
+impl DomNode {
+  /// Converts this node and its descendants, including template contents and
+  /// shadow roots, to an [`AstNode`].
+  pub fn to_ast(&self) -> AstNode {
+    node_to_ast(&self.0)
+  }
+
+  /// Builds a new tree from an [`AstNode`]. Shadow roots can only be built as part of their host.
+  pub fn from_ast(ast: &AstNode) -> Result<DomNode, String> {
+    node_from_ast(ast).map(DomNode)
+  }
+
+  /// Serializes this node and its descendants to the JSON AST format.
+  #[cfg(feature = "serde")]
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(&self.to_ast()).unwrap()
+  }
+
+  /// Builds a new tree from the JSON AST format.
+  #[cfg(feature = "serde")]
+  pub fn from_json(json: &str) -> Result<DomNode, String> {
+    let ast: AstNode = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    DomNode::from_ast(&ast)
+  }
+}
@@ -0,0 +1,871 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use cssparser::color::{parse_hash_color, parse_named_color, serialize_color_alpha};
+use cssparser::{ParseError, Parser, ParserInput, Token};
+use indexmap::IndexMap;
+use markup5ever_rcdom::{Handle, NodeData};
+use selectors::matching::{
+  matches_selector, MatchingContext, MatchingForInvalidation, MatchingMode, NeedsSelectorFlags,
+  QuirksMode, SelectorCaches,
+};
+use selectors::{Element, SelectorList};
+
+use super::declarations::rewrite_value;
+use super::shorthands::{self, CSS_WIDE_KEYWORDS, FONT_SYSTEM_FONTS};
+use super::stylesheet::{CssRule, GroupRule, StyleSheet};
+use crate::node::selectors::DomParserSelectors;
+use crate::node::{flat_tree_parent, DomNode};
+
+const INITIAL_FONT_SIZE: f64 = 16.0;
+
+thread_local! {
+  static DEFAULT_STYLESHEET: Rc<StyleSheet> = Rc::new(StyleSheet::parse(include_str!("default.css")));
+}
+
+/// How the computed value of a property is derived from its specified value.
+#[derive(Clone, Copy)]
+enum Kind {
+  /// Kept as specified, a single keyword lowercased.
+  Keyword,
+  /// Kept as specified.
+  Verbatim,
+  /// Absolute and font-relative lengths converted to pixels, `0` to `0px`.
+  Length,
+  /// Named and hex colors converted to `rgb()`, `currentcolor` resolved.
+  Color,
+  FontSize,
+  FontWeight,
+  /// Like `Length`, with percentages of the font size converted and numbers kept.
+  LineHeight,
+  /// Like `Length`, with keywords converted, and `0px` if the given style property is
+  /// `none` or `hidden`.
+  BorderWidth(&'static str),
+}
+
+struct Property {
+  name: &'static str,
+  inherited: bool,
+  initial: &'static str,
+  kind: Kind,
+}
+
+const fn property(
+  name: &'static str,
+  inherited: bool,
+  initial: &'static str,
+  kind: Kind,
+) -> Property {
+  Property {
+    name,
+    inherited,
+    initial,
+    kind,
+  }
+}
+
+/// The properties that always have a computed value, in alphabetical order. Other properties
+/// are computed only when declared, as non-inherited properties kept as specified.
+static PROPERTIES: &[Property] = &[
+  property("align-items", false, "normal", Kind::Keyword),
+  property("background-color", false, "rgba(0, 0, 0, 0)", Kind::Color),
+  property("background-image", false, "none", Kind::Verbatim),
+  property("border-bottom-color", false, "currentcolor", Kind::Color),
+  property("border-bottom-style", false, "none", Kind::Keyword),
+  property(
+    "border-bottom-width",
+    false,
+    "medium",
+    Kind::BorderWidth("border-bottom-style"),
+  ),
+  property("border-collapse", true, "separate", Kind::Keyword),
+  property("border-left-color", false, "currentcolor", Kind::Color),
+  property("border-left-style", false, "none", Kind::Keyword),
+  property(
+    "border-left-width",
+    false,
+    "medium",
+    Kind::BorderWidth("border-left-style"),
+  ),
+  property("border-right-color", false, "currentcolor", Kind::Color),
+  property("border-right-style", false, "none", Kind::Keyword),
+  property(
+    "border-right-width",
+    false,
+    "medium",
+    Kind::BorderWidth("border-right-style"),
+  ),
+  property("border-spacing", true, "0px", Kind::Length),
+  property("border-top-color", false, "currentcolor", Kind::Color),
+  property("border-top-style", false, "none", Kind::Keyword),
+  property(
+    "border-top-width",
+    false,
+    "medium",
+    Kind::BorderWidth("border-top-style"),
+  ),
+  property("bottom", false, "auto", Kind::Length),
+  property("box-sizing", false, "content-box", Kind::Keyword),
+  property("clear", false, "none", Kind::Keyword),
+  property("color", true, "rgb(0, 0, 0)", Kind::Color),
+  property("column-gap", false, "normal", Kind::Length),
+  property("content", false, "normal", Kind::Verbatim),
+  property("content-visibility", false, "visible", Kind::Keyword),
+  property("cursor", true, "auto", Kind::Keyword),
+  property("direction", true, "ltr", Kind::Keyword),
+  property("display", false, "inline", Kind::Keyword),
+  property("flex-basis", false, "auto", Kind::Length),
+  property("flex-direction", false, "row", Kind::Keyword),
+  property("flex-grow", false, "0", Kind::Keyword),
+  property("flex-shrink", false, "1", Kind::Keyword),
+  property("flex-wrap", false, "nowrap", Kind::Keyword),
+  property("float", false, "none", Kind::Keyword),
+  property("font-family", true, "serif", Kind::Verbatim),
+  property("font-size", true, "medium", Kind::FontSize),
+  property("font-style", true, "normal", Kind::Keyword),
+  property("font-weight", true, "normal", Kind::FontWeight),
+  property("height", false, "auto", Kind::Length),
+  property("justify-content", false, "normal", Kind::Keyword),
+  property("left", false, "auto", Kind::Length),
+  property("letter-spacing", true, "normal", Kind::Length),
+  property("line-height", true, "normal", Kind::LineHeight),
+  property("list-style-position", true, "outside", Kind::Keyword),
+  property("list-style-type", true, "disc", Kind::Keyword),
+  property("margin-bottom", false, "0px", Kind::Length),
+  property("margin-left", false, "0px", Kind::Length),
+  property("margin-right", false, "0px", Kind::Length),
+  property("margin-top", false, "0px", Kind::Length),
+  property("max-height", false, "none", Kind::Length),
+  property("max-width", false, "none", Kind::Length),
+  property("min-height", false, "auto", Kind::Length),
+  property("min-width", false, "auto", Kind::Length),
+  property("opacity", false, "1", Kind::Keyword),
+  property("outline-color", false, "currentcolor", Kind::Color),
+  property("outline-style", false, "none", Kind::Keyword),
+  property(
+    "outline-width",
+    false,
+    "medium",
+    Kind::BorderWidth("outline-style"),
+  ),
+  property("overflow-x", false, "visible", Kind::Keyword),
+  property("overflow-y", false, "visible", Kind::Keyword),
+  property("padding-bottom", false, "0px", Kind::Length),
+  property("padding-left", false, "0px", Kind::Length),
+  property("padding-right", false, "0px", Kind::Length),
+  property("padding-top", false, "0px", Kind::Length),
+  property("pointer-events", true, "auto", Kind::Keyword),
+  property("position", false, "static", Kind::Keyword),
+  property("right", false, "auto", Kind::Length),
+  property("row-gap", false, "normal", Kind::Length),
+  property("text-align", true, "start", Kind::Keyword),
+  property("text-decoration", false, "none", Kind::Verbatim),
+  property("text-indent", true, "0px", Kind::Length),
+  property("text-transform", true, "none", Kind::Keyword),
+  property("top", false, "auto", Kind::Length),
+  property("transform", false, "none", Kind::Verbatim),
+  property("vertical-align", false, "baseline", Kind::Length),
+  property("visibility", true, "visible", Kind::Keyword),
+  property("white-space", true, "normal", Kind::Keyword),
+  property("width", false, "auto", Kind::Length),
+  property("word-spacing", true, "0px", Kind::Length),
+  property("z-index", false, "auto", Kind::Keyword),
+];
+
+fn find_property(name: &str) -> Option<&'static Property> {
+  PROPERTIES.iter().find(|property| property.name == name)
+}
+
+/// The computed values of an element's properties, returned by [`StyleResolver::compute_style`].
+///
+/// Font sizes and lengths in absolute or font-relative units are computed to pixels, font
+/// weights to numbers, and named and hex colors to `rgb()`; other values are kept as
+/// specified, since there is no layout.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ComputedStyle {
+  properties: IndexMap<String, String>,
+}
+
+impl ComputedStyle {
+  /// Returns the computed value of a property, or an empty string if it has none.
+  pub fn get_property_value(&self, name: &str) -> String {
+    let name = if name.starts_with("--") {
+      name.to_string()
+    } else {
+      name.to_ascii_lowercase()
+    };
+    self.properties.get(&name).cloned().unwrap_or_default()
+  }
+
+  /// The properties and their values in alphabetical order, custom properties last.
+  pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+    self
+      .properties
+      .iter()
+      .map(|(name, value)| (name.as_str(), value.as_str()))
+  }
+
+  pub fn len(&self) -> usize {
+    self.properties.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.properties.is_empty()
+  }
+
+  fn get(&self, name: &str) -> Option<&str> {
+    self.properties.get(name).map(String::as_str)
+  }
+
+  fn font_size(&self) -> f64 {
+    self
+      .get("font-size")
+      .and_then(|value| value.strip_suffix("px"))
+      .and_then(|value| value.parse().ok())
+      .unwrap_or(INITIAL_FONT_SIZE)
+  }
+}
+
+/// Options for [`StyleResolver::check_visibility`], like those of `Element.checkVisibility()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct VisibilityOptions {
+  /// Also treat elements with an `opacity` of 0, or inside one, as invisible.
+  pub check_opacity: bool,
+  /// Also treat elements whose `visibility` is not `visible` as invisible.
+  pub check_visibility_css: bool,
+}
+
+/// Where a declaration comes from, in cascade order for normal declarations.
+#[derive(Clone, Copy)]
+pub(super) enum Origin {
+  UserAgent,
+  Author,
+  Inline,
+}
+
+/// The cascade precedence of a declaration, `!important` ones reversing the origin order.
+pub(super) fn precedence(origin: Origin, important: bool) -> u8 {
+  match (origin, important) {
+    (Origin::UserAgent, false) => 0,
+    (Origin::Author, false) => 1,
+    (Origin::Inline, false) => 2,
+    (Origin::Author, true) => 3,
+    (Origin::Inline, true) => 4,
+    (Origin::UserAgent, true) => 5,
+  }
+}
+
+/// Whether a media query list applies. There is no viewport, so only queries without media
+/// features match, for the `all` and `screen` media types.
+pub(super) fn media_matches(query: &str) -> bool {
+  if query.trim().is_empty() {
+    return true;
+  }
+  query.split(',').any(|query| {
+    let words: Vec<String> = query
+      .split_whitespace()
+      .map(|word| word.to_ascii_lowercase())
+      .collect();
+    let (negated, media_type) = match &words[..] {
+      [media_type] => (false, media_type),
+      [prefix, media_type] if prefix == "only" => (false, media_type),
+      [prefix, media_type] if prefix == "not" => (true, media_type),
+      _ => return false,
+    };
+    (media_type == "all" || media_type == "screen") != negated
+  })
+}
+
+fn group_applies(group: &GroupRule) -> bool {
+  match group.name.as_str() {
+    "media" => media_matches(&group.prelude),
+    "supports" | "layer" => true,
+    _ => false,
+  }
+}
+
+type Entry = (u8, u32, String, String);
+
+type ScopedStyleSheets = Vec<StyleSheet>;
+
+/// The highest specificity of the selectors matching `element`, or `None` if none match.
+pub(super) fn matching_specificity(
+  selectors: &SelectorList<DomParserSelectors>,
+  element: &DomNode,
+  context: &mut MatchingContext<DomParserSelectors>,
+) -> Option<u32> {
+  selectors
+    .slice()
+    .iter()
+    .filter(|selector| matches_selector(selector, 0, None, element, context))
+    .map(|selector| selector.specificity())
+    .max()
+}
+
+fn collect_declarations(
+  rules: &[CssRule],
+  origin: Origin,
+  element: &DomNode,
+  context: &mut MatchingContext<DomParserSelectors>,
+  entries: &mut Vec<Entry>,
+) {
+  for rule in rules {
+    match rule {
+      CssRule::Style(rule) => {
+        let Some(selectors) = &rule.selectors else {
+          continue;
+        };
+        if let Some(specificity) = matching_specificity(selectors, element, context) {
+          for declaration in rule.style.declarations() {
+            entries.push((
+              precedence(origin, declaration.important),
+              specificity,
+              declaration.name.clone(),
+              declaration.value.clone(),
+            ));
+          }
+        }
+      }
+      CssRule::Group(group) if group_applies(group) => {
+        collect_declarations(&group.rules, origin, element, context, entries);
+      }
+      _ => {}
+    }
+  }
+}
+
+/// Parses the `<style>` elements under `node`, skipping those for other media or types.
+fn collect_style_elements(node: &Handle, stylesheets: &mut Vec<StyleSheet>) {
+  for child in node.children.borrow().iter() {
+    if let NodeData::Element { name, .. } = &child.data {
+      if name.local.as_ref() == "style" {
+        let element = DomNode(child.clone());
+        let is_css = element
+          .get_attribute("type".to_string())
+          .is_none_or(|kind| kind.is_empty() || kind.eq_ignore_ascii_case("text/css"));
+        let media = element
+          .get_attribute("media".to_string())
+          .is_none_or(|media| media_matches(&media));
+        if is_css && media {
+          stylesheets.push(StyleSheet::parse(&element.text_content_getter()));
+        }
+        continue;
+      }
+    }
+    collect_style_elements(child, stylesheets);
+  }
+}
+
+/// The element `node` inherits from: its parent in the flat tree.
+fn inheritance_parent(node: &Handle) -> Option<Handle> {
+  let (parent, _) = flat_tree_parent(node)?;
+  matches!(parent.data, NodeData::Element { .. }).then_some(parent)
+}
+
+enum Numeric {
+  Number(f64),
+  Percentage(f64),
+  Dimension(f64, String),
+}
+
+/// Parses a value made of a single number, percentage or dimension.
+fn parse_numeric(value: &str) -> Option<Numeric> {
+  let mut input = ParserInput::new(value);
+  let mut parser = Parser::new(&mut input);
+  let token = parser.next().ok()?.clone();
+  parser.expect_exhausted().ok()?;
+  match token {
+    Token::Number { value, .. } => Some(Numeric::Number(value as f64)),
+    Token::Percentage { unit_value, .. } => Some(Numeric::Percentage(unit_value as f64 * 100.0)),
+    Token::Dimension { value, unit, .. } => {
+      Some(Numeric::Dimension(value as f64, unit.to_ascii_lowercase()))
+    }
+    _ => None,
+  }
+}
+
+/// Converts a length in an absolute or font-relative unit to pixels.
+fn length_to_px(value: f64, unit: &str, font_size: f64, root_font_size: f64) -> Option<f64> {
+  let factor = match unit.to_ascii_lowercase().as_str() {
+    "px" => 1.0,
+    "em" => font_size,
+    "rem" => root_font_size,
+    // Without font metrics, as browsers do when they are not available.
+    "ex" | "ch" => font_size / 2.0,
+    "pt" => 96.0 / 72.0,
+    "pc" => 16.0,
+    "in" => 96.0,
+    "cm" => 96.0 / 2.54,
+    "mm" => 96.0 / 25.4,
+    "q" => 96.0 / 101.6,
+    _ => return None,
+  };
+  Some(value * factor)
+}
+
+fn format_number(value: f64) -> String {
+  let rounded = (value * 1000.0).round() / 1000.0;
+  // Avoids "-0".
+  format!("{}", rounded + 0.0)
+}
+
+fn format_px(value: f64) -> String {
+  format!("{}px", format_number(value))
+}
+
+/// Converts the lengths of a value to pixels, and `0` as well if `zero` is set.
+fn absolutize_lengths(value: &str, font_size: f64, root_font_size: f64, zero: bool) -> String {
+  rewrite_value(value, &mut |token, _| {
+    Ok(match token {
+      Token::Dimension { value, unit, .. } => {
+        length_to_px(*value as f64, unit, font_size, root_font_size).map(format_px)
+      }
+      Token::Number { value, .. } if zero && *value == 0.0 => Some("0px".to_string()),
+      _ => None,
+    })
+  })
+  .unwrap_or_else(|| value.to_string())
+}
+
+fn compute_font_size(value: &str, parent: f64, root: f64) -> Option<f64> {
+  let size = match value.to_ascii_lowercase().as_str() {
+    "xx-small" => 9.0,
+    "x-small" => 10.0,
+    "small" => 13.0,
+    "medium" => 16.0,
+    "large" => 18.0,
+    "x-large" => 24.0,
+    "xx-large" => 32.0,
+    "xxx-large" => 48.0,
+    "smaller" => parent / 1.2,
+    "larger" => parent * 1.2,
+    _ => match parse_numeric(value)? {
+      Numeric::Percentage(percentage) => parent * percentage / 100.0,
+      Numeric::Dimension(value, unit) => length_to_px(value, &unit, parent, root)?,
+      Numeric::Number(0.0) => 0.0,
+      Numeric::Number(_) => return None,
+    },
+  };
+  Some(size)
+}
+
+/// https://drafts.csswg.org/css-fonts/#relative-weights
+fn compute_font_weight(value: &str, parent: f64) -> String {
+  let weight = match value.to_ascii_lowercase().as_str() {
+    "normal" => 400.0,
+    "bold" => 700.0,
+    "bolder" if parent < 350.0 => 400.0,
+    "bolder" if parent < 550.0 => 700.0,
+    "bolder" => parent.max(900.0),
+    "lighter" if parent < 550.0 => parent.min(100.0),
+    "lighter" if parent < 750.0 => 400.0,
+    "lighter" => 700.0,
+    _ => match value.parse::<f64>() {
+      Ok(weight) => weight,
+      Err(_) => return value.to_string(),
+    },
+  };
+  format_number(weight)
+}
+
+fn compute_color(value: &str, current_color: &str) -> String {
+  let lower = value.to_ascii_lowercase();
+  if lower == "currentcolor" {
+    return current_color.to_string();
+  }
+  if lower == "transparent" {
+    return "rgba(0, 0, 0, 0)".to_string();
+  }
+  let (red, green, blue, alpha) = if let Ok((red, green, blue)) = parse_named_color(&lower) {
+    (red, green, blue, 1.0)
+  } else if let Some(Ok(color)) = lower
+    .strip_prefix('#')
+    .map(|hex| parse_hash_color(hex.as_bytes()))
+  {
+    color
+  } else {
+    return value.to_string();
+  };
+  let mut color = format!(
+    "{}({}, {}, {}",
+    if alpha == 1.0 { "rgb" } else { "rgba" },
+    red,
+    green,
+    blue
+  );
+  let _ = serialize_color_alpha(&mut color, Some(alpha), true);
+  color.push(')');
+  color
+}
+
+/// Replaces the `var()` functions of a value with custom property values, or returns `None`
+/// if a variable is missing without a fallback or the references are cyclic.
+fn substitute_variables(
+  value: &str,
+  variables: &IndexMap<String, String>,
+  depth: usize,
+) -> Option<String> {
+  if !value.to_ascii_lowercase().contains("var(") {
+    return Some(value.to_string());
+  }
+  if depth > 16 {
+    return None;
+  }
+  rewrite_value(value, &mut |token, input| {
+    match token {
+      Token::Function(name) if name.eq_ignore_ascii_case("var") => {}
+      _ => return Ok(None),
+    }
+    let (name, fallback) = input
+      .parse_nested_block(|input| {
+        let name = input.expect_ident_cloned()?.to_string();
+        let fallback = input
+          .try_parse(|input| input.expect_comma())
+          .is_ok()
+          .then(|| {
+            let start = input.position();
+            while input.next_including_whitespace_and_comments().is_ok() {}
+            input.slice_from(start).trim().to_string()
+          });
+        Ok::<_, ParseError<()>>((name, fallback))
+      })
+      .map_err(|_| ())?;
+    let value = variables.get(&name).cloned().or(fallback).ok_or(())?;
+    substitute_variables(&value, variables, depth + 1)
+      .map(Some)
+      .ok_or(())
+  })
+}
+
+/// Computes styles without layout: the cascade of the default stylesheet, the `<style>`
+/// elements of the element's document or shadow tree, added stylesheets and the `style`
+/// attribute, followed by inheritance.
+///
+/// Styles and parsed stylesheets are cached, so create a new resolver after changing the
+/// document. `@media` rules only apply without media features, and selectors using
+/// unsupported pseudo-classes such as `:hover` never match.
+pub struct StyleResolver {
+  default_stylesheet: Rc<StyleSheet>,
+  stylesheets: Vec<StyleSheet>,
+  scopes: RefCell<HashMap<usize, (Handle, Rc<ScopedStyleSheets>)>>,
+  styles: RefCell<HashMap<usize, (Handle, Rc<ComputedStyle>)>>,
+}
+
+impl Default for StyleResolver {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl StyleResolver {
+  pub fn new() -> Self {
+    StyleResolver {
+      default_stylesheet: DEFAULT_STYLESHEET.with(Rc::clone),
+      stylesheets: Vec::new(),
+      scopes: RefCell::new(HashMap::new()),
+      styles: RefCell::new(HashMap::new()),
+    }
+  }
+
+  /// Adds an author stylesheet, applied to documents after their `<style>` elements.
+  pub fn add_stylesheet(&mut self, stylesheet: StyleSheet) {
+    self.stylesheets.push(stylesheet);
+    self.styles.borrow_mut().clear();
+  }
+
+  /// The stylesheets of the `<style>` elements in the tree rooted at `root`.
+  fn scoped_stylesheets(&self, root: &DomNode) -> Rc<ScopedStyleSheets> {
+    let key = Rc::as_ptr(&root.0) as usize;
+    if let Some((_, stylesheets)) = self.scopes.borrow().get(&key) {
+      return stylesheets.clone();
+    }
+    let mut stylesheets = Vec::new();
+    collect_style_elements(&root.0, &mut stylesheets);
+    let stylesheets = Rc::new(stylesheets);
+    self
+      .scopes
+      .borrow_mut()
+      .insert(key, (root.0.clone(), stylesheets.clone()));
+    stylesheets
+  }
+
+  /// The cascaded value of each declared property.
+  fn cascaded_values(&self, element: &DomNode) -> IndexMap<String, String> {
+    let mut caches = SelectorCaches::default();
+    let mut context = MatchingContext::new(
+      MatchingMode::Normal,
+      None,
+      &mut caches,
+      QuirksMode::NoQuirks,
+      NeedsSelectorFlags::No,
+      MatchingForInvalidation::No,
+    );
+    let root = element.get_root_node();
+    context.current_host = root.host().map(|host| host.opaque());
+    let mut entries = Vec::new();
+    collect_declarations(
+      &self.default_stylesheet.rules,
+      Origin::UserAgent,
+      element,
+      &mut context,
+      &mut entries,
+    );
+    let scoped = self.scoped_stylesheets(&root);
+    let added: &[StyleSheet] = if matches!(root.0.data, NodeData::Document) {
+      &self.stylesheets
+    } else {
+      &[]
+    };
+    for stylesheet in scoped.iter().chain(added) {
+      collect_declarations(
+        &stylesheet.rules,
+        Origin::Author,
+        element,
+        &mut context,
+        &mut entries,
+      );
+    }
+    for declaration in element.style().declarations() {
+      entries.push((
+        precedence(Origin::Inline, declaration.important),
+        0,
+        declaration.name.clone(),
+        declaration.value.clone(),
+      ));
+    }
+    // A stable sort, so that later declarations win among equal ones.
+    entries.sort_by_key(|(precedence, specificity, ..)| (*precedence, *specificity));
+    let mut values = IndexMap::new();
+    for (_, _, name, value) in entries {
+      values.insert(name, value);
+    }
+    values
+  }
+
+  /// Returns the computed style of an element, or an empty style for other nodes and for
+  /// elements that are not in a document, which browsers don't style either.
+  pub fn compute_style(&self, element: &DomNode) -> Rc<ComputedStyle> {
+    if !matches!(element.0.data, NodeData::Element { .. }) || element.owner_document().is_none() {
+      return Rc::default();
+    }
+    let key = Rc::as_ptr(&element.0) as usize;
+    if let Some((_, style)) = self.styles.borrow().get(&key) {
+      return style.clone();
+    }
+    let parent = inheritance_parent(&element.0).map(|parent| self.compute_style(&DomNode(parent)));
+    let mut root = element.0.clone();
+    while let Some(parent) = inheritance_parent(&root) {
+      root = parent;
+    }
+    let root_font_size = if Rc::ptr_eq(&root, &element.0) {
+      INITIAL_FONT_SIZE
+    } else {
+      self.compute_style(&DomNode(root)).font_size()
+    };
+    let style = Rc::new(self.compute(element, parent.as_deref(), root_font_size));
+    self
+      .styles
+      .borrow_mut()
+      .insert(key, (element.0.clone(), style.clone()));
+    style
+  }
+
+  fn compute(
+    &self,
+    element: &DomNode,
+    parent: Option<&ComputedStyle>,
+    root_font_size: f64,
+  ) -> ComputedStyle {
+    let cascaded = self.cascaded_values(element);
+
+    // Custom properties are inherited, and computed with their variables substituted.
+    let mut variables: IndexMap<String, String> = parent
+      .map(|parent| {
+        parent
+          .iter()
+          .filter(|(name, _)| name.starts_with("--"))
+          .map(|(name, value)| (name.to_string(), value.to_string()))
+          .collect()
+      })
+      .unwrap_or_default();
+    for (name, value) in cascaded.iter().filter(|(name, _)| name.starts_with("--")) {
+      match value.to_ascii_lowercase().as_str() {
+        "initial" => {
+          variables.shift_remove(name);
+        }
+        keyword if CSS_WIDE_KEYWORDS.contains(&keyword) => {}
+        _ => {
+          variables.insert(name.clone(), value.clone());
+        }
+      }
+    }
+    let variables: IndexMap<String, String> = variables
+      .iter()
+      .filter_map(|(name, value)| {
+        substitute_variables(value, &variables, 0).map(|value| (name.clone(), value))
+      })
+      .collect();
+
+    let specified = |name: &str| -> Option<String> {
+      let property = find_property(name);
+      let initial = property.map(|property| property.initial.to_string());
+      let inherited = || {
+        parent
+          .and_then(|parent| parent.get(name))
+          .map(str::to_string)
+          .or_else(|| initial.clone())
+      };
+      let value = cascaded
+        .get(name)
+        .map(|value| substitute_variables(value, &variables, 0).unwrap_or_else(|| "unset".into()));
+      match value.as_deref().map(str::to_ascii_lowercase).as_deref() {
+        Some("inherit") => inherited(),
+        Some("initial") => initial,
+        // The fonts of the system are not known, so the longhands of a system `font` are initial.
+        Some(keyword)
+          if FONT_SYSTEM_FONTS.contains(&keyword)
+            && shorthands::shorthand("font").is_some_and(|font| font.longhands.contains(&name)) =>
+        {
+          initial
+        }
+        None | Some("unset" | "revert" | "revert-layer") => {
+          if property.is_some_and(|property| property.inherited) {
+            inherited()
+          } else {
+            initial
+          }
+        }
+        Some(_) => value,
+      }
+    };
+
+    let parent_font_size = parent.map_or(INITIAL_FONT_SIZE, ComputedStyle::font_size);
+    let font_size = specified("font-size")
+      .and_then(|value| compute_font_size(&value, parent_font_size, root_font_size))
+      .unwrap_or(parent_font_size);
+    let parent_color = parent
+      .and_then(|parent| parent.get("color"))
+      .unwrap_or("rgb(0, 0, 0)");
+    let color = specified("color").map_or_else(
+      || parent_color.to_string(),
+      |value| compute_color(&value, parent_color),
+    );
+
+    let mut properties = IndexMap::new();
+    properties.insert("font-size".to_string(), format_px(font_size));
+    properties.insert("color".to_string(), color.clone());
+    let mut names: Vec<&str> = PROPERTIES.iter().map(|property| property.name).collect();
+    names.extend(
+      cascaded
+        .keys()
+        .map(String::as_str)
+        .filter(|name| !name.starts_with("--") && find_property(name).is_none()),
+    );
+    // Border widths depend on the computed border styles.
+    let (widths, others): (Vec<&str>, Vec<&str>) = names.into_iter().partition(|name| {
+      find_property(name).is_some_and(|property| matches!(property.kind, Kind::BorderWidth(_)))
+    });
+    for name in others.into_iter().chain(widths) {
+      if properties.contains_key(name) {
+        continue;
+      }
+      let Some(value) = specified(name) else {
+        continue;
+      };
+      let kind = find_property(name).map_or(Kind::Verbatim, |property| property.kind);
+      let value = match kind {
+        Kind::Verbatim => value,
+        Kind::Keyword => {
+          if value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            value.to_ascii_lowercase()
+          } else {
+            value
+          }
+        }
+        Kind::Length => absolutize_lengths(&value, font_size, root_font_size, true),
+        Kind::Color => compute_color(&value, &color),
+        Kind::FontSize => format_px(font_size),
+        Kind::FontWeight => {
+          let parent_weight = parent
+            .and_then(|parent| parent.get("font-weight"))
+            .and_then(|weight| weight.parse().ok())
+            .unwrap_or(400.0);
+          compute_font_weight(&value, parent_weight)
+        }
+        Kind::LineHeight => match parse_numeric(&value) {
+          Some(Numeric::Percentage(percentage)) => format_px(font_size * percentage / 100.0),
+          _ => absolutize_lengths(&value, font_size, root_font_size, false),
+        },
+        Kind::BorderWidth(style) => {
+          let style = properties.get(style).map(String::as_str);
+          if matches!(style, Some("none" | "hidden")) {
+            "0px".to_string()
+          } else {
+            match value.to_ascii_lowercase().as_str() {
+              "thin" => "1px".to_string(),
+              "medium" => "3px".to_string(),
+              "thick" => "5px".to_string(),
+              _ => absolutize_lengths(&value, font_size, root_font_size, true),
+            }
+          }
+        }
+      };
+      properties.insert(name.to_string(), value);
+    }
+    properties.extend(variables);
+    properties.sort_by(|a, _, b, _| (a.starts_with("--"), a).cmp(&(b.starts_with("--"), b)));
+    ComputedStyle { properties }
+  }
+
+  /// Returns whether an element is rendered, like `Element.checkVisibility()`: it is connected,
+  /// in the flat tree, and neither it nor an ancestor has `display: none`, nor an ancestor
+  /// `content-visibility: hidden`. `display: contents` elements have no box and are not
+  /// visible either.
+  pub fn check_visibility(&self, element: &DomNode, options: &VisibilityOptions) -> bool {
+    if !matches!(element.0.data, NodeData::Element { .. }) || element.owner_document().is_none() {
+      return false;
+    }
+    let style = self.compute_style(element);
+    if style.get("display") == Some("contents") {
+      return false;
+    }
+    if options.check_visibility_css && style.get("visibility") != Some("visible") {
+      return false;
+    }
+    let mut current = element.0.clone();
+    let mut is_ancestor = false;
+    loop {
+      let style = self.compute_style(&DomNode(current.clone()));
+      if style.get("display") == Some("none")
+        || (is_ancestor && style.get("content-visibility") == Some("hidden"))
+      {
+        return false;
+      }
+      if options.check_opacity {
+        let opacity = style.get("opacity").and_then(parse_numeric);
+        if matches!(
+          opacity,
+          Some(Numeric::Number(0.0) | Numeric::Percentage(0.0))
+        ) {
+          return false;
+        }
+      }
+      match flat_tree_parent(&current) {
+        Some((_, false)) => return false,
+        Some((parent, true)) if matches!(parent.data, NodeData::Element { .. }) => {
+          current = parent;
+          is_ancestor = true;
+        }
+        _ => return true,
+      }
+    }
+  }
+}
+
+/// Computes the style of an element with the default stylesheet and the `<style>` elements of
+/// its document or shadow tree. Use a [`StyleResolver`] to add stylesheets, or to compute the
+/// styles of many elements.
+pub fn compute_style(element: &DomNode) -> ComputedStyle {
+  StyleResolver::new().compute_style(element).as_ref().clone()
+}
@@ -0,0 +1,256 @@
+use cssparser::{ParseError, Parser, ParserInput, Token};
+use markup5ever_rcdom::{Handle, NodeData};
+use selectors::matching::{
+  matches_selector, MatchingContext, MatchingForInvalidation, MatchingMode, NeedsSelectorFlags,
+  QuirksMode, SelectorCaches,
+};
+
+use super::declarations::rewrite_value;
+use super::stylesheet::{rest, CssRule, GroupRule, StyleRule, StyleSheet};
+use crate::node::selectors::{parse_selectors, DomParserSelectors};
+use crate::node::DomNode;
+
+/// Pseudo-classes that depend on user interaction or form state, treated as matching.
+const STATE_PSEUDO_CLASSES: &[&str] = &[
+  "active",
+  "any-link",
+  "autofill",
+  "checked",
+  "default",
+  "defined",
+  "disabled",
+  "enabled",
+  "focus",
+  "focus-visible",
+  "focus-within",
+  "fullscreen",
+  "hover",
+  "in-range",
+  "indeterminate",
+  "invalid",
+  "link",
+  "modal",
+  "open",
+  "optional",
+  "out-of-range",
+  "placeholder-shown",
+  "popover-open",
+  "read-only",
+  "read-write",
+  "required",
+  "target",
+  "user-invalid",
+  "user-valid",
+  "valid",
+  "visited",
+];
+
+/// Pseudo-elements that can be written with a single colon.
+const LEGACY_PSEUDO_ELEMENTS: &[&str] = &["after", "before", "first-letter", "first-line"];
+
+/// The usage of a style rule, returned by [`css_coverage`].
+#[derive(Clone, Debug)]
+pub struct RuleCoverage {
+  pub selector_text: String,
+  /// The at-rules the rule is nested in, such as `@media print`, outermost first.
+  pub conditions: Vec<String>,
+  /// The selectors of the list that match an element. Selectors the engine does not support
+  /// are included, so that they are never pruned.
+  pub used_selectors: Vec<String>,
+}
+
+impl RuleCoverage {
+  pub fn is_used(&self) -> bool {
+    !self.used_selectors.is_empty()
+  }
+}
+
+/// The result of [`css_coverage`].
+#[derive(Clone, Debug, Default)]
+pub struct CssCoverage {
+  /// The style rules of the stylesheet in source order, including those in at-rules.
+  pub rules: Vec<RuleCoverage>,
+  /// The stylesheet without unused style rules and selectors, and without at-rules left empty.
+  pub pruned: StyleSheet,
+}
+
+/// Splits a selector list into its selectors.
+fn split_selectors(selector_text: &str) -> Vec<String> {
+  let mut input = ParserInput::new(selector_text);
+  let mut parser = Parser::new(&mut input);
+  parser
+    .parse_comma_separated(|input| Ok::<_, ParseError<()>>(rest(input).trim().to_string()))
+    .unwrap_or_else(|_| vec![selector_text.to_string()])
+}
+
+/// Removes state pseudo-classes and pseudo-elements, so that `a:hover` and `p::first-line`
+/// match every `a` and `p`. A compound left empty, such as `:hover`, becomes `*`.
+/// A `:not()` whose argument has a state is removed as a whole: the state may be off, so
+/// `p:not(:hover)` matches every `p` too.
+fn ignore_states(selector: &str) -> Option<String> {
+  const REMOVED: char = '\u{1}';
+  let rewritten = rewrite_value(selector, &mut |token, input| {
+    if *token != Token::Colon {
+      return Ok(None);
+    }
+    let negated_state = input.try_parse(|input| {
+      let is_not = matches!(
+        input.next_including_whitespace()?,
+        Token::Function(name) if name.eq_ignore_ascii_case("not")
+      );
+      if !is_not {
+        return Err(input.new_custom_error::<(), ()>(()));
+      }
+      let argument = input.parse_nested_block(|input| Ok::<_, ParseError<()>>(rest(input)))?;
+      if ignore_states(argument) != rewrite_value(argument, &mut |_, _| Ok(None)) {
+        Ok(())
+      } else {
+        Err(input.new_custom_error::<(), ()>(()))
+      }
+    });
+    if negated_state.is_ok() {
+      return Ok(Some(REMOVED.to_string()));
+    }
+    let ignored = input.try_parse(|input| {
+      let is_ignored = match input.next_including_whitespace()?.clone() {
+        Token::Colon => match input.next_including_whitespace()?.clone() {
+          Token::Ident(_) => true,
+          Token::Function(_) => input
+            .parse_nested_block(|input| Ok::<_, ParseError<()>>(rest(input).is_empty()))
+            .is_ok(),
+          _ => false,
+        },
+        Token::Ident(name) => {
+          let name = name.to_ascii_lowercase();
+          STATE_PSEUDO_CLASSES.contains(&name.as_str())
+            || LEGACY_PSEUDO_ELEMENTS.contains(&name.as_str())
+        }
+        _ => false,
+      };
+      if is_ignored {
+        Ok(())
+      } else {
+        Err(input.new_custom_error::<(), ()>(()))
+      }
+    });
+    Ok(ignored.is_ok().then(|| REMOVED.to_string()))
+  })?;
+  let mut out = String::new();
+  for (index, part) in rewritten.split(REMOVED).enumerate() {
+    let starts_compound = out
+      .chars()
+      .last()
+      .is_none_or(|c| c.is_whitespace() || matches!(c, '>' | '+' | '~' | '(' | ','));
+    if index > 0 && starts_compound {
+      out.push('*');
+    }
+    out.push_str(part);
+  }
+  Some(out)
+}
+
+/// The elements of the tree rooted at `root`, including `root`.
+fn elements(root: &Handle) -> Vec<DomNode> {
+  let mut found = vec![];
+  let mut stack = vec![root.clone()];
+  while let Some(node) = stack.pop() {
+    if matches!(node.data, NodeData::Element { .. }) {
+      found.push(DomNode(node.clone()));
+    }
+    stack.extend(node.children.borrow().iter().rev().cloned());
+  }
+  found
+}
+
+struct Coverage<'a> {
+  elements: Vec<DomNode>,
+  context: MatchingContext<'a, DomParserSelectors>,
+  rules: Vec<RuleCoverage>,
+}
+
+impl Coverage<'_> {
+  fn matches(&mut self, selector: &str) -> bool {
+    let Some(selectors) = ignore_states(selector).and_then(|selector| parse_selectors(&selector))
+    else {
+      return true;
+    };
+    let context = &mut self.context;
+    self.elements.iter().any(|element| {
+      selectors
+        .slice()
+        .iter()
+        .any(|selector| matches_selector(selector, 0, None, element, context))
+    })
+  }
+
+  fn prune(&mut self, rules: &[CssRule], conditions: &[String]) -> Vec<CssRule> {
+    let mut pruned = vec![];
+    for rule in rules {
+      match rule {
+        CssRule::Style(rule) => {
+          let used_selectors: Vec<String> = split_selectors(&rule.selector_text)
+            .into_iter()
+            .filter(|selector| self.matches(selector))
+            .collect();
+          if !used_selectors.is_empty() {
+            let selector_text = used_selectors.join(", ");
+            pruned.push(CssRule::Style(StyleRule {
+              selectors: parse_selectors(&selector_text),
+              selector_text,
+              style: rule.style.clone(),
+            }));
+          }
+          self.rules.push(RuleCoverage {
+            selector_text: rule.selector_text.clone(),
+            conditions: conditions.to_vec(),
+            used_selectors,
+          });
+        }
+        CssRule::Group(group) => {
+          let separator = if group.prelude.is_empty() { "" } else { " " };
+          let mut conditions = conditions.to_vec();
+          conditions.push(format!("@{}{}{}", group.name, separator, group.prelude));
+          let rules = self.prune(&group.rules, &conditions);
+          if !rules.is_empty() {
+            pruned.push(CssRule::Group(GroupRule {
+              rules,
+              ..group.clone()
+            }));
+          }
+        }
+        CssRule::Other(_) => pruned.push(rule.clone()),
+      }
+    }
+    pruned
+  }
+}
+
+/// Reports which style rules of a stylesheet match an element of the tree rooted at `root`,
+/// and returns the stylesheet pruned of the others. Pass a document to find unused CSS, or the
+/// above-the-fold part of a page to extract its critical CSS.
+///
+/// Selectors are matched in the whole document, so ancestors outside `root` are taken into
+/// account. State pseudo-classes such as `:hover`, `:focus` or `:checked` are ignored and
+/// pseudo-elements match their element. Rules in `@media` and other at-rules are evaluated
+/// whatever their condition, and at-rules such as `@font-face` and `@keyframes` are kept.
+pub fn css_coverage(root: &DomNode, css: &str) -> CssCoverage {
+  let stylesheet = StyleSheet::parse(css);
+  let mut caches = SelectorCaches::default();
+  let mut coverage = Coverage {
+    elements: elements(&root.0),
+    context: MatchingContext::new(
+      MatchingMode::Normal,
+      None,
+      &mut caches,
+      QuirksMode::NoQuirks,
+      NeedsSelectorFlags::No,
+      MatchingForInvalidation::No,
+    ),
+    rules: vec![],
+  };
+  let rules = coverage.prune(&stylesheet.rules, &[]);
+  CssCoverage {
+    rules: coverage.rules,
+    pruned: StyleSheet { rules },
+  }
+}
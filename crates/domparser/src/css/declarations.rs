@@ -0,0 +1,448 @@
+use cssparser::{
+  parse_important, AtRuleParser, CowRcStr, DeclarationParser, ParseError, Parser, ParserInput,
+  ParserState, QualifiedRuleParser, RuleBodyItemParser, RuleBodyParser, ToCss, Token,
+};
+
+use super::shorthands::{self, Shorthand};
+
+/// A single `name: value` declaration of a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CssDeclaration {
+  /// The property name, ASCII-lowercased unless it is a custom property.
+  pub name: String,
+  /// The value with comments removed and white space collapsed.
+  pub value: String,
+  pub important: bool,
+}
+
+/// A CSS declaration block, such as the contents of a `style` attribute.
+///
+/// Supported shorthands (`margin`, `padding`, `border`, `flex`, ...) are stored as their
+/// longhands and combined again when serialized. Other properties are stored as written,
+/// without checking their values against the property grammar. Unlike in browsers, unknown
+/// properties are kept too.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CssStyleDeclaration {
+  declarations: Vec<CssDeclaration>,
+}
+
+/// Lowercases a property name, leaving custom properties alone.
+fn property_name(name: &str) -> String {
+  if name.starts_with("--") {
+    name.to_string()
+  } else {
+    name.to_ascii_lowercase()
+  }
+}
+
+/// Called for each token of a value being rewritten. Returns the text replacing the token,
+/// consuming the arguments of a function from the parser, or `None` to keep the token.
+/// An error makes the whole value invalid.
+pub(super) type TokenVisitor<'a> =
+  dyn for<'i, 't> FnMut(&Token<'i>, &mut Parser<'i, 't>) -> Result<Option<String>, ()> + 'a;
+
+/// Writes the tokens of `input` to `out` with comments removed, white space collapsed and a
+/// single space after commas.
+/// Returns false if the tokens can't be part of a declaration value.
+fn serialize_tokens(input: &mut Parser, out: &mut String, visit: &mut TokenVisitor) -> bool {
+  let mut space = false;
+  while let Ok(token) = input.next_including_whitespace_and_comments() {
+    let token = token.clone();
+    match token {
+      Token::WhiteSpace(_) | Token::Comment(_) => {
+        space = true;
+        continue;
+      }
+      Token::BadUrl(_)
+      | Token::BadString(_)
+      | Token::CloseParenthesis
+      | Token::CloseSquareBracket
+      | Token::CloseCurlyBracket => return false,
+      _ => {}
+    }
+    if token == Token::Comma {
+      // Commas are written as ", ", like browsers do.
+      out.push(',');
+      space = true;
+      continue;
+    }
+    if space && !out.is_empty() {
+      out.push(' ');
+    }
+    space = false;
+    match visit(&token, input) {
+      Ok(Some(replacement)) => {
+        out.push_str(&replacement);
+        continue;
+      }
+      Ok(None) => {}
+      Err(()) => return false,
+    }
+    if token.to_css(out).is_err() {
+      return false;
+    }
+    let closing = match token {
+      Token::Function(_) | Token::ParenthesisBlock => ')',
+      Token::SquareBracketBlock => ']',
+      Token::CurlyBracketBlock => '}',
+      _ => continue,
+    };
+    let nested = input.parse_nested_block(|input| {
+      let mut inner = String::new();
+      let valid = serialize_tokens(input, &mut inner, visit);
+      Ok::<_, ParseError<()>>((inner, valid))
+    });
+    match nested {
+      Ok((inner, true)) => {
+        out.push_str(&inner);
+        out.push(closing);
+      }
+      _ => return false,
+    }
+  }
+  true
+}
+
+/// Normalizes a value like [`normalize_value`], letting `visit` replace tokens.
+pub(super) fn rewrite_value(value: &str, visit: &mut TokenVisitor) -> Option<String> {
+  let mut input = ParserInput::new(value);
+  let mut parser = Parser::new(&mut input);
+  let mut out = String::new();
+  serialize_tokens(&mut parser, &mut out, visit).then_some(out)
+}
+
+/// Normalizes a declaration value, or returns `None` if it is not a valid one.
+/// Custom property values are only trimmed.
+fn normalize_value(name: &str, value: &str) -> Option<String> {
+  if name.starts_with("--") {
+    return Some(value.trim().to_string());
+  }
+  rewrite_value(value, &mut |_, _| Ok(None)).filter(|value| !value.is_empty())
+}
+
+/// Whether `value` is a single declaration value on its own: one without a top-level `;`
+/// that would end the declaration, or an `!important` that belongs to the priority.
+fn is_single_value(value: &str) -> bool {
+  let mut input = ParserInput::new(value);
+  let mut parser = Parser::new(&mut input);
+  while let Ok(token) = parser.next() {
+    match token.clone() {
+      Token::Semicolon => return false,
+      Token::Delim('!')
+        if parser
+          .try_parse(|input| input.expect_ident_matching("important"))
+          .is_ok() =>
+      {
+        return false
+      }
+      _ => {}
+    }
+  }
+  true
+}
+
+struct DeclarationListParser;
+
+impl<'i> DeclarationParser<'i> for DeclarationListParser {
+  type Declaration = CssDeclaration;
+  type Error = ();
+
+  fn parse_value<'t>(
+    &mut self,
+    name: CowRcStr<'i>,
+    input: &mut Parser<'i, 't>,
+    _declaration_start: &ParserState,
+  ) -> Result<CssDeclaration, ParseError<'i, ()>> {
+    let start = input.position();
+    let mut important = false;
+    let end = loop {
+      let before = input.position();
+      let is_important = input
+        .try_parse(|input| {
+          parse_important(input)?;
+          input.expect_exhausted()
+        })
+        .is_ok();
+      if is_important {
+        important = true;
+        break before;
+      }
+      if input.next_including_whitespace_and_comments().is_err() {
+        break input.position();
+      }
+    };
+    let name = property_name(&name);
+    let value = input.slice(start..end);
+    match normalize_value(&name, value) {
+      Some(value) => Ok(CssDeclaration {
+        name,
+        value,
+        important,
+      }),
+      None => Err(input.new_custom_error(())),
+    }
+  }
+}
+
+impl<'i> AtRuleParser<'i> for DeclarationListParser {
+  type Prelude = ();
+  type AtRule = CssDeclaration;
+  type Error = ();
+}
+
+impl<'i> QualifiedRuleParser<'i> for DeclarationListParser {
+  type Prelude = ();
+  type QualifiedRule = CssDeclaration;
+  type Error = ();
+}
+
+impl<'i> RuleBodyItemParser<'i, CssDeclaration, ()> for DeclarationListParser {
+  fn parse_declarations(&self) -> bool {
+    true
+  }
+
+  fn parse_qualified(&self) -> bool {
+    false
+  }
+}
+
+impl CssStyleDeclaration {
+  /// Parses a declaration list, skipping invalid declarations.
+  pub fn parse(css: &str) -> Self {
+    let mut style = CssStyleDeclaration::default();
+    style.set_css_text(css);
+    style
+  }
+
+  /// The declarations in order, with supported shorthands expanded.
+  pub fn declarations(&self) -> &[CssDeclaration] {
+    &self.declarations
+  }
+
+  pub fn len(&self) -> usize {
+    self.declarations.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.declarations.is_empty()
+  }
+
+  /// The name of the `index`th declared property.
+  pub fn item(&self, index: usize) -> Option<&str> {
+    self
+      .declarations
+      .get(index)
+      .map(|declaration| declaration.name.as_str())
+  }
+
+  fn find(&self, name: &str) -> Option<&CssDeclaration> {
+    self
+      .declarations
+      .iter()
+      .find(|declaration| declaration.name == name)
+  }
+
+  /// The longhand declarations of `shorthand`, if all of them are declared with the same
+  /// priority.
+  fn longhands(&self, shorthand: &Shorthand) -> Option<(Vec<&str>, bool)> {
+    let declarations = shorthand
+      .longhands
+      .iter()
+      .map(|longhand| self.find(longhand))
+      .collect::<Option<Vec<_>>>()?;
+    let important = declarations.first()?.important;
+    if declarations
+      .iter()
+      .any(|declaration| declaration.important != important)
+    {
+      return None;
+    }
+    let values = declarations
+      .iter()
+      .map(|declaration| declaration.value.as_str())
+      .collect();
+    Some((values, important))
+  }
+
+  /// Returns the value of a property, or an empty string if it is not set. Shorthands are
+  /// serialized from their longhands.
+  pub fn get_property_value(&self, name: &str) -> String {
+    let name = property_name(name);
+    if let Some(declaration) = self.find(&name) {
+      return declaration.value.clone();
+    }
+    shorthands::shorthand(&name)
+      .and_then(|shorthand| {
+        let (values, _) = self.longhands(shorthand)?;
+        shorthands::serialize(shorthand, &values)
+      })
+      .unwrap_or_default()
+  }
+
+  /// Returns "important" if the property is set with `!important`, or an empty string.
+  pub fn get_property_priority(&self, name: &str) -> String {
+    let name = property_name(name);
+    let important = match self.find(&name) {
+      Some(declaration) => declaration.important,
+      None => shorthands::shorthand(&name)
+        .and_then(|shorthand| self.longhands(shorthand))
+        .is_some_and(|(_, important)| important),
+    };
+    if important {
+      "important".to_string()
+    } else {
+      String::new()
+    }
+  }
+
+  /// Sets a property, keeping its position if it is already declared. An empty value removes
+  /// the property; invalid values, values that are more than a single declaration value, and
+  /// priorities other than "important" or "" are ignored.
+  pub fn set_property(&mut self, name: &str, value: &str, priority: &str) {
+    let important = if priority.is_empty() {
+      false
+    } else if priority.eq_ignore_ascii_case("important") {
+      true
+    } else {
+      return;
+    };
+    let name = property_name(name);
+    if value.trim().is_empty() && !name.starts_with("--") {
+      self.remove_property(&name);
+      return;
+    }
+    if !is_single_value(value) {
+      return;
+    }
+    let Some(value) = normalize_value(&name, value) else {
+      return;
+    };
+    for declaration in self.expand(CssDeclaration {
+      name,
+      value,
+      important,
+    }) {
+      match self
+        .declarations
+        .iter_mut()
+        .find(|existing| existing.name == declaration.name)
+      {
+        Some(existing) => *existing = declaration,
+        None => self.declarations.push(declaration),
+      }
+    }
+  }
+
+  /// Removes a property, or all longhands of a shorthand, and returns its previous value.
+  pub fn remove_property(&mut self, name: &str) -> String {
+    let name = property_name(name);
+    let value = self.get_property_value(&name);
+    let longhands = shorthands::shorthand(&name).map_or(&[][..], |shorthand| shorthand.longhands);
+    self
+      .declarations
+      .retain(|declaration| declaration.name != name && !longhands.contains(&&*declaration.name));
+    value
+  }
+
+  /// Expands a supported shorthand into its longhands. Other declarations, and shorthands
+  /// whose value uses `var()`, are returned as they are.
+  fn expand(&mut self, declaration: CssDeclaration) -> Vec<CssDeclaration> {
+    let Some(shorthand) = shorthands::shorthand(&declaration.name) else {
+      return vec![declaration];
+    };
+    if declaration.value.to_ascii_lowercase().contains("var(") {
+      // The longhands are only known once the variables are substituted.
+      self.remove_property(&declaration.name);
+      return vec![declaration];
+    }
+    let Some(values) = shorthands::expand(shorthand, &declaration.value) else {
+      return Vec::new();
+    };
+    self
+      .declarations
+      .retain(|existing| existing.name != declaration.name);
+    shorthand
+      .longhands
+      .iter()
+      .zip(values)
+      .map(|(longhand, value)| CssDeclaration {
+        name: longhand.to_string(),
+        value,
+        important: declaration.important,
+      })
+      .collect()
+  }
+
+  /// Serializes the block, combining longhands into shorthands where possible.
+  pub fn css_text(&self) -> String {
+    let mut serialized = vec![false; self.declarations.len()];
+    let mut parts = Vec::new();
+    let position = |name: &str| {
+      self
+        .declarations
+        .iter()
+        .position(|declaration| declaration.name == name)
+    };
+    for (index, declaration) in self.declarations.iter().enumerate() {
+      if serialized[index] {
+        continue;
+      }
+      let combined = shorthands::shorthands_of(&declaration.name).find_map(|shorthand| {
+        let positions = shorthand
+          .longhands
+          .iter()
+          .map(|longhand| position(longhand))
+          .collect::<Option<Vec<_>>>()?;
+        if positions.iter().any(|&position| serialized[position]) {
+          return None;
+        }
+        let (values, important) = self.longhands(shorthand)?;
+        let value = shorthands::serialize(shorthand, &values)?;
+        Some((shorthand.name, value, important, positions))
+      });
+      let (name, value, important) = match combined {
+        Some((name, value, important, positions)) => {
+          for position in positions {
+            serialized[position] = true;
+          }
+          (name, value, important)
+        }
+        None => (
+          declaration.name.as_str(),
+          declaration.value.clone(),
+          declaration.important,
+        ),
+      };
+      serialized[index] = true;
+      let priority = if important { " !important" } else { "" };
+      parts.push(format!("{}: {}{};", name, value, priority));
+    }
+    parts.join(" ")
+  }
+
+  /// Replaces the declarations with those parsed from `css`. When a property is declared
+  /// twice, the last declaration wins unless only the earlier one is `!important`.
+  pub fn set_css_text(&mut self, css: &str) {
+    self.declarations.clear();
+    let mut input = ParserInput::new(css);
+    let mut parser = Parser::new(&mut input);
+    let parsed: Vec<CssDeclaration> = RuleBodyParser::new(&mut parser, &mut DeclarationListParser)
+      .filter_map(Result::ok)
+      .collect();
+    for declaration in parsed {
+      for declaration in self.expand(declaration) {
+        if let Some(index) = self
+          .declarations
+          .iter()
+          .position(|existing| existing.name == declaration.name)
+        {
+          if self.declarations[index].important && !declaration.important {
+            continue;
+          }
+          self.declarations.remove(index);
+        }
+        self.declarations.push(declaration);
+      }
+    }
+  }
+}
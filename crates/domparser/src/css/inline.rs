@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+
+use cssparser::{ParseError, Parser, ParserInput, Token};
+use markup5ever_rcdom::{Handle, NodeData};
+use selectors::matching::{
+  MatchingContext, MatchingForInvalidation, MatchingMode, NeedsSelectorFlags, QuirksMode,
+  SelectorCaches,
+};
+
+use super::cascade::{matching_specificity, media_matches, precedence, Origin};
+use super::stylesheet::{CssRule, StyleRule, StyleSheet};
+use super::CssStyleDeclaration;
+use crate::node::DomNode;
+
+/// Elements that never get inlined styles.
+const SKIPPED_ELEMENTS: &[&str] = &["head", "script", "style", "template"];
+
+/// Options for [`inline_css`].
+#[derive(Clone, Debug, Default)]
+pub struct InlineOptions {
+  /// Keep `!important` on declarations inlined from stylesheets. Defaults to false.
+  pub preserve_important: bool,
+  /// Remove class names that the remaining rules do not use. Defaults to false.
+  pub remove_classes: bool,
+  /// Remove ids that the remaining rules do not use. Defaults to false, since ids may also be
+  /// link targets.
+  pub remove_ids: bool,
+}
+
+fn local_name(handle: &Handle) -> Option<&str> {
+  match &handle.data {
+    NodeData::Element { name, .. } => Some(name.local.as_ref()),
+    _ => None,
+  }
+}
+
+/// The elements under `node` in tree order, not descending into skipped elements.
+fn elements(node: &Handle) -> Vec<Handle> {
+  let mut found = vec![];
+  let mut stack: Vec<Handle> = node.children.borrow().iter().rev().cloned().collect();
+  while let Some(node) = stack.pop() {
+    match local_name(&node) {
+      Some(name) if SKIPPED_ELEMENTS.contains(&name) => continue,
+      Some(_) => found.push(node.clone()),
+      None => {}
+    }
+    stack.extend(node.children.borrow().iter().rev().cloned());
+  }
+  found
+}
+
+/// The `<style>` elements under `node` that apply to screens.
+fn style_elements(node: &Handle) -> Vec<DomNode> {
+  let mut found = vec![];
+  let mut stack: Vec<Handle> = node.children.borrow().iter().rev().cloned().collect();
+  while let Some(node) = stack.pop() {
+    if local_name(&node) == Some("style") {
+      let element = DomNode(node.clone());
+      let is_css = element
+        .get_attribute("type".to_string())
+        .is_none_or(|kind| kind.is_empty() || kind.eq_ignore_ascii_case("text/css"));
+      let media = element
+        .get_attribute("media".to_string())
+        .is_none_or(|media| media_matches(&media));
+      if is_css && media {
+        found.push(element);
+      }
+      continue;
+    }
+    stack.extend(node.children.borrow().iter().rev().cloned());
+  }
+  found
+}
+
+/// Adds the class names and ids used by selectors to `classes` and `ids`.
+fn collect_selector_names(
+  input: &mut Parser,
+  classes: &mut HashSet<String>,
+  ids: &mut HashSet<String>,
+) {
+  let mut after_dot = false;
+  while let Ok(token) = input.next_including_whitespace() {
+    let token = token.clone();
+    match &token {
+      Token::Ident(name) if after_dot => {
+        classes.insert(name.to_string());
+      }
+      Token::IDHash(name) => {
+        ids.insert(name.to_string());
+      }
+      Token::Function(_) | Token::ParenthesisBlock => {
+        let _ = input.parse_nested_block(|input| {
+          collect_selector_names(input, classes, ids);
+          Ok::<_, ParseError<()>>(())
+        });
+      }
+      _ => {}
+    }
+    after_dot = matches!(token, Token::Delim('.'));
+  }
+}
+
+fn collect_rule_names(rules: &[CssRule], classes: &mut HashSet<String>, ids: &mut HashSet<String>) {
+  for rule in rules {
+    match rule {
+      CssRule::Style(rule) => {
+        let mut input = ParserInput::new(&rule.selector_text);
+        collect_selector_names(&mut Parser::new(&mut input), classes, ids);
+      }
+      CssRule::Group(group) => collect_rule_names(&group.rules, classes, ids),
+      CssRule::Other(_) => {}
+    }
+  }
+}
+
+/// Moves the style rules of a document's `<style>` elements into the `style` attributes of the
+/// elements they match, for mail clients that ignore stylesheets.
+///
+/// Declarations are merged by the cascade: `!important`, then the `style` attribute, then
+/// specificity and source order. Rules that cannot be inlined, such as `@media` and
+/// `@font-face` rules and selectors with pseudo-classes like `:hover` or pseudo-elements, are
+/// kept in their `<style>` element, which is removed once empty. `<style>` elements for other
+/// media, such as `media="print"`, are left alone.
+pub fn inline_css(document: &DomNode, options: &InlineOptions) {
+  let mut inlined: Vec<StyleRule> = vec![];
+  let mut classes = HashSet::new();
+  let mut ids = HashSet::new();
+  for element in style_elements(&document.0) {
+    let stylesheet = StyleSheet::parse(&element.text_content_getter());
+    let (inlinable, remaining): (Vec<CssRule>, Vec<CssRule>) =
+      stylesheet.rules.into_iter().partition(|rule| {
+        matches!(
+          rule,
+          CssRule::Style(StyleRule {
+            selectors: Some(_),
+            ..
+          })
+        )
+      });
+    inlined.extend(inlinable.into_iter().filter_map(|rule| match rule {
+      CssRule::Style(rule) => Some(rule),
+      _ => None,
+    }));
+    if remaining.is_empty() {
+      element.remove();
+    } else {
+      let remaining = StyleSheet { rules: remaining };
+      element.set_text_content(remaining.css_text());
+    }
+  }
+  // The classes and ids used by the remaining rules, including those for other media.
+  let mut stack = vec![document.0.clone()];
+  while let Some(node) = stack.pop() {
+    if local_name(&node) == Some("style") {
+      let stylesheet = StyleSheet::parse(&DomNode(node.clone()).text_content_getter());
+      collect_rule_names(&stylesheet.rules, &mut classes, &mut ids);
+    }
+    stack.extend(node.children.borrow().iter().cloned());
+  }
+
+  let mut caches = SelectorCaches::default();
+  let mut context = MatchingContext::new(
+    MatchingMode::Normal,
+    None,
+    &mut caches,
+    QuirksMode::NoQuirks,
+    NeedsSelectorFlags::No,
+    MatchingForInvalidation::No,
+  );
+  for handle in elements(&document.0) {
+    let element = DomNode(handle);
+    let inline = element.style();
+    let mut entries = vec![];
+    for rule in &inlined {
+      let Some(selectors) = &rule.selectors else {
+        continue;
+      };
+      if let Some(specificity) = matching_specificity(selectors, &element, &mut context) {
+        for declaration in rule.style.declarations() {
+          let priority = if options.preserve_important && declaration.important {
+            "important"
+          } else {
+            ""
+          };
+          entries.push((
+            precedence(Origin::Author, declaration.important),
+            specificity,
+            declaration,
+            priority,
+          ));
+        }
+      }
+    }
+    if !entries.is_empty() {
+      for declaration in inline.declarations() {
+        let priority = if declaration.important {
+          "important"
+        } else {
+          ""
+        };
+        entries.push((
+          precedence(Origin::Inline, declaration.important),
+          0,
+          declaration,
+          priority,
+        ));
+      }
+      // A stable sort, so that later declarations win among equal ones.
+      entries.sort_by_key(|(precedence, specificity, ..)| (*precedence, *specificity));
+      let mut style = CssStyleDeclaration::default();
+      for (_, _, declaration, priority) in entries {
+        style.set_property(&declaration.name, &declaration.value, priority);
+      }
+      element.set_style(&style);
+    }
+
+    if options.remove_classes {
+      if let Some(class_name) = element.get_attribute("class".to_string()) {
+        let kept: Vec<&str> = class_name
+          .split_ascii_whitespace()
+          .filter(|name| classes.contains(*name))
+          .collect();
+        if kept.is_empty() {
+          element.remove_attribute("class".to_string());
+        } else {
+          element.set_attribute("class".to_string(), kept.join(" "));
+        }
+      }
+    }
+    if options.remove_ids {
+      if let Some(id) = element.get_attribute("id".to_string()) {
+        if !ids.contains(&id) {
+          element.remove_attribute("id".to_string());
+        }
+      }
+    }
+  }
+}
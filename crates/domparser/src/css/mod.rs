@@ -0,0 +1,15 @@
+//! CSS declaration blocks, stylesheets, a cascade computing styles without layout, an inliner
+//! for email and unused CSS detection.
+
+mod cascade;
+mod coverage;
+mod declarations;
+mod inline;
+mod shorthands;
+mod stylesheet;
+
+pub use cascade::{compute_style, ComputedStyle, StyleResolver, VisibilityOptions};
+pub use coverage::{css_coverage, CssCoverage, RuleCoverage};
+pub use declarations::{CssDeclaration, CssStyleDeclaration};
+pub use inline::{inline_css, InlineOptions};
+pub use stylesheet::{CssRule, GroupRule, StyleRule, StyleSheet};
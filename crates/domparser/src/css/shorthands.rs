@@ -0,0 +1,866 @@
+use cssparser::{ParseError, Parser, ParserInput, Token};
+
+/// How the value of a shorthand maps to its longhands.
+enum Kind {
+  /// One to four values for the top, right, bottom and left longhands.
+  Box,
+  /// Like `Box`, with optional vertical radii after a `/`.
+  BorderRadius,
+  /// A width, style and color in any order, each optional.
+  Line,
+  /// A `Line` applied to all four sides, longhands grouped by width, style and color.
+  Border,
+  /// `none`, `auto`, or a grow and shrink factor and a basis.
+  Flex,
+  /// A direction and a wrap mode in any order, each optional.
+  FlexFlow,
+  /// One or two values, the first also used for the second if it is missing.
+  Pair,
+  /// Style, variant, weight and stretch in any order, then a size, an optional line height
+  /// after a `/`, and the family list.
+  Font,
+  /// Comma-separated layers of an image, a position with an optional size after a `/`, a
+  /// repeat style, an attachment and boxes in any order, the color only in the last layer.
+  Background,
+}
+
+pub(super) struct Shorthand {
+  pub name: &'static str,
+  pub longhands: &'static [&'static str],
+  kind: Kind,
+}
+
+/// The supported shorthands, those with the most longhands first.
+static SHORTHANDS: &[Shorthand] = &[
+  Shorthand {
+    name: "border",
+    longhands: &[
+      "border-top-width",
+      "border-right-width",
+      "border-bottom-width",
+      "border-left-width",
+      "border-top-style",
+      "border-right-style",
+      "border-bottom-style",
+      "border-left-style",
+      "border-top-color",
+      "border-right-color",
+      "border-bottom-color",
+      "border-left-color",
+    ],
+    kind: Kind::Border,
+  },
+  Shorthand {
+    name: "background",
+    longhands: &[
+      "background-image",
+      "background-position",
+      "background-size",
+      "background-repeat",
+      "background-attachment",
+      "background-origin",
+      "background-clip",
+      "background-color",
+    ],
+    kind: Kind::Background,
+  },
+  Shorthand {
+    name: "font",
+    longhands: &[
+      "font-style",
+      "font-variant",
+      "font-weight",
+      "font-stretch",
+      "font-size",
+      "line-height",
+      "font-family",
+    ],
+    kind: Kind::Font,
+  },
+  Shorthand {
+    name: "margin",
+    longhands: &["margin-top", "margin-right", "margin-bottom", "margin-left"],
+    kind: Kind::Box,
+  },
+  Shorthand {
+    name: "padding",
+    longhands: &[
+      "padding-top",
+      "padding-right",
+      "padding-bottom",
+      "padding-left",
+    ],
+    kind: Kind::Box,
+  },
+  Shorthand {
+    name: "inset",
+    longhands: &["top", "right", "bottom", "left"],
+    kind: Kind::Box,
+  },
+  Shorthand {
+    name: "border-width",
+    longhands: &[
+      "border-top-width",
+      "border-right-width",
+      "border-bottom-width",
+      "border-left-width",
+    ],
+    kind: Kind::Box,
+  },
+  Shorthand {
+    name: "border-style",
+    longhands: &[
+      "border-top-style",
+      "border-right-style",
+      "border-bottom-style",
+      "border-left-style",
+    ],
+    kind: Kind::Box,
+  },
+  Shorthand {
+    name: "border-color",
+    longhands: &[
+      "border-top-color",
+      "border-right-color",
+      "border-bottom-color",
+      "border-left-color",
+    ],
+    kind: Kind::Box,
+  },
+  Shorthand {
+    name: "border-radius",
+    longhands: &[
+      "border-top-left-radius",
+      "border-top-right-radius",
+      "border-bottom-right-radius",
+      "border-bottom-left-radius",
+    ],
+    kind: Kind::BorderRadius,
+  },
+  Shorthand {
+    name: "border-top",
+    longhands: &["border-top-width", "border-top-style", "border-top-color"],
+    kind: Kind::Line,
+  },
+  Shorthand {
+    name: "border-right",
+    longhands: &[
+      "border-right-width",
+      "border-right-style",
+      "border-right-color",
+    ],
+    kind: Kind::Line,
+  },
+  Shorthand {
+    name: "border-bottom",
+    longhands: &[
+      "border-bottom-width",
+      "border-bottom-style",
+      "border-bottom-color",
+    ],
+    kind: Kind::Line,
+  },
+  Shorthand {
+    name: "border-left",
+    longhands: &[
+      "border-left-width",
+      "border-left-style",
+      "border-left-color",
+    ],
+    kind: Kind::Line,
+  },
+  Shorthand {
+    name: "outline",
+    longhands: &["outline-width", "outline-style", "outline-color"],
+    kind: Kind::Line,
+  },
+  Shorthand {
+    name: "flex",
+    longhands: &["flex-grow", "flex-shrink", "flex-basis"],
+    kind: Kind::Flex,
+  },
+  Shorthand {
+    name: "flex-flow",
+    longhands: &["flex-direction", "flex-wrap"],
+    kind: Kind::FlexFlow,
+  },
+  Shorthand {
+    name: "gap",
+    longhands: &["row-gap", "column-gap"],
+    kind: Kind::Pair,
+  },
+  Shorthand {
+    name: "overflow",
+    longhands: &["overflow-x", "overflow-y"],
+    kind: Kind::Pair,
+  },
+];
+
+pub(super) const CSS_WIDE_KEYWORDS: &[&str] =
+  &["initial", "inherit", "unset", "revert", "revert-layer"];
+
+const LINE_STYLES: &[&str] = &[
+  "none", "hidden", "dotted", "dashed", "solid", "double", "groove", "ridge", "inset", "outset",
+];
+
+const LINE_INITIAL: [&str; 3] = ["medium", "none", "currentcolor"];
+
+const FLEX_DIRECTIONS: &[&str] = &["row", "row-reverse", "column", "column-reverse"];
+
+const FLEX_WRAPS: &[&str] = &["nowrap", "wrap", "wrap-reverse"];
+
+const FONT_STRETCHES: &[&str] = &[
+  "ultra-condensed",
+  "extra-condensed",
+  "condensed",
+  "semi-condensed",
+  "semi-expanded",
+  "expanded",
+  "extra-expanded",
+  "ultra-expanded",
+];
+
+const FONT_SIZES: &[&str] = &[
+  "xx-small",
+  "x-small",
+  "small",
+  "medium",
+  "large",
+  "x-large",
+  "xx-large",
+  "xxx-large",
+  "larger",
+  "smaller",
+];
+
+/// Keywords that set `font` to a system font. They can only be the whole value.
+pub(super) const FONT_SYSTEM_FONTS: &[&str] = &[
+  "caption",
+  "icon",
+  "menu",
+  "message-box",
+  "small-caption",
+  "status-bar",
+];
+
+/// The initial style, variant, weight and stretch of `font`.
+const FONT_INITIAL: [&str; 4] = ["normal", "normal", "normal", "normal"];
+
+const BACKGROUND_POSITIONS: &[&str] = &["left", "right", "top", "bottom", "center"];
+
+const BACKGROUND_REPEATS: &[&str] = &["repeat", "space", "round", "no-repeat"];
+
+const BACKGROUND_BOXES: &[&str] = &["border-box", "padding-box", "content-box"];
+
+/// The initial image, position, size, repeat, attachment, origin, clip and color of `background`.
+const BACKGROUND_INITIAL: [&str; 8] = [
+  "none",
+  "0% 0%",
+  "auto",
+  "repeat",
+  "scroll",
+  "padding-box",
+  "border-box",
+  "transparent",
+];
+
+pub(super) fn shorthand(name: &str) -> Option<&'static Shorthand> {
+  SHORTHANDS.iter().find(|shorthand| shorthand.name == name)
+}
+
+/// The shorthands `longhand` belongs to, those with the most longhands first.
+pub(super) fn shorthands_of(longhand: &str) -> impl Iterator<Item = &'static Shorthand> + '_ {
+  SHORTHANDS
+    .iter()
+    .filter(move |shorthand| shorthand.longhands.contains(&longhand))
+}
+
+/// Splits a value into its top-level component values. `/` and `,` are components of their own.
+fn tokenize(value: &str) -> Vec<String> {
+  let mut input = ParserInput::new(value);
+  let mut parser = Parser::new(&mut input);
+  let mut components = Vec::new();
+  loop {
+    parser.skip_whitespace();
+    let start = parser.position();
+    let Ok(token) = parser.next() else {
+      break;
+    };
+    match token {
+      Token::Function(_)
+      | Token::ParenthesisBlock
+      | Token::SquareBracketBlock
+      | Token::CurlyBracketBlock => {
+        // Only consumes the block, its contents are kept as written.
+        let _ = parser.parse_nested_block(|_| Ok::<_, ParseError<()>>(()));
+      }
+      _ => {}
+    }
+    components.push(parser.slice_from(start).to_string());
+  }
+  components
+}
+
+/// Splits a value into its top-level component values like [`tokenize`].
+/// Returns `None` for comma-separated values, which only `font` and `background` take.
+fn components(value: &str) -> Option<Vec<String>> {
+  let components = tokenize(value);
+  (!components.iter().any(|component| component == ",")).then_some(components)
+}
+
+/// Joins components back into a value, with a single space after commas.
+fn join(components: &[String]) -> String {
+  let mut value = String::new();
+  for component in components {
+    if component != "," && !value.is_empty() {
+      value.push(' ');
+    }
+    value.push_str(component);
+  }
+  value
+}
+
+/// Splits a value at its top-level commas.
+fn layers(value: &str) -> Vec<Vec<String>> {
+  tokenize(value)
+    .split(|component| component == ",")
+    .map(|layer| layer.to_vec())
+    .collect()
+}
+
+fn is_keyword(value: &str, keywords: &[&str]) -> bool {
+  keywords
+    .iter()
+    .any(|keyword| value.eq_ignore_ascii_case(keyword))
+}
+
+/// Whether a value is a number, a dimension or a percentage, or a math function.
+fn is_numeric(value: &str) -> bool {
+  let lower = value.to_ascii_lowercase();
+  let mut chars = lower.chars();
+  let numeric = match chars.next() {
+    Some('0'..='9' | '.') => true,
+    Some('+' | '-') => matches!(chars.next(), Some('0'..='9' | '.')),
+    _ => false,
+  };
+  numeric
+    || ["calc(", "min(", "max(", "clamp("]
+      .iter()
+      .any(|function| lower.starts_with(function))
+}
+
+fn is_line_width(value: &str) -> bool {
+  is_numeric(value) || is_keyword(value, &["thin", "medium", "thick"])
+}
+
+/// Expands the top, right, bottom and left values of one to four components.
+fn expand_box(components: &[String]) -> Option<Vec<String>> {
+  let [top, right, bottom, left] = match components {
+    [all] => [all, all, all, all],
+    [vertical, horizontal] => [vertical, horizontal, vertical, horizontal],
+    [top, horizontal, bottom] => [top, horizontal, bottom, horizontal],
+    [top, right, bottom, left] => [top, right, bottom, left],
+    _ => return None,
+  };
+  if components.iter().any(|component| component == "/") {
+    return None;
+  }
+  Some(vec![
+    top.clone(),
+    right.clone(),
+    bottom.clone(),
+    left.clone(),
+  ])
+}
+
+/// The shortest list of components that expands to the top, right, bottom and left values.
+fn serialize_box(values: &[&str]) -> String {
+  let [top, right, bottom, left] = [values[0], values[1], values[2], values[3]];
+  let components: &[&str] = if left != right {
+    &[top, right, bottom, left]
+  } else if bottom != top {
+    &[top, right, bottom]
+  } else if right != top {
+    &[top, right]
+  } else {
+    &[top]
+  };
+  components.join(" ")
+}
+
+/// Expands a width, style and color given in any order.
+fn expand_line(components: &[String]) -> Option<Vec<String>> {
+  let mut values: [Option<String>; 3] = Default::default();
+  for component in components {
+    let slot = if is_keyword(component, LINE_STYLES) {
+      1
+    } else if is_line_width(component) {
+      0
+    } else {
+      2
+    };
+    if component == "/" || values[slot].is_some() {
+      return None;
+    }
+    values[slot] = Some(component.clone());
+  }
+  if components.is_empty() {
+    return None;
+  }
+  Some(
+    values
+      .into_iter()
+      .zip(LINE_INITIAL)
+      .map(|(value, initial)| value.unwrap_or_else(|| initial.to_string()))
+      .collect(),
+  )
+}
+
+/// Serializes a width, style and color, leaving out initial values.
+fn serialize_line(values: &[&str]) -> String {
+  let components: Vec<&str> = values
+    .iter()
+    .zip(LINE_INITIAL)
+    .filter(|(value, initial)| !value.eq_ignore_ascii_case(initial))
+    .map(|(value, _)| *value)
+    .collect();
+  if components.is_empty() {
+    "none".to_string()
+  } else {
+    components.join(" ")
+  }
+}
+
+fn expand_border_radius(components: &[String]) -> Option<Vec<String>> {
+  let (horizontal, vertical) = match components.iter().position(|component| component == "/") {
+    Some(slash) => (
+      expand_box(&components[..slash])?,
+      expand_box(&components[slash + 1..])?,
+    ),
+    None => (expand_box(components)?, expand_box(components)?),
+  };
+  // The box order of border-radius starts at the top left corner.
+  Some(
+    horizontal
+      .into_iter()
+      .zip(vertical)
+      .map(|(horizontal, vertical)| {
+        if horizontal == vertical {
+          horizontal
+        } else {
+          format!("{} {}", horizontal, vertical)
+        }
+      })
+      .collect(),
+  )
+}
+
+fn serialize_border_radius(values: &[&str]) -> Option<String> {
+  let mut horizontal = Vec::new();
+  let mut vertical = Vec::new();
+  for value in values {
+    match value.split(' ').collect::<Vec<_>>()[..] {
+      [both] => {
+        horizontal.push(both);
+        vertical.push(both);
+      }
+      [h, v] => {
+        horizontal.push(h);
+        vertical.push(v);
+      }
+      _ => return None,
+    }
+  }
+  let horizontal = serialize_box(&horizontal);
+  let vertical = serialize_box(&vertical);
+  if horizontal == vertical {
+    Some(horizontal)
+  } else {
+    Some(format!("{} / {}", horizontal, vertical))
+  }
+}
+
+fn is_flex_factor(value: &str) -> bool {
+  value.parse::<f64>().is_ok_and(|factor| factor >= 0.0)
+}
+
+fn expand_flex(components: &[String]) -> Option<Vec<String>> {
+  let components: Vec<&str> = components.iter().map(String::as_str).collect();
+  let values = match components[..] {
+    [keyword] if keyword.eq_ignore_ascii_case("none") => ["0", "0", "auto"],
+    [keyword] if keyword.eq_ignore_ascii_case("auto") => ["1", "1", "auto"],
+    [grow] if is_flex_factor(grow) => [grow, "1", "0%"],
+    [basis] => ["1", "1", basis],
+    [grow, shrink] if is_flex_factor(grow) && is_flex_factor(shrink) => [grow, shrink, "0%"],
+    [grow, basis] if is_flex_factor(grow) => [grow, "1", basis],
+    [basis, grow] if is_flex_factor(grow) => [grow, "1", basis],
+    [grow, shrink, basis] if is_flex_factor(grow) && is_flex_factor(shrink) => {
+      [grow, shrink, basis]
+    }
+    [basis, grow, shrink] if is_flex_factor(grow) && is_flex_factor(shrink) => {
+      [grow, shrink, basis]
+    }
+    _ => return None,
+  };
+  if values.contains(&"/") {
+    return None;
+  }
+  Some(values.iter().map(|value| value.to_string()).collect())
+}
+
+fn expand_flex_flow(components: &[String]) -> Option<Vec<String>> {
+  let mut values: [Option<String>; 2] = Default::default();
+  for component in components {
+    let slot = if is_keyword(component, FLEX_DIRECTIONS) {
+      0
+    } else if is_keyword(component, FLEX_WRAPS) {
+      1
+    } else {
+      return None;
+    };
+    if values[slot].is_some() {
+      return None;
+    }
+    values[slot] = Some(component.to_ascii_lowercase());
+  }
+  if components.is_empty() {
+    return None;
+  }
+  let [direction, wrap] = values;
+  Some(vec![
+    direction.unwrap_or_else(|| "row".to_string()),
+    wrap.unwrap_or_else(|| "nowrap".to_string()),
+  ])
+}
+
+fn serialize_flex_flow(values: &[&str]) -> String {
+  match values {
+    [direction, wrap] if wrap.eq_ignore_ascii_case("nowrap") => direction.to_string(),
+    [direction, wrap] if direction.eq_ignore_ascii_case("row") => wrap.to_string(),
+    _ => values.join(" "),
+  }
+}
+
+/// Expands a `font` value. A system font keyword such as `caption` is stored in every
+/// longhand, since the fonts it stands for are not known.
+fn expand_font(components: &[String]) -> Option<Vec<String>> {
+  if let [keyword] = components {
+    if is_keyword(keyword, FONT_SYSTEM_FONTS) {
+      return Some(vec![keyword.to_ascii_lowercase(); FONT_INITIAL.len() + 3]);
+    }
+  }
+  let mut values: [Option<String>; 4] = Default::default();
+  let mut normals = 0;
+  let mut rest = components;
+  let size = loop {
+    let (component, tail) = rest.split_first()?;
+    rest = tail;
+    let slot = if component.eq_ignore_ascii_case("normal") {
+      normals += 1;
+      None
+    } else if is_keyword(component, &["italic", "oblique"]) {
+      Some(0)
+    } else if component.eq_ignore_ascii_case("small-caps") {
+      Some(1)
+    } else if is_keyword(component, &["bold", "bolder", "lighter"])
+      || component
+        .parse::<f64>()
+        .is_ok_and(|weight| (1.0..=1000.0).contains(&weight))
+    {
+      Some(2)
+    } else if is_keyword(component, FONT_STRETCHES) {
+      Some(3)
+    } else if is_keyword(component, FONT_SIZES) || is_numeric(component) {
+      break component.clone();
+    } else {
+      return None;
+    };
+    if let Some(slot) = slot {
+      if values[slot].is_some() {
+        return None;
+      }
+      values[slot] = Some(component.to_ascii_lowercase());
+    }
+    if normals + values.iter().flatten().count() > 4 {
+      return None;
+    }
+  };
+  let line_height = match rest {
+    [slash, line_height, tail @ ..] if slash == "/" => {
+      rest = tail;
+      line_height.clone()
+    }
+    _ => "normal".to_string(),
+  };
+  if rest.is_empty() || rest.iter().any(|component| component == "/") {
+    return None;
+  }
+  let mut expanded: Vec<String> = values
+    .into_iter()
+    .zip(FONT_INITIAL)
+    .map(|(value, initial)| value.unwrap_or_else(|| initial.to_string()))
+    .collect();
+  expanded.extend([size, line_height, join(rest)]);
+  Some(expanded)
+}
+
+/// Serializes the `font` longhands, or returns `None` if the variant or stretch can't be
+/// written in the shorthand or only some longhands hold a system font keyword.
+fn serialize_font(values: &[&str]) -> Option<String> {
+  let [style, variant, weight, stretch, size, line_height, family] = values else {
+    return None;
+  };
+  if is_keyword(style, FONT_SYSTEM_FONTS) {
+    return values
+      .iter()
+      .all(|value| value.eq_ignore_ascii_case(style))
+      .then(|| style.to_string());
+  }
+  if !is_keyword(variant, &["normal", "small-caps"])
+    || !(stretch.eq_ignore_ascii_case("normal") || is_keyword(stretch, FONT_STRETCHES))
+  {
+    return None;
+  }
+  let mut components: Vec<&str> = [*style, *variant, *weight, *stretch]
+    .into_iter()
+    .filter(|value| !value.eq_ignore_ascii_case("normal"))
+    .collect();
+  components.push(size);
+  if !line_height.eq_ignore_ascii_case("normal") {
+    components.extend(["/", line_height]);
+  }
+  components.push(family);
+  Some(components.join(" "))
+}
+
+/// Expands one layer of a `background` value into its eight longhand values.
+fn expand_background_layer(components: &[String], last: bool) -> Option<[String; 8]> {
+  let mut values: [Option<String>; 8] = Default::default();
+  let mut boxes = Vec::new();
+  let mut index = 0;
+  while index < components.len() {
+    let component = &components[index];
+    let lower = component.to_ascii_lowercase();
+    let is_position = |value: &String| is_keyword(value, BACKGROUND_POSITIONS) || is_numeric(value);
+    let slot = if lower == "none"
+      || ["url(", "image(", "image-set(", "cross-fade(", "element("]
+        .iter()
+        .any(|function| lower.starts_with(function))
+      || lower.contains("gradient(")
+    {
+      0
+    } else if is_position(component) {
+      let count = components[index..]
+        .iter()
+        .take_while(|value| is_position(value))
+        .count();
+      if count > 4 || values[1].is_some() {
+        return None;
+      }
+      values[1] = Some(join(&components[index..index + count]));
+      index += count;
+      if components.get(index).is_some_and(|value| value == "/") {
+        let count = components[index + 1..]
+          .iter()
+          .take_while(|value| is_keyword(value, &["auto", "cover", "contain"]) || is_numeric(value))
+          .count();
+        if !(1..=2).contains(&count) {
+          return None;
+        }
+        values[2] = Some(join(&components[index + 1..index + 1 + count]));
+        index += 1 + count;
+      }
+      continue;
+    } else if is_keyword(&lower, &["repeat-x", "repeat-y"]) {
+      3
+    } else if is_keyword(&lower, BACKGROUND_REPEATS) {
+      let two = components
+        .get(index + 1)
+        .is_some_and(|next| is_keyword(next, BACKGROUND_REPEATS));
+      if values[3].is_some() {
+        return None;
+      }
+      let count = if two { 2 } else { 1 };
+      values[3] = Some(join(&components[index..index + count]).to_ascii_lowercase());
+      index += count;
+      continue;
+    } else if is_keyword(&lower, &["scroll", "fixed", "local"]) {
+      4
+    } else if is_keyword(&lower, BACKGROUND_BOXES) {
+      boxes.push(lower);
+      index += 1;
+      continue;
+    } else if last && component != "/" {
+      7
+    } else {
+      return None;
+    };
+    if values[slot].is_some() {
+      return None;
+    }
+    values[slot] = Some(component.clone());
+    index += 1;
+  }
+  match &boxes[..] {
+    [] => {}
+    [both] => {
+      values[5] = Some(both.clone());
+      values[6] = Some(both.clone());
+    }
+    [origin, clip] => {
+      values[5] = Some(origin.clone());
+      values[6] = Some(clip.clone());
+    }
+    _ => return None,
+  }
+  let mut expanded = BACKGROUND_INITIAL.map(str::to_string);
+  for (value, slot) in values.into_iter().zip(expanded.iter_mut()) {
+    if let Some(value) = value {
+      *slot = value;
+    }
+  }
+  Some(expanded)
+}
+
+/// Expands a `background` value, joining the values of each longhand across layers.
+fn expand_background(value: &str) -> Option<Vec<String>> {
+  let layers = layers(value);
+  let mut expanded: Vec<Vec<String>> = vec![Vec::new(); 8];
+  for (index, layer) in layers.iter().enumerate() {
+    let last = index == layers.len() - 1;
+    if layer.is_empty() {
+      return None;
+    }
+    let values = expand_background_layer(layer, last)?;
+    for (longhand, value) in expanded.iter_mut().zip(values) {
+      longhand.push(value);
+    }
+  }
+  let color = expanded[7].pop()?;
+  let mut values: Vec<String> = expanded[..7]
+    .iter()
+    .map(|layers| layers.join(", "))
+    .collect();
+  values.push(color);
+  Some(values)
+}
+
+/// Serializes the `background` longhands, or returns `None` if they don't have the same
+/// number of layers.
+fn serialize_background(values: &[&str]) -> Option<String> {
+  let layered: Vec<Vec<String>> = values[..7]
+    .iter()
+    .map(|value| layers(value).iter().map(|layer| join(layer)).collect())
+    .collect();
+  let count = layered[0].len();
+  if layered.iter().any(|layer| layer.len() != count) {
+    return None;
+  }
+  let color = values[7];
+  let serialized: Vec<String> = (0..count)
+    .map(|index| {
+      let [image, position, size, repeat, attachment, origin, clip] =
+        [0, 1, 2, 3, 4, 5, 6].map(|longhand| layered[longhand][index].as_str());
+      let is_initial = |value: &str, longhand: usize| value == BACKGROUND_INITIAL[longhand];
+      let mut components = Vec::new();
+      if !is_initial(image, 0) {
+        components.push(image);
+      }
+      if !is_initial(position, 1) || !is_initial(size, 2) {
+        components.push(position);
+      }
+      if !is_initial(size, 2) {
+        components.extend(["/", size]);
+      }
+      for (value, longhand) in [(repeat, 3), (attachment, 4)] {
+        if !is_initial(value, longhand) {
+          components.push(value);
+        }
+      }
+      if origin == clip {
+        components.push(origin);
+      } else if !is_initial(origin, 5) || !is_initial(clip, 6) {
+        components.extend([origin, clip]);
+      }
+      if index == count - 1 && !color.eq_ignore_ascii_case("transparent") {
+        components.push(color);
+      }
+      if components.is_empty() {
+        "none".to_string()
+      } else {
+        components.join(" ")
+      }
+    })
+    .collect();
+  Some(serialized.join(", "))
+}
+
+/// Returns the longhand values of a shorthand value, or `None` if it is not valid.
+pub(super) fn expand(shorthand: &Shorthand, value: &str) -> Option<Vec<String>> {
+  if is_keyword(value, CSS_WIDE_KEYWORDS) {
+    let keyword = value.to_ascii_lowercase();
+    return Some(vec![keyword; shorthand.longhands.len()]);
+  }
+  let split = || components(value);
+  match shorthand.kind {
+    Kind::Box => expand_box(&split()?),
+    Kind::BorderRadius => expand_border_radius(&split()?),
+    Kind::Line => expand_line(&split()?),
+    Kind::Border => {
+      let line = expand_line(&split()?)?;
+      Some(
+        line
+          .iter()
+          .flat_map(|value| std::iter::repeat_n(value.clone(), 4))
+          .collect(),
+      )
+    }
+    Kind::Flex => expand_flex(&split()?),
+    Kind::FlexFlow => expand_flex_flow(&split()?),
+    Kind::Pair => match &split()?[..] {
+      [both] if both != "/" => Some(vec![both.clone(), both.clone()]),
+      [first, second] if first != "/" && second != "/" => Some(vec![first.clone(), second.clone()]),
+      _ => None,
+    },
+    Kind::Font => expand_font(&tokenize(value)),
+    Kind::Background => expand_background(value),
+  }
+}
+
+/// Serializes the longhand values of a shorthand, or returns `None` if the shorthand can't
+/// represent them.
+pub(super) fn serialize(shorthand: &Shorthand, values: &[&str]) -> Option<String> {
+  if values
+    .iter()
+    .any(|value| is_keyword(value, CSS_WIDE_KEYWORDS))
+  {
+    let first = values[0];
+    return values
+      .iter()
+      .all(|value| value.eq_ignore_ascii_case(first))
+      .then(|| first.to_string());
+  }
+  match shorthand.kind {
+    Kind::Box => Some(serialize_box(values)),
+    Kind::BorderRadius => serialize_border_radius(values),
+    Kind::Line => Some(serialize_line(values)),
+    Kind::Border => {
+      let sides: Vec<[&str; 3]> = (0..4)
+        .map(|side| [values[side], values[side + 4], values[side + 8]])
+        .collect();
+      sides
+        .iter()
+        .all(|side| side == &sides[0])
+        .then(|| serialize_line(&sides[0]))
+    }
+    Kind::Flex => Some(values.join(" ")),
+    Kind::FlexFlow => Some(serialize_flex_flow(values)),
+    Kind::Pair => Some(if values[0] == values[1] {
+      values[0].to_string()
+    } else {
+      values.join(" ")
+    }),
+    Kind::Font => serialize_font(values),
+    Kind::Background => serialize_background(values),
+  }
+}
@@ -0,0 +1,177 @@
+use cssparser::{
+  AtRuleParser, CowRcStr, ParseError, Parser, ParserInput, ParserState, QualifiedRuleParser,
+  StyleSheetParser,
+};
+use selectors::SelectorList;
+
+use super::CssStyleDeclaration;
+use crate::node::selectors::{parse_selectors, DomParserSelectors};
+
+/// The at-rules whose block holds rules, kept as [`CssRule::Group`].
+const GROUP_RULES: &[&str] = &["media", "supports", "layer", "container"];
+
+/// A style rule, such as `h1, h2 { margin: 0 }`.
+#[derive(Clone, Debug)]
+pub struct StyleRule {
+  pub selector_text: String,
+  /// The parsed selectors, or `None` if a selector is not supported, for instance one using
+  /// `:hover` or a pseudo-element.
+  pub selectors: Option<SelectorList<DomParserSelectors>>,
+  pub style: CssStyleDeclaration,
+}
+
+/// A conditional or layer at-rule holding other rules, such as `@media print { ... }`.
+#[derive(Clone, Debug)]
+pub struct GroupRule {
+  /// The at-keyword without `@`, ASCII-lowercased.
+  pub name: String,
+  /// The text between the at-keyword and the block, trimmed.
+  pub prelude: String,
+  pub rules: Vec<CssRule>,
+}
+
+#[derive(Clone, Debug)]
+pub enum CssRule {
+  Style(StyleRule),
+  Group(GroupRule),
+  /// Any other at-rule, such as `@font-face` or `@import`, kept as its source text.
+  Other(String),
+}
+
+/// A parsed stylesheet. Invalid rules are skipped.
+#[derive(Clone, Debug, Default)]
+pub struct StyleSheet {
+  pub rules: Vec<CssRule>,
+}
+
+/// Consumes the rest of `input`, returning its source text.
+pub(super) fn rest<'i>(input: &mut Parser<'i, '_>) -> &'i str {
+  let start = input.position();
+  while input.next_including_whitespace_and_comments().is_ok() {}
+  input.slice_from(start)
+}
+
+fn parse_rules(input: &mut Parser) -> Vec<CssRule> {
+  StyleSheetParser::new(input, &mut RuleParser)
+    .filter_map(Result::ok)
+    .collect()
+}
+
+struct RuleParser;
+
+impl<'i> QualifiedRuleParser<'i> for RuleParser {
+  type Prelude = String;
+  type QualifiedRule = CssRule;
+  type Error = ();
+
+  fn parse_prelude<'t>(
+    &mut self,
+    input: &mut Parser<'i, 't>,
+  ) -> Result<String, ParseError<'i, ()>> {
+    Ok(rest(input).trim().to_string())
+  }
+
+  fn parse_block<'t>(
+    &mut self,
+    selector_text: String,
+    _start: &ParserState,
+    input: &mut Parser<'i, 't>,
+  ) -> Result<CssRule, ParseError<'i, ()>> {
+    Ok(CssRule::Style(StyleRule {
+      selectors: parse_selectors(&selector_text),
+      selector_text,
+      style: CssStyleDeclaration::parse(rest(input)),
+    }))
+  }
+}
+
+impl<'i> AtRuleParser<'i> for RuleParser {
+  type Prelude = (String, String);
+  type AtRule = CssRule;
+  type Error = ();
+
+  fn parse_prelude<'t>(
+    &mut self,
+    name: CowRcStr<'i>,
+    input: &mut Parser<'i, 't>,
+  ) -> Result<(String, String), ParseError<'i, ()>> {
+    Ok((name.to_string(), rest(input).trim().to_string()))
+  }
+
+  fn rule_without_block(
+    &mut self,
+    (name, prelude): (String, String),
+    _start: &ParserState,
+  ) -> Result<CssRule, ()> {
+    Ok(CssRule::Other(format!("@{} {};", name, prelude)))
+  }
+
+  fn parse_block<'t>(
+    &mut self,
+    (name, prelude): (String, String),
+    _start: &ParserState,
+    input: &mut Parser<'i, 't>,
+  ) -> Result<CssRule, ParseError<'i, ()>> {
+    let lower = name.to_ascii_lowercase();
+    if GROUP_RULES.contains(&lower.as_str()) {
+      return Ok(CssRule::Group(GroupRule {
+        name: lower,
+        prelude,
+        rules: parse_rules(input),
+      }));
+    }
+    let separator = if prelude.is_empty() { "" } else { " " };
+    Ok(CssRule::Other(format!(
+      "@{}{}{} {{{}}}",
+      name,
+      separator,
+      prelude,
+      rest(input)
+    )))
+  }
+}
+
+fn block(body: &str) -> String {
+  if body.is_empty() {
+    "{}".to_string()
+  } else {
+    format!("{{ {} }}", body)
+  }
+}
+
+impl CssRule {
+  /// Serializes the rule on one line, with its declarations normalized.
+  pub fn css_text(&self) -> String {
+    match self {
+      CssRule::Style(rule) => format!("{} {}", rule.selector_text, block(&rule.style.css_text())),
+      CssRule::Group(group) => {
+        let separator = if group.prelude.is_empty() { "" } else { " " };
+        let rules: Vec<String> = group.rules.iter().map(CssRule::css_text).collect();
+        format!(
+          "@{}{}{} {}",
+          group.name,
+          separator,
+          group.prelude,
+          block(&rules.join(" "))
+        )
+      }
+      CssRule::Other(text) => text.clone(),
+    }
+  }
+}
+
+impl StyleSheet {
+  pub fn parse(css: &str) -> Self {
+    let mut input = ParserInput::new(css);
+    let mut parser = Parser::new(&mut input);
+    StyleSheet {
+      rules: parse_rules(&mut parser),
+    }
+  }
+
+  /// Serializes the rules, one per line.
+  pub fn css_text(&self) -> String {
+    let rules: Vec<String> = self.rules.iter().map(CssRule::css_text).collect();
+    rules.join("\n")
+  }
+}
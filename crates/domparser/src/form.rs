@@ -0,0 +1,383 @@
+//! The name/value pairs a form submits and their encodings.
+//!
+//! [`form_entries`] follows the HTML "constructing the entry list" algorithm over the form's
+//! controls, as a browser would when submitting it with a given submitter. [`submit_form`]
+//! also resolves the form's `action`, `method` and `enctype`, which the submitter may
+//! override, and encodes the entries as `application/x-www-form-urlencoded`,
+//! `multipart/form-data` or `text/plain`.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use html5ever::ns;
+use markup5ever_rcdom::NodeData;
+use url::form_urlencoded;
+use url::Url;
+
+use crate::node::DomNode;
+
+pub const URLENCODED: &str = "application/x-www-form-urlencoded";
+pub const MULTIPART: &str = "multipart/form-data";
+pub const TEXT_PLAIN: &str = "text/plain";
+
+/// An entry of a form's entry list.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormEntry {
+  pub name: String,
+  pub value: String,
+  /// The file name of a file entry. The tree has no selected files, so a file input submits
+  /// one empty file with an empty name.
+  pub filename: Option<String>,
+}
+
+impl FormEntry {
+  fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+    Self {
+      name: name.into(),
+      value: value.into(),
+      filename: None,
+    }
+  }
+}
+
+/// Options for [`submit_form`].
+#[derive(Clone, Debug, Default)]
+pub struct SubmitOptions {
+  /// The button the form is submitted with. Defaults to the form's default button, unless
+  /// that button is disabled.
+  pub submitter: Option<DomNode>,
+  /// The coordinates clicked on an image button submitter. Defaults to (0, 0).
+  pub coordinates: (i32, i32),
+  /// The boundary of a `multipart/form-data` body. Defaults to a random one.
+  pub boundary: Option<String>,
+}
+
+/// The request a browser would make to submit a form, returned by [`submit_form`].
+#[derive(Clone, Debug)]
+pub struct FormSubmission {
+  /// The URL to submit to. For the `get` method, the entries are the query of `http(s)` and
+  /// `data` URLs and the headers of `mailto` URLs.
+  pub url: String,
+  /// `"get"`, `"post"` or `"dialog"`.
+  pub method: String,
+  /// The encoding of the entries for the `post` method.
+  pub enctype: String,
+  pub entries: Vec<FormEntry>,
+  /// The request body, for the `post` method.
+  pub body: Option<String>,
+  /// The `Content-Type` of the body, with its boundary for `multipart/form-data`.
+  pub content_type: Option<String>,
+}
+
+fn local_name(node: &DomNode) -> Option<&str> {
+  match &node.0.data {
+    NodeData::Element { name, .. } if name.ns == ns!(html) => Some(name.local.as_ref()),
+    _ => None,
+  }
+}
+
+/// Returns whether `node` is inside a `datalist`, whose controls are never submitted.
+fn in_datalist(node: &DomNode) -> bool {
+  let mut current = node.parent_element();
+  while let Some(element) = current {
+    if local_name(&element) == Some("datalist") {
+      return true;
+    }
+    current = element.parent_element();
+  }
+  false
+}
+
+/// The directionality of a text control, for its `dirname` entry.
+fn directionality(element: &DomNode, value: &str) -> &'static str {
+  let mut current = Some(element.clone());
+  while let Some(node) = current {
+    match node.dir().as_deref() {
+      Some("ltr") => return "ltr",
+      Some("rtl") => return "rtl",
+      Some("auto") => {
+        // The direction of the first strong character of the value.
+        let text = if node.is_same_node(element) {
+          value.to_string()
+        } else {
+          node.text_content_getter()
+        };
+        let is_rtl = text
+          .chars()
+          .find(|c| c.is_alphabetic())
+          .is_some_and(|c| matches!(c as u32, 0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF));
+        return if is_rtl { "rtl" } else { "ltr" };
+      }
+      _ => current = node.parent_element(),
+    }
+  }
+  "ltr"
+}
+
+/// Returns the default button of a `form`: its first submit button in tree order.
+pub fn default_button(form: &DomNode) -> Option<DomNode> {
+  form
+    .form_associated_elements()?
+    .into_iter()
+    .find(|element| element.is_submit_button())
+}
+
+/// Constructs the entry list of a `form` submitted with `submitter`, which must be one of its
+/// submit buttons. `coordinates` are those clicked on an image button submitter.
+///
+/// Disabled controls, controls in a `datalist`, unchecked checkboxes and radio buttons,
+/// buttons other than the submitter and controls without a name are skipped. The tree has
+/// no form state, so values come from the markup, as [`DomNode::value`] returns them.
+pub fn form_entries(
+  form: &DomNode,
+  submitter: Option<&DomNode>,
+  coordinates: (i32, i32),
+) -> Result<Vec<FormEntry>, String> {
+  let Some(controls) = form.form_associated_elements() else {
+    return Err("The node is not a form element.".to_string());
+  };
+  if let Some(submitter) = submitter {
+    if !submitter.is_submit_button() {
+      return Err("The submitter is not a submit button.".to_string());
+    }
+    if !submitter
+      .form()
+      .is_some_and(|owner| owner.is_same_node(form))
+    {
+      return Err("The submitter is not owned by this form.".to_string());
+    }
+  }
+
+  let mut entries = vec![];
+  for control in controls {
+    if in_datalist(&control) || control.is_disabled() {
+      continue;
+    }
+    let is_submitter = submitter.is_some_and(|submitter| submitter.is_same_node(&control));
+    let tag = local_name(&control).unwrap_or_default();
+    let input_type = control.input_type();
+    let is_button = tag == "button"
+      || matches!(
+        input_type.as_deref(),
+        Some("submit" | "image" | "reset" | "button")
+      );
+    if (is_button && !is_submitter) || matches!(tag, "fieldset" | "object" | "output") {
+      continue;
+    }
+    if matches!(input_type.as_deref(), Some("checkbox" | "radio"))
+      && control.checked() != Some(true)
+    {
+      continue;
+    }
+    let name = control
+      .get_attribute("name".to_string())
+      .unwrap_or_default();
+
+    if input_type.as_deref() == Some("image") {
+      let (x, y) = coordinates;
+      let prefix = if name.is_empty() {
+        String::new()
+      } else {
+        format!("{}.", name)
+      };
+      entries.push(FormEntry::new(format!("{}x", prefix), x.to_string()));
+      entries.push(FormEntry::new(format!("{}y", prefix), y.to_string()));
+      continue;
+    }
+    if name.is_empty() {
+      continue;
+    }
+
+    match (tag, input_type.as_deref()) {
+      ("select", _) => {
+        for option in control.selected_options() {
+          let disabled = option.has_attribute("disabled".to_string())
+            || option.parent_element().is_some_and(|parent| {
+              local_name(&parent) == Some("optgroup")
+                && parent.has_attribute("disabled".to_string())
+            });
+          if !disabled {
+            entries.push(FormEntry::new(&name, option.value().unwrap_or_default()));
+          }
+        }
+      }
+      (_, Some("file")) => entries.push(FormEntry {
+        name: name.clone(),
+        value: String::new(),
+        filename: Some(String::new()),
+      }),
+      (_, Some("hidden")) if name.eq_ignore_ascii_case("_charset_") => {
+        entries.push(FormEntry::new(&name, "UTF-8"))
+      }
+      _ => {
+        let value = control.value().unwrap_or_default();
+        // The direction of text controls is submitted under their `dirname`.
+        let dirname = control
+          .get_attribute("dirname".to_string())
+          .filter(|dirname| !dirname.is_empty());
+        let is_text_control = tag == "textarea"
+          || matches!(
+            input_type.as_deref(),
+            Some("text" | "search" | "tel" | "url" | "email" | "password" | "hidden" | "submit")
+          );
+        let direction = directionality(&control, &value);
+        entries.push(FormEntry::new(&name, value));
+        if let Some(dirname) = dirname.filter(|_| is_text_control) {
+          entries.push(FormEntry::new(dirname, direction));
+        }
+      }
+    }
+  }
+  Ok(entries)
+}
+
+/// Replaces every CR not followed by LF and every LF not preceded by CR with CRLF.
+fn normalize_newlines(value: &str) -> String {
+  value
+    .replace("\r\n", "\n")
+    .replace('\r', "\n")
+    .replace('\n', "\r\n")
+}
+
+/// The name and value of an entry as submitted in a text encoding: a file submits its name.
+fn text_pair(entry: &FormEntry) -> (String, String) {
+  let value = entry.filename.as_deref().unwrap_or(&entry.value);
+  (normalize_newlines(&entry.name), normalize_newlines(value))
+}
+
+/// Encodes entries as `application/x-www-form-urlencoded`.
+pub fn encode_urlencoded(entries: &[FormEntry]) -> String {
+  let mut serializer = form_urlencoded::Serializer::new(String::new());
+  for entry in entries {
+    let (name, value) = text_pair(entry);
+    serializer.append_pair(&name, &value);
+  }
+  serializer.finish()
+}
+
+/// Encodes entries as `text/plain`, one `name=value` line each.
+pub fn encode_text_plain(entries: &[FormEntry]) -> String {
+  entries
+    .iter()
+    .map(|entry| {
+      let (name, value) = text_pair(entry);
+      format!("{}={}\r\n", name, value)
+    })
+    .collect()
+}
+
+/// Escapes a name or file name for a `Content-Disposition` header.
+fn escape_multipart(value: &str) -> String {
+  normalize_newlines(value)
+    .replace('\n', "%0A")
+    .replace('\r', "%0D")
+    .replace('"', "%22")
+}
+
+/// Encodes entries as `multipart/form-data` with `boundary`.
+pub fn encode_multipart(entries: &[FormEntry], boundary: &str) -> String {
+  let mut body = String::new();
+  for entry in entries {
+    body.push_str(&format!(
+      "--{}\r\nContent-Disposition: form-data; name=\"{}\"",
+      boundary,
+      escape_multipart(&entry.name)
+    ));
+    match &entry.filename {
+      Some(filename) => body.push_str(&format!(
+        "; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n{}\r\n",
+        escape_multipart(filename),
+        entry.value
+      )),
+      None => body.push_str(&format!("\r\n\r\n{}\r\n", normalize_newlines(&entry.value))),
+    }
+  }
+  body.push_str(&format!("--{}--\r\n", boundary));
+  body
+}
+
+fn random_boundary() -> String {
+  let mut hasher = RandomState::new().build_hasher();
+  hasher.write_u8(0);
+  format!("----formdata-domparser-{:016x}", hasher.finish())
+}
+
+/// Builds the request a browser would make to submit a `form`.
+///
+/// The submitter's `formaction`, `formmethod` and `formenctype` override the form's `action`,
+/// `method` and `enctype`. With the `get` method the entries replace the query of `http`,
+/// `https` and `data` URLs, whatever the `enctype`, and are written as headers of `mailto`
+/// URLs; other URLs, such as `javascript:` ones, are kept as they are. With `post` the entries
+/// are encoded into the body. Returns an error if the action is not a valid URL, which aborts
+/// the submission in browsers.
+pub fn submit_form(form: &DomNode, options: &SubmitOptions) -> Result<FormSubmission, String> {
+  let submitter = match &options.submitter {
+    Some(submitter) => Some(submitter.clone()),
+    None => default_button(form).filter(|button| !button.is_disabled()),
+  };
+  let entries = form_entries(form, submitter.as_ref(), options.coordinates)?;
+
+  let overridden = |attr_name: &str, value: fn(&DomNode) -> Option<String>| {
+    submitter
+      .as_ref()
+      .filter(|submitter| submitter.has_attribute(attr_name.to_string()))
+      .and_then(value)
+  };
+  let action = overridden("formaction", DomNode::form_action)
+    .or_else(|| form.action())
+    .unwrap_or_default();
+  let method = overridden("formmethod", DomNode::form_method)
+    .or_else(|| form.method())
+    .unwrap_or_else(|| "get".to_string());
+  let enctype = overridden("formenctype", DomNode::form_enctype)
+    .or_else(|| form.enctype())
+    .unwrap_or_else(|| URLENCODED.to_string());
+
+  let mut submission = FormSubmission {
+    url: action,
+    method,
+    enctype,
+    entries,
+    body: None,
+    content_type: None,
+  };
+  if submission.method == "dialog" {
+    return Ok(submission);
+  }
+  let mut url = Url::parse(&submission.url)
+    .map_err(|_| format!("'{}' is not a valid form action URL.", submission.url))?;
+  match submission.method.as_str() {
+    "get" => {
+      let query = encode_urlencoded(&submission.entries);
+      match url.scheme() {
+        "http" | "https" | "data" => url.set_query(Some(&query)),
+        "mailto" => url.set_query(Some(&query.replace('+', "%20"))),
+        _ => {}
+      }
+      submission.url = url.to_string();
+    }
+    "post" => {
+      let (body, content_type) = match submission.enctype.as_str() {
+        MULTIPART => {
+          let boundary = options.boundary.clone().unwrap_or_else(random_boundary);
+          (
+            encode_multipart(&submission.entries, &boundary),
+            format!("{}; boundary={}", MULTIPART, boundary),
+          )
+        }
+        TEXT_PLAIN => (
+          encode_text_plain(&submission.entries),
+          TEXT_PLAIN.to_string(),
+        ),
+        _ => (
+          encode_urlencoded(&submission.entries),
+          URLENCODED.to_string(),
+        ),
+      };
+      submission.body = Some(body);
+      submission.content_type = Some(content_type);
+    }
+    _ => {}
+  }
+  Ok(submission)
+}
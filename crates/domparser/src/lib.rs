@@ -2,17 +2,59 @@ use html5ever::parse_document;
 use html5ever::tendril::TendrilSink;
 use markup5ever_rcdom::RcDom;
 
+pub mod article;
+pub mod ast;
+pub mod css;
+pub mod form;
+pub mod markdown;
+pub mod metadata;
 pub mod node;
+pub mod patch;
+pub mod resources;
+pub mod sanitizer;
 pub mod serializer;
+pub mod snapshot;
 
 pub use markup5ever_rcdom;
 pub use node::DomNode;
+pub use url;
 
 /// Parse string input to a html tree, return the root node.
+/// `<template shadowrootmode>` elements become declarative shadow roots.
 pub fn parse(html: String) -> DomNode {
-  let mut parser = parse_document(RcDom::default(), Default::default());
-  parser.process(html.into());
-  let dom = parser.finish();
+  parse_with_options(html, &ParseOptions::default())
+}
+
+/// Options for [`parse_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct ParseOptions {
+  /// Record where each node came from in the input, see [`DomNode::source_location`].
+  /// Parsing is slower with this enabled.
+  pub source_locations: bool,
+  /// The URL of the document, used to resolve relative URLs. Defaults to `about:blank`.
+  pub url: Option<url::Url>,
+}
+
+/// Like [`parse`], with options.
+pub fn parse_with_options(html: String, options: &ParseOptions) -> DomNode {
+  // Attribute names are case-insensitive, so `SHADOWROOTMODE` declares a shadow root too.
+  let has_declarative_shadow_roots = html
+    .as_bytes()
+    .windows("shadowrootmode".len())
+    .any(|window| window.eq_ignore_ascii_case(b"shadowrootmode"));
+  let document = if options.source_locations {
+    node::parse_with_source_locations(&html)
+  } else {
+    let mut parser = parse_document(RcDom::default(), Default::default());
+    parser.process(html.into());
+    parser.finish().document
+  };
 
-  DomNode(dom.document)
+  if has_declarative_shadow_roots {
+    node::attach_declarative_shadow_roots(&document);
+  }
+  if let Some(url) = &options.url {
+    node::set_document_url(&document, url.clone());
+  }
+  DomNode(document)
 }
@@ -0,0 +1,866 @@
+//! Markdown serialization of a DOM tree, targeting CommonMark with the GFM
+//! extensions for tables, strikethrough and task lists.
+
+use html5ever::ns;
+use markup5ever_rcdom::{Handle, NodeData};
+use std::collections::HashMap;
+
+use crate::node::DomNode;
+
+/// How headings are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeadingStyle {
+  /// `# Heading`
+  #[default]
+  Atx,
+  /// `Heading` underlined with `===` or `---`; only used for `<h1>` and `<h2>`.
+  Setext,
+}
+
+/// How `<pre>` blocks are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CodeBlockStyle {
+  /// Fenced with backticks or tildes, carrying the language from a `language-*` class.
+  #[default]
+  Fenced,
+  /// Indented by four spaces.
+  Indented,
+}
+
+/// A per-tag override of the default conversion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkdownRule {
+  /// Drop the element and its contents.
+  Remove,
+  /// Keep the element as raw HTML.
+  Keep,
+  /// Convert only the contents, ignoring the element itself.
+  Content,
+  /// Ask the handler passed to [`to_markdown`], falling back to the default when it returns `None`.
+  Custom,
+}
+
+#[derive(Clone, Debug)]
+pub struct MarkdownOptions {
+  pub heading_style: HeadingStyle,
+  /// `-`, `*` or `+`.
+  pub bullet_list_marker: char,
+  pub code_block_style: CodeBlockStyle,
+  /// `` ` `` or `~`.
+  pub fence: char,
+  /// `_` or `*`.
+  pub em_delimiter: char,
+  /// `*` or `_`, written twice.
+  pub strong_delimiter: char,
+  /// Write tables, strikethrough and task lists. Without it tables are kept as HTML.
+  pub gfm: bool,
+  /// Overrides keyed by lowercase tag name.
+  pub rules: HashMap<String, MarkdownRule>,
+}
+
+impl Default for MarkdownOptions {
+  fn default() -> Self {
+    MarkdownOptions {
+      heading_style: HeadingStyle::Atx,
+      bullet_list_marker: '-',
+      code_block_style: CodeBlockStyle::Fenced,
+      fence: '`',
+      em_delimiter: '_',
+      strong_delimiter: '*',
+      gfm: true,
+      rules: HashMap::new(),
+    }
+  }
+}
+
+/// Elements converted as blocks: they start a new paragraph.
+const BLOCK_ELEMENTS: &[&str] = &[
+  "address",
+  "article",
+  "aside",
+  "blockquote",
+  "body",
+  "caption",
+  "center",
+  "dd",
+  "details",
+  "dialog",
+  "dir",
+  "div",
+  "dl",
+  "dt",
+  "fieldset",
+  "figcaption",
+  "figure",
+  "footer",
+  "form",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "header",
+  "hgroup",
+  "hr",
+  "html",
+  "legend",
+  "li",
+  "main",
+  "menu",
+  "nav",
+  "ol",
+  "optgroup",
+  "option",
+  "p",
+  "pre",
+  "search",
+  "section",
+  "summary",
+  "table",
+  "tbody",
+  "td",
+  "tfoot",
+  "th",
+  "thead",
+  "tr",
+  "ul",
+];
+
+/// Elements dropped by default because they hold no readable content.
+const REMOVED_ELEMENTS: &[&str] = &[
+  "canvas", "head", "iframe", "noscript", "script", "style", "template", "title",
+];
+
+/// Stands for a `<br>` in inline content until line starts are known.
+const HARD_BREAK: char = '\u{E000}';
+/// Stands for a paragraph boundary met inside inline content.
+const PARAGRAPH_BREAK: char = '\u{E001}';
+
+fn local_name(handle: &Handle) -> Option<&str> {
+  match &handle.data {
+    NodeData::Element { name, .. } => Some(name.local.as_ref()),
+    _ => None,
+  }
+}
+
+fn is_html_element(handle: &Handle, names: &[&str]) -> bool {
+  matches!(&handle.data, NodeData::Element { name, .. } if name.ns == ns!(html) && names.contains(&name.local.as_ref()))
+}
+
+fn attribute(handle: &Handle, name: &str) -> Option<String> {
+  match &handle.data {
+    NodeData::Element { attrs, .. } => attrs
+      .borrow()
+      .iter()
+      .find(|attr| attr.name.ns == ns!() && attr.name.local.as_ref() == name)
+      .map(|attr| attr.value.to_string()),
+    _ => None,
+  }
+}
+
+fn children(handle: &Handle) -> Vec<Handle> {
+  handle.children.borrow().clone()
+}
+
+fn element_children(handle: &Handle) -> Vec<Handle> {
+  handle
+    .children
+    .borrow()
+    .iter()
+    .filter(|c| matches!(c.data, NodeData::Element { .. }))
+    .cloned()
+    .collect()
+}
+
+fn text_content(handle: &Handle) -> String {
+  DomNode(handle.clone()).text_content_getter()
+}
+
+fn is_block(handle: &Handle) -> bool {
+  is_html_element(handle, BLOCK_ELEMENTS)
+}
+
+fn contains_block(handle: &Handle) -> bool {
+  handle
+    .children
+    .borrow()
+    .iter()
+    .any(|c| is_block(c) || contains_block(c))
+}
+
+/// The longest run of `c` in `text`.
+fn longest_run(text: &str, c: char) -> usize {
+  let mut longest = 0;
+  let mut current = 0;
+  for ch in text.chars() {
+    if ch == c {
+      current += 1;
+      longest = longest.max(current);
+    } else {
+      current = 0;
+    }
+  }
+  longest
+}
+
+/// Escapes what would start a block construct at the beginning of a line.
+fn escape_line_start(line: &str) -> String {
+  let trimmed = line.trim_end();
+  let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+  let after = |n: usize| line[n..].chars().next();
+  let is_break = |c: Option<char>| c.is_none_or(|c| c == ' ');
+  if line.starts_with('#') {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    if hashes <= 6 && is_break(after(hashes)) {
+      return format!("\\{}", line);
+    }
+  } else if line.starts_with('>')
+    || ((line.starts_with('-') || line.starts_with('+')) && is_break(after(1)))
+  {
+    return format!("\\{}", line);
+  } else if (1..=9).contains(&digits)
+    && matches!(after(digits), Some('.' | ')'))
+    && is_break(after(digits + 1))
+  {
+    return format!("{}\\{}", &line[..digits], &line[digits..]);
+  }
+  if !trimmed.is_empty() && (trimmed.chars().all(|c| c == '=') || trimmed.chars().all(|c| c == '-'))
+  {
+    return format!("\\{}", line);
+  }
+  line.to_string()
+}
+
+/// Inline Markdown under construction, with white space collapsed as it is added.
+#[derive(Default)]
+struct Inline(String);
+
+impl Inline {
+  fn push_space(&mut self) {
+    if !self.0.ends_with([' ', HARD_BREAK, PARAGRAPH_BREAK]) {
+      self.0.push(' ');
+    }
+  }
+
+  fn push_raw(&mut self, markdown: &str) {
+    self.0.push_str(markdown);
+  }
+
+  /// Appends `content` between delimiters, keeping its outer white space outside them.
+  fn push_wrapped(&mut self, content: Inline, open: &str, close: &str) {
+    let trimmed = content.0.trim_matches(' ');
+    if content.0.starts_with(' ') {
+      self.push_space();
+    }
+    if !trimmed.is_empty() {
+      self.0.push_str(open);
+      self.0.push_str(trimmed);
+      self.0.push_str(close);
+      if content.0.ends_with(' ') {
+        self.push_space();
+      }
+    }
+  }
+
+  /// Splits the content into paragraphs, resolving hard breaks.
+  fn into_paragraphs(self) -> Vec<String> {
+    self
+      .0
+      .split(PARAGRAPH_BREAK)
+      .filter_map(|paragraph| {
+        let paragraph = paragraph
+          .trim_matches([' ', HARD_BREAK])
+          .replace(concat!(' ', '\u{E000}'), "\u{E000}")
+          .replace(concat!('\u{E000}', ' '), "\u{E000}");
+        if paragraph.is_empty() {
+          return None;
+        }
+        Some(
+          paragraph
+            .split(HARD_BREAK)
+            .map(escape_line_start)
+            .collect::<Vec<_>>()
+            .join("  \n"),
+        )
+      })
+      .collect()
+  }
+
+  /// Joins the content into a single line, writing breaks as `line_break`.
+  fn into_line(self, line_break: &str) -> String {
+    let line = self
+      .0
+      .trim_matches([' ', HARD_BREAK, PARAGRAPH_BREAK])
+      .replace([HARD_BREAK, PARAGRAPH_BREAK], line_break);
+    line.replace(&format!(" {}", line_break), line_break)
+  }
+}
+
+struct Converter<'a> {
+  options: &'a MarkdownOptions,
+  handler: &'a dyn Fn(&DomNode, &str) -> Option<String>,
+  /// Greater than zero inside links, emphasis and table cells, where blocks cannot start.
+  inline_depth: usize,
+  in_table_cell: bool,
+}
+
+impl Converter<'_> {
+  fn rule(&self, handle: &Handle) -> Option<MarkdownRule> {
+    let rule = self.options.rules.get(local_name(handle)?).copied();
+    if rule.is_none()
+      && (is_html_element(handle, REMOVED_ELEMENTS)
+        || local_name(handle) == Some("svg")
+        || attribute(handle, "hidden").is_some())
+    {
+      return Some(MarkdownRule::Remove);
+    }
+    rule
+  }
+
+  /// Converts a list of sibling nodes, joining the blocks with `separator`.
+  fn blocks(&mut self, nodes: &[Handle], separator: &str) -> String {
+    let mut blocks = vec![];
+    let mut inline = Inline::default();
+    for node in nodes {
+      if self.rule(node) == Some(MarkdownRule::Remove) {
+        continue;
+      }
+      let block_context = matches!(node.data, NodeData::Element { .. })
+        && (is_block(node) || (local_name(node) != Some("a") && contains_block(node)));
+      if block_context {
+        blocks.extend(std::mem::take(&mut inline).into_paragraphs());
+        let block = self.block(node);
+        if !block.is_empty() {
+          blocks.push(block);
+        }
+      } else {
+        self.inline(node, &mut inline);
+      }
+    }
+    blocks.extend(inline.into_paragraphs());
+    blocks.join(separator)
+  }
+
+  fn block(&mut self, element: &Handle) -> String {
+    match self.rule(element) {
+      Some(MarkdownRule::Remove) => return String::new(),
+      Some(MarkdownRule::Keep) => return DomNode(element.clone()).outer_html(),
+      Some(MarkdownRule::Content) => return self.blocks(&children(element), "\n\n"),
+      Some(MarkdownRule::Custom) => {
+        let content = self.blocks(&children(element), "\n\n");
+        if let Some(markdown) = (self.handler)(&DomNode(element.clone()), &content) {
+          return markdown.trim_matches('\n').to_string();
+        }
+      }
+      None => {}
+    }
+
+    let name = local_name(element).unwrap_or_default();
+    if !is_html_element(element, BLOCK_ELEMENTS) {
+      return self.blocks(&children(element), "\n\n");
+    }
+    match name {
+      "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => self.heading(element, name.as_bytes()[1] - b'0'),
+      "blockquote" => self
+        .blocks(&children(element), "\n\n")
+        .lines()
+        .map(|line| {
+          if line.is_empty() {
+            ">".to_string()
+          } else {
+            format!("> {}", line)
+          }
+        })
+        .collect::<Vec<_>>()
+        .join("\n"),
+      "ul" | "ol" => self.list(element),
+      "li" => {
+        let marker = self.options.bullet_list_marker.to_string();
+        self.list_item(element, &marker, false)
+      }
+      "pre" => self.code_block(element),
+      "hr" => "---".to_string(),
+      "table" if self.options.gfm => self.table(element),
+      "table" => DomNode(element.clone()).outer_html(),
+      _ => self.blocks(&children(element), "\n\n"),
+    }
+  }
+
+  fn heading(&mut self, element: &Handle, level: u8) -> String {
+    let mut inline = Inline::default();
+    self.inline_children(element, &mut inline);
+    let mut text = inline.into_line(" ");
+    if text.is_empty() {
+      return text;
+    }
+    if text.ends_with('#') {
+      text.insert(text.len() - 1, '\\');
+    }
+    if self.options.heading_style == HeadingStyle::Setext && level <= 2 {
+      let underline = if level == 1 { "=" } else { "-" };
+      let width = text.chars().count().max(3);
+      return format!("{}\n{}", text, underline.repeat(width));
+    }
+    format!("{} {}", "#".repeat(level as usize), text)
+  }
+
+  fn list(&mut self, list: &Handle) -> String {
+    let ordered = local_name(list) == Some("ol");
+    let start = attribute(list, "start")
+      .and_then(|start| start.trim().parse::<i64>().ok())
+      .unwrap_or(1);
+    let items = element_children(list);
+    let loose = items.iter().any(|item| {
+      element_children(item)
+        .iter()
+        .any(|c| is_html_element(c, &["p"]))
+    });
+
+    let mut number = start;
+    let mut markdown = vec![];
+    for item in items {
+      if !is_html_element(&item, &["li"]) {
+        let block = self.block(&item);
+        if !block.is_empty() {
+          markdown.push(block);
+        }
+        continue;
+      }
+      if self.rule(&item) == Some(MarkdownRule::Remove) {
+        continue;
+      }
+      let marker = if ordered {
+        format!("{}.", number)
+      } else {
+        self.options.bullet_list_marker.to_string()
+      };
+      number += 1;
+      markdown.push(self.list_item(&item, &marker, loose));
+    }
+    markdown.join(if loose { "\n\n" } else { "\n" })
+  }
+
+  fn list_item(&mut self, item: &Handle, marker: &str, loose: bool) -> String {
+    let mut content = self.blocks(&children(item), if loose { "\n\n" } else { "\n" });
+    if self.options.gfm {
+      if let Some(checked) = leading_checkbox(item) {
+        content.insert_str(0, if checked { "[x] " } else { "[ ] " });
+      }
+    }
+    let indent = " ".repeat(marker.len() + 1);
+    let mut lines = content.lines();
+    let mut markdown = match lines.next() {
+      Some(first) if !first.is_empty() => format!("{} {}", marker, first),
+      _ => marker.to_string(),
+    };
+    for line in lines {
+      markdown.push('\n');
+      if !line.is_empty() {
+        markdown.push_str(&indent);
+        markdown.push_str(line);
+      }
+    }
+    markdown
+  }
+
+  fn code_block(&mut self, pre: &Handle) -> String {
+    let code = element_children(pre)
+      .into_iter()
+      .find(|c| is_html_element(c, &["code"]));
+    let language = code
+      .iter()
+      .chain(std::iter::once(pre))
+      .filter_map(|el| attribute(el, "class"))
+      .flat_map(|class| {
+        class
+          .split_ascii_whitespace()
+          .filter_map(|token| {
+            token
+              .strip_prefix("language-")
+              .or_else(|| token.strip_prefix("lang-"))
+              .map(str::to_string)
+          })
+          .collect::<Vec<_>>()
+      })
+      .next()
+      .unwrap_or_default();
+    let text = text_content(pre);
+    let text = text.strip_suffix('\n').unwrap_or(&text);
+
+    match self.options.code_block_style {
+      CodeBlockStyle::Fenced => {
+        let fence = self
+          .options
+          .fence
+          .to_string()
+          .repeat(longest_run(text, self.options.fence).max(2) + 1);
+        format!("{}{}\n{}\n{}", fence, language, text, fence)
+      }
+      CodeBlockStyle::Indented => text
+        .lines()
+        .map(|line| format!("    {}", line))
+        .collect::<Vec<_>>()
+        .join("\n"),
+    }
+  }
+
+  fn table(&mut self, table: &Handle) -> String {
+    let mut caption = String::new();
+    let mut rows = vec![];
+    for child in element_children(table) {
+      match local_name(&child) {
+        Some("caption") => {
+          let mut inline = Inline::default();
+          self.inline_children(&child, &mut inline);
+          caption = inline.into_paragraphs().join("\n\n");
+        }
+        Some("tr") => rows.push(child),
+        Some("thead" | "tbody" | "tfoot") => rows.extend(
+          element_children(&child)
+            .into_iter()
+            .filter(|row| is_html_element(row, &["tr"])),
+        ),
+        _ => {}
+      }
+    }
+
+    let mut cells: Vec<Vec<String>> = vec![];
+    let mut alignments = vec![];
+    for (index, row) in rows.iter().enumerate() {
+      let mut row_cells = vec![];
+      for cell in element_children(row)
+        .into_iter()
+        .filter(|cell| is_html_element(cell, &["td", "th"]))
+      {
+        let mut inline = Inline::default();
+        self.inline_depth += 1;
+        self.in_table_cell = true;
+        self.inline_children(&cell, &mut inline);
+        self.in_table_cell = false;
+        self.inline_depth -= 1;
+        row_cells.push(inline.into_line("<br>"));
+        if index == 0 {
+          alignments.push(cell_alignment(&cell));
+        }
+        let colspan = attribute(&cell, "colspan")
+          .and_then(|span| span.trim().parse::<usize>().ok())
+          .unwrap_or(1)
+          .clamp(1, 1000);
+        for _ in 1..colspan {
+          row_cells.push(String::new());
+          if index == 0 {
+            alignments.push(None);
+          }
+        }
+      }
+      cells.push(row_cells);
+    }
+
+    let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+      return caption;
+    }
+    alignments.resize(columns, None);
+    let mut widths = vec![3; columns];
+    for row in &mut cells {
+      row.resize(columns, String::new());
+      for (width, cell) in widths.iter_mut().zip(row.iter()) {
+        *width = (*width).max(cell.chars().count());
+      }
+    }
+
+    let format_row = |row: &[String]| {
+      let cells = row
+        .iter()
+        .zip(&widths)
+        .map(|(cell, &width)| format!("{}{}", cell, " ".repeat(width - cell.chars().count())))
+        .collect::<Vec<_>>();
+      format!("| {} |", cells.join(" | "))
+    };
+    let delimiter = alignments
+      .iter()
+      .zip(&widths)
+      .map(|(alignment, &width)| match alignment.as_deref() {
+        Some("left") => format!(":{}", "-".repeat(width - 1)),
+        Some("center") => format!(":{}:", "-".repeat(width - 2)),
+        Some("right") => format!("{}:", "-".repeat(width - 1)),
+        _ => "-".repeat(width),
+      })
+      .collect::<Vec<_>>();
+
+    let mut lines = vec![
+      format_row(&cells[0]),
+      format!("| {} |", delimiter.join(" | ")),
+    ];
+    lines.extend(cells[1..].iter().map(|row| format_row(row)));
+    let table = lines.join("\n");
+    if caption.is_empty() {
+      table
+    } else {
+      format!("{}\n\n{}", caption, table)
+    }
+  }
+
+  fn inline_children(&mut self, element: &Handle, inline: &mut Inline) {
+    for child in children(element) {
+      self.inline(&child, inline);
+    }
+  }
+
+  fn inline(&mut self, node: &Handle, inline: &mut Inline) {
+    match &node.data {
+      NodeData::Text { contents } => {
+        let text = contents.borrow().to_string();
+        self.push_text(&text, inline);
+      }
+      NodeData::Element { .. } => self.inline_element(node, inline),
+      _ => {}
+    }
+  }
+
+  fn inline_element(&mut self, element: &Handle, inline: &mut Inline) {
+    match self.rule(element) {
+      Some(MarkdownRule::Remove) => return,
+      Some(MarkdownRule::Keep) => {
+        inline.push_raw(&DomNode(element.clone()).outer_html());
+        return;
+      }
+      Some(MarkdownRule::Content) => {
+        self.inline_children(element, inline);
+        return;
+      }
+      Some(MarkdownRule::Custom) => {
+        let mut content = Inline::default();
+        self.inline_children(element, &mut content);
+        let content = content.into_paragraphs().join("\n\n");
+        if let Some(markdown) = (self.handler)(&DomNode(element.clone()), &content) {
+          inline.push_raw(&markdown);
+          return;
+        }
+      }
+      None => {}
+    }
+
+    if is_block(element) {
+      // A block inside inline content, such as a `<div>` in a link or a table cell.
+      if self.inline_depth > 0 {
+        inline.push_space();
+        self.inline_children(element, inline);
+        inline.push_space();
+      } else {
+        inline.push_raw(&PARAGRAPH_BREAK.to_string());
+        self.inline_children(element, inline);
+        inline.push_raw(&PARAGRAPH_BREAK.to_string());
+      }
+      return;
+    }
+    if !matches!(&element.data, NodeData::Element { name, .. } if name.ns == ns!(html)) {
+      self.inline_children(element, inline);
+      return;
+    }
+
+    let name = local_name(element).unwrap_or_default();
+    match name {
+      "br" => inline.push_raw(&HARD_BREAK.to_string()),
+      "em" | "i" => {
+        let delimiter = self.options.em_delimiter.to_string();
+        self.wrapped(element, inline, &delimiter);
+      }
+      "strong" | "b" => {
+        let delimiter = self.options.strong_delimiter.to_string().repeat(2);
+        self.wrapped(element, inline, &delimiter);
+      }
+      "del" | "s" | "strike" if self.options.gfm => self.wrapped(element, inline, "~~"),
+      "code" | "kbd" | "samp" | "tt" => {
+        let code = text_content(element).replace(['\r', '\n'], " ");
+        if code.is_empty() {
+          return;
+        }
+        let ticks = "`".repeat(longest_run(&code, '`') + 1);
+        let padding = if code.starts_with('`')
+          || code.ends_with('`')
+          || (code.starts_with(' ') && code.ends_with(' ') && code.trim() != "")
+        {
+          " "
+        } else {
+          ""
+        };
+        inline.push_raw(&format!("{ticks}{padding}{code}{padding}{ticks}"));
+      }
+      "a" => self.link(element, inline),
+      "img" => {
+        let Some(src) = attribute(element, "src").filter(|src| !src.trim().is_empty()) else {
+          return;
+        };
+        let mut alt = Inline::default();
+        self.push_text(&attribute(element, "alt").unwrap_or_default(), &mut alt);
+        inline.push_raw(&format!(
+          "![{}]({}{})",
+          alt.into_line(" "),
+          escape_url(&src),
+          title_suffix(element)
+        ));
+      }
+      "input" | "select" | "button" | "textarea" => {}
+      _ => self.inline_children(element, inline),
+    }
+  }
+
+  fn wrapped(&mut self, element: &Handle, inline: &mut Inline, delimiter: &str) {
+    let mut content = Inline::default();
+    self.inline_depth += 1;
+    self.inline_children(element, &mut content);
+    self.inline_depth -= 1;
+    inline.push_wrapped(content, delimiter, delimiter);
+  }
+
+  fn link(&mut self, element: &Handle, inline: &mut Inline) {
+    let mut content = Inline::default();
+    self.inline_depth += 1;
+    self.inline_children(element, &mut content);
+    self.inline_depth -= 1;
+
+    let Some(href) = attribute(element, "href").map(|href| href.trim().to_string()) else {
+      inline.push_wrapped(content, "", "");
+      return;
+    };
+    let text = content.0.trim_matches(' ');
+    let is_absolute = href.split_once(':').is_some_and(|(scheme, _)| {
+      !scheme.is_empty()
+        && scheme
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    });
+    if is_absolute && text == href && !href.contains([' ', '<', '>']) && !self.in_table_cell {
+      if content.0.starts_with(' ') {
+        inline.push_space();
+      }
+      inline.push_raw(&format!("<{}>", href));
+      if content.0.ends_with(' ') {
+        inline.push_space();
+      }
+      return;
+    }
+    let close = format!("]({}{})", escape_url(&href), title_suffix(element));
+    inline.push_wrapped(content, "[", &close);
+  }
+
+  fn push_text(&self, text: &str, inline: &mut Inline) {
+    let chars: Vec<char> = text
+      .chars()
+      .filter(|&c| c != HARD_BREAK && c != PARAGRAPH_BREAK)
+      .collect();
+    for (i, &c) in chars.iter().enumerate() {
+      let prev = i.checked_sub(1).map(|p| chars[p]);
+      let next = chars.get(i + 1).copied();
+      let escape = match c {
+        ' ' | '\t' | '\n' | '\r' | '\x0C' => {
+          inline.push_space();
+          continue;
+        }
+        '\\' | '*' | '`' | '[' | ']' | '~' => true,
+        '_' => {
+          !(prev.is_some_and(char::is_alphanumeric) && next.is_some_and(char::is_alphanumeric))
+        }
+        '|' => self.in_table_cell,
+        '<' => next.is_some_and(|n| n.is_ascii_alphabetic() || "/!?".contains(n)),
+        '&' => next.is_some_and(|n| n.is_ascii_alphanumeric() || n == '#'),
+        _ => false,
+      };
+      if escape {
+        inline.0.push('\\');
+      }
+      inline.0.push(c);
+    }
+  }
+}
+
+/// Whether a list item starts with a checkbox, and whether it is checked.
+fn leading_checkbox(item: &Handle) -> Option<bool> {
+  fn find(node: &Handle) -> Option<Option<bool>> {
+    for child in node.children.borrow().iter() {
+      match &child.data {
+        NodeData::Text { contents } if !contents.borrow().trim().is_empty() => return Some(None),
+        NodeData::Element { .. } if is_html_element(child, &["ul", "ol"]) => return Some(None),
+        NodeData::Element { .. } if is_html_element(child, &["input"]) => {
+          let checkbox =
+            attribute(child, "type").is_some_and(|t| t.eq_ignore_ascii_case("checkbox"));
+          return Some(checkbox.then(|| attribute(child, "checked").is_some()));
+        }
+        NodeData::Element { .. } => {
+          if let Some(found) = find(child) {
+            return Some(found);
+          }
+        }
+        _ => {}
+      }
+    }
+    None
+  }
+  find(item).flatten()
+}
+
+fn cell_alignment(cell: &Handle) -> Option<String> {
+  let from_style = attribute(cell, "style").and_then(|style| {
+    style
+      .split(';')
+      .filter_map(|declaration| declaration.split_once(':'))
+      .find(|(name, _)| name.trim().eq_ignore_ascii_case("text-align"))
+      .map(|(_, value)| value.trim().to_ascii_lowercase())
+  });
+  from_style
+    .or_else(|| attribute(cell, "align").map(|align| align.trim().to_ascii_lowercase()))
+    .filter(|align| matches!(align.as_str(), "left" | "center" | "right"))
+}
+
+fn escape_url(url: &str) -> String {
+  let mut escaped = String::with_capacity(url.len());
+  for c in url.chars() {
+    match c {
+      ' ' => escaped.push_str("%20"),
+      '<' => escaped.push_str("%3C"),
+      '>' => escaped.push_str("%3E"),
+      '(' | ')' => {
+        escaped.push('\\');
+        escaped.push(c);
+      }
+      _ => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+fn title_suffix(element: &Handle) -> String {
+  match attribute(element, "title").filter(|title| !title.is_empty()) {
+    Some(title) => format!(
+      " \"{}\"",
+      title
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', " ")
+    ),
+    None => String::new(),
+  }
+}
+
+/// Converts the node and its descendants to Markdown. Elements with a
+/// [`MarkdownRule::Custom`] rule are passed to `handler` together with their
+/// converted contents; returning `None` falls back to the default conversion.
+pub fn to_markdown(
+  handle: &Handle,
+  options: &MarkdownOptions,
+  handler: &dyn Fn(&DomNode, &str) -> Option<String>,
+) -> String {
+  let mut converter = Converter {
+    options,
+    handler,
+    inline_depth: 0,
+    in_table_cell: false,
+  };
+  let markdown = match &handle.data {
+    NodeData::Document => converter.blocks(&children(handle), "\n\n"),
+    _ => converter.blocks(std::slice::from_ref(handle), "\n\n"),
+  };
+  markdown.trim_matches('\n').to_string()
+}
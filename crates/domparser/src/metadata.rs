@@ -0,0 +1,472 @@
+//! Extraction of page metadata: the document's title, description, canonical
+//! URL, language and icons, OpenGraph and Twitter card properties, JSON-LD
+//! blocks, and the items described by HTML microdata and RDFa Lite.
+//!
+//! [`extract_metadata`] gathers everything in one pass over the tree, so a
+//! binding can hand the result over in a single call.
+
+use indexmap::IndexMap;
+use markup5ever_rcdom::{Handle, NodeData};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use url::Url;
+
+use crate::node::DomNode;
+
+/// Prefixes of the OpenGraph protocol and its object types.
+const OPEN_GRAPH_PREFIXES: &[&str] = &[
+  "og:", "fb:", "article:", "book:", "books:", "music:", "profile:", "video:",
+];
+
+/// Prefixes RDFa defines without a `prefix` declaration, from its initial context.
+const RDFA_PREFIXES: &[(&str, &str)] = &[
+  ("dc", "http://purl.org/dc/terms/"),
+  ("dcterms", "http://purl.org/dc/terms/"),
+  ("foaf", "http://xmlns.com/foaf/0.1/"),
+  ("og", "http://ogp.me/ns#"),
+  ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+  ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+  ("schema", "http://schema.org/"),
+  ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+];
+
+/// An icon declared with `<link rel="icon">`, `apple-touch-icon` or similar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Icon {
+  /// The whole `rel` attribute, such as `"icon"` or `"apple-touch-icon"`.
+  pub rel: String,
+  /// The resolved `href`.
+  pub href: String,
+  pub sizes: Option<String>,
+  /// The `type` attribute.
+  pub media_type: Option<String>,
+}
+
+/// An item described by microdata or RDFa Lite.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Item {
+  /// The `itemtype` or `typeof` types, as absolute URLs where they can be expanded.
+  pub types: Vec<String>,
+  /// The global identifier: `itemid` in microdata, `resource` in RDFa.
+  pub id: Option<String>,
+  /// The values of each property, in document order. RDFa property names are
+  /// expanded against the vocabulary and prefixes in scope.
+  pub properties: IndexMap<String, Vec<ItemValue>>,
+}
+
+/// The value of an [`Item`] property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ItemValue {
+  Text(String),
+  Item(Item),
+}
+
+/// Metadata of a page, returned by [`extract_metadata`].
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Metadata {
+  pub title: Option<String>,
+  /// The `<meta name="description">` content.
+  pub description: Option<String>,
+  /// The resolved `href` of `<link rel="canonical">`.
+  pub canonical: Option<String>,
+  /// The `lang` of the root element, or the `Content-Language` pragma.
+  pub language: Option<String>,
+  pub icons: Vec<Icon>,
+  /// `og:`, `article:` and other OpenGraph `<meta property>` values, by property.
+  pub open_graph: IndexMap<String, Vec<String>>,
+  /// `twitter:` `<meta>` values, by name.
+  pub twitter: IndexMap<String, Vec<String>>,
+  /// The text of each `<script type="application/ld+json">` block.
+  pub json_ld: Vec<String>,
+  /// The top-level microdata items.
+  pub microdata: Vec<Item>,
+  /// The RDFa Lite items, those declared with `typeof`.
+  pub rdfa: Vec<Item>,
+}
+
+fn attribute(node: &DomNode, name: &str) -> Option<String> {
+  node.get_attribute(name.to_string())
+}
+
+fn has_token(value: &str, token: &str) -> bool {
+  value
+    .split_ascii_whitespace()
+    .any(|t| t.eq_ignore_ascii_case(token))
+}
+
+fn non_empty(value: String) -> Option<String> {
+  let value = value.trim();
+  (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Collapses runs of ASCII white space to single spaces, as `document.title` does.
+fn collapse_whitespace(value: &str) -> String {
+  value.split_ascii_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn resolve(base: &Option<Url>, value: &str) -> String {
+  let value = value.trim_matches(|c: char| c.is_ascii_whitespace());
+  match base {
+    Some(base) => base.join(value),
+    None => Url::parse(value),
+  }
+  .map_or_else(|_| value.to_string(), String::from)
+}
+
+fn push_value(map: &mut IndexMap<String, Vec<String>>, key: String, value: String) {
+  map.entry(key).or_default().push(value);
+}
+
+fn head_metadata(scope: &DomNode, base: &Option<Url>, metadata: &mut Metadata) {
+  metadata.title = scope
+    .query_selector("title".to_string())
+    .and_then(|title| non_empty(collapse_whitespace(&title.text())));
+
+  for meta in scope.query_selector_all("meta".to_string()) {
+    let Some(content) = attribute(&meta, "content") else {
+      continue;
+    };
+    if let Some(name) = attribute(&meta, "name") {
+      let name = name.trim().to_ascii_lowercase();
+      if name == "description" && metadata.description.is_none() {
+        metadata.description = non_empty(content.clone());
+      } else if name.starts_with("twitter:") {
+        push_value(&mut metadata.twitter, name, content.clone());
+        continue;
+      }
+    }
+    if let Some(property) = attribute(&meta, "property") {
+      let property = property.trim().to_ascii_lowercase();
+      if OPEN_GRAPH_PREFIXES
+        .iter()
+        .any(|prefix| property.starts_with(prefix))
+      {
+        push_value(&mut metadata.open_graph, property, content);
+      } else if property.starts_with("twitter:") {
+        push_value(&mut metadata.twitter, property, content);
+      }
+    } else if attribute(&meta, "http-equiv")
+      .is_some_and(|pragma| pragma.trim().eq_ignore_ascii_case("content-language"))
+      && metadata.language.is_none()
+    {
+      // The pragma takes the first language of a comma-separated list.
+      metadata.language = content
+        .split(',')
+        .next()
+        .and_then(|lang| non_empty(lang.to_string()));
+    }
+  }
+
+  for link in scope.query_selector_all("link[rel][href]".to_string()) {
+    let rel = attribute(&link, "rel").unwrap_or_default();
+    let href = resolve(base, &attribute(&link, "href").unwrap_or_default());
+    if has_token(&rel, "canonical") && metadata.canonical.is_none() {
+      metadata.canonical = Some(href.clone());
+    }
+    if rel
+      .split_ascii_whitespace()
+      .any(|token| token.to_ascii_lowercase().contains("icon"))
+    {
+      metadata.icons.push(Icon {
+        rel: rel.trim().to_string(),
+        href,
+        sizes: attribute(&link, "sizes"),
+        media_type: attribute(&link, "type"),
+      });
+    }
+  }
+}
+
+fn element_name(handle: &Handle) -> Option<&str> {
+  match &handle.data {
+    NodeData::Element { name, .. } => Some(name.local.as_ref()),
+    _ => None,
+  }
+}
+
+fn key(handle: &Handle) -> usize {
+  Rc::as_ptr(handle) as usize
+}
+
+/// Runs the microdata and RDFa algorithms over the tree of `scope`.
+struct ItemExtractor {
+  base: Option<Url>,
+  /// Positions of the elements of the tree in tree order, for sorting properties.
+  order: HashMap<usize, usize>,
+  /// The first element with each id, for `itemref`.
+  ids: HashMap<String, Handle>,
+}
+
+impl ItemExtractor {
+  fn new(root: &Handle, base: Option<Url>) -> ItemExtractor {
+    let mut extractor = ItemExtractor {
+      base,
+      order: HashMap::new(),
+      ids: HashMap::new(),
+    };
+    let mut stack = vec![root.clone()];
+    while let Some(node) = stack.pop() {
+      extractor.order.insert(key(&node), extractor.order.len());
+      if let Some(id) = attribute(&DomNode(node.clone()), "id") {
+        extractor.ids.entry(id).or_insert_with(|| node.clone());
+      }
+      stack.extend(node.children.borrow().iter().rev().cloned());
+    }
+    extractor
+  }
+
+  /// https://html.spec.whatwg.org/multipage/microdata.html#the-properties-of-an-item
+  fn crawl_properties(&self, root: &Handle) -> Vec<Handle> {
+    let mut pending: Vec<Handle> = root.children.borrow().iter().cloned().collect();
+    if let Some(itemref) = attribute(&DomNode(root.clone()), "itemref") {
+      for id in itemref.split_ascii_whitespace() {
+        if let Some(element) = self.ids.get(id) {
+          pending.push(element.clone());
+        }
+      }
+    }
+    let mut visited = HashSet::from([key(root)]);
+    let mut results = vec![];
+    while let Some(current) = pending.pop() {
+      if element_name(&current).is_none() || !visited.insert(key(&current)) {
+        continue;
+      }
+      let element = DomNode(current.clone());
+      if attribute(&element, "itemscope").is_none() {
+        pending.extend(current.children.borrow().iter().cloned());
+      }
+      if attribute(&element, "itemprop").is_some_and(|names| !names.trim().is_empty()) {
+        results.push(current);
+      }
+    }
+    results.sort_by_key(|element| self.order.get(&key(element)).copied().unwrap_or(usize::MAX));
+    results
+  }
+
+  /// https://html.spec.whatwg.org/multipage/microdata.html#values
+  fn property_value(&self, handle: &Handle) -> String {
+    let element = DomNode(handle.clone());
+    let url = |name: &str| {
+      attribute(&element, name)
+        .map(|value| resolve(&self.base, &value))
+        .unwrap_or_default()
+    };
+    match element_name(handle).unwrap_or_default() {
+      "meta" => attribute(&element, "content").unwrap_or_default(),
+      "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => url("src"),
+      "a" | "area" | "link" => url("href"),
+      "object" => url("data"),
+      "data" | "meter" => attribute(&element, "value").unwrap_or_default(),
+      "time" => attribute(&element, "datetime").unwrap_or_else(|| element.text_content_getter()),
+      _ => element.text_content_getter(),
+    }
+  }
+
+  fn microdata_item(&self, handle: &Handle, memory: &mut Vec<usize>) -> Item {
+    let element = DomNode(handle.clone());
+    let types: Vec<String> = attribute(&element, "itemtype")
+      .map(|types| types.split_ascii_whitespace().map(String::from).collect())
+      .unwrap_or_default();
+    // An `itemid` is only meaningful on typed items.
+    let id = attribute(&element, "itemid")
+      .filter(|_| !types.is_empty())
+      .map(|id| resolve(&self.base, &id));
+    let mut item = Item {
+      types,
+      id,
+      properties: IndexMap::new(),
+    };
+    memory.push(key(handle));
+    for property in self.crawl_properties(handle) {
+      let value = if attribute(&DomNode(property.clone()), "itemscope").is_some() {
+        if memory.contains(&key(&property)) {
+          ItemValue::Text("ERROR".to_string())
+        } else {
+          ItemValue::Item(self.microdata_item(&property, memory))
+        }
+      } else {
+        ItemValue::Text(self.property_value(&property))
+      };
+      let names = attribute(&DomNode(property.clone()), "itemprop").unwrap_or_default();
+      let mut seen = HashSet::new();
+      for name in names
+        .split_ascii_whitespace()
+        .filter(|name| seen.insert(*name))
+      {
+        item
+          .properties
+          .entry(name.to_string())
+          .or_default()
+          .push(value.clone());
+      }
+    }
+    memory.pop();
+    item
+  }
+
+  /// https://html.spec.whatwg.org/multipage/microdata.html#top-level-microdata-item
+  fn microdata(&self, scope: &DomNode) -> Vec<Item> {
+    let mut elements = vec![scope.clone()];
+    elements.extend(scope.query_selector_all("[itemscope]".to_string()));
+    elements
+      .into_iter()
+      .filter(|element| {
+        attribute(element, "itemscope").is_some() && attribute(element, "itemprop").is_none()
+      })
+      .map(|element| self.microdata_item(&element.0, &mut vec![]))
+      .collect()
+  }
+}
+
+/// The vocabulary and prefixes in scope while walking RDFa.
+#[derive(Clone)]
+struct RdfaContext {
+  vocab: Option<String>,
+  prefixes: HashMap<String, String>,
+}
+
+impl RdfaContext {
+  /// Expands a term, CURIE or absolute IRI.
+  fn expand(&self, term: &str) -> String {
+    if let Some((prefix, reference)) = term.split_once(':') {
+      if let Some(iri) = self.prefixes.get(prefix) {
+        return format!("{}{}", iri, reference);
+      }
+      if let Some((_, iri)) = RDFA_PREFIXES.iter().find(|(name, _)| *name == prefix) {
+        return format!("{}{}", iri, reference);
+      }
+      return term.to_string();
+    }
+    match &self.vocab {
+      Some(vocab) => format!("{}{}", vocab, term),
+      None => term.to_string(),
+    }
+  }
+}
+
+impl ItemExtractor {
+  fn rdfa_resource(&self, element: &DomNode) -> Option<String> {
+    ["resource", "href", "src"]
+      .iter()
+      .find_map(|name| attribute(element, name))
+      .map(|value| resolve(&self.base, &value))
+  }
+
+  /// https://www.w3.org/TR/rdfa-lite/
+  fn rdfa(
+    &self,
+    handle: &Handle,
+    context: &RdfaContext,
+    mut subject: Option<&mut Item>,
+    items: &mut Vec<Item>,
+  ) {
+    let element = DomNode(handle.clone());
+    let mut context = context.clone();
+    if element_name(handle).is_some() {
+      if let Some(vocab) = attribute(&element, "vocab") {
+        context.vocab = non_empty(vocab);
+      }
+      if let Some(prefix) = attribute(&element, "prefix") {
+        let mut tokens = prefix.split_ascii_whitespace();
+        while let (Some(name), Some(iri)) = (tokens.next(), tokens.next()) {
+          if let Some(name) = name.strip_suffix(':') {
+            context.prefixes.insert(name.to_string(), iri.to_string());
+          }
+        }
+      }
+    }
+    let properties: Vec<String> = attribute(&element, "property")
+      .map(|names| {
+        names
+          .split_ascii_whitespace()
+          .map(|name| context.expand(name))
+          .collect()
+      })
+      .unwrap_or_default();
+
+    if let Some(types) = attribute(&element, "typeof") {
+      let mut item = Item {
+        types: types
+          .split_ascii_whitespace()
+          .map(|name| context.expand(name))
+          .collect(),
+        id: self.rdfa_resource(&element),
+        properties: IndexMap::new(),
+      };
+      for child in handle.children.borrow().iter() {
+        self.rdfa(child, &context, Some(&mut item), items);
+      }
+      match subject {
+        Some(subject) if !properties.is_empty() => {
+          for property in properties {
+            subject
+              .properties
+              .entry(property)
+              .or_default()
+              .push(ItemValue::Item(item.clone()));
+          }
+        }
+        _ => items.push(item),
+      }
+      return;
+    }
+
+    if !properties.is_empty() {
+      if let Some(subject) = subject.as_deref_mut() {
+        let value = attribute(&element, "content")
+          .or_else(|| self.rdfa_resource(&element))
+          .unwrap_or_else(|| element.text_content_getter());
+        for property in properties {
+          subject
+            .properties
+            .entry(property)
+            .or_default()
+            .push(ItemValue::Text(value.clone()));
+        }
+      }
+    }
+    for child in handle.children.borrow().iter() {
+      self.rdfa(child, &context, subject.as_deref_mut(), items);
+    }
+  }
+}
+
+/// Extracts the metadata of a document, or of the part of one under `node`.
+/// Head metadata comes from the document's `<head>` when there is one; JSON-LD,
+/// microdata and RDFa come from the whole tree. URLs are resolved against the
+/// node's base URL.
+pub fn extract_metadata(node: &DomNode) -> Metadata {
+  let base = node.base_url();
+  let mut metadata = Metadata::default();
+  let scope = node.head().unwrap_or_else(|| node.clone());
+  head_metadata(&scope, &base, &mut metadata);
+
+  // The root element's `lang` wins over the pragma.
+  let root = node.document_element().unwrap_or_else(|| node.clone());
+  if let Some(lang) = attribute(&root, "lang").and_then(non_empty) {
+    metadata.language = Some(lang);
+  }
+
+  metadata.json_ld = node
+    .query_selector_all("script[type]".to_string())
+    .into_iter()
+    .filter(|script| {
+      attribute(script, "type").is_some_and(|kind| {
+        kind
+          .split(';')
+          .next()
+          .is_some_and(|kind| kind.trim().eq_ignore_ascii_case("application/ld+json"))
+      })
+    })
+    .map(|script| script.text_content_getter())
+    .collect();
+
+  let extractor = ItemExtractor::new(&node.get_root_node().0, base);
+  metadata.microdata = extractor.microdata(node);
+  let context = RdfaContext {
+    vocab: None,
+    prefixes: HashMap::new(),
+  };
+  extractor.rdfa(&node.0, &context, None, &mut metadata.rdfa);
+  metadata
+}
@@ -0,0 +1,369 @@
+use cssparser::{Parser, ParserInput, Token};
+use html5ever::{local_name, ns, Attribute};
+use markup5ever_rcdom::{Handle, NodeData};
+use url::Url;
+
+use super::document::document_url;
+use super::{attached_shadow_root, template_contents, DomNode};
+
+/// HTML attributes holding a single URL, with the elements they are URLs on.
+const URL_ATTRIBUTES: &[(&str, &[&str])] = &[
+  ("href", &["a", "area", "base", "link"]),
+  (
+    "src",
+    &[
+      "audio", "embed", "frame", "iframe", "img", "input", "script", "source", "track", "video",
+    ],
+  ),
+  ("action", &["form"]),
+  ("formaction", &["button", "input"]),
+  ("poster", &["video"]),
+  ("cite", &["blockquote", "del", "ins", "q"]),
+  ("data", &["object"]),
+  ("background", &["body", "table", "td", "th"]),
+  ("longdesc", &["frame", "iframe", "img"]),
+  ("manifest", &["html"]),
+];
+
+/// HTML attributes holding a `srcset`-style list of image candidates.
+const SRCSET_ATTRIBUTES: &[(&str, &[&str])] =
+  &[("srcset", &["img", "source"]), ("imagesrcset", &["link"])];
+
+/// The first `<base>` element with an `href` in tree order.
+fn first_base_href(document: &Handle) -> Option<String> {
+  let mut stack = vec![document.clone()];
+  while let Some(node) = stack.pop() {
+    if let NodeData::Element { name, attrs, .. } = &node.data {
+      if name.ns == ns!(html) && name.local == local_name!("base") {
+        let href = attrs
+          .borrow()
+          .iter()
+          .find(|attr| attr.name.ns == ns!() && attr.name.local == local_name!("href"))
+          .map(|attr| attr.value.to_string());
+        if href.is_some() {
+          return href;
+        }
+      }
+    }
+    stack.extend(node.children.borrow().iter().rev().cloned());
+  }
+  None
+}
+
+/// https://html.spec.whatwg.org/multipage/urls-and-fetching.html#document-base-url
+fn document_base_url(document: &Handle) -> Option<Url> {
+  let url = document_url(document);
+  let Some(href) = first_base_href(document) else {
+    return url;
+  };
+  let href = href.trim_matches(|c: char| c.is_ascii_whitespace());
+  let base = match &url {
+    Some(url) => url.join(href),
+    None => Url::parse(href),
+  };
+  // A `<base>` cannot point at a `data:` or `javascript:` URL.
+  match base {
+    Ok(base) if !matches!(base.scheme(), "data" | "javascript") => Some(base),
+    _ => url,
+  }
+}
+
+/// Resolves `value` against `base`, returning `None` if it is already absolute
+/// or cannot be resolved, in which case it is left as it is.
+fn absolutize(value: &str, base: &Url) -> Option<String> {
+  let value = value.trim_matches(|c: char| c.is_ascii_whitespace());
+  if Url::parse(value).is_ok() {
+    return None;
+  }
+  base.join(value).ok().map(String::from)
+}
+
+/// Splits a `srcset` attribute into its candidates' URLs and descriptors,
+/// the descriptors being empty when there are none.
+/// https://html.spec.whatwg.org/multipage/images.html#parsing-a-srcset-attribute
+pub(crate) fn parse_srcset(srcset: &str) -> Vec<(String, String)> {
+  let mut candidates = vec![];
+  let mut rest = srcset;
+  loop {
+    rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+    if rest.is_empty() {
+      break;
+    }
+    let url_end = rest
+      .find(|c: char| c.is_ascii_whitespace())
+      .unwrap_or(rest.len());
+    let (url, after) = rest.split_at(url_end);
+    // A URL ending in commas has no descriptors.
+    let trimmed = url.trim_end_matches(',');
+    let (descriptors, after) = if trimmed.len() < url.len() {
+      ("", after)
+    } else {
+      let mut depth = 0;
+      let end = after
+        .char_indices()
+        .find(|&(_, c)| match c {
+          '(' => {
+            depth += 1;
+            false
+          }
+          ')' => {
+            depth -= 1;
+            false
+          }
+          ',' => depth <= 0,
+          _ => false,
+        })
+        .map_or(after.len(), |(index, _)| index);
+      (after[..end].trim(), &after[end..])
+    };
+    candidates.push((trimmed.to_string(), descriptors.to_string()));
+    rest = after;
+  }
+  candidates
+}
+
+/// Rewrites each URL of a `srcset` attribute, keeping its descriptors.
+fn absolutize_srcset(srcset: &str, base: &Url) -> String {
+  parse_srcset(srcset)
+    .into_iter()
+    .map(|(url, descriptors)| {
+      let url = absolutize(&url, base).unwrap_or(url);
+      if descriptors.is_empty() {
+        url
+      } else {
+        format!("{} {}", url, descriptors)
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// A URL referenced from CSS by `url()` or `@import`.
+pub(crate) struct CssUrl {
+  /// Byte range of the token holding the URL: the whole `url(...)` if it is
+  /// unquoted, otherwise the string.
+  pub start: usize,
+  pub end: usize,
+  pub url: String,
+  pub unquoted: bool,
+}
+
+fn collect_css_urls(parser: &mut Parser, urls: &mut Vec<CssUrl>) {
+  let mut after_import = false;
+  loop {
+    let start = parser.position().byte_index();
+    let Ok(token) = parser.next_including_whitespace_and_comments() else {
+      return;
+    };
+    let token = token.clone();
+    let end = parser.position().byte_index();
+    match &token {
+      Token::UnquotedUrl(url) => urls.push(CssUrl {
+        start,
+        end,
+        url: url.to_string(),
+        unquoted: true,
+      }),
+      Token::QuotedString(url) if after_import => urls.push(CssUrl {
+        start,
+        end,
+        url: url.to_string(),
+        unquoted: false,
+      }),
+      Token::Function(name) if name.eq_ignore_ascii_case("url") => {
+        let _ = parser.parse_nested_block(|parser| {
+          parser.skip_whitespace();
+          let start = parser.position().byte_index();
+          if let Ok(Token::QuotedString(url)) = parser.next_including_whitespace() {
+            let url = url.to_string();
+            urls.push(CssUrl {
+              start,
+              end: parser.position().byte_index(),
+              url,
+              unquoted: false,
+            });
+          }
+          Ok::<(), cssparser::ParseError<()>>(())
+        });
+      }
+      Token::Function(_)
+      | Token::ParenthesisBlock
+      | Token::SquareBracketBlock
+      | Token::CurlyBracketBlock => {
+        let _ = parser.parse_nested_block(|parser| {
+          collect_css_urls(parser, urls);
+          Ok::<(), cssparser::ParseError<()>>(())
+        });
+      }
+      _ => {}
+    }
+    match &token {
+      Token::AtKeyword(name) => after_import = name.eq_ignore_ascii_case("import"),
+      Token::WhiteSpace(_) | Token::Comment(_) => {}
+      _ => after_import = false,
+    }
+  }
+}
+
+/// Finds the `url()` and `@import` URLs of a style sheet or declaration list, in source order.
+pub(crate) fn css_urls(css: &str) -> Vec<CssUrl> {
+  let mut input = ParserInput::new(css);
+  let mut parser = Parser::new(&mut input);
+  let mut urls = vec![];
+  collect_css_urls(&mut parser, &mut urls);
+  urls
+}
+
+/// Rewrites the relative URLs of a style sheet or `style` attribute.
+fn absolutize_css(css: &str, base: &Url) -> Option<String> {
+  let mut result = String::with_capacity(css.len());
+  let mut last = 0;
+  for css_url in css_urls(css) {
+    // Fragment references point into the document itself.
+    if css_url.url.starts_with('#') {
+      continue;
+    }
+    let Some(url) = absolutize(&css_url.url, base) else {
+      continue;
+    };
+    result.push_str(&css[last..css_url.start]);
+    if css_url.unquoted {
+      result.push_str("url(");
+    }
+    cssparser::serialize_string(&url, &mut result).unwrap();
+    if css_url.unquoted {
+      result.push(')');
+    }
+    last = css_url.end;
+  }
+  if last == 0 {
+    return None;
+  }
+  result.push_str(&css[last..]);
+  Some(result)
+}
+
+fn absolutize_attributes(
+  element: &Handle,
+  attrs: &mut [Attribute],
+  base: &Url,
+  document: Option<&Url>,
+) {
+  let NodeData::Element { name, .. } = &element.data else {
+    return;
+  };
+  let element_name = name.local.as_ref();
+  let is_html = name.ns == ns!(html);
+  let is_svg = name.ns == ns!(svg);
+  for attr in attrs.iter_mut() {
+    let attr_name = attr.name.local.as_ref();
+    let listed = |lists: &[(&str, &[&str])]| {
+      is_html
+        && attr.name.ns == ns!()
+        && lists
+          .iter()
+          .any(|(name, elements)| *name == attr_name && elements.contains(&element_name))
+    };
+    let is_form_action = matches!(
+      (element_name, attr_name),
+      ("form", "action") | ("button" | "input", "formaction")
+    );
+    let value = if listed(URL_ATTRIBUTES) && is_form_action && attr.value.is_empty() {
+      // An empty action submits to the document, not to the base URL.
+      document.map(Url::to_string)
+    } else if listed(URL_ATTRIBUTES) {
+      absolutize(&attr.value, base)
+    } else if listed(SRCSET_ATTRIBUTES) {
+      Some(absolutize_srcset(&attr.value, base))
+    } else if is_svg && attr_name == "href" && (attr.name.ns == ns!() || attr.name.ns == ns!(xlink))
+    {
+      // Fragment references point into the SVG document itself.
+      (!attr.value.starts_with('#'))
+        .then(|| absolutize(&attr.value, base))
+        .flatten()
+    } else if attr.name.ns == ns!() && attr_name == "style" {
+      absolutize_css(&attr.value, base)
+    } else {
+      None
+    };
+    if let Some(value) = value {
+      attr.value = value.into();
+    }
+  }
+}
+
+fn absolutize_tree(node: &Handle, base: &Url, document: Option<&Url>) {
+  if let NodeData::Element { name, attrs, .. } = &node.data {
+    absolutize_attributes(node, &mut attrs.borrow_mut(), base, document);
+    if name.local == local_name!("style") && (name.ns == ns!(html) || name.ns == ns!(svg)) {
+      let css: String = node
+        .children
+        .borrow()
+        .iter()
+        .filter_map(|child| match &child.data {
+          NodeData::Text { contents } => Some(contents.borrow().to_string()),
+          _ => None,
+        })
+        .collect();
+      if let Some(css) = absolutize_css(&css, base) {
+        DomNode(node.clone()).set_text_content(css);
+      }
+      return;
+    }
+  }
+  for child in node.children.borrow().iter() {
+    absolutize_tree(child, base, document);
+  }
+  if let Some(contents) = template_contents(node) {
+    absolutize_tree(&contents, base, document);
+  }
+  if let Some(root) = attached_shadow_root(node) {
+    absolutize_tree(&root, base, document);
+  }
+}
+
+impl DomNode {
+  /// Returns the URL of a document, `about:blank` if it has none, or `None` for other nodes.
+  pub fn url(&self) -> Option<String> {
+    match self.0.data {
+      NodeData::Document => {
+        Some(document_url(&self.0).map_or_else(|| "about:blank".to_string(), String::from))
+      }
+      _ => None,
+    }
+  }
+
+  /// The URL relative URLs in this node are resolved against: the first
+  /// `<base href>` of its document resolved against the document URL, or the
+  /// document URL. `None` for `about:blank` and nodes outside a document.
+  pub(crate) fn base_url(&self) -> Option<Url> {
+    document_base_url(&self.owner_document()?.0)
+  }
+
+  /// Returns the absolute base URL of the node, `about:blank` if it has none.
+  pub fn base_uri(&self) -> String {
+    self
+      .base_url()
+      .map_or_else(|| "about:blank".to_string(), String::from)
+  }
+
+  /// Rewrites the relative URLs in this node and its descendants to absolute ones:
+  /// URL attributes such as `href`, `src`, `action` and `poster`, `srcset`
+  /// candidates, SVG `href`s and `url()`s in `style` attributes and `<style>`
+  /// elements. URLs are resolved against `base`, or the node's base URL if it
+  /// is `None`; without either nothing changes. Empty form actions become the
+  /// document URL they submit to, and stay empty without one.
+  pub fn absolutize_urls(&self, base: Option<&Url>) {
+    let base = match base {
+      Some(base) => base.clone(),
+      None => match self.base_url() {
+        Some(base) => base,
+        None => return,
+      },
+    };
+    let document = self
+      .owner_document()
+      .and_then(|document| document_url(&document.0));
+    absolutize_tree(&self.0, &base, document.as_ref());
+  }
+}
@@ -0,0 +1,317 @@
+use html5ever::tree_builder::TreeBuilderOpts;
+use html5ever::{ns, LocalName, ParseOpts, QualName};
+use markup5ever_rcdom::{Handle, Node, NodeData, WeakHandle};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use url::Url;
+
+use super::location::{SourceLocation, SourceLocations};
+use super::DomNode;
+
+const HTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// Per-document data that `NodeData::Document` has no room for.
+/// Documents without an entry are HTML documents produced by `parse`.
+#[derive(Clone, Debug)]
+struct DocumentState {
+  is_html: bool,
+  content_type: &'static str,
+  /// The scripting mode the document was parsed with, which decides whether
+  /// `<noscript>` holds raw text. `parse` uses html5ever's default, enabled.
+  scripting_enabled: bool,
+  /// The document's URL; `None` stands for `about:blank`.
+  url: Option<Url>,
+  /// The locations of the nodes parsed into the document, when parsed with source locations.
+  source_locations: Option<Rc<SourceLocations>>,
+}
+
+impl Default for DocumentState {
+  fn default() -> Self {
+    DocumentState {
+      is_html: true,
+      content_type: "text/html",
+      scripting_enabled: true,
+      url: None,
+      source_locations: None,
+    }
+  }
+}
+
+thread_local! {
+  static DOCUMENT_STATES: RefCell<HashMap<usize, (WeakHandle, DocumentState)>> =
+    RefCell::new(HashMap::new());
+  /// Whether `DOCUMENT_STATES` has an entry for an XML document, which may have been
+  /// dropped since. Without one every node is in an HTML document, which spares
+  /// `tagName` and friends from walking to the root.
+  static HAS_XML_DOCUMENTS: Cell<bool> = const { Cell::new(false) };
+}
+
+fn set_document_state(document: &Handle, state: DocumentState) {
+  DOCUMENT_STATES.with(|states| {
+    let mut states = states.borrow_mut();
+    states.retain(|_, (weak, _)| weak.strong_count() > 0);
+    states.insert(
+      Rc::as_ptr(document) as usize,
+      (Rc::downgrade(document), state),
+    );
+    HAS_XML_DOCUMENTS.set(states.values().any(|(_, state)| !state.is_html));
+  });
+}
+
+fn document_state(document: &Handle) -> DocumentState {
+  DOCUMENT_STATES.with(|states| {
+    states
+      .borrow()
+      .get(&(Rc::as_ptr(document) as usize))
+      .filter(|(weak, _)| weak.strong_count() > 0)
+      .map(|(_, state)| state.clone())
+      .unwrap_or_default()
+  })
+}
+
+/// Sets the URL of a document, which relative URLs in it are resolved against.
+pub(crate) fn set_document_url(document: &Handle, url: Url) {
+  let state = document_state(document);
+  set_document_state(
+    document,
+    DocumentState {
+      url: Some(url),
+      ..state
+    },
+  );
+}
+
+/// Keeps the source locations of the nodes parsed into `document` for as long as it lives.
+pub(crate) fn set_source_locations(document: &Handle, locations: SourceLocations) {
+  let state = document_state(document);
+  set_document_state(
+    document,
+    DocumentState {
+      source_locations: Some(Rc::new(locations)),
+      ..state
+    },
+  );
+}
+
+/// The source location of `node` in the document it was parsed into, wherever it is now.
+pub(crate) fn find_source_location(node: &Handle) -> Option<SourceLocation> {
+  let key = Rc::as_ptr(node) as usize;
+  DOCUMENT_STATES.with(|states| {
+    states
+      .borrow()
+      .values()
+      .filter(|(document, _)| document.strong_count() > 0)
+      .filter_map(|(_, state)| state.source_locations.as_ref()?.get(&key))
+      .find(|(weak, _)| weak.strong_count() > 0)
+      .map(|(_, location)| location.clone())
+  })
+}
+
+/// The URL of a document, or `None` if it is `about:blank`.
+pub(crate) fn document_url(document: &Handle) -> Option<Url> {
+  document_state(document).url
+}
+
+/// Creates an empty document with the given content type, which must be one the
+/// documents of this crate can have.
+pub(crate) fn new_document_of_type(
+  content_type: &str,
+  scripting_enabled: bool,
+) -> Result<Handle, String> {
+  let content_type = match content_type {
+    "text/html" => "text/html",
+    "application/xhtml+xml" => "application/xhtml+xml",
+    "image/svg+xml" => "image/svg+xml",
+    "application/xml" => "application/xml",
+    other => return Err(format!("'{}' is not a supported document type.", other)),
+  };
+  let document = Node::new(NodeData::Document);
+  set_document_state(
+    &document,
+    DocumentState {
+      is_html: content_type == "text/html",
+      content_type,
+      scripting_enabled,
+      url: None,
+      source_locations: None,
+    },
+  );
+  Ok(document)
+}
+
+impl DomNode {
+  /// Creates a new empty XML document, like `new Document()`.
+  pub fn new_document() -> DomNode {
+    let document = Node::new(NodeData::Document);
+    set_document_state(
+      &document,
+      DocumentState {
+        is_html: false,
+        content_type: "application/xml",
+        scripting_enabled: false,
+        url: None,
+        source_locations: None,
+      },
+    );
+    DomNode(document)
+  }
+
+  /// Creates an HTML document with the basic doctype/html/head/body skeleton,
+  /// and a `<title>` element if `title` is given.
+  pub fn create_html_document(title: Option<String>) -> DomNode {
+    let document = DomNode(Node::new(NodeData::Document));
+    // Created documents have no browsing context, so scripting is disabled.
+    set_document_state(
+      &document.0,
+      DocumentState {
+        scripting_enabled: false,
+        ..DocumentState::default()
+      },
+    );
+    document.append(&DomNode(Node::new(NodeData::Doctype {
+      name: "html".into(),
+      public_id: "".into(),
+      system_id: "".into(),
+    })));
+
+    let html = document.create_element("html".to_string());
+    let head = document.create_element("head".to_string());
+    if let Some(title) = title {
+      let title_element = document.create_element("title".to_string());
+      title_element.append(&document.create_text_node(title));
+      head.append(&title_element);
+    }
+    html.append(&head);
+    html.append(&document.create_element("body".to_string()));
+    document.append(&html);
+    document
+  }
+
+  /// Creates an XML document, with an optional doctype and document element.
+  /// The content type is derived from `namespace`, like the browser's `createDocument`.
+  pub fn create_document(
+    namespace: Option<String>,
+    qualified_name: String,
+    doctype: Option<&DomNode>,
+  ) -> Result<DomNode, String> {
+    let document = DomNode::new_document();
+    let content_type = match namespace.as_deref() {
+      Some(HTML_NAMESPACE) => "application/xhtml+xml",
+      Some(SVG_NAMESPACE) => "image/svg+xml",
+      _ => "application/xml",
+    };
+    let element = if qualified_name.is_empty() {
+      None
+    } else {
+      Some(document.create_element_ns(namespace, qualified_name)?)
+    };
+
+    if let Some(doctype) = doctype {
+      if !matches!(doctype.0.data, NodeData::Doctype { .. }) {
+        return Err("The doctype provided is not a DocumentType node.".to_string());
+      }
+      document.append(doctype);
+    }
+    if let Some(element) = element {
+      document.append(&element);
+    }
+    set_document_state(
+      &document.0,
+      DocumentState {
+        is_html: false,
+        content_type,
+        scripting_enabled: false,
+        url: None,
+        source_locations: None,
+      },
+    );
+    Ok(document)
+  }
+
+  /// Creates a detached DocumentType node.
+  pub fn create_document_type(
+    qualified_name: String,
+    public_id: String,
+    system_id: String,
+  ) -> Result<DomNode, String> {
+    if qualified_name
+      .chars()
+      .any(|c| c.is_ascii_whitespace() || c == '\0' || c == '>')
+    {
+      return Err(format!(
+        "The doctype name '{}' is not a valid name.",
+        qualified_name
+      ));
+    }
+    Ok(DomNode(Node::new(NodeData::Doctype {
+      name: qualified_name.into(),
+      public_id: public_id.into(),
+      system_id: system_id.into(),
+    })))
+  }
+
+  /// Returns the MIME type of the document, or `None` if this is not a document node.
+  pub fn content_type(&self) -> Option<String> {
+    match self.0.data {
+      NodeData::Document => Some(document_state(&self.0).content_type.to_string()),
+      _ => None,
+    }
+  }
+
+  /// Whether this node belongs to an HTML document, as opposed to an XML document.
+  /// Detached nodes are treated as belonging to an HTML document.
+  pub(crate) fn is_in_html_document(&self) -> bool {
+    if !HAS_XML_DOCUMENTS.get() {
+      return true;
+    }
+    let root = self.get_root_node();
+    match root.0.data {
+      NodeData::Document => document_state(&root.0).is_html,
+      _ => true,
+    }
+  }
+
+  /// Whether the document this node belongs to was parsed with scripting enabled.
+  /// Detached nodes use the parser default, enabled.
+  pub(crate) fn scripting_enabled(&self) -> bool {
+    match self.owner_document() {
+      Some(document) => document_state(&document.0).scripting_enabled,
+      None => true,
+    }
+  }
+
+  /// Options for parsing markup into this node's document, such as `innerHTML`.
+  pub(crate) fn fragment_parse_opts(&self) -> ParseOpts {
+    ParseOpts {
+      tree_builder: TreeBuilderOpts {
+        scripting_enabled: self.scripting_enabled(),
+        ..Default::default()
+      },
+      ..Default::default()
+    }
+  }
+
+  /// The element a `create_element` call on this node produces for `tag_name`.
+  /// HTML documents lowercase the name, XML documents keep it and only use the
+  /// HTML namespace for XHTML documents.
+  pub(crate) fn element_name_for(&self, tag_name: String) -> QualName {
+    let state = if HAS_XML_DOCUMENTS.get() {
+      let root = self.get_root_node();
+      match root.0.data {
+        NodeData::Document => document_state(&root.0),
+        _ => DocumentState::default(),
+      }
+    } else {
+      DocumentState::default()
+    };
+    if state.is_html {
+      QualName::new(None, ns!(html), LocalName::from(tag_name.to_lowercase()))
+    } else if state.content_type == "application/xhtml+xml" {
+      QualName::new(None, ns!(html), LocalName::from(tag_name))
+    } else {
+      QualName::new(None, ns!(), LocalName::from(tag_name))
+    }
+  }
+}
@@ -0,0 +1,204 @@
+use html5ever::Attribute;
+use markup5ever_rcdom::{Handle, NodeData};
+
+use super::DomNode;
+
+/// The first place where two trees are not equal, as found by [`DomNode::diff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeDifference {
+  /// Child indices leading from the compared nodes down to the nodes that differ.
+  pub path: Vec<usize>,
+  /// What differs: `nodeType`, `name`, `publicId`, `systemId`, `namespaceURI`,
+  /// `prefix`, `localName`, `attributes`, `target`, `data` or `childNodes.length`.
+  pub property: &'static str,
+  /// The value on this node, or `None` if it has none.
+  pub value: Option<String>,
+  /// The value on the other node, or `None` if it has none.
+  pub other_value: Option<String>,
+}
+
+fn difference(
+  property: &'static str,
+  value: Option<String>,
+  other_value: Option<String>,
+) -> Option<NodeDifference> {
+  Some(NodeDifference {
+    path: vec![],
+    property,
+    value,
+    other_value,
+  })
+}
+
+fn compare_str(property: &'static str, value: &str, other_value: &str) -> Option<NodeDifference> {
+  if value == other_value {
+    return None;
+  }
+  difference(
+    property,
+    Some(value.to_string()),
+    Some(other_value.to_string()),
+  )
+}
+
+fn optional_str(value: &str) -> Option<String> {
+  (!value.is_empty()).then(|| value.to_string())
+}
+
+fn attribute_string(attr: &Attribute) -> String {
+  match &attr.name.prefix {
+    Some(prefix) => format!("{}:{}=\"{}\"", prefix, attr.name.local, attr.value),
+    None => format!("{}=\"{}\"", attr.name.local, attr.value),
+  }
+}
+
+fn same_attribute(attr: &Attribute, other: &Attribute) -> bool {
+  attr.name.ns == other.name.ns && attr.name.local == other.name.local && attr.value == other.value
+}
+
+/// Compares attribute lists regardless of order. The reported attribute is the
+/// first one on either side without an equal counterpart, paired with the
+/// attribute of the same name on the other side if there is one.
+fn compare_attributes(attrs: &[Attribute], other_attrs: &[Attribute]) -> Option<NodeDifference> {
+  let counterpart = |attr: &Attribute, attrs: &[Attribute]| {
+    attrs
+      .iter()
+      .find(|other| other.name.ns == attr.name.ns && other.name.local == attr.name.local)
+      .map(attribute_string)
+  };
+  if let Some(attr) = attrs
+    .iter()
+    .find(|attr| !other_attrs.iter().any(|other| same_attribute(attr, other)))
+  {
+    return difference(
+      "attributes",
+      Some(attribute_string(attr)),
+      counterpart(attr, other_attrs),
+    );
+  }
+  if let Some(other) = other_attrs
+    .iter()
+    .find(|other| !attrs.iter().any(|attr| same_attribute(attr, other)))
+  {
+    return difference(
+      "attributes",
+      counterpart(other, attrs),
+      Some(attribute_string(other)),
+    );
+  }
+  if attrs.len() != other_attrs.len() {
+    return difference(
+      "attributes",
+      Some(attrs.len().to_string()),
+      Some(other_attrs.len().to_string()),
+    );
+  }
+  None
+}
+
+/// Compares the node itself, without its children, following the DOM "equals" algorithm.
+fn compare_node(node: &Handle, other: &Handle) -> Option<NodeDifference> {
+  let node_type = DomNode(node.clone()).node_type();
+  let other_type = DomNode(other.clone()).node_type();
+  if node_type != other_type {
+    return difference(
+      "nodeType",
+      Some(node_type.to_string()),
+      Some(other_type.to_string()),
+    );
+  }
+
+  match (&node.data, &other.data) {
+    (
+      NodeData::Doctype {
+        name,
+        public_id,
+        system_id,
+      },
+      NodeData::Doctype {
+        name: other_name,
+        public_id: other_public_id,
+        system_id: other_system_id,
+      },
+    ) => compare_str("name", name, other_name)
+      .or_else(|| compare_str("publicId", public_id, other_public_id))
+      .or_else(|| compare_str("systemId", system_id, other_system_id)),
+    (
+      NodeData::Element { name, attrs, .. },
+      NodeData::Element {
+        name: other_name,
+        attrs: other_attrs,
+        ..
+      },
+    ) if node_type == 1 => {
+      if name.ns != other_name.ns {
+        return difference(
+          "namespaceURI",
+          optional_str(&name.ns),
+          optional_str(&other_name.ns),
+        );
+      }
+      if name.prefix != other_name.prefix {
+        return difference(
+          "prefix",
+          name.prefix.as_ref().map(|p| p.to_string()),
+          other_name.prefix.as_ref().map(|p| p.to_string()),
+        );
+      }
+      compare_str("localName", &name.local, &other_name.local)
+        .or_else(|| compare_attributes(&attrs.borrow(), &other_attrs.borrow()))
+    }
+    (NodeData::Text { contents }, NodeData::Text { contents: other }) => {
+      compare_str("data", &contents.borrow(), &other.borrow())
+    }
+    (NodeData::Comment { contents }, NodeData::Comment { contents: other }) => {
+      compare_str("data", contents, other)
+    }
+    (
+      NodeData::ProcessingInstruction { target, contents },
+      NodeData::ProcessingInstruction {
+        target: other_target,
+        contents: other_contents,
+      },
+    ) => compare_str("target", target, other_target)
+      .or_else(|| compare_str("data", contents, other_contents)),
+    _ => None,
+  }
+}
+
+fn compare_tree(node: &Handle, other: &Handle) -> Option<NodeDifference> {
+  if let Some(difference) = compare_node(node, other) {
+    return Some(difference);
+  }
+  let children = node.children.borrow();
+  let other_children = other.children.borrow();
+  for (index, (child, other_child)) in children.iter().zip(other_children.iter()).enumerate() {
+    if let Some(mut difference) = compare_tree(child, other_child) {
+      difference.path.insert(0, index);
+      return Some(difference);
+    }
+  }
+  if children.len() != other_children.len() {
+    return difference(
+      "childNodes.length",
+      Some(children.len().to_string()),
+      Some(other_children.len().to_string()),
+    );
+  }
+  None
+}
+
+impl DomNode {
+  /// Returns whether the two nodes are equal as defined by the DOM: same node
+  /// type, names, data and attributes (in any order), and equal children.
+  /// Template contents and shadow roots are not compared.
+  pub fn is_equal_node(&self, other_node: &DomNode) -> bool {
+    compare_tree(&self.0, &other_node.0).is_none()
+  }
+
+  /// Returns the first difference that makes `is_equal_node` false, or `None`
+  /// if the nodes are equal. Meant for test assertions.
+  pub fn diff(&self, other_node: &DomNode) -> Option<NodeDifference> {
+    compare_tree(&self.0, &other_node.0)
+  }
+}
@@ -0,0 +1,121 @@
+use html5ever::ns;
+use markup5ever_rcdom::{Handle, NodeData};
+
+use super::DomNode;
+
+/// Elements that can have a form owner and are listed in `form.elements`, with the
+/// exception of image buttons.
+/// https://html.spec.whatwg.org/multipage/forms.html#category-listed
+const LISTED_ELEMENTS: &[&str] = &[
+  "button", "fieldset", "input", "object", "output", "select", "textarea",
+];
+
+/// Listed elements that can be disabled.
+const DISABLEABLE_ELEMENTS: &[&str] = &["button", "fieldset", "input", "select", "textarea"];
+
+fn html_local_name(handle: &Handle) -> Option<&str> {
+  match &handle.data {
+    NodeData::Element { name, .. } if name.ns == ns!(html) => Some(name.local.as_ref()),
+    _ => None,
+  }
+}
+
+impl DomNode {
+  /// Returns the form owner of a listed element: the `form` its `form` attribute names by
+  /// id, or else its nearest ancestor `form`.
+  pub fn form(&self) -> Option<DomNode> {
+    let name = html_local_name(&self.0)?;
+    if !LISTED_ELEMENTS.contains(&name) {
+      return None;
+    }
+    if let Some(id) = self.get_attribute("form".to_string()) {
+      return self
+        .get_root_node()
+        .get_element_by_id(id)
+        .filter(|form| html_local_name(&form.0) == Some("form"));
+    }
+    let mut current = self.parent_element();
+    while let Some(element) = current {
+      if html_local_name(&element.0) == Some("form") {
+        return Some(element);
+      }
+      current = element.parent_element();
+    }
+    None
+  }
+
+  /// Returns the listed elements whose form owner is this `form`, in tree order, as
+  /// `form.elements` does. Image buttons are left out.
+  pub fn elements(&self) -> Option<Vec<DomNode>> {
+    Some(
+      self
+        .form_associated_elements()?
+        .into_iter()
+        .filter(|element| element.input_type().as_deref() != Some("image"))
+        .collect(),
+    )
+  }
+
+  /// The listed elements whose form owner is this `form`, in tree order, image buttons included.
+  pub(crate) fn form_associated_elements(&self) -> Option<Vec<DomNode>> {
+    if html_local_name(&self.0)? != "form" {
+      return None;
+    }
+    let mut found = vec![];
+    let mut stack = vec![self.get_root_node().0];
+    while let Some(node) = stack.pop() {
+      if html_local_name(&node).is_some_and(|name| LISTED_ELEMENTS.contains(&name)) {
+        let element = DomNode(node.clone());
+        if element.form().is_some_and(|form| form.is_same_node(self)) {
+          found.push(element);
+        }
+      }
+      stack.extend(node.children.borrow().iter().rev().cloned());
+    }
+    Some(found)
+  }
+
+  /// Returns whether a form control is disabled, by its own `disabled` attribute or by a
+  /// disabled `fieldset` ancestor, unless it is in that fieldset's first `legend`.
+  pub fn is_disabled(&self) -> bool {
+    if !html_local_name(&self.0).is_some_and(|name| DISABLEABLE_ELEMENTS.contains(&name)) {
+      return false;
+    }
+    if self.has_attribute("disabled".to_string()) {
+      return true;
+    }
+    let mut child = self.clone();
+    let mut current = self.parent_element();
+    while let Some(element) = current {
+      if html_local_name(&element.0) == Some("fieldset")
+        && element.has_attribute("disabled".to_string())
+      {
+        let first_legend = element
+          .0
+          .children
+          .borrow()
+          .iter()
+          .find(|c| html_local_name(c) == Some("legend"))
+          .cloned();
+        if !first_legend.is_some_and(|legend| DomNode(legend).is_same_node(&child)) {
+          return true;
+        }
+      }
+      child = element.clone();
+      current = element.parent_element();
+    }
+    false
+  }
+
+  /// Returns whether this is a submit button: a `button` of type `submit`, which is the
+  /// default, or an `input` of type `submit` or `image`.
+  pub(crate) fn is_submit_button(&self) -> bool {
+    match html_local_name(&self.0) {
+      Some("button") => self
+        .get_attribute("type".to_string())
+        .is_none_or(|kind| !matches!(kind.to_ascii_lowercase().as_str(), "reset" | "button")),
+      Some("input") => matches!(self.input_type().as_deref(), Some("submit" | "image")),
+      _ => false,
+    }
+  }
+}
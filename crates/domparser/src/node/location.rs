@@ -0,0 +1,604 @@
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::{
+  BufferQueue, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use html5ever::tree_builder::{
+  ElementFlags, NodeOrText, QuirksMode, Tracer, TreeBuilder, TreeBuilderOpts, TreeSink,
+};
+use html5ever::{Attribute, LocalName, QualName};
+use markup5ever_rcdom::{Handle, NodeData, RcDom, WeakHandle};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::DomNode;
+
+/// A point in the parsed input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourcePosition {
+  /// The byte offset from the start of the input.
+  pub offset: usize,
+  /// The 1-based line number.
+  pub line: usize,
+  /// The 1-based column, counted in characters.
+  pub column: usize,
+}
+
+/// A range of the parsed input, such as a start tag or an attribute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceSpan {
+  pub start: SourcePosition,
+  pub end: SourcePosition,
+}
+
+/// Where a node came from in the input of a parse with source locations enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceLocation {
+  /// The start of the node; the start of its start tag for elements.
+  pub start: SourcePosition,
+  /// The end of the node. For elements this is the end of the end tag, or
+  /// of the last located descendant or the start tag if there is none.
+  pub end: SourcePosition,
+  /// The start tag of an element.
+  pub start_tag: Option<SourceSpan>,
+  /// The end tag of an element, if it had one in the input.
+  pub end_tag: Option<SourceSpan>,
+  /// The attributes of the start tag by qualified name, in source order.
+  pub attributes: Vec<(String, SourceSpan)>,
+}
+
+/// The locations of the nodes parsed into a document, keyed by node address. The document
+/// state owns them, so they are dropped along with the document.
+pub(crate) type SourceLocations = HashMap<usize, (WeakHandle, SourceLocation)>;
+
+/// Byte offsets of a node found while parsing.
+#[derive(Clone, Copy, Default)]
+struct Record {
+  span: (usize, usize),
+  start_tag: Option<(usize, usize)>,
+  end_tag: Option<(usize, usize)>,
+}
+
+/// Wraps `RcDom` to see which nodes the tree builder creates, appends and pops
+/// while each token is processed.
+struct LocationSink {
+  dom: RcDom,
+  token: Cell<(usize, usize)>,
+  created: RefCell<Vec<Handle>>,
+  popped: RefCell<Vec<Handle>>,
+  records: RefCell<HashMap<usize, (Handle, Record)>>,
+}
+
+impl LocationSink {
+  fn record(&self, node: &Handle) {
+    let token = self.token.get();
+    let mut records = self.records.borrow_mut();
+    let (_, record) = records.entry(Rc::as_ptr(node) as usize).or_insert_with(|| {
+      (
+        node.clone(),
+        Record {
+          span: token,
+          ..Default::default()
+        },
+      )
+    });
+    record.span.1 = token.1;
+  }
+
+  /// Records the text node that text was just appended to or merged into.
+  fn record_text(&self, text: Option<Handle>) {
+    if let Some(text) = text.filter(|node| matches!(node.data, NodeData::Text { .. })) {
+      self.record(&text);
+    }
+  }
+
+  fn tag_processed(&self, kind: TagKind, name: &LocalName) {
+    let same_name = |node: &&Handle| match &node.data {
+      NodeData::Element { name: element, .. } => element.local.eq_ignore_ascii_case(name),
+      _ => false,
+    };
+    let token = self.token.get();
+    let mut records = self.records.borrow_mut();
+    match kind {
+      TagKind::StartTag => {
+        if let Some(element) = self.created.borrow().iter().rev().find(same_name) {
+          let record = Record {
+            span: token,
+            start_tag: Some(token),
+            end_tag: None,
+          };
+          records.insert(Rc::as_ptr(element) as usize, (element.clone(), record));
+        }
+      }
+      TagKind::EndTag => {
+        if let Some(element) = self.popped.borrow().iter().rev().find(same_name) {
+          if let Some((_, record)) = records.get_mut(&(Rc::as_ptr(element) as usize)) {
+            record.end_tag.get_or_insert(token);
+          }
+        }
+      }
+    }
+  }
+}
+
+fn sibling_before(sibling: &Handle) -> Option<Handle> {
+  let parent = super::get_parent(sibling)?;
+  let children = parent.children.borrow();
+  let index = children
+    .iter()
+    .position(|child| Rc::ptr_eq(child, sibling))?;
+  index.checked_sub(1).map(|index| children[index].clone())
+}
+
+impl TreeSink for LocationSink {
+  type Handle = Handle;
+  type Output = RcDom;
+  type ElemName<'a> = <RcDom as TreeSink>::ElemName<'a>;
+
+  fn finish(self) -> RcDom {
+    self.dom
+  }
+
+  fn parse_error(&self, msg: Cow<'static, str>) {
+    self.dom.parse_error(msg)
+  }
+
+  fn get_document(&self) -> Handle {
+    self.dom.get_document()
+  }
+
+  fn elem_name<'a>(&'a self, target: &'a Handle) -> Self::ElemName<'a> {
+    self.dom.elem_name(target)
+  }
+
+  fn create_element(&self, name: QualName, attrs: Vec<Attribute>, flags: ElementFlags) -> Handle {
+    let element = self.dom.create_element(name, attrs, flags);
+    self.created.borrow_mut().push(element.clone());
+    element
+  }
+
+  fn create_comment(&self, text: StrTendril) -> Handle {
+    let comment = self.dom.create_comment(text);
+    self.record(&comment);
+    comment
+  }
+
+  fn create_pi(&self, target: StrTendril, data: StrTendril) -> Handle {
+    let pi = self.dom.create_pi(target, data);
+    self.record(&pi);
+    pi
+  }
+
+  fn append(&self, parent: &Handle, child: NodeOrText<Handle>) {
+    let is_text = matches!(child, NodeOrText::AppendText(_));
+    self.dom.append(parent, child);
+    if is_text {
+      self.record_text(parent.children.borrow().last().cloned());
+    }
+  }
+
+  fn append_based_on_parent_node(
+    &self,
+    element: &Handle,
+    prev_element: &Handle,
+    child: NodeOrText<Handle>,
+  ) {
+    if super::get_parent(element).is_some() {
+      self.append_before_sibling(element, child);
+    } else {
+      self.append(prev_element, child);
+    }
+  }
+
+  fn append_doctype_to_document(
+    &self,
+    name: StrTendril,
+    public_id: StrTendril,
+    system_id: StrTendril,
+  ) {
+    self
+      .dom
+      .append_doctype_to_document(name, public_id, system_id);
+    if let Some(doctype) = self.dom.document.children.borrow().last() {
+      self.record(doctype);
+    }
+  }
+
+  fn mark_script_already_started(&self, node: &Handle) {
+    self.dom.mark_script_already_started(node)
+  }
+
+  fn pop(&self, node: &Handle) {
+    self.popped.borrow_mut().push(node.clone());
+    self.dom.pop(node)
+  }
+
+  fn get_template_contents(&self, target: &Handle) -> Handle {
+    self.dom.get_template_contents(target)
+  }
+
+  fn same_node(&self, x: &Handle, y: &Handle) -> bool {
+    self.dom.same_node(x, y)
+  }
+
+  fn set_quirks_mode(&self, mode: QuirksMode) {
+    self.dom.set_quirks_mode(mode)
+  }
+
+  fn append_before_sibling(&self, sibling: &Handle, new_node: NodeOrText<Handle>) {
+    let is_text = matches!(new_node, NodeOrText::AppendText(_));
+    self.dom.append_before_sibling(sibling, new_node);
+    if is_text {
+      self.record_text(sibling_before(sibling));
+    }
+  }
+
+  fn add_attrs_if_missing(&self, target: &Handle, attrs: Vec<Attribute>) {
+    self.dom.add_attrs_if_missing(target, attrs)
+  }
+
+  fn associate_with_form(&self, target: &Handle, form: &Handle, nodes: (&Handle, Option<&Handle>)) {
+    self.dom.associate_with_form(target, form, nodes)
+  }
+
+  fn remove_from_parent(&self, target: &Handle) {
+    self.dom.remove_from_parent(target)
+  }
+
+  fn reparent_children(&self, node: &Handle, new_parent: &Handle) {
+    self.dom.reparent_children(node, new_parent)
+  }
+
+  fn is_mathml_annotation_xml_integration_point(&self, handle: &Handle) -> bool {
+    self.dom.is_mathml_annotation_xml_integration_point(handle)
+  }
+
+  fn set_current_line(&self, line_number: u64) {
+    self.dom.set_current_line(line_number)
+  }
+
+  fn allow_declarative_shadow_roots(&self, intended_parent: &Handle) -> bool {
+    self.dom.allow_declarative_shadow_roots(intended_parent)
+  }
+
+  fn attach_declarative_shadow(
+    &self,
+    location: &Handle,
+    template: &Handle,
+    attrs: &[Attribute],
+  ) -> bool {
+    self
+      .dom
+      .attach_declarative_shadow(location, template, attrs)
+  }
+
+  fn maybe_clone_an_option_into_selectedcontent(&self, option: &Handle) {
+    self.dom.maybe_clone_an_option_into_selectedcontent(option)
+  }
+}
+
+/// Collects the handles the tree builder holds on to, open elements first in stack order.
+struct HandleCollector(RefCell<Vec<Handle>>);
+
+impl Tracer for HandleCollector {
+  type Handle = Handle;
+
+  fn trace_handle(&self, node: &Handle) {
+    self.0.borrow_mut().push(node.clone());
+  }
+}
+
+/// Splits the input into the chunks it is fed to the tokenizer in. Every `<` is
+/// a chunk of its own and every `>` ends one, so no chunk holds the end of one
+/// tag and the start of another. Whitespace right after a tag is its own chunk
+/// too, as the tree builder may drop it before the text that follows.
+fn chunk_bounds(html: &str) -> Vec<(usize, usize)> {
+  let bytes = html.as_bytes();
+  let mut bounds = vec![0];
+  for (index, byte) in bytes.iter().enumerate() {
+    match byte {
+      b'<' => bounds.extend([index, index + 1]),
+      b'>' => {
+        bounds.push(index + 1);
+        let whitespace = bytes[index + 1..]
+          .iter()
+          .take_while(|byte| byte.is_ascii_whitespace())
+          .count();
+        bounds.push(index + 1 + whitespace);
+      }
+      _ => {}
+    }
+  }
+  bounds.push(html.len());
+  bounds.dedup();
+  bounds.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+/// Sits between the tokenizer and the tree builder to learn the byte range of
+/// each token. Tokens are emitted while the chunk they end in is fed, so a
+/// token ends at the end of that chunk and starts where the previous one ended.
+struct Tracker {
+  builder: TreeBuilder<Handle, LocationSink>,
+  offset: Cell<usize>,
+  last_end: Cell<usize>,
+}
+
+impl Tracker {
+  fn traced_handles(&self) -> Vec<Handle> {
+    let collector = HandleCollector(RefCell::new(vec![]));
+    self.builder.trace_handles(&collector);
+    collector.0.into_inner()
+  }
+}
+
+impl TokenSink for Tracker {
+  type Handle = Handle;
+
+  fn process_token(&self, token: Token, line_number: u64) -> TokenSinkResult<Handle> {
+    if matches!(token, Token::ParseError(_) | Token::EOFToken) {
+      return self.builder.process_token(token, line_number);
+    }
+    let sink = &self.builder.sink;
+    sink.token.set((self.last_end.get(), self.offset.get()));
+    self.last_end.set(self.offset.get());
+    let tag = match &token {
+      Token::TagToken(tag) => Some((tag.kind, tag.name.clone())),
+      _ => None,
+    };
+    // Most ways of closing elements leave the stack of open elements without
+    // telling the sink, so compare what the tree builder holds before and after.
+    let held = match tag {
+      Some((TagKind::EndTag, _)) => self.traced_handles(),
+      _ => vec![],
+    };
+    let result = self.builder.process_token(token, line_number);
+    if let Some((TagKind::EndTag, name)) = &tag {
+      let still_held = self.traced_handles();
+      // `</body>` and `</html>` only switch the insertion mode, so they close
+      // the open element of that name.
+      let closes_open = matches!(name.as_ref(), "body" | "html");
+      sink
+        .popped
+        .borrow_mut()
+        .extend(held.into_iter().filter(|node| {
+          let open = still_held.iter().any(|other| Rc::ptr_eq(node, other));
+          !open || closes_open
+        }));
+    }
+    if let Some((kind, name)) = tag {
+      sink.tag_processed(kind, &name);
+    }
+    sink.created.borrow_mut().clear();
+    sink.popped.borrow_mut().clear();
+    result
+  }
+
+  fn end(&self) {
+    self.builder.end()
+  }
+
+  fn adjusted_current_node_present_but_not_in_html_namespace(&self) -> bool {
+    self
+      .builder
+      .adjusted_current_node_present_but_not_in_html_namespace()
+  }
+}
+
+/// Finds the attributes of a start tag, as byte ranges of `name` or `name=value`
+/// relative to the tag, following the tokenizer's attribute states.
+fn scan_attributes(tag: &str) -> Vec<(String, (usize, usize))> {
+  let bytes = tag.as_bytes();
+  let is_space = |b: u8| matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b'\x0c');
+  let mut attributes = vec![];
+  let mut i = 1;
+  while i < bytes.len() && !is_space(bytes[i]) && bytes[i] != b'/' && bytes[i] != b'>' {
+    i += 1;
+  }
+  loop {
+    while i < bytes.len() && (is_space(bytes[i]) || bytes[i] == b'/') {
+      i += 1;
+    }
+    if i >= bytes.len() || bytes[i] == b'>' {
+      return attributes;
+    }
+    let start = i;
+    i += 1;
+    while i < bytes.len() && !is_space(bytes[i]) && !matches!(bytes[i], b'/' | b'>' | b'=') {
+      i += 1;
+    }
+    let name = tag[start..i].to_ascii_lowercase();
+    let mut end = i;
+    let mut j = i;
+    while j < bytes.len() && is_space(bytes[j]) {
+      j += 1;
+    }
+    if j < bytes.len() && bytes[j] == b'=' {
+      j += 1;
+      while j < bytes.len() && is_space(bytes[j]) {
+        j += 1;
+      }
+      match bytes.get(j) {
+        Some(&quote @ (b'"' | b'\'')) => {
+          j += 1;
+          while j < bytes.len() && bytes[j] != quote {
+            j += 1;
+          }
+          j = (j + 1).min(bytes.len());
+        }
+        _ => {
+          while j < bytes.len() && !is_space(bytes[j]) && bytes[j] != b'>' {
+            j += 1;
+          }
+        }
+      }
+      end = j;
+      i = j;
+    }
+    attributes.push((name, (start, end)));
+  }
+}
+
+/// Converts byte offsets to positions in a single pass over the input.
+struct Positions {
+  offsets: Vec<usize>,
+  positions: Vec<SourcePosition>,
+}
+
+impl Positions {
+  fn new(source: &str, mut offsets: Vec<usize>) -> Positions {
+    offsets.sort_unstable();
+    offsets.dedup();
+    let mut positions = Vec::with_capacity(offsets.len());
+    let (mut line, mut column, mut from) = (1, 1, 0);
+    for &offset in &offsets {
+      for c in source[from..offset].chars() {
+        if c == '\n' {
+          line += 1;
+          column = 1;
+        } else {
+          column += 1;
+        }
+      }
+      from = offset;
+      positions.push(SourcePosition {
+        offset,
+        line,
+        column,
+      });
+    }
+    Positions { offsets, positions }
+  }
+
+  fn get(&self, offset: usize) -> SourcePosition {
+    self.positions[self.offsets.binary_search(&offset).unwrap()]
+  }
+
+  fn span(&self, (start, end): (usize, usize)) -> SourceSpan {
+    SourceSpan {
+      start: self.get(start),
+      end: self.get(end),
+    }
+  }
+}
+
+/// Extends element records to their last located descendant when they have no end tag.
+fn close_elements(node: &Handle, records: &mut HashMap<usize, (Handle, Record)>) -> Option<usize> {
+  let mut end = None;
+  for child in node.children.borrow().iter() {
+    end = end.max(close_elements(child, records));
+  }
+  if let NodeData::Element {
+    template_contents, ..
+  } = &node.data
+  {
+    if let Some(contents) = template_contents.borrow().as_ref() {
+      end = end.max(close_elements(contents, records));
+    }
+  }
+  let (_, record) = records.get_mut(&(Rc::as_ptr(node) as usize))?;
+  if let Some(start_tag) = record.start_tag {
+    record.span.1 = match record.end_tag {
+      Some(end_tag) => end_tag.1,
+      None => end.unwrap_or(start_tag.1).max(start_tag.1),
+    };
+  }
+  Some(end.unwrap_or(0).max(record.span.1))
+}
+
+/// Parses a document like `parse`, recording the source location of every node
+/// that comes from a token of the input.
+pub(crate) fn parse_with_source_locations(html: &str) -> Handle {
+  let sink = LocationSink {
+    dom: RcDom::default(),
+    token: Cell::new((0, 0)),
+    created: RefCell::new(vec![]),
+    popped: RefCell::new(vec![]),
+    records: RefCell::new(HashMap::new()),
+  };
+  let tracker = Tracker {
+    builder: TreeBuilder::new(sink, TreeBuilderOpts::default()),
+    offset: Cell::new(0),
+    last_end: Cell::new(0),
+  };
+  let tokenizer = Tokenizer::new(tracker, TokenizerOpts::default());
+  let input = BufferQueue::default();
+  for (start, end) in chunk_bounds(html) {
+    tokenizer.sink.offset.set(end);
+    input.push_back(StrTendril::from_slice(&html[start..end]));
+    while !matches!(tokenizer.feed(&input), html5ever::TokenizerResult::Done) {}
+  }
+  tokenizer.end();
+
+  let sink = &tokenizer.sink.builder.sink;
+  let document = sink.dom.document.clone();
+  let mut records = sink.records.take();
+  close_elements(&document, &mut records);
+
+  let mut attributes = HashMap::new();
+  let mut offsets = vec![];
+  for (key, (node, record)) in &records {
+    offsets.extend([record.span.0, record.span.1]);
+    offsets.extend(record.end_tag.iter().flat_map(|tag| [tag.0, tag.1]));
+    let Some((tag_start, tag_end)) = record.start_tag else {
+      continue;
+    };
+    offsets.extend([tag_start, tag_end]);
+    let NodeData::Element { attrs, .. } = &node.data else {
+      continue;
+    };
+    let attrs = attrs.borrow();
+    let mut found = vec![];
+    for (name, (start, end)) in scan_attributes(&html[tag_start..tag_end]) {
+      let attr = attrs.iter().find(|attr| {
+        let qualified = match &attr.name.prefix {
+          Some(prefix) => format!("{}:{}", prefix, attr.name.local),
+          None => attr.name.local.to_string(),
+        };
+        qualified.eq_ignore_ascii_case(&name)
+      });
+      let Some(attr) = attr else {
+        continue;
+      };
+      let qualified = match &attr.name.prefix {
+        Some(prefix) => format!("{}:{}", prefix, attr.name.local),
+        None => attr.name.local.to_string(),
+      };
+      if found.iter().any(|(other, _)| *other == qualified) {
+        continue;
+      }
+      offsets.extend([tag_start + start, tag_start + end]);
+      found.push((qualified, (tag_start + start, tag_start + end)));
+    }
+    attributes.insert(*key, found);
+  }
+
+  let positions = Positions::new(html, offsets);
+  let mut locations = SourceLocations::new();
+  for (key, (node, record)) in records {
+    let location = SourceLocation {
+      start: positions.get(record.span.0),
+      end: positions.get(record.span.1),
+      start_tag: record.start_tag.map(|tag| positions.span(tag)),
+      end_tag: record.end_tag.map(|tag| positions.span(tag)),
+      attributes: attributes
+        .remove(&key)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, span)| (name, positions.span(span)))
+        .collect(),
+    };
+    locations.insert(key, (Rc::downgrade(&node), location));
+  }
+  super::document::set_source_locations(&document, locations);
+  document
+}
+
+impl DomNode {
+  /// Returns where the node came from in the input, if it was parsed with
+  /// source locations enabled. Nodes created by the API, implied by the parser
+  /// (such as a missing `<body>`), cloned or parsed by `set_inner_html` have none.
+  pub fn source_location(&self) -> Option<SourceLocation> {
+    super::document::find_source_location(&self.0)
+  }
+}
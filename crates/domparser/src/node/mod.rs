@@ -2,9 +2,19 @@ use markup5ever_rcdom::{Handle, Node, NodeData};
 use std::cell::RefCell;
 use std::rc::Rc;
 
+mod base_url;
+mod document;
+mod equal;
+mod form;
+mod location;
 mod modify;
 mod properties;
 mod query;
+mod reflect;
+mod shadow;
+mod style;
+mod template;
+mod text;
 
 pub(crate) fn get_parent(node: &Handle) -> Option<Handle> {
   let parent_weak = node.parent.take();
@@ -29,6 +39,17 @@ impl DomNode {
   ///
   pub fn clone_self_only(&self) -> DomNode {
     let new_node = Node::new(clone_node_data(&self.0.data));
+    shadow::copy_shadow_root_state(&self.0, &new_node);
+    shadow::link_shadow_root(&new_node);
+    // Template contents are children for cloning purposes, so a shallow clone gets empty contents.
+    if let NodeData::Element {
+      template_contents, ..
+    } = &new_node.data
+    {
+      if template::is_template(&new_node) {
+        template_contents.take();
+      }
+    }
     DomNode(new_node)
   }
 
@@ -75,9 +96,13 @@ fn clone_node_data(data: &NodeData) -> NodeData {
       mathml_annotation_xml_integration_point,
     } => {
       let new_attrs = attrs.borrow().clone();
+      // Shadow roots are only copied along with their host when they are clonable.
       let new_template_contents = template_contents
         .borrow()
         .as_ref()
+        .filter(|contents| {
+          !shadow::is_shadow_root(contents) || DomNode((*contents).clone()).clonable()
+        })
         .map(clone_handle_recursive);
       NodeData::Element {
         name: name.clone(),
@@ -95,6 +120,8 @@ fn clone_node_data(data: &NodeData) -> NodeData {
 
 fn clone_handle_recursive(handle: &Handle) -> Handle {
   let new_node = Node::new(clone_node_data(&handle.data));
+  shadow::copy_shadow_root_state(handle, &new_node);
+  shadow::link_shadow_root(&new_node);
   for child in handle.children.borrow().iter() {
     let new_child = clone_handle_recursive(child);
     new_node.children.borrow_mut().push(new_child.clone());
@@ -103,3 +130,20 @@ fn clone_handle_recursive(handle: &Handle) -> Handle {
   new_node
 }
 pub mod selectors;
+
+pub(crate) use base_url::{css_urls, parse_srcset};
+pub(crate) use document::{new_document_of_type, set_document_url};
+pub use equal::NodeDifference;
+pub(crate) use location::parse_with_source_locations;
+pub use location::{SourceLocation, SourcePosition, SourceSpan};
+pub use modify::Attr;
+pub(crate) use modify::{
+  is_valid_attribute_local_name, is_valid_element_local_name, is_valid_namespace_prefix,
+  is_valid_ncname,
+};
+pub use reflect::ReflectedValue;
+pub use shadow::ShadowRootInit;
+pub(crate) use shadow::{
+  attach_declarative_shadow_roots, attached_shadow_root, flat_tree_parent, is_shadow_root,
+};
+pub(crate) use template::template_contents;
@@ -1,4 +1,4 @@
-use html5ever::{ns, tendril::StrTendril, LocalName, QualName};
+use html5ever::{ns, tendril::StrTendril, Attribute, LocalName, Namespace, Prefix, QualName};
 use markup5ever_rcdom::{Handle, Node, NodeData};
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -109,7 +109,7 @@ impl DomNode {
   }
 
   pub fn set_attribute(&self, name: String, value: String) {
-    if let NodeData::Element { attrs, .. } = &self.0.data {
+    if let (NodeData::Element { attrs, .. }, true) = (&self.0.data, self.is_element()) {
       let mut attributes = attrs.borrow_mut();
       if let Some(attr) = attributes
         .iter_mut()
@@ -138,7 +138,7 @@ impl DomNode {
   }
 
   pub fn toggle_attribute(&self, name: String, force: Option<bool>) -> bool {
-    if let NodeData::Element { attrs, .. } = &self.0.data {
+    if let (NodeData::Element { attrs, .. }, true) = (&self.0.data, self.is_element()) {
       let mut attributes = attrs.borrow_mut();
       let local_name = LocalName::from(name.clone());
       let idx = attributes.iter().position(|a| a.name.local == local_name);
@@ -169,7 +169,7 @@ impl DomNode {
   }
 
   pub fn set_attribute_ns(&self, namespace: Option<String>, name: String, value: String) {
-    if let NodeData::Element { attrs, .. } = &self.0.data {
+    if let (NodeData::Element { attrs, .. }, true) = (&self.0.data, self.is_element()) {
       let (prefix, local) = if let Some(idx) = name.find(':') {
         (Some(name[..idx].to_string()), name[idx + 1..].to_string())
       } else {
@@ -209,7 +209,7 @@ impl DomNode {
   }
 
   pub fn create_element(&self, tag_name: String) -> DomNode {
-    let qual_name = QualName::new(None, ns!(html), LocalName::from(tag_name.to_lowercase()));
+    let qual_name = self.element_name_for(tag_name);
     let node = Node::new(NodeData::Element {
       name: qual_name,
       attrs: RefCell::new(vec![]),
@@ -219,6 +219,58 @@ impl DomNode {
     DomNode(node)
   }
 
+  pub fn create_element_ns(
+    &self,
+    namespace: Option<String>,
+    qualified_name: String,
+  ) -> Result<DomNode, String> {
+    let qual_name = validate_and_extract(namespace, &qualified_name)?;
+    let node = Node::new(NodeData::Element {
+      name: qual_name,
+      attrs: RefCell::new(vec![]),
+      template_contents: RefCell::new(None),
+      mathml_annotation_xml_integration_point: false,
+    });
+    Ok(DomNode(node))
+  }
+
+  /// Creates a detached attribute with an empty value, validating the namespace
+  /// and qualified name like `create_element_ns`.
+  pub fn create_attribute_ns(
+    &self,
+    namespace: Option<String>,
+    qualified_name: String,
+  ) -> Result<Attr, String> {
+    validate_and_extract(namespace, &qualified_name).map(|name| Attr::from_qual_name(name, ""))
+  }
+
+  /// Adds `attr` to the element, replacing the attribute with the same
+  /// namespace and local name, which is returned.
+  pub fn set_attribute_node_ns(&self, attr: Attr) -> Result<Option<Attr>, String> {
+    let name = validate_and_extract(attr.namespace_uri.clone(), &attr.name())?;
+    let NodeData::Element { attrs, .. } = &self.0.data else {
+      return Ok(None);
+    };
+    if !self.is_element() {
+      return Ok(None);
+    }
+    let mut attributes = attrs.borrow_mut();
+    let value = StrTendril::from(attr.value);
+    match attributes
+      .iter_mut()
+      .find(|a| a.name.ns == name.ns && a.name.local == name.local)
+    {
+      Some(old) => {
+        let old = std::mem::replace(old, Attribute { name, value });
+        Ok(Some(Attr::from_qual_name(old.name, &old.value)))
+      }
+      None => {
+        attributes.push(Attribute { name, value });
+        Ok(None)
+      }
+    }
+  }
+
   pub fn create_text_node(&self, data: String) -> DomNode {
     let node = Node::new(NodeData::Text {
       contents: RefCell::new(data.into()),
@@ -290,3 +342,131 @@ impl DomNode {
     }
   }
 }
+
+/// A namespaced attribute that is not on an element, as created by
+/// [`DomNode::create_attribute_ns`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Attr {
+  /// `None` for no namespace.
+  pub namespace_uri: Option<String>,
+  pub prefix: Option<String>,
+  pub local_name: String,
+  pub value: String,
+}
+
+impl Attr {
+  fn from_qual_name(name: QualName, value: &str) -> Self {
+    Attr {
+      namespace_uri: (name.ns != ns!()).then(|| name.ns.to_string()),
+      prefix: name.prefix.map(|prefix| prefix.to_string()),
+      local_name: name.local.to_string(),
+      value: value.to_string(),
+    }
+  }
+
+  /// The qualified name, `prefix:localName` or `localName`.
+  pub fn name(&self) -> String {
+    match &self.prefix {
+      Some(prefix) => format!("{}:{}", prefix, self.local_name),
+      None => self.local_name.clone(),
+    }
+  }
+}
+
+/// Implements the DOM "validate and extract" steps for a namespace and qualified name.
+/// https://dom.spec.whatwg.org/#validate-and-extract
+pub(crate) fn validate_and_extract(
+  namespace: Option<String>,
+  qualified_name: &str,
+) -> Result<QualName, String> {
+  let namespace = namespace.filter(|ns| !ns.is_empty());
+
+  let (prefix, local) = match qualified_name.split_once(':') {
+    Some((prefix, local)) => (Some(prefix), local),
+    None => (None, qualified_name),
+  };
+  if !prefix.is_none_or(is_valid_ncname) || !is_valid_ncname(local) {
+    return Err(format!(
+      "The qualified name '{}' is not a valid XML name.",
+      qualified_name
+    ));
+  }
+
+  let is_xmlns_ns = namespace.as_deref() == Some(XMLNS_NAMESPACE);
+  if prefix.is_some() && namespace.is_none() {
+    return Err("A prefix was given without a namespace.".to_string());
+  }
+  if prefix == Some("xml") && namespace.as_deref() != Some(XML_NAMESPACE) {
+    return Err("The 'xml' prefix is reserved for the XML namespace.".to_string());
+  }
+  if (qualified_name == "xmlns" || prefix == Some("xmlns")) && !is_xmlns_ns {
+    return Err("The 'xmlns' prefix is reserved for the XMLNS namespace.".to_string());
+  }
+  if is_xmlns_ns && qualified_name != "xmlns" && prefix != Some("xmlns") {
+    return Err("The XMLNS namespace requires the 'xmlns' prefix.".to_string());
+  }
+
+  Ok(QualName::new(
+    prefix.map(Prefix::from),
+    namespace.map(Namespace::from).unwrap_or(ns!()),
+    LocalName::from(local),
+  ))
+}
+
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+const XMLNS_NAMESPACE: &str = "http://www.w3.org/2000/xmlns/";
+
+pub(crate) fn is_valid_ncname(name: &str) -> bool {
+  let mut chars = name.chars();
+  match chars.next() {
+    Some(c) if is_name_start_char(c) => chars.all(is_name_char),
+    _ => false,
+  }
+}
+
+/// https://dom.spec.whatwg.org/#valid-element-local-name
+///
+/// Looser than an NCName, so that every name the HTML parser produces is valid.
+pub(crate) fn is_valid_element_local_name(name: &str) -> bool {
+  let mut chars = name.chars();
+  match chars.next() {
+    Some(c) if c.is_ascii_alphabetic() => {
+      !chars.any(|c| c.is_ascii_whitespace() || matches!(c, '\0' | '/' | '>'))
+    }
+    Some(c) if c == ':' || c == '_' || c >= '\u{80}' => chars
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | ':' | '_') || c >= '\u{80}'),
+    _ => false,
+  }
+}
+
+/// https://dom.spec.whatwg.org/#valid-attribute-local-name
+pub(crate) fn is_valid_attribute_local_name(name: &str) -> bool {
+  !name.is_empty()
+    && !name
+      .chars()
+      .any(|c| c.is_ascii_whitespace() || matches!(c, '\0' | '/' | '=' | '>'))
+}
+
+/// https://dom.spec.whatwg.org/#valid-namespace-prefix
+pub(crate) fn is_valid_namespace_prefix(prefix: &str) -> bool {
+  !prefix.is_empty()
+    && !prefix
+      .chars()
+      .any(|c| c.is_ascii_whitespace() || matches!(c, '\0' | '/' | '>'))
+}
+
+// https://www.w3.org/TR/xml/#NT-NameStartChar, minus ':'
+fn is_name_start_char(c: char) -> bool {
+  matches!(c,
+    'A'..='Z' | '_' | 'a'..='z'
+    | '\u{C0}'..='\u{D6}' | '\u{D8}'..='\u{F6}' | '\u{F8}'..='\u{2FF}'
+    | '\u{370}'..='\u{37D}' | '\u{37F}'..='\u{1FFF}' | '\u{200C}'..='\u{200D}'
+    | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}' | '\u{3001}'..='\u{D7FF}'
+    | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}' | '\u{10000}'..='\u{EFFFF}')
+}
+
+fn is_name_char(c: char) -> bool {
+  is_name_start_char(c)
+    || matches!(c,
+      '-' | '.' | '0'..='9' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}')
+}
@@ -1,9 +1,8 @@
 use super::DomNode;
 use html5ever::parse_fragment;
-use html5ever::serialize::{serialize, SerializeOpts, TraversalScope};
 use html5ever::tendril::TendrilSink;
 use html5ever::{local_name, ns, QualName};
-use markup5ever_rcdom::{Handle, Node, NodeData, RcDom, SerializableHandle};
+use markup5ever_rcdom::{Handle, Node, NodeData, RcDom};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -12,7 +11,7 @@ impl DomNode {
   pub fn node_type(&self) -> i32 {
     match &self.0.data {
       NodeData::Element { name, .. } => {
-        if name.local.as_ref() == "#document-fragment" {
+        if name.local.as_ref() == "#document-fragment" || super::shadow::is_shadow_root(&self.0) {
           11
         } else {
           1
@@ -29,10 +28,10 @@ impl DomNode {
   pub fn node_name(&self) -> String {
     match &self.0.data {
       NodeData::Element { name, .. } => {
-        if name.local.as_ref() == "#document-fragment" {
+        if name.local.as_ref() == "#document-fragment" || super::shadow::is_shadow_root(&self.0) {
           "#document-fragment".to_string()
         } else {
-          name.local.to_string().to_uppercase()
+          self.html_uppercased_qualified_name(name)
         }
       }
       NodeData::Text { .. } => "#text".to_string(),
@@ -43,30 +42,54 @@ impl DomNode {
     }
   }
 
+  /// Whether this node is an element, as opposed to the element nodes standing in for
+  /// DocumentFragments and ShadowRoots.
+  pub(crate) fn is_element(&self) -> bool {
+    self.node_type() == 1
+  }
+
   pub fn tag_name(&self) -> Option<String> {
     match &self.0.data {
-      NodeData::Element { name, .. } => Some(name.local.to_string().to_uppercase()),
+      NodeData::Element { name, .. } if self.is_element() => {
+        Some(self.html_uppercased_qualified_name(name))
+      }
       _ => None,
     }
   }
 
+  /// The element's qualified name, uppercased only for HTML elements in an HTML document.
+  /// Foreign (SVG, MathML, ...) elements keep their case, e.g. `foreignObject`.
+  fn html_uppercased_qualified_name(&self, name: &QualName) -> String {
+    let qualified = match &name.prefix {
+      Some(prefix) => format!("{}:{}", prefix, name.local),
+      None => name.local.to_string(),
+    };
+    if name.ns == ns!(html) && self.is_in_html_document() {
+      qualified.to_ascii_uppercase()
+    } else {
+      qualified
+    }
+  }
+
   pub fn namespace_uri(&self) -> Option<String> {
     match &self.0.data {
-      NodeData::Element { name, .. } => Some(name.ns.to_string()),
+      NodeData::Element { name, .. } if self.is_element() => Some(name.ns.to_string()),
       _ => None,
     }
   }
 
   pub fn prefix(&self) -> Option<String> {
     match &self.0.data {
-      NodeData::Element { name, .. } => name.prefix.as_ref().map(|p| p.to_string()),
+      NodeData::Element { name, .. } if self.is_element() => {
+        name.prefix.as_ref().map(|p| p.to_string())
+      }
       _ => None,
     }
   }
 
   pub fn local_name(&self) -> Option<String> {
     match &self.0.data {
-      NodeData::Element { name, .. } => Some(name.local.to_string()),
+      NodeData::Element { name, .. } if self.is_element() => Some(name.local.to_string()),
       _ => None,
     }
   }
@@ -88,7 +111,7 @@ impl DomNode {
   }
 
   pub fn parent_node(&self) -> Option<DomNode> {
-    super::get_parent(&self.0).map(DomNode)
+    super::shadow::tree_parent(&self.0).map(DomNode)
   }
 
   pub fn first_child(&self) -> Option<DomNode> {
@@ -123,7 +146,9 @@ impl DomNode {
 
   pub fn parent_element(&self) -> Option<DomNode> {
     super::get_parent(&self.0).and_then(|n| {
-      if let NodeData::Element { .. } = n.data {
+      if super::shadow::is_shadow_root(&self.0) || super::shadow::is_shadow_root(&n) {
+        None
+      } else if let NodeData::Element { .. } = n.data {
         Some(DomNode(n))
       } else {
         None
@@ -201,7 +226,7 @@ impl DomNode {
   pub fn get_root_node(&self) -> DomNode {
     let mut current = self.0.clone();
     loop {
-      let parent = super::get_parent(&current);
+      let parent = super::shadow::tree_parent(&current);
       if let Some(p) = parent {
         current = p;
       } else {
@@ -306,20 +331,9 @@ impl DomNode {
     Rc::ptr_eq(&self.0, &other_node.0)
   }
 
+  /// Same as [`inner_html`](Self::inner_html).
   pub fn inner_html_getter(&self) -> String {
-    let mut bytes = Vec::new();
-    let serializable = SerializableHandle::from(self.0.clone());
-    serialize(
-      &mut bytes,
-      &serializable,
-      SerializeOpts {
-        scripting_enabled: false,
-        traversal_scope: TraversalScope::ChildrenOnly(None),
-        create_missing_parent: false,
-      },
-    )
-    .unwrap();
-    String::from_utf8(bytes).unwrap()
+    self.inner_html()
   }
 
   pub fn length(&self) -> u32 {
@@ -331,16 +345,22 @@ impl DomNode {
   }
 
   pub fn set_inner_html(&self, html: String) {
-    self.0.children.borrow_mut().clear();
+    // A template's markup goes into its contents rather than its children.
+    let target = super::template_contents(&self.0).unwrap_or_else(|| self.0.clone());
+    for child in target.children.take() {
+      child.parent.set(None);
+    }
 
-    let context_name = match &self.0.data {
+    // A shadow root parses its markup in the context of its host.
+    let context = self.host().unwrap_or_else(|| self.clone());
+    let context_name = match &context.0.data {
       NodeData::Element { name, .. } => name.clone(),
       _ => QualName::new(None, ns!(html), local_name!("body")),
     };
 
     let dom = parse_fragment(
       RcDom::default(),
-      Default::default(),
+      self.fragment_parse_opts(),
       context_name.clone(),
       vec![],
       false,
@@ -413,30 +433,14 @@ impl DomNode {
     }
 
     for child in nodes_to_append {
-      self.0.children.borrow_mut().push(child.clone());
-      child.parent.set(Some(Rc::downgrade(&self.0)));
+      target.children.borrow_mut().push(child.clone());
+      child.parent.set(Some(Rc::downgrade(&target)));
     }
   }
 
+  /// Same as [`outer_html`](Self::outer_html).
   pub fn outer_html_getter(&self) -> String {
-    let mut bytes = Vec::new();
-    let serializable = SerializableHandle::from(self.0.clone());
-    let traversal_scope = if let NodeData::Document = self.0.data {
-      TraversalScope::ChildrenOnly(None)
-    } else {
-      TraversalScope::IncludeNode
-    };
-    serialize(
-      &mut bytes,
-      &serializable,
-      SerializeOpts {
-        scripting_enabled: false,
-        traversal_scope,
-        create_missing_parent: false,
-      },
-    )
-    .unwrap();
-    String::from_utf8(bytes).unwrap()
+    self.outer_html()
   }
 
   pub fn set_outer_html(&self, html: String) {
@@ -448,7 +452,7 @@ impl DomNode {
 
       let dom = parse_fragment(
         RcDom::default(),
-        Default::default(),
+        self.fragment_parse_opts(),
         context_name,
         vec![],
         false,
@@ -470,7 +474,10 @@ impl DomNode {
   }
 
   pub fn owner_document(&self) -> Option<DomNode> {
-    let root = self.get_root_node();
+    let mut root = self.get_root_node();
+    while let Some(host) = root.host() {
+      root = host.get_root_node();
+    }
     if matches!(root.0.data, NodeData::Document) {
       Some(root)
     } else {
@@ -647,7 +654,7 @@ impl DomNode {
 
     let dom = parse_fragment(
       RcDom::default(),
-      Default::default(),
+      self.fragment_parse_opts(),
       context_name.clone(),
       vec![],
       false,
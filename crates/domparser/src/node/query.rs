@@ -1,7 +1,10 @@
-use crate::serializer::serialize_text_only;
-use html5ever::serialize::{self, serialize, SerializeOpts};
+use crate::markdown::{self, MarkdownOptions};
+use crate::serializer::{
+  serialize_text_only, serialize_with_options, serialize_xml, SerializableDomNode, SerializeOptions,
+};
 use html5ever::{ns, LocalName};
-use markup5ever_rcdom::{Handle, NodeData, SerializableHandle};
+use markup5ever_rcdom::{Handle, NodeData};
+use std::io;
 use std::rc::Rc;
 
 use super::selectors::{parse_selectors, DomParserSelectors};
@@ -10,7 +13,7 @@ use selectors::matching::{
   matches_selector_list, MatchingContext, MatchingForInvalidation, MatchingMode,
   NeedsSelectorFlags, QuirksMode, SelectorCaches,
 };
-use selectors::SelectorList;
+use selectors::{Element, SelectorList};
 
 impl DomNode {
   pub fn get_attribute(&self, name: String) -> Option<String> {
@@ -68,36 +71,53 @@ impl DomNode {
     !self.0.children.borrow().is_empty()
   }
 
+  /// Returns the HTML serialization of this node and its descendants.
+  /// Documents and fragments serialize their children.
   pub fn outer_html(&self) -> String {
-    let mut u8_vec = Vec::new();
-    let serializable = SerializableHandle::from(self.0.clone());
-    serialize(
-      &mut u8_vec,
-      &serializable,
-      SerializeOpts {
-        traversal_scope: serialize::TraversalScope::IncludeNode,
-        create_missing_parent: false,
-        scripting_enabled: true,
-      },
-    )
-    .unwrap();
-    unsafe { String::from_utf8_unchecked(u8_vec) }
+    SerializableDomNode::new(self.0.clone()).to_html(true)
   }
 
+  /// Returns the HTML serialization of this node's children.
   pub fn inner_html(&self) -> String {
-    let mut buf = Vec::<u8>::new();
-    let serializable = SerializableHandle::from(self.0.clone());
-    serialize(
-      &mut buf,
-      &serializable,
-      SerializeOpts {
-        traversal_scope: serialize::TraversalScope::ChildrenOnly(None),
-        create_missing_parent: false,
-        scripting_enabled: true,
-      },
-    )
-    .unwrap();
-    unsafe { String::from_utf8_unchecked(buf) }
+    SerializableDomNode::new(self.0.clone()).to_html(false)
+  }
+
+  /// Writes the HTML serialization of this node and its descendants to `writer`.
+  pub fn write_outer_html<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+    SerializableDomNode::new(self.0.clone()).write_html(true, writer)
+  }
+
+  /// Writes the HTML serialization of this node's children to `writer`.
+  pub fn write_inner_html<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+    SerializableDomNode::new(self.0.clone()).write_html(false, writer)
+  }
+
+  /// Serializes this node as HTML with formatting controlled by `options`.
+  /// Documents and fragments serialize their children.
+  pub fn serialize(&self, options: &SerializeOptions) -> String {
+    let mut html = String::new();
+    serialize_with_options(&self.0, true, options, &mut html).unwrap();
+    html
+  }
+
+  /// Serializes this node as XML, as `XMLSerializer.serializeToString` does.
+  /// With `require_well_formed`, nodes that cannot be written as well-formed XML are an error.
+  pub fn serialize_to_xml(&self, require_well_formed: bool) -> Result<String, String> {
+    serialize_xml(&self.0, require_well_formed)
+  }
+
+  /// Converts this node and its descendants to Markdown.
+  pub fn to_markdown(&self, options: &MarkdownOptions) -> String {
+    markdown::to_markdown(&self.0, options, &|_, _| None)
+  }
+
+  /// Converts this node to Markdown, passing elements with a custom rule to `handler`.
+  pub fn to_markdown_with(
+    &self,
+    options: &MarkdownOptions,
+    handler: &dyn Fn(&DomNode, &str) -> Option<String>,
+  ) -> String {
+    markdown::to_markdown(&self.0, options, handler)
   }
 
   pub fn text(&self) -> String {
@@ -117,6 +137,7 @@ impl DomNode {
         NeedsSelectorFlags::No,
         MatchingForInvalidation::No,
       );
+      ctx.current_host = self.scope_shadow_host().map(|host| host.opaque());
 
       fn find(
         node: &DomNode,
@@ -159,6 +180,7 @@ impl DomNode {
         NeedsSelectorFlags::No,
         MatchingForInvalidation::No,
       );
+      ctx.current_host = self.scope_shadow_host().map(|host| host.opaque());
 
       fn find_all(
         node: &DomNode,
@@ -286,42 +308,84 @@ impl DomNode {
 
   pub fn get_elements_by_tag_name(&self, tag_name: String) -> Vec<DomNode> {
     let mut results = Vec::new();
-    let tag_upper = tag_name.to_uppercase();
+    let tag_lower = tag_name.to_ascii_lowercase();
     let is_wildcard = tag_name == "*";
 
-    fn find_tags(handle: &Handle, tag_upper: &str, is_wildcard: bool, results: &mut Vec<DomNode>) {
+    // HTML elements match case-insensitively, foreign elements must match the qualified name exactly.
+    fn find_tags(
+      handle: &Handle,
+      tag_name: &str,
+      tag_lower: &str,
+      is_wildcard: bool,
+      results: &mut Vec<DomNode>,
+    ) {
+      if let NodeData::Element { name, .. } = &handle.data {
+        let wanted = if name.ns == ns!(html) {
+          tag_lower
+        } else {
+          tag_name
+        };
+        let matched = match &name.prefix {
+          Some(prefix) => wanted
+            .strip_prefix(prefix.as_ref())
+            .and_then(|rest| rest.strip_prefix(':'))
+            .is_some_and(|local| local == name.local.as_ref()),
+          None => wanted == name.local.as_ref(),
+        };
+        if is_wildcard || matched {
+          results.push(DomNode(handle.clone()));
+        }
+      }
+      for child in handle.children.borrow().iter() {
+        find_tags(child, tag_name, tag_lower, is_wildcard, results);
+      }
+    }
+
+    for child in self.0.children.borrow().iter() {
+      find_tags(child, &tag_name, &tag_lower, is_wildcard, &mut results);
+    }
+    results
+  }
+
+  pub fn get_elements_by_tag_name_ns(
+    &self,
+    namespace: Option<String>,
+    local_name: String,
+  ) -> Vec<DomNode> {
+    let mut results = Vec::new();
+    let namespace = namespace.unwrap_or_default();
+
+    fn find_tags(handle: &Handle, namespace: &str, local_name: &str, results: &mut Vec<DomNode>) {
       if let NodeData::Element { name, .. } = &handle.data {
-        if is_wildcard || name.local.to_string().to_uppercase() == tag_upper {
+        if (namespace == "*" || name.ns.as_ref() == namespace)
+          && (local_name == "*" || name.local.as_ref() == local_name)
+        {
           results.push(DomNode(handle.clone()));
         }
       }
       for child in handle.children.borrow().iter() {
-        find_tags(child, tag_upper, is_wildcard, results);
+        find_tags(child, namespace, local_name, results);
       }
     }
 
     for child in self.0.children.borrow().iter() {
-      find_tags(child, &tag_upper, is_wildcard, &mut results);
+      find_tags(child, &namespace, &local_name, &mut results);
     }
     results
   }
 
   pub fn contains(&self, other_node: &DomNode) -> bool {
     // Check if self is ancestor of other_node
-    let mut current = super::get_parent(&other_node.0);
+    let mut current = super::shadow::tree_parent(&other_node.0);
     while let Some(parent) = current {
       if Rc::ptr_eq(&parent, &self.0) {
         return true;
       }
-      current = super::get_parent(&parent);
+      current = super::shadow::tree_parent(&parent);
     }
     false
   }
 
-  pub fn is_equal_node(&self, other_node: &DomNode) -> bool {
-    self.outer_html() == other_node.outer_html()
-  }
-
   pub fn head(&self) -> Option<DomNode> {
     // Manual search for head
     if let NodeData::Document = self.0.data {
@@ -363,7 +427,11 @@ impl DomNode {
     None
   }
 
+  /// Returns the title of the document, or the `title` attribute of an HTML element.
   pub fn title(&self) -> String {
+    if !matches!(self.0.data, NodeData::Document) {
+      return self.element_title().unwrap_or_default();
+    }
     if let Some(head) = self.head() {
       for child in head.0.children.borrow().iter() {
         if let NodeData::Element { name, .. } = &child.data {
@@ -376,6 +444,26 @@ impl DomNode {
     "".to_string()
   }
 
+  /// Sets the text of the document's `<title>`, creating it in the head if needed,
+  /// or the `title` attribute of an HTML element.
+  pub fn set_title(&self, title: String) {
+    if !matches!(self.0.data, NodeData::Document) {
+      return self.set_element_title(title);
+    }
+    let Some(head) = self.head() else {
+      return;
+    };
+    let existing = head.children().into_iter().find(|child| {
+      matches!(&child.0.data, NodeData::Element { name, .. } if name.local.as_ref() == "title")
+    });
+    let title_element = existing.unwrap_or_else(|| {
+      let element = self.create_element("title".to_string());
+      head.append_child(&element);
+      element
+    });
+    title_element.set_text_content(title);
+  }
+
   pub fn document_element(&self) -> Option<DomNode> {
     if let NodeData::Document = self.0.data {
       self
@@ -402,15 +490,14 @@ impl DomNode {
         NeedsSelectorFlags::No,
         MatchingForInvalidation::No,
       );
+      ctx.current_host = self.scope_shadow_host().map(|host| host.opaque());
 
       let mut current = Some(self.clone());
       while let Some(node) = current {
-        if matches!(node.0.data, NodeData::Element { .. })
-          && matches_selector_list(&list, &node, &mut ctx)
-        {
+        if node.is_element() && matches_selector_list(&list, &node, &mut ctx) {
           return Some(node.clone());
         }
-        current = super::get_parent(&node.0).map(DomNode);
+        current = super::shadow::tree_parent(&node.0).map(DomNode);
       }
     }
     None
@@ -427,10 +514,38 @@ impl DomNode {
         NeedsSelectorFlags::No,
         MatchingForInvalidation::No,
       );
-      if matches!(self.0.data, NodeData::Element { .. }) {
+      ctx.current_host = self.scope_shadow_host().map(|host| host.opaque());
+      if self.is_element() {
+        return matches_selector_list(&list, self, &mut ctx);
+      }
+    }
+    false
+  }
+
+  /// Returns whether this element matches `selectors` as if they came from a
+  /// stylesheet inside `host`'s shadow tree, so `:host`, `::slotted` and `::part`
+  /// are resolved against that host.
+  pub fn matches_in_shadow_tree(&self, selectors: String, host: &DomNode) -> bool {
+    if let Some(list) = parse_selectors(&selectors) {
+      let mut cache = SelectorCaches::default();
+      let mut ctx = MatchingContext::new(
+        MatchingMode::Normal,
+        None,
+        &mut cache,
+        QuirksMode::NoQuirks,
+        NeedsSelectorFlags::No,
+        MatchingForInvalidation::No,
+      );
+      ctx.current_host = Some(host.opaque());
+      if self.is_element() {
         return matches_selector_list(&list, self, &mut ctx);
       }
     }
     false
   }
+
+  /// The host of the shadow tree this node is in, if any.
+  fn scope_shadow_host(&self) -> Option<DomNode> {
+    self.get_root_node().host()
+  }
 }
@@ -0,0 +1,738 @@
+use html5ever::ns;
+use markup5ever_rcdom::{Handle, NodeData};
+use url::Url;
+
+use super::DomNode;
+
+/// How an IDL attribute reflects its content attribute.
+/// https://html.spec.whatwg.org/multipage/common-dom-interfaces.html#reflecting-content-attributes-in-idl-attributes
+#[derive(Clone, Copy, Debug)]
+enum ReflectKind {
+  String,
+  Boolean,
+  /// Known keywords are returned lowercased, anything else as `invalid`, a missing attribute as `missing`.
+  Enumerated {
+    keywords: &'static [&'static str],
+    missing: &'static str,
+    invalid: &'static str,
+  },
+  /// Parsed with the rules for signed integers; `default` is used when missing or invalid.
+  Integer {
+    default: i32,
+  },
+  /// Resolved to an absolute URL when possible.
+  Url,
+}
+
+struct Reflection {
+  idl_name: &'static str,
+  attr_name: &'static str,
+  /// HTML elements carrying the IDL attribute; empty means every HTML element.
+  elements: &'static [&'static str],
+  kind: ReflectKind,
+}
+
+const FORM_METHODS: &[&str] = &["get", "post", "dialog"];
+
+const FORM_ENCTYPES: &[&str] = &[
+  "application/x-www-form-urlencoded",
+  "multipart/form-data",
+  "text/plain",
+];
+
+const REFLECTIONS: &[Reflection] = &[
+  Reflection {
+    idl_name: "title",
+    attr_name: "title",
+    elements: &[],
+    kind: ReflectKind::String,
+  },
+  Reflection {
+    idl_name: "lang",
+    attr_name: "lang",
+    elements: &[],
+    kind: ReflectKind::String,
+  },
+  Reflection {
+    idl_name: "dir",
+    attr_name: "dir",
+    elements: &[],
+    kind: ReflectKind::Enumerated {
+      keywords: &["ltr", "rtl", "auto"],
+      missing: "",
+      invalid: "",
+    },
+  },
+  Reflection {
+    idl_name: "hidden",
+    attr_name: "hidden",
+    elements: &[],
+    kind: ReflectKind::Boolean,
+  },
+  Reflection {
+    idl_name: "tabIndex",
+    attr_name: "tabindex",
+    elements: &[],
+    kind: ReflectKind::Integer { default: -1 },
+  },
+  Reflection {
+    idl_name: "href",
+    attr_name: "href",
+    elements: &["a", "area", "base", "link"],
+    kind: ReflectKind::Url,
+  },
+  Reflection {
+    idl_name: "action",
+    attr_name: "action",
+    elements: &["form"],
+    kind: ReflectKind::Url,
+  },
+  Reflection {
+    idl_name: "method",
+    attr_name: "method",
+    elements: &["form"],
+    kind: ReflectKind::Enumerated {
+      keywords: FORM_METHODS,
+      missing: "get",
+      invalid: "get",
+    },
+  },
+  Reflection {
+    idl_name: "enctype",
+    attr_name: "enctype",
+    elements: &["form"],
+    kind: ReflectKind::Enumerated {
+      keywords: FORM_ENCTYPES,
+      missing: "application/x-www-form-urlencoded",
+      invalid: "application/x-www-form-urlencoded",
+    },
+  },
+  Reflection {
+    idl_name: "formAction",
+    attr_name: "formaction",
+    elements: &["button", "input"],
+    kind: ReflectKind::Url,
+  },
+  Reflection {
+    idl_name: "formMethod",
+    attr_name: "formmethod",
+    elements: &["button", "input"],
+    kind: ReflectKind::Enumerated {
+      keywords: FORM_METHODS,
+      missing: "",
+      invalid: "get",
+    },
+  },
+  Reflection {
+    idl_name: "formEnctype",
+    attr_name: "formenctype",
+    elements: &["button", "input"],
+    kind: ReflectKind::Enumerated {
+      keywords: FORM_ENCTYPES,
+      missing: "",
+      invalid: "application/x-www-form-urlencoded",
+    },
+  },
+  Reflection {
+    idl_name: "src",
+    attr_name: "src",
+    elements: &[
+      "audio", "embed", "frame", "iframe", "img", "input", "script", "source", "track", "video",
+    ],
+    kind: ReflectKind::Url,
+  },
+  Reflection {
+    idl_name: "checked",
+    attr_name: "checked",
+    elements: &["input"],
+    kind: ReflectKind::Boolean,
+  },
+  Reflection {
+    idl_name: "defaultValue",
+    attr_name: "value",
+    elements: &["input"],
+    kind: ReflectKind::String,
+  },
+];
+
+/// The value of a reflected IDL attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReflectedValue {
+  String(String),
+  Boolean(bool),
+  Integer(i32),
+}
+
+/// Elements whose `tabIndex` defaults to 0 because they are focusable.
+const FOCUSABLE_ELEMENTS: &[&str] = &[
+  "a", "area", "button", "frame", "iframe", "input", "object", "select", "textarea", "summary",
+];
+
+fn html_local_name(handle: &Handle) -> Option<&str> {
+  match &handle.data {
+    NodeData::Element { name, .. } if name.ns == ns!(html) => Some(name.local.as_ref()),
+    _ => None,
+  }
+}
+
+fn find_reflection(handle: &Handle, idl_name: &str) -> Option<&'static Reflection> {
+  let local_name = html_local_name(handle)?;
+  REFLECTIONS.iter().find(|reflection| {
+    reflection.idl_name == idl_name
+      && (reflection.elements.is_empty() || reflection.elements.contains(&local_name))
+  })
+}
+
+fn is_form_action(reflection: &Reflection) -> bool {
+  matches!(reflection.idl_name, "action" | "formAction")
+}
+
+/// https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#rules-for-parsing-integers
+fn parse_integer(value: &str) -> Option<i32> {
+  let value = value.trim_start_matches(['\t', '\n', '\x0C', '\r', ' ']);
+  let (negative, digits) = match value.as_bytes().first() {
+    Some(b'-') => (true, &value[1..]),
+    Some(b'+') => (false, &value[1..]),
+    _ => (false, value),
+  };
+  let end = digits
+    .find(|c: char| !c.is_ascii_digit())
+    .unwrap_or(digits.len());
+  let number: i64 = digits[..end].parse().ok()?;
+  let number = if negative { -number } else { number };
+  i32::try_from(number).ok()
+}
+
+/// Applies the value sanitization algorithm of an `input` type to its value: text-like types
+/// lose their line breaks, and URLs and email addresses their surrounding white space.
+/// https://html.spec.whatwg.org/multipage/input.html#value-sanitization-algorithm
+fn sanitize_value(input_type: &str, value: String, multiple: bool) -> String {
+  let strip_newlines = |value: &str| value.replace(['\r', '\n'], "");
+  let trim = |value: &str| {
+    value
+      .trim_matches(['\t', '\n', '\x0C', '\r', ' '])
+      .to_string()
+  };
+  match input_type {
+    "text" | "search" | "tel" | "password" => strip_newlines(&value),
+    "url" => trim(&strip_newlines(&value)),
+    "email" if multiple => value.split(',').map(trim).collect::<Vec<_>>().join(","),
+    "email" => trim(&strip_newlines(&value)),
+    _ => value,
+  }
+}
+
+impl DomNode {
+  /// Returns the value of the IDL attribute `idl_name` (for example `"tabIndex"`), reflected
+  /// from its content attribute. Returns `None` if this node has no such IDL attribute.
+  pub fn reflected_attribute(&self, idl_name: &str) -> Option<ReflectedValue> {
+    let reflection = find_reflection(&self.0, idl_name)?;
+    let value = self.get_attribute(reflection.attr_name.to_string());
+    Some(match reflection.kind {
+      ReflectKind::String => ReflectedValue::String(value.unwrap_or_default()),
+      ReflectKind::Boolean => ReflectedValue::Boolean(value.is_some()),
+      ReflectKind::Enumerated {
+        keywords,
+        missing,
+        invalid,
+      } => ReflectedValue::String(match value {
+        None => missing.to_string(),
+        Some(value) => {
+          let value = value.to_ascii_lowercase();
+          if keywords.contains(&value.as_str()) {
+            value
+          } else {
+            invalid.to_string()
+          }
+        }
+      }),
+      ReflectKind::Integer { default } => {
+        let default = if reflection.idl_name == "tabIndex" && self.is_focusable_by_default() {
+          0
+        } else {
+          default
+        };
+        ReflectedValue::Integer(value.as_deref().and_then(parse_integer).unwrap_or(default))
+      }
+      ReflectKind::Url => ReflectedValue::String(match value {
+        // A form without an action submits to its document.
+        Some(value) if value.is_empty() && is_form_action(reflection) => self.document_url(),
+        None if is_form_action(reflection) => self.document_url(),
+        Some(value) => self.resolve_url(&value),
+        None => String::new(),
+      }),
+    })
+  }
+
+  /// Sets the IDL attribute `idl_name` by updating its content attribute.
+  pub fn set_reflected_attribute(
+    &self,
+    idl_name: &str,
+    value: ReflectedValue,
+  ) -> Result<(), String> {
+    let Some(reflection) = find_reflection(&self.0, idl_name) else {
+      return Err(format!(
+        "'{}' is not a reflected attribute of this node.",
+        idl_name
+      ));
+    };
+    let attr_name = reflection.attr_name.to_string();
+    match (reflection.kind, value) {
+      (ReflectKind::Boolean, ReflectedValue::Boolean(true)) => {
+        self.set_attribute(attr_name, String::new())
+      }
+      (ReflectKind::Boolean, ReflectedValue::Boolean(false)) => self.remove_attribute(attr_name),
+      (ReflectKind::Integer { .. }, ReflectedValue::Integer(value)) => {
+        self.set_attribute(attr_name, value.to_string())
+      }
+      (
+        ReflectKind::String | ReflectKind::Enumerated { .. } | ReflectKind::Url,
+        ReflectedValue::String(value),
+      ) => self.set_attribute(attr_name, value),
+      (kind, value) => {
+        return Err(format!(
+          "Cannot set '{}' to {:?}, expected a {:?} value.",
+          idl_name, value, kind
+        ))
+      }
+    }
+    Ok(())
+  }
+
+  fn reflected_string(&self, idl_name: &str) -> Option<String> {
+    match self.reflected_attribute(idl_name)? {
+      ReflectedValue::String(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  fn reflected_bool(&self, idl_name: &str) -> Option<bool> {
+    match self.reflected_attribute(idl_name)? {
+      ReflectedValue::Boolean(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  fn set_reflected_string(&self, idl_name: &str, value: String) {
+    let _ = self.set_reflected_attribute(idl_name, ReflectedValue::String(value));
+  }
+
+  fn set_reflected_bool(&self, idl_name: &str, value: bool) {
+    let _ = self.set_reflected_attribute(idl_name, ReflectedValue::Boolean(value));
+  }
+
+  fn is_focusable_by_default(&self) -> bool {
+    html_local_name(&self.0).is_some_and(|name| FOCUSABLE_ELEMENTS.contains(&name))
+  }
+
+  /// Resolves `value` against the node's base URL, returning it unchanged if it cannot be parsed.
+  fn resolve_url(&self, value: &str) -> String {
+    let value = value.trim();
+    let url = match self.base_url() {
+      Some(base) => base.join(value),
+      None => Url::parse(value),
+    };
+    url.map_or_else(|_| value.to_string(), String::from)
+  }
+
+  fn document_url(&self) -> String {
+    self
+      .owner_document()
+      .and_then(|document| document.url())
+      .unwrap_or_else(|| "about:blank".to_string())
+  }
+
+  /// Returns the `href` of an `a`, `area`, `base` or `link` element.
+  pub fn href(&self) -> Option<String> {
+    self.reflected_string("href")
+  }
+
+  pub fn set_href(&self, value: String) {
+    self.set_reflected_string("href", value)
+  }
+
+  /// Returns the `action` of a `form` element, its document's URL if it has none.
+  pub fn action(&self) -> Option<String> {
+    self.reflected_string("action")
+  }
+
+  pub fn set_action(&self, value: String) {
+    self.set_reflected_string("action", value)
+  }
+
+  /// Returns the `method` of a `form` element: `"get"`, `"post"` or `"dialog"`.
+  pub fn method(&self) -> Option<String> {
+    self.reflected_string("method")
+  }
+
+  pub fn set_method(&self, value: String) {
+    self.set_reflected_string("method", value)
+  }
+
+  /// Returns the `enctype` of a `form` element, `"application/x-www-form-urlencoded"` by default.
+  pub fn enctype(&self) -> Option<String> {
+    self.reflected_string("enctype")
+  }
+
+  pub fn set_enctype(&self, value: String) {
+    self.set_reflected_string("enctype", value)
+  }
+
+  /// Returns the `formaction` of a `button` or `input`, which overrides its form's `action`.
+  pub fn form_action(&self) -> Option<String> {
+    self.reflected_string("formAction")
+  }
+
+  pub fn set_form_action(&self, value: String) {
+    self.set_reflected_string("formAction", value)
+  }
+
+  /// Returns the `formmethod` of a `button` or `input`, `""` if it has none.
+  pub fn form_method(&self) -> Option<String> {
+    self.reflected_string("formMethod")
+  }
+
+  pub fn set_form_method(&self, value: String) {
+    self.set_reflected_string("formMethod", value)
+  }
+
+  /// Returns the `formenctype` of a `button` or `input`, `""` if it has none.
+  pub fn form_enctype(&self) -> Option<String> {
+    self.reflected_string("formEnctype")
+  }
+
+  pub fn set_form_enctype(&self, value: String) {
+    self.set_reflected_string("formEnctype", value)
+  }
+
+  /// Returns the `src` of an embedding element such as `img`, `script` or `iframe`.
+  pub fn src(&self) -> Option<String> {
+    self.reflected_string("src")
+  }
+
+  pub fn set_src(&self, value: String) {
+    self.set_reflected_string("src", value)
+  }
+
+  /// Returns whether an HTML element has the `hidden` attribute.
+  pub fn hidden(&self) -> Option<bool> {
+    self.reflected_bool("hidden")
+  }
+
+  pub fn set_hidden(&self, value: bool) {
+    self.set_reflected_bool("hidden", value)
+  }
+
+  /// Returns the advisory `title` of an HTML element.
+  pub fn element_title(&self) -> Option<String> {
+    self.reflected_string("title")
+  }
+
+  pub fn set_element_title(&self, value: String) {
+    self.set_reflected_string("title", value)
+  }
+
+  /// Returns the `lang` of an HTML element.
+  pub fn lang(&self) -> Option<String> {
+    self.reflected_string("lang")
+  }
+
+  pub fn set_lang(&self, value: String) {
+    self.set_reflected_string("lang", value)
+  }
+
+  /// Returns the `dir` of an HTML element: `"ltr"`, `"rtl"`, `"auto"` or `""`.
+  pub fn dir(&self) -> Option<String> {
+    self.reflected_string("dir")
+  }
+
+  pub fn set_dir(&self, value: String) {
+    self.set_reflected_string("dir", value)
+  }
+
+  /// Returns the `tabIndex` of an HTML element, 0 by default for focusable elements and -1 otherwise.
+  pub fn tab_index(&self) -> Option<i32> {
+    match self.reflected_attribute("tabIndex")? {
+      ReflectedValue::Integer(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  pub fn set_tab_index(&self, value: i32) {
+    let _ = self.set_reflected_attribute("tabIndex", ReflectedValue::Integer(value));
+  }
+
+  /// Returns whether an `input` element is checked.
+  /// The tree has no form state, so this reflects the `checked` attribute.
+  pub fn checked(&self) -> Option<bool> {
+    self.reflected_bool("checked")
+  }
+
+  /// Checks or unchecks an `input` element. Checking a radio button unchecks the
+  /// other radio buttons with the same name in the same tree.
+  pub fn set_checked(&self, value: bool) {
+    if value && self.input_type().as_deref() == Some("radio") {
+      let group = self.get_attribute("name".to_string()).unwrap_or_default();
+      if !group.is_empty() {
+        let root = self.get_root_node();
+        for radio in root.get_elements_by_tag_name("input".to_string()) {
+          if radio.input_type().as_deref() == Some("radio")
+            && radio.get_attribute("name".to_string()).as_deref() == Some(group.as_str())
+          {
+            radio.remove_attribute("checked".to_string());
+          }
+        }
+      }
+    }
+    self.set_reflected_bool("checked", value)
+  }
+
+  /// Returns the default value of an `input` (its `value` attribute) or `textarea` (its text).
+  pub fn default_value(&self) -> Option<String> {
+    match html_local_name(&self.0)? {
+      "textarea" => Some(self.child_text_content()),
+      _ => self.reflected_string("defaultValue"),
+    }
+  }
+
+  pub fn set_default_value(&self, value: String) {
+    match html_local_name(&self.0) {
+      Some("textarea") => self.set_text_content(value),
+      _ => self.set_reflected_string("defaultValue", value),
+    }
+  }
+
+  /// Returns the value of a form control: `input`, `textarea`, `select`, `option`, `button` or `output`.
+  /// The tree has no dirty form state, so values come from the markup, sanitized by the
+  /// `input` type.
+  pub fn value(&self) -> Option<String> {
+    match html_local_name(&self.0)? {
+      "input" => Some(match self.input_type().as_deref() {
+        Some("file") => String::new(),
+        Some("checkbox" | "radio") => self
+          .get_attribute("value".to_string())
+          .unwrap_or_else(|| "on".to_string()),
+        input_type => sanitize_value(
+          input_type.unwrap_or_default(),
+          self.get_attribute("value".to_string()).unwrap_or_default(),
+          self.has_attribute("multiple".to_string()),
+        ),
+      }),
+      "button" | "data" => Some(self.get_attribute("value".to_string()).unwrap_or_default()),
+      "textarea" => Some(self.child_text_content()),
+      "output" => Some(self.text_content_getter()),
+      "option" => Some(
+        self
+          .get_attribute("value".to_string())
+          .unwrap_or_else(|| self.option_text()),
+      ),
+      "select" => Some(
+        self
+          .selected_options()
+          .first()
+          .and_then(|option| option.value())
+          .unwrap_or_default(),
+      ),
+      _ => None,
+    }
+  }
+
+  pub fn set_value(&self, value: String) {
+    match html_local_name(&self.0) {
+      // A file input's value can only be cleared.
+      Some("input") if self.input_type().as_deref() != Some("file") || value.is_empty() => {
+        self.set_attribute("value".to_string(), value)
+      }
+      Some("button" | "data" | "option") => self.set_attribute("value".to_string(), value),
+      Some("textarea" | "output") => self.set_text_content(value),
+      Some("select") => {
+        let options = self.options();
+        let index = options
+          .iter()
+          .position(|option| option.value().as_deref() == Some(value.as_str()));
+        self.select_option_at(&options, index);
+      }
+      _ => {}
+    }
+  }
+
+  /// Returns the index of the first selected option of a `select` element, or -1.
+  pub fn selected_index(&self) -> Option<i32> {
+    if html_local_name(&self.0)? != "select" {
+      return None;
+    }
+    let options = self.options();
+    Some(
+      self
+        .selected_options()
+        .first()
+        .and_then(|selected| {
+          options
+            .iter()
+            .position(|option| option.is_same_node(selected))
+        })
+        .map_or(-1, |index| index as i32),
+    )
+  }
+
+  pub fn set_selected_index(&self, index: i32) {
+    if html_local_name(&self.0) == Some("select") {
+      let options = self.options();
+      self.select_option_at(&options, usize::try_from(index).ok());
+    }
+  }
+
+  /// Returns whether an `option` element is selected, taking its `select` into account.
+  pub fn selected(&self) -> Option<bool> {
+    if html_local_name(&self.0)? != "option" {
+      return None;
+    }
+    Some(match self.owner_select() {
+      Some(select) => select
+        .selected_options()
+        .iter()
+        .any(|option| option.is_same_node(self)),
+      None => self.has_attribute("selected".to_string()),
+    })
+  }
+
+  /// Selects or deselects an `option` element. Selecting an option of a single-selection
+  /// `select` deselects the others.
+  pub fn set_selected(&self, value: bool) {
+    if html_local_name(&self.0) != Some("option") {
+      return;
+    }
+    match self.owner_select() {
+      Some(select) if !select.has_attribute("multiple".to_string()) => {
+        if value {
+          let options = select.options();
+          let index = options.iter().position(|option| option.is_same_node(self));
+          select.select_option_at(&options, index);
+        } else {
+          // Keep the current selection, which may be this option by default.
+          for option in select.selected_options() {
+            if !option.is_same_node(self) {
+              option.set_attribute("selected".to_string(), String::new());
+            }
+          }
+          self.remove_attribute("selected".to_string());
+        }
+      }
+      _ => {
+        if value {
+          self.set_attribute("selected".to_string(), String::new())
+        } else {
+          self.remove_attribute("selected".to_string())
+        }
+      }
+    }
+  }
+
+  /// The `type` of an `input` element, lowercased, defaulting to `"text"`.
+  pub(crate) fn input_type(&self) -> Option<String> {
+    if html_local_name(&self.0)? != "input" {
+      return None;
+    }
+    Some(
+      self
+        .get_attribute("type".to_string())
+        .map(|t| t.to_ascii_lowercase())
+        .unwrap_or_else(|| "text".to_string()),
+    )
+  }
+
+  /// The concatenated data of the text node children.
+  fn child_text_content(&self) -> String {
+    self
+      .0
+      .children
+      .borrow()
+      .iter()
+      .filter_map(|child| match &child.data {
+        NodeData::Text { contents } => Some(contents.borrow().to_string()),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// The text of an `option` with ASCII whitespace stripped and collapsed.
+  fn option_text(&self) -> String {
+    self
+      .text_content_getter()
+      .split_ascii_whitespace()
+      .collect::<Vec<_>>()
+      .join(" ")
+  }
+
+  /// The `select` an `option` belongs to, directly or through an `optgroup`.
+  fn owner_select(&self) -> Option<DomNode> {
+    let parent = self.parent_element()?;
+    match html_local_name(&parent.0)? {
+      "select" => Some(parent),
+      "optgroup" => parent
+        .parent_element()
+        .filter(|grandparent| html_local_name(&grandparent.0) == Some("select")),
+      _ => None,
+    }
+  }
+
+  /// The list of options of a `select`: its `option` children and those of its `optgroup` children.
+  fn options(&self) -> Vec<DomNode> {
+    let mut options = Vec::new();
+    for child in self.0.children.borrow().iter() {
+      match html_local_name(child) {
+        Some("option") => options.push(DomNode(child.clone())),
+        Some("optgroup") => options.extend(
+          child
+            .children
+            .borrow()
+            .iter()
+            .filter(|c| html_local_name(c) == Some("option"))
+            .map(|c| DomNode(c.clone())),
+        ),
+        _ => {}
+      }
+    }
+    options
+  }
+
+  /// The selected options of a `select`, following the selectedness setting algorithm:
+  /// without `multiple` only the last option marked `selected` counts, and a drop-down
+  /// box falls back to its first enabled option.
+  pub(crate) fn selected_options(&self) -> Vec<DomNode> {
+    let options = self.options();
+    let is_selected = |option: &&DomNode| option.has_attribute("selected".to_string());
+    if self.has_attribute("multiple".to_string()) {
+      return options.iter().filter(is_selected).cloned().collect();
+    }
+    if let Some(option) = options.iter().rev().find(is_selected) {
+      return vec![option.clone()];
+    }
+    let display_size = self
+      .get_attribute("size".to_string())
+      .as_deref()
+      .and_then(parse_integer)
+      .filter(|size| *size > 0)
+      .unwrap_or(1);
+    if display_size == 1 {
+      if let Some(option) = options
+        .iter()
+        .find(|option| !option.has_attribute("disabled".to_string()))
+      {
+        return vec![option.clone()];
+      }
+    }
+    vec![]
+  }
+
+  /// Marks `options[index]` as the only selected option; `None` deselects all.
+  fn select_option_at(&self, options: &[DomNode], index: Option<usize>) {
+    for (i, option) in options.iter().enumerate() {
+      if Some(i) == index {
+        option.set_attribute("selected".to_string(), String::new());
+      } else {
+        option.remove_attribute("selected".to_string());
+      }
+    }
+  }
+}
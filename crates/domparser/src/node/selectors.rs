@@ -1,5 +1,7 @@
+use crate::node::shadow::{is_shadow_root, tree_parent};
 use crate::node::{get_parent, DomNode};
 use cssparser::ToCss;
+use html5ever::ns;
 use markup5ever_rcdom::NodeData;
 use precomputed_hash::PrecomputedHash;
 use selectors::attr::{AttrSelectorOperation, CaseSensitivity, NamespaceConstraint};
@@ -100,8 +102,11 @@ impl Element for DomNode {
     OpaqueElement::new(self.0.as_ref())
   }
   fn parent_element(&self) -> Option<Self> {
-    let mut cur = get_parent(&self.0);
+    let mut cur = tree_parent(&self.0);
     while let Some(parent) = cur {
+      if is_shadow_root(&parent) {
+        return None;
+      }
       if matches!(parent.data, NodeData::Element { .. }) {
         return Some(DomNode(parent));
       }
@@ -110,10 +115,10 @@ impl Element for DomNode {
     None
   }
   fn parent_node_is_shadow_root(&self) -> bool {
-    false
+    tree_parent(&self.0).is_some_and(|parent| is_shadow_root(&parent))
   }
   fn containing_shadow_host(&self) -> Option<Self> {
-    None
+    self.get_root_node().host()
   }
   fn is_pseudo_element(&self) -> bool {
     false
@@ -150,7 +155,7 @@ impl Element for DomNode {
       .map(|n| DomNode(n.clone()))
   }
   fn is_html_element_in_html_document(&self) -> bool {
-    true
+    self.has_namespace(ns!(html).as_ref())
   }
   fn has_local_name(&self, local_name: &str) -> bool {
     if let NodeData::Element { name, .. } = &self.0.data {
@@ -220,7 +225,10 @@ impl Element for DomNode {
     }
   }
   fn is_html_slot_element(&self) -> bool {
-    false
+    self.has_namespace(ns!(html).as_ref()) && self.has_local_name("slot")
+  }
+  fn assigned_slot(&self) -> Option<Self> {
+    self.find_slot()
   }
   fn has_id(&self, id: &CssString, _: CaseSensitivity) -> bool {
     if let NodeData::Element { attrs, .. } = &self.0.data {
@@ -249,11 +257,18 @@ impl Element for DomNode {
   fn has_custom_state(&self, _: &CssString) -> bool {
     false
   }
-  fn imported_part(&self, _: &CssString) -> Option<CssString> {
-    None
+  fn imported_part(&self, name: &CssString) -> Option<CssString> {
+    // `exportparts="inner: outer, label"` exposes inner parts of this host under outer names.
+    let exportparts = self.get_attribute("exportparts".to_string())?;
+    exportparts.split(',').find_map(|mapping| {
+      let (inner, outer) = mapping.split_once(':').unwrap_or((mapping, mapping));
+      (outer.trim() == name.as_ref()).then(|| CssString::from(inner.trim()))
+    })
   }
-  fn is_part(&self, _: &CssString) -> bool {
-    false
+  fn is_part(&self, name: &CssString) -> bool {
+    self
+      .get_attribute("part".to_string())
+      .is_some_and(|part| part.split_whitespace().any(|p| p == name.as_ref()))
   }
   fn is_empty(&self) -> bool {
     self
@@ -264,7 +279,7 @@ impl Element for DomNode {
       .all(|n| !matches!(n.data, NodeData::Element { .. } | NodeData::Text { .. }))
   }
   fn is_root(&self) -> bool {
-    self.parent_element().is_none()
+    self.parent_element().is_none() && !self.parent_node_is_shadow_root()
   }
   fn add_element_unique_hashes(&self, _: &mut CountingBloomFilter<BloomStorageU8>) -> bool {
     false
@@ -275,6 +290,16 @@ pub struct SelectorParser;
 impl<'i> selectors::parser::Parser<'i> for SelectorParser {
   type Impl = DomParserSelectors;
   type Error = selectors::parser::SelectorParseErrorKind<'i>;
+
+  fn parse_host(&self) -> bool {
+    true
+  }
+  fn parse_slotted(&self) -> bool {
+    true
+  }
+  fn parse_part(&self) -> bool {
+    true
+  }
 }
 
 pub fn parse_selectors(selectors: &str) -> Option<selectors::SelectorList<DomParserSelectors>> {
@@ -0,0 +1,468 @@
+use crate::sanitizer::{is_unsafe_element, Sanitizer};
+use crate::serializer::SerializableDomNode;
+use html5ever::{local_name, ns, LocalName, QualName};
+use markup5ever_rcdom::{Handle, Node, NodeData, WeakHandle};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::{get_parent, DomNode};
+
+/// Local name of the element standing in for a ShadowRoot, in the same way
+/// `#document-fragment` stands in for a DocumentFragment.
+pub(crate) const SHADOW_ROOT_NAME: &str = "#shadow-root";
+
+/// Options for [`DomNode::attach_shadow`], mirroring the DOM `ShadowRootInit` dictionary.
+#[derive(Clone, Debug, Default)]
+pub struct ShadowRootInit {
+  /// `"open"` or `"closed"`.
+  pub mode: String,
+  pub delegates_focus: bool,
+  /// `"named"` (the default) or `"manual"`.
+  pub slot_assignment: Option<String>,
+  pub clonable: bool,
+  pub serializable: bool,
+}
+
+thread_local! {
+  /// The options of each shadow root, which are not attributes of the node standing in for it.
+  static SHADOW_ROOT_STATES: RefCell<HashMap<usize, (WeakHandle, ShadowRootInit)>> =
+    RefCell::new(HashMap::new());
+}
+
+fn set_shadow_root_state(root: &Handle, init: ShadowRootInit) {
+  SHADOW_ROOT_STATES.with(|states| {
+    let mut states = states.borrow_mut();
+    states.retain(|_, (weak, _)| weak.strong_count() > 0);
+    states.insert(Rc::as_ptr(root) as usize, (Rc::downgrade(root), init));
+  });
+}
+
+fn shadow_root_state(root: &Handle) -> Option<ShadowRootInit> {
+  if !is_shadow_root(root) {
+    return None;
+  }
+  SHADOW_ROOT_STATES.with(|states| {
+    states
+      .borrow()
+      .get(&(Rc::as_ptr(root) as usize))
+      .filter(|(weak, _)| weak.strong_count() > 0)
+      .map(|(_, init)| init.clone())
+  })
+}
+
+/// Gives the copy `new` of a shadow root the options of the original `old`.
+pub(crate) fn copy_shadow_root_state(old: &Handle, new: &Handle) {
+  if let Some(init) = shadow_root_state(old) {
+    set_shadow_root_state(new, init);
+  }
+}
+
+pub(crate) fn is_shadow_root(handle: &Handle) -> bool {
+  matches!(&handle.data, NodeData::Element { name, .. } if name.ns == ns!() && name.local.as_ref() == SHADOW_ROOT_NAME)
+}
+
+/// The shadow root attached to `host`, regardless of its mode.
+pub(crate) fn attached_shadow_root(host: &Handle) -> Option<Handle> {
+  if let NodeData::Element {
+    template_contents, ..
+  } = &host.data
+  {
+    template_contents
+      .borrow()
+      .as_ref()
+      .filter(|root| is_shadow_root(root))
+      .cloned()
+  } else {
+    None
+  }
+}
+
+/// The parent of `node` in its own tree: the host is not the parent of its shadow root.
+pub(crate) fn tree_parent(node: &Handle) -> Option<Handle> {
+  if is_shadow_root(node) {
+    None
+  } else {
+    get_parent(node)
+  }
+}
+
+/// The parent of `node` in the flat tree, and whether `node` is rendered there. A shadow root's
+/// children have the host as parent; a host's children have their slot, or the host if they
+/// are not assigned to one, in which case they are not rendered.
+pub(crate) fn flat_tree_parent(node: &Handle) -> Option<(Handle, bool)> {
+  let parent = get_parent(node)?;
+  if is_shadow_root(&parent) {
+    return get_parent(&parent).map(|host| (host, true));
+  }
+  if attached_shadow_root(&parent).is_some() {
+    return Some(match find_slot(node) {
+      Some(slot) => (slot, true),
+      None => (parent, false),
+    });
+  }
+  Some((parent, true))
+}
+
+/// Re-points the parent of a shadow root copied by `clone_node_data` to the new host.
+pub(crate) fn link_shadow_root(host: &Handle) {
+  if let Some(root) = attached_shadow_root(host) {
+    root.parent.set(Some(Rc::downgrade(host)));
+  }
+}
+
+/// Whether `name` is allowed to host a shadow root.
+/// https://dom.spec.whatwg.org/#dom-element-attachshadow
+fn is_valid_shadow_host_name(name: &QualName) -> bool {
+  if name.ns != ns!(html) {
+    return false;
+  }
+  let local = name.local.as_ref();
+  let is_custom_element = local.starts_with(|c: char| c.is_ascii_lowercase())
+    && local.contains('-')
+    && !local.chars().any(|c| c.is_ascii_uppercase());
+  is_custom_element
+    || matches!(
+      local,
+      "article"
+        | "aside"
+        | "blockquote"
+        | "body"
+        | "div"
+        | "footer"
+        | "h1"
+        | "h2"
+        | "h3"
+        | "h4"
+        | "h5"
+        | "h6"
+        | "header"
+        | "main"
+        | "nav"
+        | "p"
+        | "section"
+        | "span"
+    )
+}
+
+fn shadow_root_mode(root: &Handle) -> Option<String> {
+  shadow_root_state(root).map(|init| init.mode)
+}
+
+fn slottable_name(node: &Handle) -> String {
+  match &node.data {
+    NodeData::Element { .. } => DomNode(node.clone())
+      .get_attribute("slot".to_string())
+      .unwrap_or_default(),
+    _ => String::new(),
+  }
+}
+
+fn is_slot(node: &Handle) -> bool {
+  matches!(&node.data, NodeData::Element { name, .. } if name.ns == ns!(html) && name.local == local_name!("slot"))
+}
+
+fn is_slottable(node: &Handle) -> bool {
+  matches!(node.data, NodeData::Element { .. } | NodeData::Text { .. })
+}
+
+/// The first slot in tree order under `root` named `name`, not descending into nested shadow trees.
+fn find_slot_named(root: &Handle, name: &str) -> Option<Handle> {
+  for child in root.children.borrow().iter() {
+    if is_slot(child)
+      && DomNode(child.clone())
+        .get_attribute("name".to_string())
+        .unwrap_or_default()
+        == name
+    {
+      return Some(child.clone());
+    }
+    if let Some(found) = find_slot_named(child, name) {
+      return Some(found);
+    }
+  }
+  None
+}
+
+/// https://dom.spec.whatwg.org/#find-a-slot
+fn find_slot(slottable: &Handle) -> Option<Handle> {
+  let parent = get_parent(slottable)?;
+  let root = attached_shadow_root(&parent)?;
+  if shadow_root_state(&root)
+    .and_then(|init| init.slot_assignment)
+    .as_deref()
+    == Some("manual")
+  {
+    return None;
+  }
+  find_slot_named(&root, &slottable_name(slottable))
+}
+
+/// https://dom.spec.whatwg.org/#find-slotables
+fn find_slottables(slot: &Handle) -> Vec<Handle> {
+  let root = DomNode(slot.clone()).get_root_node();
+  if !is_shadow_root(&root.0) {
+    return vec![];
+  }
+  let Some(host) = get_parent(&root.0) else {
+    return vec![];
+  };
+  let children = host.children.borrow();
+  children
+    .iter()
+    .filter(|child| is_slottable(child))
+    .filter(|child| find_slot(child).is_some_and(|found| Rc::ptr_eq(&found, slot)))
+    .cloned()
+    .collect()
+}
+
+/// https://dom.spec.whatwg.org/#find-flattened-slotables
+fn find_flattened_slottables(slot: &Handle, results: &mut Vec<Handle>) {
+  if !is_shadow_root(&DomNode(slot.clone()).get_root_node().0) {
+    return;
+  }
+  let mut slottables = find_slottables(slot);
+  if slottables.is_empty() {
+    slottables = slot
+      .children
+      .borrow()
+      .iter()
+      .filter(|child| is_slottable(child))
+      .cloned()
+      .collect();
+  }
+  for node in slottables {
+    if is_slot(&node) && is_shadow_root(&DomNode(node.clone()).get_root_node().0) {
+      find_flattened_slottables(&node, results);
+    } else {
+      results.push(node);
+    }
+  }
+}
+
+impl DomNode {
+  /// Attaches a shadow root to this element and returns it.
+  pub fn attach_shadow(&self, init: ShadowRootInit) -> Result<DomNode, String> {
+    let NodeData::Element {
+      name,
+      template_contents,
+      ..
+    } = &self.0.data
+    else {
+      return Err("Only elements can host a shadow root.".to_string());
+    };
+    if init.mode != "open" && init.mode != "closed" {
+      return Err(format!(
+        "'{}' is not a valid shadow root mode, expected 'open' or 'closed'.",
+        init.mode
+      ));
+    }
+    if !is_valid_shadow_host_name(name) {
+      return Err(format!(
+        "The <{}> element does not support attachShadow.",
+        name.local
+      ));
+    }
+    if template_contents.borrow().is_some() {
+      return Err("The element already hosts a shadow root.".to_string());
+    }
+
+    let root = Node::new(NodeData::Element {
+      name: QualName::new(None, ns!(), LocalName::from(SHADOW_ROOT_NAME)),
+      attrs: RefCell::new(vec![]),
+      template_contents: RefCell::new(None),
+      mathml_annotation_xml_integration_point: false,
+    });
+    set_shadow_root_state(
+      &root,
+      ShadowRootInit {
+        slot_assignment: Some(init.slot_assignment.unwrap_or_else(|| "named".to_string())),
+        ..init
+      },
+    );
+    root.parent.set(Some(Rc::downgrade(&self.0)));
+    *template_contents.borrow_mut() = Some(root.clone());
+    Ok(DomNode(root))
+  }
+
+  /// Returns the shadow root attached to this element if its mode is `"open"`.
+  pub fn shadow_root(&self) -> Option<DomNode> {
+    attached_shadow_root(&self.0)
+      .filter(|root| shadow_root_mode(root).as_deref() == Some("open"))
+      .map(DomNode)
+  }
+
+  /// Returns the shadow root attached to this element, including closed ones.
+  pub fn attached_shadow_root(&self) -> Option<DomNode> {
+    attached_shadow_root(&self.0).map(DomNode)
+  }
+
+  /// Returns true if this node is a ShadowRoot.
+  pub fn is_shadow_root(&self) -> bool {
+    is_shadow_root(&self.0)
+  }
+
+  /// Returns the host element of this shadow root.
+  pub fn host(&self) -> Option<DomNode> {
+    if is_shadow_root(&self.0) {
+      get_parent(&self.0).map(DomNode)
+    } else {
+      None
+    }
+  }
+
+  /// Returns the mode of this shadow root, `"open"` or `"closed"`.
+  pub fn mode(&self) -> Option<String> {
+    shadow_root_mode(&self.0)
+  }
+
+  /// Returns the slot assignment mode of this shadow root, `"named"` or `"manual"`.
+  pub fn slot_assignment(&self) -> Option<String> {
+    shadow_root_state(&self.0).and_then(|init| init.slot_assignment)
+  }
+
+  pub fn delegates_focus(&self) -> bool {
+    shadow_root_state(&self.0).is_some_and(|init| init.delegates_focus)
+  }
+
+  pub fn clonable(&self) -> bool {
+    shadow_root_state(&self.0).is_some_and(|init| init.clonable)
+  }
+
+  pub fn serializable(&self) -> bool {
+    shadow_root_state(&self.0).is_some_and(|init| init.serializable)
+  }
+
+  /// Returns the nodes assigned to this slot.
+  /// If `flatten` is true, slots are replaced by their own assigned nodes, and
+  /// a slot without assigned nodes contributes its fallback content.
+  pub fn assigned_nodes(&self, flatten: Option<bool>) -> Vec<DomNode> {
+    if !is_slot(&self.0) {
+      return vec![];
+    }
+    if flatten.unwrap_or(false) {
+      let mut results = Vec::new();
+      find_flattened_slottables(&self.0, &mut results);
+      results.into_iter().map(DomNode).collect()
+    } else {
+      find_slottables(&self.0).into_iter().map(DomNode).collect()
+    }
+  }
+
+  /// Returns the elements assigned to this slot, see [`DomNode::assigned_nodes`].
+  pub fn assigned_elements(&self, flatten: Option<bool>) -> Vec<DomNode> {
+    self
+      .assigned_nodes(flatten)
+      .into_iter()
+      .filter(|node| matches!(node.0.data, NodeData::Element { .. }))
+      .collect()
+  }
+
+  /// Returns the slot this node is assigned to, if that slot is in an open shadow tree.
+  pub fn assigned_slot(&self) -> Option<DomNode> {
+    let slot = find_slot(&self.0)?;
+    let root = DomNode(slot.clone()).get_root_node();
+    if shadow_root_mode(&root.0).as_deref() == Some("open") {
+      Some(DomNode(slot))
+    } else {
+      None
+    }
+  }
+
+  /// Replaces the children of this element with the result of parsing `html`,
+  /// turning `<template shadowrootmode>` elements into shadow roots.
+  pub fn set_html_unsafe(&self, html: String) {
+    self.set_inner_html(html);
+    attach_declarative_shadow_roots(&self.0);
+  }
+
+  /// Replaces the children of this element with the result of parsing `html`,
+  /// sanitized by `sanitizer` or the default [`Sanitizer`]. Declarative shadow
+  /// roots are not attached. Elements the sanitizer always removes, such as
+  /// `<script>`, are left empty.
+  pub fn set_html_safe(&self, html: String, sanitizer: Option<&Sanitizer>) {
+    if let NodeData::Element { name, .. } = &self.0.data {
+      if is_unsafe_element(&name.ns, &name.local) {
+        for child in self.0.children.take() {
+          child.parent.set(None);
+        }
+        return;
+      }
+    }
+    self.set_inner_html(html);
+    let default = Sanitizer::default();
+    sanitizer.unwrap_or(&default).sanitize_contents(&self.0);
+  }
+
+  /// Returns the HTML serialization of this node's children, including shadow roots
+  /// that are serializable (when `serializable_shadow_roots` is true) or listed in `shadow_roots`.
+  pub fn get_html(&self, serializable_shadow_roots: bool, shadow_roots: Vec<DomNode>) -> String {
+    SerializableDomNode {
+      handle: self.0.clone(),
+      serializable_shadow_roots,
+      shadow_roots: shadow_roots.into_iter().map(|n| n.0).collect(),
+    }
+    .to_html(false)
+  }
+
+  pub(crate) fn find_slot(&self) -> Option<DomNode> {
+    find_slot(&self.0).map(DomNode)
+  }
+}
+
+/// Converts each `<template shadowrootmode="open|closed">` into a shadow root on its parent,
+/// as the HTML parser does for declarative shadow DOM.
+pub(crate) fn attach_declarative_shadow_roots(parent: &Handle) {
+  let mut i = 0;
+  loop {
+    let child = match parent.children.borrow().get(i) {
+      Some(child) => child.clone(),
+      None => break,
+    };
+    let NodeData::Element {
+      name,
+      attrs,
+      template_contents,
+      ..
+    } = &child.data
+    else {
+      i += 1;
+      continue;
+    };
+
+    if let Some(contents) = template_contents.borrow().as_ref() {
+      attach_declarative_shadow_roots(contents);
+    }
+
+    let is_template = name.ns == ns!(html) && name.local == local_name!("template");
+    let mode = attrs
+      .borrow()
+      .iter()
+      .find(|a| a.name.local.as_ref() == "shadowrootmode")
+      .map(|a| a.value.to_ascii_lowercase());
+    if let (true, Some(mode)) = (is_template, mode) {
+      let has_attr = |name: &str| attrs.borrow().iter().any(|a| a.name.local.as_ref() == name);
+      let init = ShadowRootInit {
+        mode,
+        delegates_focus: has_attr("shadowrootdelegatesfocus"),
+        slot_assignment: None,
+        clonable: has_attr("shadowrootclonable"),
+        serializable: has_attr("shadowrootserializable"),
+      };
+      if let Ok(root) = DomNode(parent.clone()).attach_shadow(init) {
+        if let Some(contents) = template_contents.borrow().as_ref() {
+          for node in contents.children.take() {
+            node.parent.set(Some(Rc::downgrade(&root.0)));
+            root.0.children.borrow_mut().push(node);
+          }
+        }
+        parent.children.borrow_mut().remove(i);
+        child.parent.set(None);
+        continue;
+      }
+    }
+
+    attach_declarative_shadow_roots(&child);
+    i += 1;
+  }
+}
@@ -0,0 +1,15 @@
+use super::DomNode;
+use crate::css::CssStyleDeclaration;
+
+impl DomNode {
+  /// Parses the `style` attribute into a declaration block. Changes to the block are not
+  /// reflected until it is written back with [`DomNode::set_style`].
+  pub fn style(&self) -> CssStyleDeclaration {
+    CssStyleDeclaration::parse(&self.get_attribute("style".to_string()).unwrap_or_default())
+  }
+
+  /// Replaces the `style` attribute with the serialization of `style`.
+  pub fn set_style(&self, style: &CssStyleDeclaration) {
+    self.set_attribute("style".to_string(), style.css_text());
+  }
+}
@@ -0,0 +1,57 @@
+use html5ever::{local_name, ns, LocalName, QualName};
+use markup5ever_rcdom::{Handle, Node, NodeData};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::DomNode;
+
+pub(crate) fn is_template(handle: &Handle) -> bool {
+  matches!(&handle.data, NodeData::Element { name, .. } if name.ns == ns!(html) && name.local == local_name!("template"))
+}
+
+/// The contents of a `<template>` element as a DocumentFragment, or `None` for other nodes.
+///
+/// The parser stores template children under a `NodeData::Document` node, and elements
+/// created through the API have no contents at all. Either way the contents are
+/// replaced on first access by a `#document-fragment` element owning the same children.
+pub(crate) fn template_contents(handle: &Handle) -> Option<Handle> {
+  if !is_template(handle) {
+    return None;
+  }
+  let NodeData::Element {
+    template_contents, ..
+  } = &handle.data
+  else {
+    return None;
+  };
+
+  let mut contents = template_contents.borrow_mut();
+  if let Some(fragment) = contents
+    .as_ref()
+    .filter(|c| !matches!(c.data, NodeData::Document))
+  {
+    return Some(fragment.clone());
+  }
+
+  let fragment = Node::new(NodeData::Element {
+    name: QualName::new(None, ns!(), LocalName::from("#document-fragment")),
+    attrs: RefCell::new(vec![]),
+    template_contents: RefCell::new(None),
+    mathml_annotation_xml_integration_point: false,
+  });
+  if let Some(document) = contents.take() {
+    for child in document.children.take() {
+      child.parent.set(Some(Rc::downgrade(&fragment)));
+      fragment.children.borrow_mut().push(child);
+    }
+  }
+  *contents = Some(fragment.clone());
+  Some(fragment)
+}
+
+impl DomNode {
+  /// Returns the contents of a `<template>` element as a DocumentFragment.
+  pub fn content(&self) -> Option<DomNode> {
+    template_contents(&self.0).map(DomNode)
+  }
+}
@@ -0,0 +1,519 @@
+use html5ever::{local_name, ns, QualName};
+use markup5ever_rcdom::{Handle, Node, NodeData};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::DomNode;
+
+/// The used `display` of an element under the default user-agent stylesheet.
+/// Only the distinctions the innerText algorithm cares about are kept.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Display {
+  None,
+  Inline,
+  Block,
+  TableRow,
+  TableCell,
+}
+
+/// Elements the user-agent stylesheet hides with `display: none`.
+const HIDDEN_ELEMENTS: &[&str] = &[
+  "area", "base", "basefont", "datalist", "head", "link", "meta", "noembed", "noframes", "param",
+  "rp", "script", "style", "template", "title",
+];
+
+/// Elements the user-agent stylesheet lays out as blocks, including `list-item` and `table-caption`.
+const BLOCK_ELEMENTS: &[&str] = &[
+  "address",
+  "article",
+  "aside",
+  "blockquote",
+  "body",
+  "caption",
+  "center",
+  "dd",
+  "details",
+  "dialog",
+  "dir",
+  "div",
+  "dl",
+  "dt",
+  "fieldset",
+  "figcaption",
+  "figure",
+  "footer",
+  "form",
+  "frameset",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "header",
+  "hgroup",
+  "hr",
+  "html",
+  "legend",
+  "li",
+  "listing",
+  "main",
+  "menu",
+  "nav",
+  "ol",
+  "optgroup",
+  "option",
+  "p",
+  "plaintext",
+  "pre",
+  "search",
+  "section",
+  "summary",
+  "table",
+  "ul",
+  "xmp",
+];
+
+/// Elements with `white-space: pre` (or `pre-wrap`) in the user-agent stylesheet.
+const PREFORMATTED_ELEMENTS: &[&str] = &["listing", "plaintext", "pre", "textarea", "xmp"];
+
+/// Replaced elements and form controls whose children never produce text boxes.
+const REPLACED_ELEMENTS: &[&str] = &[
+  "audio", "canvas", "embed", "iframe", "img", "input", "object", "select", "textarea", "video",
+];
+
+fn html_local_name(handle: &Handle) -> Option<&str> {
+  match &handle.data {
+    NodeData::Element { name, .. } if name.ns == ns!(html) => Some(name.local.as_ref()),
+    _ => None,
+  }
+}
+
+fn attribute(handle: &Handle, name: &str) -> Option<String> {
+  match &handle.data {
+    NodeData::Element { attrs, .. } => attrs
+      .borrow()
+      .iter()
+      .find(|attr| attr.name.ns == ns!() && attr.name.local.as_ref() == name)
+      .map(|attr| attr.value.to_string()),
+    _ => None,
+  }
+}
+
+/// The value of a property in an element's inline `style` attribute, if declared.
+fn inline_style(handle: &Handle, property: &str) -> Option<String> {
+  let style = attribute(handle, "style")?;
+  style
+    .split(';')
+    .filter_map(|declaration| declaration.split_once(':'))
+    .filter(|(name, _)| name.trim().eq_ignore_ascii_case(property))
+    .map(|(_, value)| {
+      value
+        .trim()
+        .trim_end_matches("!important")
+        .trim()
+        .to_ascii_lowercase()
+    })
+    .next_back()
+}
+
+fn display(handle: &Handle) -> Display {
+  if !matches!(handle.data, NodeData::Element { .. }) {
+    return Display::Inline;
+  }
+  match inline_style(handle, "display").as_deref() {
+    Some("none") => return Display::None,
+    Some("block" | "flex" | "grid" | "list-item" | "table" | "table-caption" | "flow-root") => {
+      return Display::Block
+    }
+    Some("table-row") => return Display::TableRow,
+    Some("table-cell") => return Display::TableCell,
+    Some("inline" | "inline-block" | "inline-flex" | "inline-grid" | "contents") => {
+      return Display::Inline
+    }
+    _ => {}
+  }
+  let Some(local_name) = html_local_name(handle) else {
+    return Display::Inline;
+  };
+  let hidden = attribute(handle, "hidden").is_some()
+    || HIDDEN_ELEMENTS.contains(&local_name)
+    || (local_name == "noscript" && DomNode(handle.clone()).scripting_enabled())
+    || (local_name == "dialog" && attribute(handle, "open").is_none())
+    || (local_name == "input"
+      && attribute(handle, "type").is_some_and(|ty| ty.eq_ignore_ascii_case("hidden")));
+  if hidden {
+    Display::None
+  } else if BLOCK_ELEMENTS.contains(&local_name) {
+    Display::Block
+  } else if local_name == "tr" {
+    Display::TableRow
+  } else if matches!(local_name, "td" | "th") {
+    Display::TableCell
+  } else {
+    Display::Inline
+  }
+}
+
+fn is_visible(handle: &Handle) -> bool {
+  !matches!(
+    inline_style(handle, "visibility").as_deref(),
+    Some("hidden" | "collapse")
+  )
+}
+
+/// Whether a child of a closed `<details>` is hidden; only its first `<summary>` is rendered.
+fn hidden_by_details(parent: &Handle, child: &Handle) -> bool {
+  if html_local_name(parent) != Some("details") || attribute(parent, "open").is_some() {
+    return false;
+  }
+  let summary = parent
+    .children
+    .borrow()
+    .iter()
+    .find(|c| html_local_name(c) == Some("summary"))
+    .cloned();
+  !summary.is_some_and(|summary| Rc::ptr_eq(&summary, child))
+}
+
+/// Whether any of the node's following siblings has the given display.
+fn has_following(handle: &Handle, wanted: Display) -> bool {
+  let Some(parent) = super::get_parent(handle) else {
+    return false;
+  };
+  let children = parent.children.borrow();
+  let Some(pos) = children.iter().position(|c| Rc::ptr_eq(c, handle)) else {
+    return false;
+  };
+  children[pos + 1..].iter().any(|c| display(c) == wanted)
+}
+
+/// Whether a later table row follows `row` in its table, looking across row groups.
+fn has_following_row(row: &Handle) -> bool {
+  if has_following(row, Display::TableRow) {
+    return true;
+  }
+  let Some(group) = super::get_parent(row) else {
+    return false;
+  };
+  if !matches!(html_local_name(&group), Some("thead" | "tbody" | "tfoot")) {
+    return false;
+  }
+  let Some(table) = super::get_parent(&group) else {
+    return false;
+  };
+  let groups = table.children.borrow();
+  let Some(pos) = groups.iter().position(|c| Rc::ptr_eq(c, &group)) else {
+    return false;
+  };
+  groups[pos + 1..].iter().any(|g| {
+    display(g) == Display::TableRow
+      || (display(g) != Display::None
+        && g
+          .children
+          .borrow()
+          .iter()
+          .any(|r| display(r) == Display::TableRow))
+  })
+}
+
+/// An entry of the innerText results list.
+#[derive(Debug)]
+enum Item {
+  /// Text whose white space still has to be collapsed.
+  Collapsible(String),
+  /// Text with preserved white space, from `white-space: pre` content.
+  Preformatted(String),
+  /// A forced line break or cell separator; ends the current line's white space.
+  Separator(char),
+  /// A required line break count.
+  LineBreaks(usize),
+}
+
+/// https://html.spec.whatwg.org/multipage/dom.html#rendered-text-collection-steps
+fn collect_rendered_text(handle: &Handle, preformatted: bool, items: &mut Vec<Item>) {
+  match &handle.data {
+    NodeData::Text { contents } => {
+      let text = contents.borrow();
+      if preformatted {
+        items.push(Item::Preformatted(text.to_string()));
+      } else {
+        items.push(Item::Collapsible(text.to_string()));
+      }
+    }
+    NodeData::Element { .. } => {
+      let display = display(handle);
+      if display == Display::None {
+        return;
+      }
+      let local_name = html_local_name(handle);
+      let preformatted =
+        preformatted || local_name.is_some_and(|n| PREFORMATTED_ELEMENTS.contains(&n));
+      let start = items.len();
+      // Descendants of an invisible element could opt back in with `visibility: visible`,
+      // which is rare enough in inline styles to be ignored.
+      if !is_visible(handle) {
+        return;
+      }
+      if !local_name.is_some_and(|n| REPLACED_ELEMENTS.contains(&n)) {
+        for child in handle
+          .children
+          .borrow()
+          .iter()
+          .filter(|child| !hidden_by_details(handle, child))
+        {
+          collect_rendered_text(child, preformatted, items);
+        }
+      }
+
+      if local_name == Some("br") {
+        items.push(Item::Separator('\n'));
+      }
+      match display {
+        Display::TableCell if has_following(handle, Display::TableCell) => {
+          items.push(Item::Separator('\t'))
+        }
+        Display::TableRow if has_following_row(handle) => items.push(Item::Separator('\n')),
+        _ => {}
+      }
+      let breaks = if local_name == Some("p") {
+        2
+      } else if display == Display::Block {
+        1
+      } else {
+        0
+      };
+      if breaks > 0 {
+        items.insert(start, Item::LineBreaks(breaks));
+        items.push(Item::LineBreaks(breaks));
+      }
+    }
+    NodeData::Document => {
+      for child in handle.children.borrow().iter() {
+        collect_rendered_text(child, preformatted, items);
+      }
+    }
+    _ => {}
+  }
+}
+
+/// Joins the results list: collapses white space the way CSS would at line
+/// boundaries, drops leading and trailing line break counts and replaces each
+/// run of counts by that many newlines.
+fn join_rendered_text(items: Vec<Item>) -> String {
+  let mut out = String::new();
+  let mut pending_breaks = 0;
+  let mut pending_space = false;
+  let mut at_line_start = true;
+
+  fn flush(out: &mut String, pending_breaks: &mut usize, pending_space: &mut bool) {
+    if *pending_breaks > 0 {
+      if !out.is_empty() {
+        out.extend(std::iter::repeat_n('\n', *pending_breaks));
+      }
+      *pending_breaks = 0;
+    } else if *pending_space {
+      out.push(' ');
+    }
+    *pending_space = false;
+  }
+
+  for item in items {
+    match item {
+      Item::LineBreaks(count) => {
+        pending_breaks = pending_breaks.max(count);
+        pending_space = false;
+        at_line_start = true;
+      }
+      Item::Separator(c) => {
+        pending_space = false;
+        flush(&mut out, &mut pending_breaks, &mut pending_space);
+        out.push(c);
+        at_line_start = true;
+      }
+      Item::Preformatted(text) => {
+        if text.is_empty() {
+          continue;
+        }
+        flush(&mut out, &mut pending_breaks, &mut pending_space);
+        out.push_str(&text);
+        at_line_start = text.ends_with('\n');
+      }
+      Item::Collapsible(text) => {
+        for c in text.chars() {
+          if matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C') {
+            pending_space = !at_line_start;
+          } else {
+            flush(&mut out, &mut pending_breaks, &mut pending_space);
+            out.push(c);
+            at_line_start = false;
+          }
+        }
+      }
+    }
+  }
+  out
+}
+
+/// Whether the node is in a subtree the user-agent stylesheet does not render,
+/// in which case innerText falls back to the text content.
+fn is_being_rendered(handle: &Handle) -> bool {
+  let mut node = Some(handle.clone());
+  while let Some(current) = node {
+    if display(&current) == Display::None {
+      return false;
+    }
+    let parent = super::get_parent(&current);
+    if let Some(parent) = &parent {
+      if hidden_by_details(parent, &current) {
+        return false;
+      }
+    }
+    node = parent;
+  }
+  true
+}
+
+/// https://html.spec.whatwg.org/multipage/dom.html#rendered-text-fragment
+fn rendered_text_fragment(input: &str) -> Vec<Handle> {
+  let mut nodes = vec![];
+  let mut rest = input;
+  while !rest.is_empty() {
+    let end = rest.find(['\r', '\n']).unwrap_or(rest.len());
+    if end > 0 {
+      nodes.push(Node::new(NodeData::Text {
+        contents: RefCell::new(rest[..end].into()),
+      }));
+    }
+    rest = &rest[end..];
+    while let Some(c) = rest.chars().next().filter(|c| matches!(c, '\r' | '\n')) {
+      rest = if c == '\r' && rest[1..].starts_with('\n') {
+        &rest[2..]
+      } else {
+        &rest[1..]
+      };
+      nodes.push(Node::new(NodeData::Element {
+        name: QualName::new(None, ns!(html), local_name!("br")),
+        attrs: RefCell::new(vec![]),
+        template_contents: RefCell::new(None),
+        mathml_annotation_xml_integration_point: false,
+      }));
+    }
+  }
+  nodes
+}
+
+/// If `node` and its next sibling are both Text nodes, merges the sibling into it.
+fn merge_with_next_text_node(node: &Handle) {
+  let Some(parent) = super::get_parent(node) else {
+    return;
+  };
+  let mut children = parent.children.borrow_mut();
+  let Some(pos) = children.iter().position(|c| Rc::ptr_eq(c, node)) else {
+    return;
+  };
+  let (NodeData::Text { contents }, Some(next)) = (&node.data, children.get(pos + 1)) else {
+    return;
+  };
+  if let NodeData::Text {
+    contents: next_contents,
+  } = &next.data
+  {
+    contents.borrow_mut().push_tendril(&next_contents.borrow());
+    next.parent.set(None);
+    children.remove(pos + 1);
+  }
+}
+
+impl DomNode {
+  /// Returns the text as rendered by the HTML innerText algorithm, using the
+  /// default user-agent display of each element and its inline `style`.
+  /// Block boundaries become newlines, `<p>` gets a blank line, table cells are
+  /// separated by tabs and hidden content is skipped. Nodes that are not rendered,
+  /// such as `<script>`, return their text content. Layout-dependent details like
+  /// CSS stylesheets and shadow trees are not taken into account.
+  pub fn inner_text(&self) -> Option<String> {
+    match &self.0.data {
+      NodeData::Element { .. } | NodeData::Document => {}
+      _ => return None,
+    }
+    if !is_being_rendered(&self.0) {
+      return Some(self.text_content_getter());
+    }
+    let mut items = vec![];
+    let preformatted =
+      std::iter::successors(super::get_parent(&self.0), super::get_parent).any(|ancestor| {
+        html_local_name(&ancestor).is_some_and(|n| PREFORMATTED_ELEMENTS.contains(&n))
+      });
+    collect_rendered_text(&self.0, preformatted, &mut items);
+    Some(join_rendered_text(items))
+  }
+
+  /// Replaces the children with the given text, turning line breaks into `<br>` elements.
+  pub fn set_inner_text(&self, text: String) {
+    for child in self.0.children.take() {
+      child.parent.set(None);
+    }
+    for child in rendered_text_fragment(&text) {
+      child.parent.set(Some(Rc::downgrade(&self.0)));
+      self.0.children.borrow_mut().push(child);
+    }
+  }
+
+  /// Same as [`inner_text`](Self::inner_text).
+  pub fn outer_text(&self) -> Option<String> {
+    self.inner_text()
+  }
+
+  /// Replaces the node itself with the given text, turning line breaks into `<br>`
+  /// elements and merging the result with adjacent Text nodes.
+  pub fn set_outer_text(&self, text: String) -> Result<(), String> {
+    let parent = super::get_parent(&self.0)
+      .ok_or_else(|| "NoModificationAllowedError: the element has no parent.".to_string())?;
+    let (pos, previous, next) = {
+      let children = parent.children.borrow();
+      let pos = children
+        .iter()
+        .position(|c| Rc::ptr_eq(c, &self.0))
+        .ok_or_else(|| "NoModificationAllowedError: the element has no parent.".to_string())?;
+      (
+        pos,
+        pos.checked_sub(1).map(|p| children[p].clone()),
+        children.get(pos + 1).cloned(),
+      )
+    };
+
+    let mut nodes = rendered_text_fragment(&text);
+    if nodes.is_empty() {
+      nodes.push(Node::new(NodeData::Text {
+        contents: RefCell::new("".into()),
+      }));
+    }
+    {
+      let mut children = parent.children.borrow_mut();
+      for node in &nodes {
+        node.parent.set(Some(Rc::downgrade(&parent)));
+      }
+      children.splice(pos..=pos, nodes);
+    }
+    self.0.parent.set(None);
+
+    if let Some(next) = next.filter(|n| matches!(n.data, NodeData::Text { .. })) {
+      let before_next = {
+        let children = parent.children.borrow();
+        children
+          .iter()
+          .position(|c| Rc::ptr_eq(c, &next))
+          .and_then(|pos| pos.checked_sub(1))
+          .map(|pos| children[pos].clone())
+      };
+      if let Some(before_next) = before_next {
+        merge_with_next_text_node(&before_next);
+      }
+    }
+    if let Some(previous) = previous {
+      merge_with_next_text_node(&previous);
+    }
+    Ok(())
+  }
+}
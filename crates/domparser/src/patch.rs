@@ -0,0 +1,471 @@
+//! Structural diffing of DOM trees.
+//!
+//! [`dom_diff`] compares two trees and produces a [`Patch`]: a list of
+//! operations that, applied in order with [`apply_patch`], turn a tree equal to
+//! the old one into a tree equal to the new one. Operations address nodes by
+//! child index paths from the patched root, taken in the state the tree is in
+//! when that operation runs, so a patch only depends on the shape of the tree
+//! and can be sent elsewhere. Inserted and replacing nodes are carried as
+//! [`AstNode`]s. With the `serde` feature a patch (de)serializes to JSON:
+//!
+//! ```json
+//! { "operations": [
+//!   { "type": "setAttribute", "path": [1, 0], "name": "class", "value": "active" },
+//!   { "type": "insert", "parent": [1], "index": 2, "node": { "type": "text", "value": "!" } }
+//! ] }
+//! ```
+//!
+//! Children are matched by position among nodes of the same kind (node type
+//! and, for elements, qualified name). With a key attribute such as `id`,
+//! elements carrying that attribute are matched by its value instead, so
+//! reordered keyed elements become moves rather than replacements.
+
+use html5ever::{ns, Attribute, LocalName};
+use markup5ever_rcdom::{Handle, NodeData};
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::ast::AstNode;
+use crate::node::{attached_shadow_root, template_contents, DomNode};
+
+/// A single edit of a [`Patch`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+  feature = "serde",
+  serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+  )
+)]
+pub enum PatchOperation {
+  /// Inserts `node` so that it becomes child `index` of the node at `parent`.
+  Insert {
+    parent: Vec<usize>,
+    index: usize,
+    node: AstNode,
+  },
+  /// Removes the node at `path`.
+  Remove { path: Vec<usize> },
+  /// Moves child `from` of the node at `parent` so that it becomes child `to`.
+  Move {
+    parent: Vec<usize>,
+    from: usize,
+    to: usize,
+  },
+  /// Replaces the node at `path`, including its descendants, with `node`.
+  Replace { path: Vec<usize>, node: AstNode },
+  /// Sets an attribute of the element at `path`. `name` is the qualified name.
+  SetAttribute {
+    path: Vec<usize>,
+    #[cfg_attr(
+      feature = "serde",
+      serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    namespace: Option<String>,
+    name: String,
+    value: String,
+  },
+  /// Removes an attribute of the element at `path`. `name` is the local name.
+  RemoveAttribute {
+    path: Vec<usize>,
+    #[cfg_attr(
+      feature = "serde",
+      serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    namespace: Option<String>,
+    name: String,
+  },
+  /// Changes the data of the text node at `path`.
+  SetText { path: Vec<usize>, value: String },
+}
+
+/// An edit script produced by [`dom_diff`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Patch {
+  pub operations: Vec<PatchOperation>,
+}
+
+impl Patch {
+  /// Returns whether the patch has no operations, that is the trees were equal.
+  pub fn is_empty(&self) -> bool {
+    self.operations.is_empty()
+  }
+
+  /// Serializes the patch to JSON.
+  #[cfg(feature = "serde")]
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).unwrap()
+  }
+
+  /// Reads a patch from JSON.
+  #[cfg(feature = "serde")]
+  pub fn from_json(json: &str) -> Result<Patch, String> {
+    serde_json::from_str(json).map_err(|e| e.to_string())
+  }
+}
+
+/// Options for [`dom_diff`].
+#[derive(Clone, Debug, Default)]
+pub struct DiffOptions {
+  /// Match elements by the value of this attribute, such as `id`, before
+  /// falling back to their position.
+  pub key_attribute: Option<String>,
+}
+
+fn optional(value: &str) -> Option<String> {
+  (!value.is_empty()).then(|| value.to_string())
+}
+
+fn qualified_name(attr: &Attribute) -> String {
+  match &attr.name.prefix {
+    Some(prefix) => format!("{}:{}", prefix, attr.name.local),
+    None => attr.name.local.to_string(),
+  }
+}
+
+/// Whether `new` can be reached from `old` by editing it in place rather than replacing it.
+fn same_kind(old: &Handle, new: &Handle) -> bool {
+  if DomNode(old.clone()).node_type() != DomNode(new.clone()).node_type() {
+    return false;
+  }
+  match (&old.data, &new.data) {
+    (NodeData::Element { name, .. }, NodeData::Element { name: new_name, .. }) => name == new_name,
+    (
+      NodeData::ProcessingInstruction { target, .. },
+      NodeData::ProcessingInstruction {
+        target: new_target, ..
+      },
+    ) => target == new_target,
+    _ => true,
+  }
+}
+
+/// Template contents and shadow roots are compared as a whole; a change in
+/// either replaces the element.
+fn same_subtrees(old: &Handle, new: &Handle) -> bool {
+  let same_contents = match (template_contents(old), template_contents(new)) {
+    (Some(contents), Some(new_contents)) => DomNode(contents).is_equal_node(&DomNode(new_contents)),
+    (contents, new_contents) => contents.is_none() && new_contents.is_none(),
+  };
+  let same_shadow_roots = match (attached_shadow_root(old), attached_shadow_root(new)) {
+    (Some(root), Some(new_root)) => DomNode(root).to_ast() == DomNode(new_root).to_ast(),
+    (root, new_root) => root.is_none() && new_root.is_none(),
+  };
+  same_contents && same_shadow_roots
+}
+
+struct Differ<'a> {
+  key_attribute: Option<LocalName>,
+  operations: &'a mut Vec<PatchOperation>,
+}
+
+impl Differ<'_> {
+  fn key(&self, node: &Handle) -> Option<String> {
+    let key_attribute = self.key_attribute.as_ref()?;
+    match &node.data {
+      NodeData::Element { attrs, .. } => attrs
+        .borrow()
+        .iter()
+        .find(|attr| attr.name.ns == ns!() && &attr.name.local == key_attribute)
+        .map(|attr| attr.value.to_string()),
+      _ => None,
+    }
+  }
+
+  fn replace(&mut self, path: &[usize], new: &Handle) {
+    self.operations.push(PatchOperation::Replace {
+      path: path.to_vec(),
+      node: DomNode(new.clone()).to_ast(),
+    });
+  }
+
+  /// Diffs two nodes of the same kind.
+  fn diff_node(&mut self, old: &Handle, new: &Handle, path: &mut Vec<usize>) {
+    match (&old.data, &new.data) {
+      (
+        NodeData::Text { contents },
+        NodeData::Text {
+          contents: new_contents,
+        },
+      ) => {
+        if *contents.borrow() != *new_contents.borrow() {
+          self.operations.push(PatchOperation::SetText {
+            path: path.clone(),
+            value: new_contents.borrow().to_string(),
+          });
+        }
+      }
+      (
+        NodeData::Element { attrs, .. },
+        NodeData::Element {
+          attrs: new_attrs, ..
+        },
+      ) => {
+        if !same_subtrees(old, new) {
+          self.replace(path, new);
+          return;
+        }
+        self.diff_attributes(&attrs.borrow(), &new_attrs.borrow(), path);
+        self.diff_children(old, new, path);
+      }
+      (NodeData::Document, _) => self.diff_children(old, new, path),
+      _ => {
+        if !DomNode(old.clone()).is_equal_node(&DomNode(new.clone())) {
+          self.replace(path, new);
+        }
+      }
+    }
+  }
+
+  fn diff_attributes(&mut self, attrs: &[Attribute], new_attrs: &[Attribute], path: &[usize]) {
+    for attr in attrs {
+      if !new_attrs
+        .iter()
+        .any(|new| new.name.ns == attr.name.ns && new.name.local == attr.name.local)
+      {
+        self.operations.push(PatchOperation::RemoveAttribute {
+          path: path.to_vec(),
+          namespace: optional(&attr.name.ns),
+          name: attr.name.local.to_string(),
+        });
+      }
+    }
+    for new in new_attrs {
+      let unchanged = attrs
+        .iter()
+        .any(|attr| attr.name == new.name && attr.value == new.value);
+      if !unchanged {
+        self.operations.push(PatchOperation::SetAttribute {
+          path: path.to_vec(),
+          namespace: optional(&new.name.ns),
+          name: qualified_name(new),
+          value: new.value.to_string(),
+        });
+      }
+    }
+  }
+
+  /// Reconciles the children of two matched nodes: unmatched old children are
+  /// removed or replaced in place, matched ones are moved into position and
+  /// diffed, and the remaining new children are inserted.
+  fn diff_children(&mut self, old: &Handle, new: &Handle, path: &mut Vec<usize>) {
+    let old_children = old.children.borrow().clone();
+    let new_children = new.children.borrow().clone();
+    let old_keys: Vec<_> = old_children.iter().map(|child| self.key(child)).collect();
+    let new_keys: Vec<_> = new_children.iter().map(|child| self.key(child)).collect();
+
+    let mut old_used = vec![false; old_children.len()];
+    let mut matches: Vec<Option<usize>> = vec![None; new_children.len()];
+
+    let mut keyed = HashMap::new();
+    for (index, key) in old_keys.iter().enumerate() {
+      if let Some(key) = key {
+        keyed.entry(key.as_str()).or_insert(index);
+      }
+    }
+    for (index, key) in new_keys.iter().enumerate() {
+      let Some(&old_index) = key.as_deref().and_then(|key| keyed.get(key)) else {
+        continue;
+      };
+      if !old_used[old_index] && same_kind(&old_children[old_index], &new_children[index]) {
+        matches[index] = Some(old_index);
+        old_used[old_index] = true;
+      }
+    }
+
+    let mut next = 0;
+    for (index, new_child) in new_children.iter().enumerate() {
+      if new_keys[index].is_some() {
+        continue;
+      }
+      let found = (next..old_children.len()).find(|&old_index| {
+        !old_used[old_index]
+          && old_keys[old_index].is_none()
+          && same_kind(&old_children[old_index], new_child)
+      });
+      if let Some(old_index) = found {
+        matches[index] = Some(old_index);
+        old_used[old_index] = true;
+        next = old_index + 1;
+      }
+    }
+
+    // A new child without a match takes over the unmatched old child at the same index.
+    let mut replacements: Vec<Option<usize>> = vec![None; new_children.len()];
+    for (index, matched) in matches.iter().enumerate() {
+      if matched.is_none() && index < old_children.len() && !old_used[index] {
+        replacements[index] = Some(index);
+        old_used[index] = true;
+      }
+    }
+
+    let mut current: Vec<Option<usize>> = (0..old_children.len()).map(Some).collect();
+    for old_index in (0..old_children.len()).rev() {
+      if !old_used[old_index] {
+        current.remove(old_index);
+        path.push(old_index);
+        self
+          .operations
+          .push(PatchOperation::Remove { path: path.clone() });
+        path.pop();
+      }
+    }
+
+    for (index, new_child) in new_children.iter().enumerate() {
+      let Some(old_index) = matches[index].or(replacements[index]) else {
+        self.operations.push(PatchOperation::Insert {
+          parent: path.clone(),
+          index,
+          node: DomNode(new_child.clone()).to_ast(),
+        });
+        current.insert(index, None);
+        continue;
+      };
+      let position = current
+        .iter()
+        .position(|&entry| entry == Some(old_index))
+        .unwrap();
+      if position != index {
+        self.operations.push(PatchOperation::Move {
+          parent: path.clone(),
+          from: position,
+          to: index,
+        });
+        let entry = current.remove(position);
+        current.insert(index, entry);
+      }
+      path.push(index);
+      if matches[index].is_some() {
+        self.diff_node(&old_children[old_index], new_child, path);
+      } else {
+        self.replace(path, new_child);
+      }
+      path.pop();
+    }
+  }
+}
+
+/// Computes the operations that turn `old` into a tree equal to `new`.
+/// Neither tree is modified. If the roots are of different kinds, the patch
+/// replaces the root itself, which can only be applied while it has a parent.
+pub fn dom_diff(old: &DomNode, new: &DomNode, options: &DiffOptions) -> Patch {
+  let mut operations = vec![];
+  let mut differ = Differ {
+    key_attribute: options.key_attribute.as_deref().map(LocalName::from),
+    operations: &mut operations,
+  };
+  let mut path = vec![];
+  if same_kind(&old.0, &new.0) {
+    differ.diff_node(&old.0, &new.0, &mut path);
+  } else {
+    differ.replace(&path, &new.0);
+  }
+  Patch { operations }
+}
+
+fn invalid_path(path: &[usize]) -> String {
+  format!("Invalid patch: there is no node at {:?}.", path)
+}
+
+fn resolve(root: &DomNode, path: &[usize]) -> Result<DomNode, String> {
+  let mut node = root.0.clone();
+  for &index in path {
+    let child = node
+      .children
+      .borrow()
+      .get(index)
+      .cloned()
+      .ok_or_else(|| invalid_path(path))?;
+    node = child;
+  }
+  Ok(DomNode(node))
+}
+
+fn child_at(parent: &DomNode, index: usize) -> Option<DomNode> {
+  parent.0.children.borrow().get(index).cloned().map(DomNode)
+}
+
+fn apply_operation(root: &DomNode, operation: &PatchOperation) -> Result<(), String> {
+  match operation {
+    PatchOperation::Insert {
+      parent,
+      index,
+      node,
+    } => {
+      let parent = resolve(root, parent)?;
+      if *index > parent.0.children.borrow().len() {
+        return Err(format!("Invalid patch: index {} is out of range.", index));
+      }
+      let node = DomNode::from_ast(node)?;
+      parent.insert_before_node(&node, child_at(&parent, *index).as_ref())?;
+    }
+    PatchOperation::Remove { path } => {
+      if path.is_empty() {
+        return Err("Invalid patch: the root cannot be removed.".to_string());
+      }
+      resolve(root, path)?.remove();
+    }
+    PatchOperation::Move { parent, from, to } => {
+      let parent = resolve(root, parent)?;
+      let child = child_at(&parent, *from).ok_or_else(|| invalid_path(&[*from]))?;
+      parent.remove_child(&child)?;
+      if *to > parent.0.children.borrow().len() {
+        return Err(format!("Invalid patch: index {} is out of range.", to));
+      }
+      parent.insert_before_node(&child, child_at(&parent, *to).as_ref())?;
+    }
+    PatchOperation::Replace { path, node } => {
+      let target = resolve(root, path)?;
+      let parent = target
+        .parent_node()
+        .ok_or_else(|| "Invalid patch: a root without a parent cannot be replaced.".to_string())?;
+      parent.replace_child(&DomNode::from_ast(node)?, &target)?;
+    }
+    PatchOperation::SetAttribute {
+      path,
+      namespace,
+      name,
+      value,
+    } => {
+      let element = resolve(root, path)?;
+      match namespace {
+        Some(_) => element.set_attribute_ns(namespace.clone(), name.clone(), value.clone()),
+        None => element.set_attribute(name.clone(), value.clone()),
+      }
+    }
+    PatchOperation::RemoveAttribute {
+      path,
+      namespace,
+      name,
+    } => {
+      resolve(root, path)?.remove_attribute_ns(namespace.clone(), name.clone());
+    }
+    PatchOperation::SetText { path, value } => {
+      let text = resolve(root, path)?;
+      if !matches!(text.0.data, NodeData::Text { .. }) {
+        return Err(format!(
+          "Invalid patch: the node at {:?} is not a text node.",
+          path
+        ));
+      }
+      text.set_data(value.clone());
+    }
+  }
+  Ok(())
+}
+
+/// Applies the operations of `patch` to `root` in order. If an operation
+/// does not fit the tree, an error is returned and the operations before it
+/// stay applied. Replacing the root swaps it out of its parent, leaving `root`
+/// detached.
+pub fn apply_patch(root: &DomNode, patch: &Patch) -> Result<(), String> {
+  for operation in &patch.operations {
+    apply_operation(root, operation)?;
+  }
+  Ok(())
+}
@@ -0,0 +1,293 @@
+//! Extraction of the links and subresources a DOM tree references.
+//!
+//! [`extract_resources`] walks a tree once and returns a [`Resource`] for each
+//! URL in an HTML URL attribute, `srcset`, `style` attribute or `<style>`
+//! element, in tree order, with the element's `rel`, `integrity`,
+//! `crossorigin` and `media` attributes and the URL resolved against the
+//! node's base URL.
+
+use html5ever::{local_name, ns, LocalName};
+use markup5ever_rcdom::{Handle, NodeData};
+use url::Url;
+
+use crate::node::{attached_shadow_root, css_urls, parse_srcset, template_contents, DomNode};
+
+/// What a referenced URL is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceKind {
+  /// A hyperlink of an `a` or `area` element, or of an SVG `a`.
+  Hyperlink,
+  /// A `link` to a style sheet.
+  Stylesheet,
+  /// Any other `link`, such as an icon, preload or alternate.
+  Link,
+  /// A script.
+  Script,
+  /// An image of an `img`, `picture` source, image button or video poster, or
+  /// an external reference of an SVG element such as `<image>` or `<use>`.
+  Image,
+  /// An audio or video source or text track.
+  Media,
+  /// A document loaded in an `iframe` or `frame`.
+  Frame,
+  /// Plugin content of an `embed` or `object`.
+  Embed,
+  /// Where a form submits to, from `action` or `formaction`.
+  Form,
+  /// A `url()` or `@import` in a `style` attribute or `<style>` element.
+  Css,
+}
+
+impl ResourceKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ResourceKind::Hyperlink => "hyperlink",
+      ResourceKind::Stylesheet => "stylesheet",
+      ResourceKind::Link => "link",
+      ResourceKind::Script => "script",
+      ResourceKind::Image => "image",
+      ResourceKind::Media => "media",
+      ResourceKind::Frame => "frame",
+      ResourceKind::Embed => "embed",
+      ResourceKind::Form => "form",
+      ResourceKind::Css => "css",
+    }
+  }
+}
+
+/// One candidate of a `srcset` attribute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SrcsetCandidate {
+  pub url: String,
+  pub resolved_url: Option<String>,
+  /// The width or density descriptor, such as `"480w"` or `"2x"`, if any.
+  pub descriptor: Option<String>,
+}
+
+/// A URL referenced by an element.
+#[derive(Clone, Debug)]
+pub struct Resource {
+  pub kind: ResourceKind,
+  pub element: DomNode,
+  /// The attribute holding the URL, or `None` for the text of a `<style>` element.
+  pub attribute: Option<String>,
+  /// The URL as written. For `srcset` and `imagesrcset` this is the whole
+  /// attribute value, split up in `srcset`.
+  pub url: String,
+  /// The URL resolved against the base URL, `None` if it cannot be resolved
+  /// and for `srcset` attributes. An empty form action resolves to the
+  /// document URL, where the form submits to.
+  pub resolved_url: Option<String>,
+  pub rel: Option<String>,
+  pub srcset: Option<Vec<SrcsetCandidate>>,
+  pub integrity: Option<String>,
+  pub crossorigin: Option<String>,
+  pub media: Option<String>,
+}
+
+fn get_attribute(element: &Handle, name: LocalName) -> Option<String> {
+  let NodeData::Element { attrs, .. } = &element.data else {
+    return None;
+  };
+  attrs
+    .borrow()
+    .iter()
+    .find(|attr| attr.name.ns == ns!() && attr.name.local == name)
+    .map(|attr| attr.value.to_string())
+}
+
+fn has_rel(element: &Handle, keyword: &str) -> bool {
+  get_attribute(element, local_name!("rel")).is_some_and(|rel| {
+    rel
+      .split_ascii_whitespace()
+      .any(|token| token.eq_ignore_ascii_case(keyword))
+  })
+}
+
+fn is_in_picture(element: &Handle) -> bool {
+  crate::node::get_parent(element).is_some_and(|parent| match &parent.data {
+    NodeData::Element { name, .. } => name.ns == ns!(html) && name.local == local_name!("picture"),
+    _ => false,
+  })
+}
+
+/// The kind of resource an HTML element's attribute refers to, if it holds a URL.
+fn attribute_kind(element: &Handle, tag: &str, attribute: &str) -> Option<ResourceKind> {
+  use ResourceKind::*;
+  let kind = match (tag, attribute) {
+    ("a" | "area", "href") => Hyperlink,
+    ("link", "href" | "imagesrcset") if has_rel(element, "stylesheet") => Stylesheet,
+    ("link", "href" | "imagesrcset") => Link,
+    ("script", "src") => Script,
+    ("img", "src" | "srcset") | ("video", "poster") => Image,
+    ("input", "src") => {
+      let image = get_attribute(element, local_name!("type"))
+        .is_some_and(|kind| kind.eq_ignore_ascii_case("image"));
+      if !image {
+        return None;
+      }
+      Image
+    }
+    ("source", "src" | "srcset") if is_in_picture(element) => Image,
+    ("source", "src") | ("audio" | "video" | "track", "src") => Media,
+    ("iframe" | "frame", "src") => Frame,
+    ("embed", "src") | ("object", "data") => Embed,
+    ("form", "action") | ("button" | "input", "formaction") => Form,
+    _ => return None,
+  };
+  Some(kind)
+}
+
+struct Extractor {
+  base: Option<Url>,
+  resources: Vec<Resource>,
+}
+
+impl Extractor {
+  fn resolve(&self, url: &str) -> Option<String> {
+    let url = url.trim_matches(|c: char| c.is_ascii_whitespace());
+    match &self.base {
+      Some(base) => base.join(url),
+      None => Url::parse(url),
+    }
+    .ok()
+    .map(String::from)
+  }
+
+  fn push(
+    &mut self,
+    kind: ResourceKind,
+    element: &Handle,
+    attribute: Option<&str>,
+    url: String,
+    srcset: Option<Vec<SrcsetCandidate>>,
+  ) {
+    let resolved_url = match srcset {
+      Some(_) => None,
+      // An empty action submits to the document, not to the base URL.
+      None if matches!(kind, ResourceKind::Form) && url.is_empty() => DomNode(element.clone())
+        .owner_document()
+        .and_then(|document| document.url()),
+      None => self.resolve(&url),
+    };
+    self.resources.push(Resource {
+      kind,
+      element: DomNode(element.clone()),
+      attribute: attribute.map(String::from),
+      url,
+      resolved_url,
+      rel: get_attribute(element, local_name!("rel")),
+      srcset,
+      integrity: get_attribute(element, local_name!("integrity")),
+      crossorigin: get_attribute(element, local_name!("crossorigin")),
+      media: get_attribute(element, local_name!("media")),
+    });
+  }
+
+  fn push_css(&mut self, element: &Handle, attribute: Option<&str>, css: &str) {
+    for css_url in css_urls(css) {
+      self.push(ResourceKind::Css, element, attribute, css_url.url, None);
+    }
+  }
+
+  fn visit_element(&mut self, element: &Handle) {
+    let NodeData::Element { name, attrs, .. } = &element.data else {
+      return;
+    };
+    if name.ns == ns!(svg) {
+      let href = attrs
+        .borrow()
+        .iter()
+        .find(|attr| {
+          attr.name.local == local_name!("href")
+            && (attr.name.ns == ns!() || attr.name.ns == ns!(xlink))
+        })
+        .map(|attr| {
+          let attribute = if attr.name.ns == ns!(xlink) {
+            "xlink:href"
+          } else {
+            "href"
+          };
+          (attribute, attr.value.to_string())
+        });
+      // Fragment references point into the SVG document itself.
+      if let Some((attribute, url)) = href.filter(|(_, url)| !url.starts_with('#')) {
+        let kind = match name.local {
+          local_name!("a") => ResourceKind::Hyperlink,
+          local_name!("script") => ResourceKind::Script,
+          _ => ResourceKind::Image,
+        };
+        self.push(kind, element, Some(attribute), url, None);
+      }
+    }
+    let is_html = name.ns == ns!(html);
+    let attributes: Vec<(String, String)> = attrs
+      .borrow()
+      .iter()
+      .filter(|attr| attr.name.ns == ns!())
+      .map(|attr| (attr.name.local.to_string(), attr.value.to_string()))
+      .collect();
+    for (attribute, value) in attributes {
+      if attribute == "style" {
+        self.push_css(element, Some(&attribute), &value);
+        continue;
+      }
+      if !is_html {
+        continue;
+      }
+      let Some(kind) = attribute_kind(element, &name.local, &attribute) else {
+        continue;
+      };
+      if attribute.ends_with("srcset") {
+        let candidates = parse_srcset(&value)
+          .into_iter()
+          .map(|(url, descriptor)| SrcsetCandidate {
+            resolved_url: self.resolve(&url),
+            url,
+            descriptor: (!descriptor.is_empty()).then_some(descriptor),
+          })
+          .collect();
+        self.push(kind, element, Some(&attribute), value, Some(candidates));
+      } else {
+        self.push(kind, element, Some(&attribute), value, None);
+      }
+    }
+    if name.local == local_name!("style") && (is_html || name.ns == ns!(svg)) {
+      let css: String = element
+        .children
+        .borrow()
+        .iter()
+        .filter_map(|child| match &child.data {
+          NodeData::Text { contents } => Some(contents.borrow().to_string()),
+          _ => None,
+        })
+        .collect();
+      self.push_css(element, None, &css);
+    }
+  }
+
+  fn visit(&mut self, node: &Handle) {
+    self.visit_element(node);
+    for child in node.children.borrow().iter() {
+      self.visit(child);
+    }
+    if let Some(contents) = template_contents(node) {
+      self.visit(&contents);
+    }
+    if let Some(root) = attached_shadow_root(node) {
+      self.visit(&root);
+    }
+  }
+}
+
+/// Returns the URLs referenced by `node` and its descendants, including
+/// template contents and shadow trees, in tree order. URLs are resolved against
+/// the node's base URL, see [`DomNode::base_uri`].
+pub fn extract_resources(node: &DomNode) -> Vec<Resource> {
+  let mut extractor = Extractor {
+    base: node.base_url(),
+    resources: vec![],
+  };
+  extractor.visit(&node.0);
+  extractor.resources
+}
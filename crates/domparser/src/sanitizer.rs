@@ -0,0 +1,820 @@
+//! Allowlist-based sanitizing of DOM trees, modeled on the browser Sanitizer API.
+//!
+//! Whatever the configuration, a safe baseline is always applied: elements that
+//! run script, embed other documents or switch parsing into HTML inside foreign
+//! content are removed, along with `on*` event handler attributes, `javascript:`
+//! URLs and elements that would change namespace if the output were parsed again.
+
+use html5ever::{ns, Namespace};
+use markup5ever_rcdom::{Handle, NodeData};
+use std::rc::Rc;
+
+use crate::node::{attached_shadow_root, get_parent, template_contents, DomNode};
+
+/// An element or attribute name with its namespace. Attributes without a
+/// namespace, which is almost all of them, use an empty namespace.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SanitizerName {
+  pub namespace: String,
+  pub name: String,
+}
+
+impl SanitizerName {
+  pub fn new(namespace: &str, name: &str) -> SanitizerName {
+    SanitizerName {
+      namespace: namespace.to_string(),
+      name: name.to_string(),
+    }
+  }
+
+  /// An element in the HTML namespace.
+  pub fn element(name: &str) -> SanitizerName {
+    SanitizerName::new(&ns!(html), name)
+  }
+
+  /// An attribute without a namespace.
+  pub fn attribute(name: &str) -> SanitizerName {
+    SanitizerName::new("", name)
+  }
+
+  fn matches(&self, namespace: &Namespace, name: &str) -> bool {
+    *self.namespace == **namespace && self.name == name
+  }
+}
+
+/// An allowed element, with the attributes allowed or removed on it in
+/// addition to the global lists of [`SanitizerConfig`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SanitizerElement {
+  pub name: SanitizerName,
+  pub attributes: Option<Vec<SanitizerName>>,
+  pub remove_attributes: Vec<SanitizerName>,
+}
+
+impl From<SanitizerName> for SanitizerElement {
+  fn from(name: SanitizerName) -> Self {
+    SanitizerElement {
+      name,
+      attributes: None,
+      remove_attributes: vec![],
+    }
+  }
+}
+
+/// What a [`Sanitizer`] keeps. Removal lists take precedence over allowlists,
+/// and an allowlist of `None` allows everything not removed.
+///
+/// The default configuration keeps common formatting, table, list, media and
+/// form markup, SVG shapes and filters and presentation MathML, with their
+/// non-scripting attributes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SanitizerConfig {
+  /// Elements to keep. Other elements are removed with their contents.
+  pub elements: Option<Vec<SanitizerElement>>,
+  /// Elements removed with their contents.
+  pub remove_elements: Vec<SanitizerName>,
+  /// Elements replaced by their sanitized children.
+  pub replace_with_children_elements: Vec<SanitizerName>,
+  /// Attributes allowed on every element.
+  pub attributes: Option<Vec<SanitizerName>>,
+  /// Attributes removed from every element.
+  pub remove_attributes: Vec<SanitizerName>,
+  /// Keep comments.
+  pub comments: bool,
+  /// Keep `data-*` attributes on HTML elements even if they are not allowed by name.
+  pub data_attributes: bool,
+  /// Schemes, lowercase and without the colon, allowed in URL attributes such
+  /// as `href`, `src`, `srcset` and `action`. Relative URLs are always allowed
+  /// and `javascript:` never is.
+  pub url_schemes: Vec<String>,
+}
+
+const HTML_ELEMENTS: &[&str] = &[
+  "a",
+  "abbr",
+  "acronym",
+  "address",
+  "area",
+  "article",
+  "aside",
+  "audio",
+  "b",
+  "bdi",
+  "bdo",
+  "big",
+  "blockquote",
+  "body",
+  "br",
+  "button",
+  "canvas",
+  "caption",
+  "center",
+  "cite",
+  "code",
+  "col",
+  "colgroup",
+  "data",
+  "datalist",
+  "dd",
+  "del",
+  "details",
+  "dfn",
+  "dialog",
+  "dir",
+  "div",
+  "dl",
+  "dt",
+  "em",
+  "fieldset",
+  "figcaption",
+  "figure",
+  "font",
+  "footer",
+  "form",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "head",
+  "header",
+  "hgroup",
+  "hr",
+  "html",
+  "i",
+  "img",
+  "input",
+  "ins",
+  "kbd",
+  "label",
+  "legend",
+  "li",
+  "main",
+  "map",
+  "mark",
+  "menu",
+  "meter",
+  "nav",
+  "ol",
+  "optgroup",
+  "option",
+  "output",
+  "p",
+  "picture",
+  "pre",
+  "progress",
+  "q",
+  "rp",
+  "rt",
+  "ruby",
+  "s",
+  "samp",
+  "search",
+  "section",
+  "select",
+  "small",
+  "source",
+  "span",
+  "strike",
+  "strong",
+  "sub",
+  "summary",
+  "sup",
+  "table",
+  "tbody",
+  "td",
+  "template",
+  "textarea",
+  "tfoot",
+  "th",
+  "thead",
+  "time",
+  "title",
+  "tr",
+  "track",
+  "tt",
+  "u",
+  "ul",
+  "var",
+  "video",
+  "wbr",
+];
+
+const SVG_ELEMENTS: &[&str] = &[
+  "svg",
+  "a",
+  "circle",
+  "clipPath",
+  "defs",
+  "desc",
+  "ellipse",
+  "feBlend",
+  "feColorMatrix",
+  "feComponentTransfer",
+  "feComposite",
+  "feConvolveMatrix",
+  "feDiffuseLighting",
+  "feDisplacementMap",
+  "feDistantLight",
+  "feDropShadow",
+  "feFlood",
+  "feFuncA",
+  "feFuncB",
+  "feFuncG",
+  "feFuncR",
+  "feGaussianBlur",
+  "feImage",
+  "feMerge",
+  "feMergeNode",
+  "feMorphology",
+  "feOffset",
+  "fePointLight",
+  "feSpecularLighting",
+  "feSpotLight",
+  "feTile",
+  "feTurbulence",
+  "filter",
+  "g",
+  "image",
+  "line",
+  "linearGradient",
+  "marker",
+  "mask",
+  "metadata",
+  "path",
+  "pattern",
+  "polygon",
+  "polyline",
+  "radialGradient",
+  "rect",
+  "stop",
+  "switch",
+  "symbol",
+  "text",
+  "textPath",
+  "title",
+  "tspan",
+  "view",
+];
+
+const MATHML_ELEMENTS: &[&str] = &[
+  "math",
+  "annotation",
+  "menclose",
+  "merror",
+  "mfrac",
+  "mi",
+  "mmultiscripts",
+  "mn",
+  "mo",
+  "mover",
+  "mpadded",
+  "mphantom",
+  "mprescripts",
+  "mroot",
+  "mrow",
+  "ms",
+  "mspace",
+  "msqrt",
+  "mstyle",
+  "msub",
+  "msubsup",
+  "msup",
+  "mtable",
+  "mtd",
+  "mtext",
+  "mtr",
+  "munder",
+  "munderover",
+  "semantics",
+];
+
+const ATTRIBUTES: &[&str] = &[
+  // HTML
+  "abbr",
+  "accept",
+  "action",
+  "align",
+  "alt",
+  "aria-describedby",
+  "aria-expanded",
+  "aria-hidden",
+  "aria-label",
+  "aria-labelledby",
+  "aria-level",
+  "aria-live",
+  "autocomplete",
+  "background",
+  "bgcolor",
+  "border",
+  "cellpadding",
+  "cellspacing",
+  "checked",
+  "cite",
+  "class",
+  "clear",
+  "color",
+  "cols",
+  "colspan",
+  "controls",
+  "coords",
+  "datetime",
+  "decoding",
+  "default",
+  "dir",
+  "disabled",
+  "download",
+  "enctype",
+  "face",
+  "for",
+  "headers",
+  "height",
+  "hidden",
+  "high",
+  "href",
+  "hreflang",
+  "id",
+  "inputmode",
+  "ismap",
+  "kind",
+  "label",
+  "lang",
+  "list",
+  "loading",
+  "loop",
+  "low",
+  "max",
+  "maxlength",
+  "media",
+  "method",
+  "min",
+  "minlength",
+  "multiple",
+  "muted",
+  "name",
+  "noshade",
+  "novalidate",
+  "nowrap",
+  "open",
+  "optimum",
+  "pattern",
+  "placeholder",
+  "playsinline",
+  "poster",
+  "preload",
+  "readonly",
+  "rel",
+  "required",
+  "reversed",
+  "role",
+  "rows",
+  "rowspan",
+  "scope",
+  "selected",
+  "shape",
+  "size",
+  "sizes",
+  "span",
+  "spellcheck",
+  "src",
+  "srclang",
+  "srcset",
+  "start",
+  "step",
+  "summary",
+  "tabindex",
+  "title",
+  "translate",
+  "type",
+  "usemap",
+  "valign",
+  "value",
+  "width",
+  "wrap",
+  // SVG
+  "clip-path",
+  "clip-rule",
+  "clipPathUnits",
+  "cx",
+  "cy",
+  "d",
+  "dx",
+  "dy",
+  "fill",
+  "fill-opacity",
+  "fill-rule",
+  "filter",
+  "filterUnits",
+  "flood-color",
+  "flood-opacity",
+  "font-family",
+  "font-size",
+  "font-style",
+  "font-weight",
+  "fx",
+  "fy",
+  "gradientTransform",
+  "gradientUnits",
+  "in",
+  "in2",
+  "k1",
+  "k2",
+  "k3",
+  "k4",
+  "markerHeight",
+  "markerUnits",
+  "markerWidth",
+  "mask",
+  "maskContentUnits",
+  "maskUnits",
+  "mode",
+  "offset",
+  "opacity",
+  "operator",
+  "orient",
+  "patternContentUnits",
+  "patternTransform",
+  "patternUnits",
+  "points",
+  "preserveAspectRatio",
+  "r",
+  "refX",
+  "refY",
+  "result",
+  "rotate",
+  "rx",
+  "ry",
+  "spreadMethod",
+  "stdDeviation",
+  "stop-color",
+  "stop-opacity",
+  "stroke",
+  "stroke-dasharray",
+  "stroke-dashoffset",
+  "stroke-linecap",
+  "stroke-linejoin",
+  "stroke-miterlimit",
+  "stroke-opacity",
+  "stroke-width",
+  "text-anchor",
+  "textLength",
+  "transform",
+  "version",
+  "viewBox",
+  "visibility",
+  "x",
+  "x1",
+  "x2",
+  "y",
+  "y1",
+  "y2",
+  // MathML
+  "accent",
+  "accentunder",
+  "columnalign",
+  "columnspan",
+  "depth",
+  "display",
+  "displaystyle",
+  "encoding",
+  "fence",
+  "largeop",
+  "linethickness",
+  "lspace",
+  "mathbackground",
+  "mathcolor",
+  "mathsize",
+  "mathvariant",
+  "movablelimits",
+  "notation",
+  "rowalign",
+  "rspace",
+  "scriptlevel",
+  "separator",
+  "stretchy",
+  "symmetric",
+  "voffset",
+];
+
+impl Default for SanitizerConfig {
+  fn default() -> Self {
+    let elements = [
+      (ns!(html), HTML_ELEMENTS),
+      (ns!(svg), SVG_ELEMENTS),
+      (ns!(mathml), MATHML_ELEMENTS),
+    ]
+    .iter()
+    .flat_map(|(namespace, names)| {
+      names
+        .iter()
+        .map(|name| SanitizerName::new(namespace, name).into())
+    })
+    .collect();
+    let mut attributes: Vec<_> = ATTRIBUTES
+      .iter()
+      .map(|name| SanitizerName::attribute(name))
+      .collect();
+    attributes.push(SanitizerName::new(&ns!(xlink), "href"));
+    SanitizerConfig {
+      elements: Some(elements),
+      remove_elements: vec![],
+      replace_with_children_elements: vec![],
+      attributes: Some(attributes),
+      remove_attributes: vec![],
+      comments: false,
+      data_attributes: true,
+      url_schemes: ["http", "https", "mailto", "tel"]
+        .iter()
+        .map(|scheme| scheme.to_string())
+        .collect(),
+    }
+  }
+}
+
+/// Elements removed whatever the configuration.
+pub(crate) fn is_unsafe_element(namespace: &Namespace, name: &str) -> bool {
+  match *namespace {
+    ns!(html) => matches!(
+      name,
+      "script" | "frame" | "frameset" | "iframe" | "object" | "embed" | "base"
+    ),
+    ns!(svg) => matches!(name, "script" | "use" | "foreignObject"),
+    ns!(mathml) => name == "annotation-xml",
+    _ => false,
+  }
+}
+
+/// Attributes whose value is loaded or navigated to as a URL.
+fn is_url_attribute(namespace: &Namespace, name: &str) -> bool {
+  match *namespace {
+    ns!() => matches!(
+      name,
+      "href" | "src" | "srcset" | "action" | "formaction" | "poster" | "cite" | "background"
+    ),
+    ns!(xlink) => name == "href",
+    _ => false,
+  }
+}
+
+/// The scheme of a URL as a browser would see it: ignoring surrounding
+/// whitespace and control characters, and tabs and newlines anywhere.
+fn url_scheme(url: &str) -> Option<String> {
+  let url: String = url
+    .trim_matches(|c: char| c <= ' ')
+    .chars()
+    .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+    .collect();
+  let (scheme, _) = url.split_once(':')?;
+  let mut chars = scheme.chars();
+  let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+    && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+  valid.then(|| scheme.to_ascii_lowercase())
+}
+
+fn is_text_integration_point(namespace: &Namespace, name: &str) -> bool {
+  *namespace == ns!(mathml) && matches!(name, "mi" | "mo" | "mn" | "ms" | "mtext")
+}
+
+fn is_html_integration_point(namespace: &Namespace, name: &str) -> bool {
+  *namespace == ns!(svg) && matches!(name, "foreignObject" | "desc" | "title")
+}
+
+/// Whether an element can be a child of its parent without being put in a
+/// different namespace when the markup is parsed again.
+fn fits_namespace(namespace: &Namespace, name: &str, parent: Option<(&Namespace, &str)>) -> bool {
+  let html = ns!(html);
+  let (parent_namespace, parent_name) = parent.unwrap_or((&html, "template"));
+  match (namespace, parent_namespace) {
+    (&ns!(html), &ns!(html)) | (&ns!(svg), &ns!(svg)) | (&ns!(mathml), &ns!(mathml)) => true,
+    (&ns!(svg), &ns!(html)) => name == "svg",
+    (&ns!(mathml), &ns!(html)) => name == "math",
+    (&ns!(svg), &ns!(mathml)) => {
+      name == "svg" && is_text_integration_point(parent_namespace, parent_name)
+    }
+    (&ns!(mathml), &ns!(svg)) => {
+      name == "math" && is_html_integration_point(parent_namespace, parent_name)
+    }
+    (&ns!(html), &ns!(svg)) => is_html_integration_point(parent_namespace, parent_name),
+    // The parser puts `mglyph` and `malignmark` in text integration points in the MathML
+    // namespace, so HTML ones can't be children of MathML elements.
+    (&ns!(html), &ns!(mathml)) => {
+      !matches!(name, "mglyph" | "malignmark")
+        && is_text_integration_point(parent_namespace, parent_name)
+    }
+    _ => false,
+  }
+}
+
+/// Whether `node` is or is inside a `<form>`. The parser never nests forms, so a
+/// nested form would move when the output is parsed again.
+fn has_form_ancestor(node: &Handle) -> bool {
+  let mut node = Some(node.clone());
+  while let Some(current) = node {
+    if let NodeData::Element { name, .. } = &current.data {
+      if name.ns == ns!(html) && &*name.local == "form" {
+        return true;
+      }
+    }
+    node = get_parent(&current);
+  }
+  false
+}
+
+enum Action {
+  Keep,
+  Remove,
+  ReplaceWithChildren,
+}
+
+/// Removes what a [`SanitizerConfig`] does not allow from DOM trees.
+#[derive(Clone, Debug, Default)]
+pub struct Sanitizer {
+  config: SanitizerConfig,
+}
+
+impl Sanitizer {
+  pub fn new(config: SanitizerConfig) -> Sanitizer {
+    Sanitizer { config }
+  }
+
+  pub fn config(&self) -> &SanitizerConfig {
+    &self.config
+  }
+
+  /// Sanitizes the node in place: its attributes if it is an element, its
+  /// descendants, template contents and shadow roots. The node itself is
+  /// never removed.
+  pub fn sanitize(&self, node: &DomNode) {
+    if let NodeData::Element { name, .. } = &node.0.data {
+      if node.node_type() == 1 {
+        let local = name.local.to_string();
+        self.sanitize_attributes(&node.0, &name.ns, &local);
+      }
+    }
+    self.sanitize_contents(&node.0);
+  }
+
+  /// Sanitizes the descendants of the node, leaving the node itself alone.
+  pub(crate) fn sanitize_contents(&self, node: &Handle) {
+    let parent = match &node.data {
+      NodeData::Element { name, .. } if DomNode(node.clone()).node_type() == 1 => {
+        Some((name.ns.clone(), name.local.to_string()))
+      }
+      _ => None,
+    };
+    self.sanitize_children(
+      node,
+      parent
+        .as_ref()
+        .map(|(namespace, name)| (namespace, name.as_str())),
+    );
+    if let Some(contents) = template_contents(node) {
+      self.sanitize_children(&contents, None);
+    }
+    if let Some(root) = attached_shadow_root(node) {
+      self.sanitize_children(&root, None);
+    }
+  }
+
+  fn sanitize_children(&self, parent: &Handle, context: Option<(&Namespace, &str)>) {
+    let mut index = 0;
+    loop {
+      let Some(child) = parent.children.borrow().get(index).cloned() else {
+        return;
+      };
+      let action = match &child.data {
+        NodeData::Text { .. } | NodeData::Doctype { .. } => Action::Keep,
+        NodeData::Comment { .. } if self.config.comments => Action::Keep,
+        NodeData::Element { name, .. } => {
+          let local = name.local.to_string();
+          if name.ns == ns!(html) && &*name.local == "form" && has_form_ancestor(parent) {
+            Action::Remove
+          } else {
+            self.element_action(&name.ns, &local, context)
+          }
+        }
+        _ => Action::Remove,
+      };
+      match action {
+        Action::Keep => {
+          if let NodeData::Element { name, .. } = &child.data {
+            let local = name.local.to_string();
+            self.sanitize_attributes(&child, &name.ns, &local);
+            self.sanitize_contents(&child);
+          }
+          index += 1;
+        }
+        Action::Remove => {
+          parent.children.borrow_mut().remove(index);
+          child.parent.set(None);
+        }
+        Action::ReplaceWithChildren => {
+          // The children take the element's place and are sanitized as children of `parent`.
+          let children = child.children.take();
+          for grandchild in &children {
+            grandchild.parent.set(Some(Rc::downgrade(parent)));
+          }
+          parent.children.borrow_mut().splice(index..=index, children);
+          child.parent.set(None);
+        }
+      }
+    }
+  }
+
+  fn element_action(
+    &self,
+    namespace: &Namespace,
+    name: &str,
+    context: Option<(&Namespace, &str)>,
+  ) -> Action {
+    let config = &self.config;
+    if is_unsafe_element(namespace, name)
+      || !fits_namespace(namespace, name, context)
+      || config
+        .remove_elements
+        .iter()
+        .any(|element| element.matches(namespace, name))
+    {
+      return Action::Remove;
+    }
+    if config
+      .replace_with_children_elements
+      .iter()
+      .any(|element| element.matches(namespace, name))
+    {
+      return Action::ReplaceWithChildren;
+    }
+    match &config.elements {
+      Some(elements) if !elements.iter().any(|e| e.name.matches(namespace, name)) => Action::Remove,
+      _ => Action::Keep,
+    }
+  }
+
+  fn sanitize_attributes(&self, element: &Handle, namespace: &Namespace, name: &str) {
+    let NodeData::Element { attrs, .. } = &element.data else {
+      return;
+    };
+    let config = &self.config;
+    let allowed_element = config
+      .elements
+      .as_ref()
+      .and_then(|elements| elements.iter().find(|e| e.name.matches(namespace, name)));
+    let animates_url = *namespace == ns!(svg)
+      && matches!(
+        name,
+        "animate" | "animateMotion" | "animateTransform" | "set"
+      );
+    attrs.borrow_mut().retain(|attr| {
+      let attr_namespace = &attr.name.ns;
+      let attr_name = attr.name.local.as_ref();
+      let listed = |names: &[SanitizerName]| {
+        names
+          .iter()
+          .any(|name| name.matches(attr_namespace, attr_name))
+      };
+      if attr_name.len() > 2 && attr_name[..2].eq_ignore_ascii_case("on") {
+        return false;
+      }
+      if animates_url && attr_name == "attributeName" {
+        let target = attr.value.trim().to_ascii_lowercase();
+        if target == "href" || target == "xlink:href" {
+          return false;
+        }
+      }
+      if listed(&config.remove_attributes)
+        || allowed_element.is_some_and(|element| listed(&element.remove_attributes))
+      {
+        return false;
+      }
+      let element_attributes = allowed_element.and_then(|element| element.attributes.as_deref());
+      let allowed = match (&config.attributes, element_attributes) {
+        (None, None) => true,
+        (global, local) => {
+          global.as_deref().is_some_and(listed)
+            || local.is_some_and(listed)
+            || (config.data_attributes
+              && *namespace == ns!(html)
+              && *attr_namespace == ns!()
+              && attr_name.starts_with("data-"))
+        }
+      };
+      allowed
+        && (!is_url_attribute(attr_namespace, attr_name) || self.allows_url(attr_name, &attr.value))
+    });
+  }
+
+  fn allows_url(&self, attr_name: &str, value: &str) -> bool {
+    let allowed = |url: &str| match url_scheme(url) {
+      None => true,
+      Some(scheme) => scheme != "javascript" && self.config.url_schemes.contains(&scheme),
+    };
+    if attr_name == "srcset" {
+      value
+        .split(',')
+        .all(|candidate| allowed(candidate.split_whitespace().next().unwrap_or("")))
+    } else {
+      allowed(value)
+    }
+  }
+}
@@ -1,5 +1,12 @@
+use crate::node::{attached_shadow_root, template_contents};
+use crate::DomNode;
+use html5ever::serialize::{serialize, Serialize, SerializeOpts, Serializer, TraversalScope};
+use html5ever::{local_name, ns, LocalName, QualName};
 use markup5ever_rcdom::{Handle, NodeData};
-use std::io::{Result, Write};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::io::{self, Result, Write};
+use std::rc::Rc;
 
 pub fn serialize_text_only<Wr: Write>(handle: &Handle, writer: &mut Wr) -> Result<()> {
   match &handle.data {
@@ -16,3 +23,1268 @@ pub fn serialize_text_only<Wr: Write>(handle: &Handle, writer: &mut Wr) -> Resul
     _ => Ok(()),
   }
 }
+
+/// A serializable node that, unlike rcdom's `SerializableHandle`, serializes the
+/// contents of `<template>` elements, and can emit shadow roots as declarative
+/// `<template shadowrootmode>` elements as `getHTML()` does.
+pub struct SerializableDomNode {
+  pub handle: Handle,
+  /// Serialize every shadow root marked as serializable.
+  pub serializable_shadow_roots: bool,
+  /// Shadow roots to serialize regardless of their serializable flag.
+  pub shadow_roots: Vec<Handle>,
+}
+
+/// Formats the node as its outer HTML.
+impl fmt::Display for DomNode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    SerializableDomNode::new(self.0.clone()).write_html_fmt(true, f)
+  }
+}
+
+/// Adapts a `fmt::Write` to the `io::Write` html5ever serializes into.
+/// html5ever writes whole `str`s and characters, so every chunk is valid UTF-8.
+struct FmtWriter<'a, W: fmt::Write>(&'a mut W);
+
+impl<W: fmt::Write> Write for FmtWriter<'_, W> {
+  fn write(&mut self, buf: &[u8]) -> Result<usize> {
+    let s = std::str::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    self.0.write_str(s).map_err(io::Error::other)?;
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> Result<()> {
+    Ok(())
+  }
+}
+
+enum SerializeOp {
+  Open(Handle),
+  OpenShadowRoot(Handle),
+  Close(QualName),
+}
+
+impl SerializableDomNode {
+  /// Serializes the node and its template contents, without shadow roots.
+  pub fn new(handle: Handle) -> Self {
+    SerializableDomNode {
+      handle,
+      serializable_shadow_roots: false,
+      shadow_roots: vec![],
+    }
+  }
+
+  /// Writes the HTML serialization of the node (`include_node`) or of its children.
+  /// Documents and fragments always write their children. `<noscript>` follows the
+  /// scripting mode the node's document was parsed with.
+  pub fn write_html<W: Write>(&self, include_node: bool, writer: &mut W) -> Result<()> {
+    let include_node =
+      include_node && !matches!(self.handle.data, NodeData::Document) && !is_fragment(&self.handle);
+    let traversal_scope = match &self.handle.data {
+      _ if include_node => TraversalScope::IncludeNode,
+      // The parent name decides whether the children are raw text.
+      NodeData::Element { name, .. } if !is_fragment(&self.handle) => {
+        TraversalScope::ChildrenOnly(Some(name.clone()))
+      }
+      _ => TraversalScope::ChildrenOnly(None),
+    };
+    serialize(
+      writer,
+      self,
+      SerializeOpts {
+        scripting_enabled: DomNode(self.handle.clone()).scripting_enabled(),
+        traversal_scope,
+        create_missing_parent: false,
+      },
+    )
+  }
+
+  /// Like [`write_html`](Self::write_html), for a `fmt::Write` such as a `String`.
+  pub fn write_html_fmt<W: fmt::Write>(&self, include_node: bool, writer: &mut W) -> fmt::Result {
+    self
+      .write_html(include_node, &mut FmtWriter(writer))
+      .map_err(|_| fmt::Error)
+  }
+
+  /// Returns the HTML serialization of the node (`include_node`) or of its children.
+  pub fn to_html(&self, include_node: bool) -> String {
+    let mut html = String::new();
+    self.write_html_fmt(include_node, &mut html).unwrap();
+    html
+  }
+
+  fn should_serialize(&self, root: &Handle) -> bool {
+    (self.serializable_shadow_roots && crate::DomNode(root.clone()).serializable())
+      || self.shadow_roots.iter().any(|r| Rc::ptr_eq(r, root))
+  }
+}
+
+impl Serialize for SerializableDomNode {
+  fn serialize<S>(&self, serializer: &mut S, traversal_scope: TraversalScope) -> Result<()>
+  where
+    S: Serializer,
+  {
+    let mut ops = VecDeque::new();
+    match traversal_scope {
+      TraversalScope::IncludeNode => ops.push_back(SerializeOp::Open(self.handle.clone())),
+      TraversalScope::ChildrenOnly(_) => {
+        if let Some(root) = attached_shadow_root(&self.handle).filter(|r| self.should_serialize(r))
+        {
+          ops.push_back(SerializeOp::OpenShadowRoot(root));
+        }
+        ops.extend(children_of(&self.handle).into_iter().map(SerializeOp::Open))
+      }
+    }
+
+    while let Some(op) = ops.pop_front() {
+      match op {
+        SerializeOp::Open(handle) => match &handle.data {
+          NodeData::Element { name, attrs, .. } => {
+            serializer.start_elem(
+              name.clone(),
+              attrs.borrow().iter().map(|at| (&at.name, &at.value[..])),
+            )?;
+
+            ops.push_front(SerializeOp::Close(name.clone()));
+            for child in children_of(&handle).into_iter().rev() {
+              ops.push_front(SerializeOp::Open(child));
+            }
+            if let Some(root) = attached_shadow_root(&handle).filter(|r| self.should_serialize(r)) {
+              ops.push_front(SerializeOp::OpenShadowRoot(root));
+            }
+          }
+          NodeData::Doctype { name, .. } => serializer.write_doctype(name)?,
+          NodeData::Text { contents } => serializer.write_text(&contents.borrow())?,
+          NodeData::Comment { contents } => serializer.write_comment(contents)?,
+          NodeData::ProcessingInstruction { target, contents } => {
+            serializer.write_processing_instruction(target, contents)?
+          }
+          NodeData::Document => {
+            for child in handle.children.borrow().iter().rev() {
+              ops.push_front(SerializeOp::Open(child.clone()));
+            }
+          }
+        },
+        SerializeOp::OpenShadowRoot(root) => {
+          let shadow = crate::DomNode(root.clone());
+          let mut attrs = vec![(
+            QualName::new(None, ns!(), LocalName::from("shadowrootmode")),
+            shadow.mode().unwrap_or_default(),
+          )];
+          for (flag, attr) in [
+            (shadow.delegates_focus(), "shadowrootdelegatesfocus"),
+            (shadow.serializable(), "shadowrootserializable"),
+            (shadow.clonable(), "shadowrootclonable"),
+          ] {
+            if flag {
+              attrs.push((
+                QualName::new(None, ns!(), LocalName::from(attr)),
+                String::new(),
+              ));
+            }
+          }
+          let template = QualName::new(None, ns!(html), local_name!("template"));
+          serializer.start_elem(
+            template.clone(),
+            attrs.iter().map(|(name, value)| (name, value.as_str())),
+          )?;
+          ops.push_front(SerializeOp::Close(template));
+          for child in root.children.borrow().iter().rev() {
+            ops.push_front(SerializeOp::Open(child.clone()));
+          }
+        }
+        SerializeOp::Close(name) => serializer.end_elem(name)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+/// The children to serialize for `handle`: a template serializes its contents instead.
+fn children_of(handle: &Handle) -> Vec<Handle> {
+  match template_contents(handle) {
+    Some(contents) => contents.children.borrow().clone(),
+    None => handle.children.borrow().clone(),
+  }
+}
+
+/// How attribute values are quoted by [`serialize_with_options`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AttributeQuote {
+  #[default]
+  Double,
+  Single,
+  /// Leave values unquoted where the HTML syntax allows it, and write empty values as bare names.
+  Minimal,
+}
+
+/// Formatting options for [`serialize_with_options`].
+/// The default options produce the same markup as `outerHTML`.
+#[derive(Clone, Debug)]
+pub struct SerializeOptions {
+  /// Put block-level elements on their own lines, indented by `indent`.
+  /// Preformatted content and inline formatting contexts are left untouched.
+  pub pretty: bool,
+  pub indent: String,
+  /// Collapse whitespace, drop comments and omit optional tags.
+  /// Attribute quotes are minimal unless `attribute_quote` says otherwise.
+  pub minify: bool,
+  pub attribute_quote: Option<AttributeQuote>,
+  /// Write attributes in alphabetical order instead of document order.
+  pub sort_attributes: bool,
+  /// Write void elements as `<br />` instead of `<br>`.
+  pub self_closing_void: bool,
+}
+
+impl Default for SerializeOptions {
+  fn default() -> Self {
+    SerializeOptions {
+      pretty: false,
+      indent: "  ".to_string(),
+      minify: false,
+      attribute_quote: None,
+      sort_attributes: false,
+      self_closing_void: false,
+    }
+  }
+}
+
+const VOID_ELEMENTS: &[&str] = &[
+  "area", "base", "basefont", "bgsound", "br", "col", "embed", "frame", "hr", "img", "input",
+  "keygen", "link", "meta", "param", "source", "track", "wbr",
+];
+
+/// Elements whose text children are written without escaping.
+/// `<noscript>` is one too when scripting is enabled.
+const RAW_TEXT_ELEMENTS: &[&str] = &[
+  "iframe",
+  "noembed",
+  "noframes",
+  "plaintext",
+  "script",
+  "style",
+  "xmp",
+];
+
+/// Elements whose whitespace is significant.
+const PREFORMATTED_ELEMENTS: &[&str] = &[
+  "listing",
+  "plaintext",
+  "pre",
+  "script",
+  "style",
+  "textarea",
+  "xmp",
+];
+
+/// Elements that take part in an inline formatting context, so whitespace around them matters.
+const INLINE_ELEMENTS: &[&str] = &[
+  "a", "abbr", "acronym", "audio", "b", "bdi", "bdo", "big", "br", "button", "canvas", "cite",
+  "code", "data", "del", "dfn", "em", "embed", "font", "i", "iframe", "img", "input", "ins", "kbd",
+  "label", "map", "mark", "math", "meter", "object", "output", "picture", "progress", "q", "ruby",
+  "s", "samp", "select", "slot", "small", "span", "strike", "strong", "sub", "sup", "svg",
+  "textarea", "time", "tt", "u", "var", "video", "wbr",
+];
+
+/// Elements after which a `</p>` end tag may be omitted.
+/// https://html.spec.whatwg.org/multipage/syntax.html#optional-tags
+const CLOSES_P: &[&str] = &[
+  "address",
+  "article",
+  "aside",
+  "blockquote",
+  "details",
+  "dialog",
+  "div",
+  "dl",
+  "fieldset",
+  "figcaption",
+  "figure",
+  "footer",
+  "form",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "header",
+  "hgroup",
+  "hr",
+  "main",
+  "menu",
+  "nav",
+  "ol",
+  "p",
+  "pre",
+  "search",
+  "section",
+  "table",
+  "ul",
+];
+
+/// A child as it will be written: text is already whitespace-processed.
+enum Child {
+  Node(Handle),
+  Text(String),
+}
+
+impl Child {
+  fn element_name(&self) -> Option<&str> {
+    match self {
+      Child::Node(handle) => html_element_name(handle),
+      Child::Text(_) => None,
+    }
+  }
+
+  fn is_comment(&self) -> bool {
+    matches!(self, Child::Node(handle) if matches!(handle.data, NodeData::Comment { .. }))
+  }
+
+  fn starts_with_whitespace(&self) -> bool {
+    matches!(self, Child::Text(text) if text.starts_with(is_ascii_whitespace))
+  }
+}
+
+fn html_element_name(handle: &Handle) -> Option<&str> {
+  match &handle.data {
+    NodeData::Element { name, .. } if name.ns == ns!(html) => Some(name.local.as_ref()),
+    _ => None,
+  }
+}
+
+fn is_ascii_whitespace(c: char) -> bool {
+  matches!(c, '\t' | '\n' | '\x0C' | '\r' | ' ')
+}
+
+fn is_fragment(handle: &Handle) -> bool {
+  matches!(&handle.data, NodeData::Element { name, .. } if name.ns == ns!() && name.local.starts_with('#'))
+}
+
+/// Whether whitespace next to this node could be rendered.
+fn is_inline(handle: &Handle) -> bool {
+  match &handle.data {
+    NodeData::Text { .. } => true,
+    NodeData::Element { name, .. } => {
+      name.ns != ns!(html) || INLINE_ELEMENTS.contains(&name.local.as_ref())
+    }
+    _ => false,
+  }
+}
+
+fn tag_name(name: &QualName) -> String {
+  if name.ns == ns!(html) || name.ns == ns!(svg) || name.ns == ns!(mathml) {
+    return name.local.to_string();
+  }
+  match &name.prefix {
+    Some(prefix) => format!("{}:{}", prefix, name.local),
+    None => name.local.to_string(),
+  }
+}
+
+fn attribute_name(name: &QualName) -> String {
+  if name.ns == ns!(xml) {
+    format!("xml:{}", name.local)
+  } else if name.ns == ns!(xmlns) {
+    if name.local.as_ref() == "xmlns" {
+      "xmlns".to_string()
+    } else {
+      format!("xmlns:{}", name.local)
+    }
+  } else if name.ns == ns!(xlink) {
+    format!("xlink:{}", name.local)
+  } else if let Some(prefix) = &name.prefix {
+    format!("{}:{}", prefix, name.local)
+  } else {
+    name.local.to_string()
+  }
+}
+
+/// Serializes `handle` (or only its children) as HTML formatted according to `options`.
+/// Documents and fragments always serialize their children only.
+pub fn serialize_with_options<W: std::fmt::Write>(
+  handle: &Handle,
+  include_node: bool,
+  options: &SerializeOptions,
+  writer: &mut W,
+) -> std::fmt::Result {
+  let quote = options.attribute_quote.unwrap_or(if options.minify {
+    AttributeQuote::Minimal
+  } else {
+    AttributeQuote::Double
+  });
+  let mut formatter = Formatter {
+    options,
+    quote,
+    scripting_enabled: DomNode(handle.clone()).scripting_enabled(),
+    writer,
+    at_start: true,
+  };
+  let pretty = options.pretty && !options.minify;
+  if include_node && !matches!(handle.data, NodeData::Document) && !is_fragment(handle) {
+    formatter.node(&Child::Node(handle.clone()), None, 0, pretty)
+  } else {
+    formatter.contents(handle, 0, pretty)
+  }
+}
+
+struct Formatter<'a, W> {
+  options: &'a SerializeOptions,
+  quote: AttributeQuote,
+  scripting_enabled: bool,
+  writer: &'a mut W,
+  /// Nothing has been written yet, so a pretty-printed line needs no leading newline.
+  at_start: bool,
+}
+
+impl<W: std::fmt::Write> Formatter<'_, W> {
+  fn write(&mut self, s: &str) -> std::fmt::Result {
+    if !s.is_empty() {
+      self.at_start = false;
+    }
+    self.writer.write_str(s)
+  }
+
+  fn new_line(&mut self, depth: usize) -> std::fmt::Result {
+    if !self.at_start {
+      self.writer.write_char('\n')?;
+      for _ in 0..depth {
+        self.writer.write_str(&self.options.indent)?;
+      }
+    }
+    Ok(())
+  }
+
+  /// The children of `parent` that will be written, with minification applied.
+  fn children(&self, parent: &Handle) -> Vec<Child> {
+    let handles = match template_contents(parent) {
+      Some(contents) => contents.children.borrow().clone(),
+      None => parent.children.borrow().clone(),
+    };
+    let preformatted = is_preformatted(parent, self.scripting_enabled);
+    let handles: Vec<Handle> = handles
+      .into_iter()
+      .filter(|h| !(self.options.minify && matches!(h.data, NodeData::Comment { .. })))
+      .collect();
+    if preformatted {
+      return handles.into_iter().map(Child::Node).collect();
+    }
+
+    let parent_is_block = !is_inline(parent);
+    let mut children = Vec::with_capacity(handles.len());
+    for (i, handle) in handles.iter().enumerate() {
+      let NodeData::Text { contents } = &handle.data else {
+        children.push(Child::Node(handle.clone()));
+        continue;
+      };
+      if !self.options.minify {
+        children.push(Child::Node(handle.clone()));
+        continue;
+      }
+
+      let text = contents.borrow();
+      let whitespace_only = text.chars().all(is_ascii_whitespace);
+      let prev_inline = i > 0 && is_inline(&handles[i - 1]);
+      let next_inline = handles.get(i + 1).is_some_and(is_inline);
+      if whitespace_only && !prev_inline && !next_inline {
+        continue;
+      }
+      let mut collapsed = String::with_capacity(text.len());
+      for c in text.chars() {
+        if is_ascii_whitespace(c) {
+          if !collapsed.ends_with(' ') {
+            collapsed.push(' ');
+          }
+        } else {
+          collapsed.push(c);
+        }
+      }
+      // Whitespace at the edges of a block, or next to a block, is never rendered.
+      if parent_is_block && i == 0 || i > 0 && !is_inline(&handles[i - 1]) {
+        collapsed = collapsed.trim_start_matches(' ').to_string();
+      }
+      if parent_is_block && i + 1 == handles.len()
+        || handles.get(i + 1).is_some_and(|h| !is_inline(h))
+      {
+        collapsed = collapsed.trim_end_matches(' ').to_string();
+      }
+      if !collapsed.is_empty() {
+        children.push(Child::Text(collapsed));
+      }
+    }
+    children
+  }
+
+  /// Writes the children of `parent`, on their own lines when pretty-printing block content.
+  fn contents(&mut self, parent: &Handle, depth: usize, pretty: bool) -> std::fmt::Result {
+    let children = self.children(parent);
+    if pretty && has_block_layout(parent, &children, self.scripting_enabled) {
+      self.block_children(&children, depth)
+    } else {
+      for (i, child) in children.iter().enumerate() {
+        self.node(child, children.get(i + 1), depth, false)?;
+      }
+      Ok(())
+    }
+  }
+
+  /// Puts each block-level child, and each run of inline content between them, on its own line.
+  /// Whitespace at the edges of an inline run is not rendered, so it is trimmed.
+  fn block_children(&mut self, children: &[Child], depth: usize) -> std::fmt::Result {
+    let mut i = 0;
+    while i < children.len() {
+      if let Child::Node(handle) = &children[i] {
+        if is_block_element(handle) {
+          self.node(&children[i], None, depth, true)?;
+          i += 1;
+          continue;
+        }
+      }
+      let start = i;
+      while i < children.len() && !matches!(&children[i], Child::Node(h) if is_block_element(h)) {
+        i += 1;
+      }
+      let run = &children[start..i];
+      if run
+        .iter()
+        .all(|child| matches!(child, Child::Node(h) if is_whitespace_text(h)))
+      {
+        continue;
+      }
+      self.new_line(depth)?;
+      for (j, child) in run.iter().enumerate() {
+        match child {
+          Child::Node(handle) if matches!(handle.data, NodeData::Text { .. }) => {
+            let NodeData::Text { contents } = &handle.data else {
+              unreachable!()
+            };
+            let contents = contents.borrow();
+            let mut text: &str = &contents;
+            if j == 0 {
+              text = text.trim_start_matches(is_ascii_whitespace);
+            }
+            if j + 1 == run.len() {
+              text = text.trim_end_matches(is_ascii_whitespace);
+            }
+            self.text(text, false)?;
+          }
+          _ => self.node(child, None, depth, false)?,
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Writes `child`. `next` is the following sibling, used to decide whether an end tag
+  /// may be omitted; `block_layout` puts the child on its own line.
+  fn node(
+    &mut self,
+    child: &Child,
+    next: Option<&Child>,
+    depth: usize,
+    block_layout: bool,
+  ) -> std::fmt::Result {
+    let handle = match child {
+      Child::Text(text) => {
+        if block_layout {
+          self.new_line(depth)?;
+        }
+        return self.text(text, false);
+      }
+      Child::Node(handle) => handle,
+    };
+
+    if block_layout {
+      self.new_line(depth)?;
+    }
+    match &handle.data {
+      NodeData::Text { contents } => {
+        let raw = get_parent_name(handle).is_some_and(|name| {
+          RAW_TEXT_ELEMENTS.contains(&name.as_str()) || self.scripting_enabled && name == "noscript"
+        });
+        self.text(&contents.borrow(), raw)
+      }
+      NodeData::Comment { contents } => {
+        self.write("<!--")?;
+        self.write(contents)?;
+        self.write("-->")
+      }
+      NodeData::Doctype { name, .. } => {
+        self.write("<!DOCTYPE ")?;
+        self.write(name)?;
+        self.write(">")
+      }
+      NodeData::ProcessingInstruction { target, contents } => {
+        self.write("<?")?;
+        self.write(target)?;
+        self.write(" ")?;
+        self.write(contents)?;
+        self.write(">")
+      }
+      NodeData::Document => self.contents(handle, depth, block_layout),
+      NodeData::Element { name, attrs, .. } => {
+        if is_fragment(handle) {
+          return self.contents(handle, depth, block_layout);
+        }
+        let local = html_element_name(handle);
+        let is_void = local.is_some_and(|name| VOID_ELEMENTS.contains(&name));
+
+        let children = self.children(handle);
+        let children_block_layout =
+          block_layout && has_block_layout(handle, &children, self.scripting_enabled);
+
+        let attrs = attrs.borrow();
+        if !(self.options.minify
+          && local.is_some_and(|name| self.can_omit_start_tag(name, attrs.is_empty(), &children)))
+        {
+          self.write("<")?;
+          self.write(&tag_name(name))?;
+          let mut attributes: Vec<(String, &str)> = attrs
+            .iter()
+            .map(|attr| (attribute_name(&attr.name), &attr.value[..]))
+            .collect();
+          if self.options.sort_attributes {
+            attributes.sort_by(|a, b| a.0.cmp(&b.0));
+          }
+          for (attr_name, value) in attributes {
+            self.write(" ")?;
+            self.write(&attr_name)?;
+            self.attribute_value(value)?;
+          }
+          if is_void && self.options.self_closing_void {
+            self.write(" /")?;
+          }
+          self.write(">")?;
+        }
+        if is_void {
+          return Ok(());
+        }
+
+        if local.is_some_and(|name| matches!(name, "pre" | "textarea" | "listing")) {
+          if let Some(Child::Node(first)) = children.first() {
+            if let NodeData::Text { contents } = &first.data {
+              if contents.borrow().starts_with('\n') {
+                self.write("\n")?;
+              }
+            }
+          }
+        }
+        if children_block_layout {
+          self.block_children(&children, depth + 1)?;
+        } else {
+          for (i, child) in children.iter().enumerate() {
+            self.node(child, children.get(i + 1), depth + 1, false)?;
+          }
+        }
+
+        if self.options.minify && local.is_some_and(|name| can_omit_end_tag(name, handle, next)) {
+          return Ok(());
+        }
+        if children_block_layout {
+          self.new_line(depth)?;
+        }
+        self.write("</")?;
+        self.write(&tag_name(name))?;
+        self.write(">")
+      }
+    }
+  }
+
+  fn text(&mut self, text: &str, raw: bool) -> std::fmt::Result {
+    if raw {
+      return self.write(text);
+    }
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+      match c {
+        '&' => escaped.push_str("&amp;"),
+        '\u{00A0}' => escaped.push_str("&nbsp;"),
+        '<' => escaped.push_str("&lt;"),
+        '>' => escaped.push_str("&gt;"),
+        c => escaped.push(c),
+      }
+    }
+    self.write(&escaped)
+  }
+
+  fn attribute_value(&mut self, value: &str) -> std::fmt::Result {
+    let quote = match self.quote {
+      AttributeQuote::Minimal if value.is_empty() => return Ok(()),
+      AttributeQuote::Minimal
+        if !value
+          .chars()
+          .any(|c| is_ascii_whitespace(c) || matches!(c, '"' | '\'' | '=' | '<' | '>' | '`')) =>
+      {
+        None
+      }
+      AttributeQuote::Single => Some('\''),
+      _ => Some('"'),
+    };
+    let mut escaped = String::with_capacity(value.len() + 3);
+    escaped.push('=');
+    escaped.extend(quote);
+    for c in value.chars() {
+      match c {
+        '&' => escaped.push_str("&amp;"),
+        '\u{00A0}' => escaped.push_str("&nbsp;"),
+        '"' if quote == Some('"') => escaped.push_str("&quot;"),
+        '\'' if quote == Some('\'') => escaped.push_str("&#39;"),
+        c => escaped.push(c),
+      }
+    }
+    escaped.extend(quote);
+    self.write(&escaped)
+  }
+
+  /// https://html.spec.whatwg.org/multipage/syntax.html#optional-tags
+  fn can_omit_start_tag(&self, name: &str, no_attributes: bool, children: &[Child]) -> bool {
+    if !no_attributes {
+      return false;
+    }
+    let first = children.first();
+    match name {
+      "html" => !first.is_some_and(Child::is_comment),
+      "head" => first.is_none_or(|child| child.element_name().is_some()),
+      "body" => first.is_none_or(|child| {
+        !child.is_comment()
+          && !child.starts_with_whitespace()
+          && !matches!(
+            child.element_name(),
+            Some("meta" | "noscript" | "link" | "script" | "style" | "template")
+          )
+      }),
+      _ => false,
+    }
+  }
+}
+
+fn is_block_element(handle: &Handle) -> bool {
+  matches!(handle.data, NodeData::Element { .. }) && !is_inline(handle)
+}
+
+/// Whether the children of `parent` are laid out on their own lines when pretty-printing:
+/// there is a block-level child, and whitespace is not significant.
+fn has_block_layout(parent: &Handle, children: &[Child], scripting_enabled: bool) -> bool {
+  !is_preformatted(parent, scripting_enabled)
+    && children
+      .iter()
+      .any(|child| matches!(child, Child::Node(h) if is_block_element(h)))
+}
+
+/// Whether `handle` or one of its ancestors keeps its whitespace as written.
+fn is_preformatted(handle: &Handle, scripting_enabled: bool) -> bool {
+  let mut node = Some(handle.clone());
+  while let Some(current) = node {
+    if html_element_name(&current).is_some_and(|name| {
+      PREFORMATTED_ELEMENTS.contains(&name)
+        || RAW_TEXT_ELEMENTS.contains(&name)
+        || scripting_enabled && name == "noscript"
+    }) {
+      return true;
+    }
+    node = crate::node::get_parent(&current);
+  }
+  false
+}
+
+fn is_whitespace_text(handle: &Handle) -> bool {
+  matches!(&handle.data, NodeData::Text { contents } if contents.borrow().chars().all(is_ascii_whitespace))
+}
+
+fn get_parent_name(handle: &Handle) -> Option<String> {
+  crate::node::get_parent(handle).and_then(|parent| html_element_name(&parent).map(str::to_string))
+}
+
+/// https://html.spec.whatwg.org/multipage/syntax.html#optional-tags
+fn can_omit_end_tag(name: &str, handle: &Handle, next: Option<&Child>) -> bool {
+  let next_name = next.and_then(Child::element_name);
+  let followed_by = |names: &[&str]| next_name.is_some_and(|n| names.contains(&n));
+  match name {
+    "html" | "body" => !next.is_some_and(Child::is_comment),
+    "head" => !next.is_some_and(|child| child.is_comment() || child.starts_with_whitespace()),
+    "li" => next.is_none() || followed_by(&["li"]),
+    "dt" => followed_by(&["dt", "dd"]),
+    "dd" => next.is_none() || followed_by(&["dt", "dd"]),
+    "p" => {
+      followed_by(CLOSES_P)
+        || next.is_none()
+          && !get_parent_name(handle).is_some_and(|parent| {
+            matches!(
+              parent.as_str(),
+              "a" | "audio" | "del" | "ins" | "map" | "noscript" | "video"
+            )
+          })
+    }
+    "option" => next.is_none() || followed_by(&["option", "optgroup", "hr"]),
+    "optgroup" => next.is_none() || followed_by(&["optgroup", "hr"]),
+    "tr" => next.is_none() || followed_by(&["tr"]),
+    "td" | "th" => next.is_none() || followed_by(&["td", "th"]),
+    "thead" => followed_by(&["tbody", "tfoot"]),
+    "tbody" => next.is_none() || followed_by(&["tbody", "tfoot"]),
+    "tfoot" => next.is_none(),
+    _ => false,
+  }
+}
+
+/// Maps each namespace to the prefixes declared for it, in declaration order.
+/// https://w3c.github.io/DOM-Parsing/#the-namespace-prefix-map
+#[derive(Clone)]
+struct NamespacePrefixMap(HashMap<Option<String>, Vec<String>>);
+
+impl NamespacePrefixMap {
+  fn new() -> Self {
+    let mut map = NamespacePrefixMap(HashMap::new());
+    map.add("xml", Some(ns!(xml).to_string()));
+    map
+  }
+
+  fn add(&mut self, prefix: &str, namespace: Option<String>) {
+    self
+      .0
+      .entry(namespace)
+      .or_default()
+      .push(prefix.to_string());
+  }
+
+  fn contains(&self, prefix: &str, namespace: &Option<String>) -> bool {
+    self
+      .0
+      .get(namespace)
+      .is_some_and(|prefixes| prefixes.iter().any(|p| p == prefix))
+  }
+
+  /// https://w3c.github.io/DOM-Parsing/#dfn-retrieving-a-preferred-prefix-string
+  fn preferred_prefix(
+    &self,
+    preferred: Option<&str>,
+    namespace: &Option<String>,
+  ) -> Option<String> {
+    let candidates = self.0.get(namespace)?;
+    match preferred {
+      Some(preferred) if candidates.iter().any(|p| p == preferred) => Some(preferred.to_string()),
+      _ => candidates.last().cloned(),
+    }
+  }
+}
+
+struct XmlSerializer {
+  require_well_formed: bool,
+  prefix_index: usize,
+  output: String,
+}
+
+fn namespace_of(name: &QualName) -> Option<String> {
+  if name.ns == ns!() {
+    None
+  } else {
+    Some(name.ns.to_string())
+  }
+}
+
+/// The `Char` production of XML 1.0.
+fn is_xml_char(c: char) -> bool {
+  matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+/// Serializes `handle` as XML following the DOM Parsing spec, generating namespace
+/// prefixes and declarations as needed. With `require_well_formed`, nodes that cannot
+/// be represented in well-formed XML are reported as errors instead of written as is.
+/// https://w3c.github.io/DOM-Parsing/#dfn-xml-serialization
+pub fn serialize_xml(
+  handle: &Handle,
+  require_well_formed: bool,
+) -> std::result::Result<String, String> {
+  let mut serializer = XmlSerializer {
+    require_well_formed,
+    prefix_index: 1,
+    output: String::new(),
+  };
+  serializer.node(handle, None, &mut NamespacePrefixMap::new())?;
+  Ok(serializer.output)
+}
+
+type XmlResult = std::result::Result<(), String>;
+
+impl XmlSerializer {
+  fn well_formed_error(&self, message: String) -> XmlResult {
+    if self.require_well_formed {
+      Err(message)
+    } else {
+      Ok(())
+    }
+  }
+
+  fn node(
+    &mut self,
+    handle: &Handle,
+    namespace: Option<&str>,
+    map: &mut NamespacePrefixMap,
+  ) -> XmlResult {
+    match &handle.data {
+      NodeData::Element { .. } if is_fragment(handle) => self.children(handle, namespace, map),
+      NodeData::Element { .. } => self.element(handle, namespace, map),
+      NodeData::Document => {
+        let has_document_element = handle
+          .children
+          .borrow()
+          .iter()
+          .any(|child| matches!(child.data, NodeData::Element { .. }));
+        if !has_document_element {
+          self.well_formed_error("The document has no document element.".to_string())?;
+        }
+        self.children(handle, namespace, map)
+      }
+      NodeData::Comment { contents } => {
+        if !contents.chars().all(is_xml_char) || contents.contains("--") || contents.ends_with('-')
+        {
+          self.well_formed_error(format!("The comment '{}' is not well-formed.", contents))?;
+        }
+        self.output.push_str("<!--");
+        self.output.push_str(contents);
+        self.output.push_str("-->");
+        Ok(())
+      }
+      NodeData::Text { contents } => {
+        let contents = contents.borrow();
+        if !contents.chars().all(is_xml_char) {
+          self.well_formed_error(
+            "The text contains characters that are not allowed in XML.".to_string(),
+          )?;
+        }
+        for c in contents.chars() {
+          match c {
+            '&' => self.output.push_str("&amp;"),
+            '<' => self.output.push_str("&lt;"),
+            '>' => self.output.push_str("&gt;"),
+            c => self.output.push(c),
+          }
+        }
+        Ok(())
+      }
+      NodeData::Doctype {
+        name,
+        public_id,
+        system_id,
+      } => {
+        let is_pubid_char = |c: char| {
+          c.is_ascii_alphanumeric()
+            || matches!(c, ' ' | '\r' | '\n')
+            || "-'()+,./:=?;!*#@$_%".contains(c)
+        };
+        if !public_id.chars().all(is_pubid_char) {
+          self.well_formed_error(format!("The public id '{}' is not well-formed.", public_id))?;
+        }
+        if !system_id.chars().all(is_xml_char)
+          || system_id.contains('"') && system_id.contains('\'')
+        {
+          self.well_formed_error(format!("The system id '{}' is not well-formed.", system_id))?;
+        }
+        self.output.push_str("<!DOCTYPE ");
+        self.output.push_str(name);
+        if !public_id.is_empty() {
+          self.output.push_str(" PUBLIC \"");
+          self.output.push_str(public_id);
+          self.output.push('"');
+        } else if !system_id.is_empty() {
+          self.output.push_str(" SYSTEM");
+        }
+        if !system_id.is_empty() {
+          self.output.push_str(" \"");
+          self.output.push_str(system_id);
+          self.output.push('"');
+        }
+        self.output.push('>');
+        Ok(())
+      }
+      NodeData::ProcessingInstruction { target, contents } => {
+        if target.contains(':') || target.eq_ignore_ascii_case("xml") {
+          self.well_formed_error(format!(
+            "The processing instruction target '{}' is not well-formed.",
+            target
+          ))?;
+        }
+        if !contents.chars().all(is_xml_char) || contents.contains("?>") {
+          self
+            .well_formed_error("The processing instruction data is not well-formed.".to_string())?;
+        }
+        self.output.push_str("<?");
+        self.output.push_str(target);
+        self.output.push(' ');
+        self.output.push_str(contents);
+        self.output.push_str("?>");
+        Ok(())
+      }
+    }
+  }
+
+  fn children(
+    &mut self,
+    handle: &Handle,
+    namespace: Option<&str>,
+    map: &mut NamespacePrefixMap,
+  ) -> XmlResult {
+    let children = match template_contents(handle) {
+      Some(contents) => contents.children.borrow().clone(),
+      None => handle.children.borrow().clone(),
+    };
+    for child in children.iter() {
+      self.node(child, namespace, map)?;
+    }
+    Ok(())
+  }
+
+  /// https://w3c.github.io/DOM-Parsing/#xml-serializing-an-element-node
+  fn element(
+    &mut self,
+    handle: &Handle,
+    context_namespace: Option<&str>,
+    parent_map: &NamespacePrefixMap,
+  ) -> XmlResult {
+    let NodeData::Element { name, attrs, .. } = &handle.data else {
+      return Ok(());
+    };
+    let local_name = name.local.as_ref();
+    if !crate::node::is_valid_ncname(local_name) {
+      self.well_formed_error(format!(
+        "The element name '{}' is not well-formed.",
+        local_name
+      ))?;
+    }
+
+    let mut map = parent_map.clone();
+    let mut local_prefixes: HashMap<String, String> = HashMap::new();
+    let attrs = attrs.borrow();
+    let local_default_namespace =
+      record_namespace_information(&attrs, &mut map, &mut local_prefixes);
+
+    let namespace = namespace_of(name);
+    let mut inherited_namespace = context_namespace.map(str::to_string);
+    let mut ignore_namespace_definition_attribute = false;
+    let qualified_name;
+    self.output.push('<');
+
+    if inherited_namespace == namespace {
+      if local_default_namespace.is_some() {
+        ignore_namespace_definition_attribute = true;
+      }
+      qualified_name = if name.ns == ns!(xml) {
+        format!("xml:{}", local_name)
+      } else {
+        local_name.to_string()
+      };
+      self.output.push_str(&qualified_name);
+    } else {
+      let mut prefix = name.prefix.as_ref().map(|p| p.to_string());
+      let mut candidate_prefix = map.preferred_prefix(prefix.as_deref(), &namespace);
+      if prefix.as_deref() == Some("xmlns") {
+        self.well_formed_error("Elements cannot have the 'xmlns' prefix.".to_string())?;
+        candidate_prefix = prefix.clone();
+      }
+      if let Some(candidate_prefix) = candidate_prefix {
+        qualified_name = format!("{}:{}", candidate_prefix, local_name);
+        if let Some(default_namespace) = &local_default_namespace {
+          if default_namespace.as_str() != ns!(xml).as_ref() {
+            inherited_namespace = Some(default_namespace.clone()).filter(|ns| !ns.is_empty());
+          }
+        }
+        self.output.push_str(&qualified_name);
+      } else if let Some(element_prefix) = prefix.take() {
+        let element_prefix = if local_prefixes.contains_key(&element_prefix) {
+          self.generate_prefix(&mut map, namespace.clone())
+        } else {
+          map.add(&element_prefix, namespace.clone());
+          element_prefix
+        };
+        qualified_name = format!("{}:{}", element_prefix, local_name);
+        self.output.push_str(&qualified_name);
+        self.output.push_str(" xmlns:");
+        self.output.push_str(&element_prefix);
+        self.output.push_str("=\"");
+        self.attribute_value(namespace.as_deref().unwrap_or_default())?;
+        self.output.push('"');
+        if let Some(default_namespace) = &local_default_namespace {
+          inherited_namespace = Some(default_namespace.clone()).filter(|ns| !ns.is_empty());
+        }
+      } else if local_default_namespace.is_none() || local_default_namespace != namespace {
+        ignore_namespace_definition_attribute = true;
+        qualified_name = local_name.to_string();
+        inherited_namespace = namespace.clone();
+        self.output.push_str(&qualified_name);
+        self.output.push_str(" xmlns=\"");
+        self.attribute_value(namespace.as_deref().unwrap_or_default())?;
+        self.output.push('"');
+      } else {
+        qualified_name = local_name.to_string();
+        inherited_namespace = namespace.clone();
+        self.output.push_str(&qualified_name);
+      }
+    }
+
+    self.attributes(
+      &attrs,
+      &mut map,
+      &local_prefixes,
+      ignore_namespace_definition_attribute,
+    )?;
+
+    let is_html = name.ns == ns!(html);
+    let has_children = match template_contents(handle) {
+      Some(contents) => !contents.children.borrow().is_empty(),
+      None => !handle.children.borrow().is_empty(),
+    };
+    if !has_children && (!is_html || VOID_ELEMENTS.contains(&local_name)) {
+      self.output.push_str(if is_html { " />" } else { "/>" });
+      return Ok(());
+    }
+    self.output.push('>');
+    self.children(handle, inherited_namespace.as_deref(), &mut map)?;
+    self.output.push_str("</");
+    self.output.push_str(&qualified_name);
+    self.output.push('>');
+    Ok(())
+  }
+
+  /// https://w3c.github.io/DOM-Parsing/#dfn-serializing-an-element-s-attributes
+  fn attributes(
+    &mut self,
+    attrs: &[html5ever::Attribute],
+    map: &mut NamespacePrefixMap,
+    local_prefixes: &HashMap<String, String>,
+    ignore_namespace_definition_attribute: bool,
+  ) -> XmlResult {
+    let mut seen = HashSet::new();
+    for attr in attrs {
+      let namespace = namespace_of(&attr.name);
+      let local_name = attr.name.local.as_ref();
+      if !seen.insert((namespace.clone(), local_name)) {
+        self.well_formed_error(format!("The attribute '{}' is duplicated.", local_name))?;
+      }
+      let attr_prefix = attr.name.prefix.as_ref().map(|p| p.as_ref());
+      let value: &str = &attr.value;
+      let mut candidate_prefix = None;
+      if let Some(attr_namespace) = &namespace {
+        candidate_prefix = map.preferred_prefix(attr_prefix, &namespace);
+        if attr.name.ns == ns!(xmlns) {
+          let redundant = value == ns!(xml).as_ref()
+            || attr_prefix.is_none() && ignore_namespace_definition_attribute
+            || attr_prefix.is_some()
+              && local_prefixes.get(local_name).map(String::as_str) != Some(value)
+              && map.contains(
+                local_name,
+                &Some(value.to_string()).filter(|v| !v.is_empty()),
+              );
+          if redundant {
+            continue;
+          }
+          if value == ns!(xmlns).as_ref() {
+            self.well_formed_error("The XMLNS namespace cannot be declared.".to_string())?;
+          }
+          if attr_prefix.is_some() && value.is_empty() {
+            self.well_formed_error(
+              "Namespace prefix declarations cannot be used to undeclare a namespace.".to_string(),
+            )?;
+          }
+          if attr_prefix == Some("xmlns") {
+            candidate_prefix = Some("xmlns".to_string());
+          }
+        } else if candidate_prefix.is_none() {
+          let generated = self.generate_prefix(map, Some(attr_namespace.clone()));
+          self.output.push_str(" xmlns:");
+          self.output.push_str(&generated);
+          self.output.push_str("=\"");
+          self.attribute_value(attr_namespace)?;
+          self.output.push('"');
+          candidate_prefix = Some(generated);
+        }
+      }
+
+      self.output.push(' ');
+      if let Some(prefix) = candidate_prefix {
+        self.output.push_str(&prefix);
+        self.output.push(':');
+      }
+      if !crate::node::is_valid_ncname(local_name) || local_name == "xmlns" && namespace.is_none() {
+        self.well_formed_error(format!(
+          "The attribute name '{}' is not well-formed.",
+          local_name
+        ))?;
+      }
+      self.output.push_str(local_name);
+      self.output.push_str("=\"");
+      self.attribute_value(value)?;
+      self.output.push('"');
+    }
+    Ok(())
+  }
+
+  fn attribute_value(&mut self, value: &str) -> XmlResult {
+    if !value.chars().all(is_xml_char) {
+      self.well_formed_error(
+        "The attribute value contains characters that are not allowed in XML.".to_string(),
+      )?;
+    }
+    for c in value.chars() {
+      match c {
+        '&' => self.output.push_str("&amp;"),
+        '"' => self.output.push_str("&quot;"),
+        '<' => self.output.push_str("&lt;"),
+        '>' => self.output.push_str("&gt;"),
+        '\t' => self.output.push_str("&#x9;"),
+        '\n' => self.output.push_str("&#xA;"),
+        '\r' => self.output.push_str("&#xD;"),
+        c => self.output.push(c),
+      }
+    }
+    Ok(())
+  }
+
+  /// https://w3c.github.io/DOM-Parsing/#dfn-generating-a-prefix
+  /// Like browsers, the XLink namespace gets its conventional `xlink` prefix when it is free.
+  fn generate_prefix(&mut self, map: &mut NamespacePrefixMap, namespace: Option<String>) -> String {
+    if namespace.as_deref() == Some(ns!(xlink).as_ref())
+      && !map
+        .0
+        .values()
+        .any(|prefixes| prefixes.iter().any(|p| p == "xlink"))
+    {
+      map.add("xlink", namespace);
+      return "xlink".to_string();
+    }
+    let prefix = format!("ns{}", self.prefix_index);
+    self.prefix_index += 1;
+    map.add(&prefix, namespace);
+    prefix
+  }
+}
+
+/// Adds the prefixes declared by `xmlns:*` attributes to `map` and `local_prefixes`,
+/// and returns the value of the `xmlns` attribute, if any.
+/// https://w3c.github.io/DOM-Parsing/#recording-the-namespace-information
+fn record_namespace_information(
+  attrs: &[html5ever::Attribute],
+  map: &mut NamespacePrefixMap,
+  local_prefixes: &mut HashMap<String, String>,
+) -> Option<String> {
+  let mut default_namespace = None;
+  for attr in attrs {
+    if attr.name.ns != ns!(xmlns) {
+      continue;
+    }
+    if attr.name.prefix.is_none() {
+      default_namespace = Some(attr.value.to_string());
+      continue;
+    }
+    let prefix = attr.name.local.as_ref();
+    let value = attr.value.to_string();
+    if value == ns!(xml).as_ref() {
+      continue;
+    }
+    let namespace = Some(value.clone()).filter(|v| !v.is_empty());
+    if map.contains(prefix, &namespace) {
+      continue;
+    }
+    map.add(prefix, namespace);
+    local_prefixes.insert(prefix.to_string(), value);
+  }
+  default_namespace
+}
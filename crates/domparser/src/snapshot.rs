@@ -0,0 +1,439 @@
+//! A compact binary format for storing DOM trees.
+//!
+//! A snapshot holds the same information as an [`AstNode`] and loads back into
+//! a tree without tokenizing any HTML. The layout is:
+//!
+//! ```text
+//! magic "DPSN" | major: u16 LE | minor: u16 LE
+//! string count: varint | (length: varint, UTF-8 bytes)*
+//! root node record
+//! ```
+//!
+//! Every string (names, namespaces, attribute values, text) is stored once in
+//! the table and referenced by index. A node record starts with a kind byte,
+//! followed by its string indices, flags and child records in document order;
+//! optional strings are stored as `index + 1` with `0` meaning absent.
+//!
+//! Readers accept snapshots with the same major version and a minor version no
+//! newer than their own. Records are not length-prefixed, so a reader cannot
+//! skip data it does not know; a new minor version may add node kinds or flags
+//! that only newer readers understand, and older readers reject it.
+
+use std::collections::HashMap;
+
+use crate::ast::{AstAttribute, AstElement, AstNode, AstShadowRoot};
+use crate::node::DomNode;
+
+const MAGIC: &[u8; 4] = b"DPSN";
+const MAJOR_VERSION: u16 = 1;
+const MINOR_VERSION: u16 = 0;
+
+const DOCUMENT: u8 = 1;
+const DOCUMENT_TYPE: u8 = 2;
+const ELEMENT: u8 = 3;
+const TEXT: u8 = 4;
+const COMMENT: u8 = 5;
+const PROCESSING_INSTRUCTION: u8 = 6;
+const DOCUMENT_FRAGMENT: u8 = 7;
+const SHADOW_ROOT: u8 = 8;
+
+const HAS_CONTENT: u8 = 1;
+const HAS_SHADOW_ROOT: u8 = 1 << 1;
+const ANNOTATION_XML_INTEGRATION_POINT: u8 = 1 << 2;
+
+const SCRIPTING_ENABLED: u8 = 1;
+
+const DELEGATES_FOCUS: u8 = 1;
+const CLONABLE: u8 = 1 << 1;
+const SERIALIZABLE: u8 = 1 << 2;
+
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+  while value >= 0x80 {
+    out.push(value as u8 | 0x80);
+    value >>= 7;
+  }
+  out.push(value as u8);
+}
+
+#[derive(Default)]
+struct Writer<'a> {
+  strings: Vec<&'a str>,
+  indices: HashMap<&'a str, usize>,
+  nodes: Vec<u8>,
+}
+
+impl<'a> Writer<'a> {
+  fn intern(&mut self, value: &'a str) -> usize {
+    let next = self.strings.len();
+    let index = *self.indices.entry(value).or_insert(next);
+    if index == next {
+      self.strings.push(value);
+    }
+    index
+  }
+
+  fn string(&mut self, value: &'a str) {
+    let index = self.intern(value);
+    write_varint(&mut self.nodes, index);
+  }
+
+  fn optional_string(&mut self, value: &'a Option<String>) {
+    let index = value.as_deref().map_or(0, |value| self.intern(value) + 1);
+    write_varint(&mut self.nodes, index);
+  }
+
+  fn children(&mut self, children: &'a [AstNode]) {
+    write_varint(&mut self.nodes, children.len());
+    for child in children {
+      self.node(child);
+    }
+  }
+
+  fn shadow_root(&mut self, root: &'a AstShadowRoot) {
+    self.string(&root.mode);
+    self.string(&root.slot_assignment);
+    let mut flags = 0;
+    if root.delegates_focus {
+      flags |= DELEGATES_FOCUS;
+    }
+    if root.clonable {
+      flags |= CLONABLE;
+    }
+    if root.serializable {
+      flags |= SERIALIZABLE;
+    }
+    self.nodes.push(flags);
+    self.children(&root.children);
+  }
+
+  fn element(&mut self, element: &'a AstElement) {
+    self.string(&element.name);
+    self.optional_string(&element.namespace);
+    self.optional_string(&element.prefix);
+    let mut flags = 0;
+    if element.content.is_some() {
+      flags |= HAS_CONTENT;
+    }
+    if element.shadow_root.is_some() {
+      flags |= HAS_SHADOW_ROOT;
+    }
+    if element.mathml_annotation_xml_integration_point {
+      flags |= ANNOTATION_XML_INTEGRATION_POINT;
+    }
+    self.nodes.push(flags);
+    write_varint(&mut self.nodes, element.attributes.len());
+    for attr in &element.attributes {
+      self.string(&attr.name);
+      self.optional_string(&attr.namespace);
+      self.optional_string(&attr.prefix);
+      self.string(&attr.value);
+    }
+    self.children(&element.children);
+    if let Some(content) = &element.content {
+      self.children(content);
+    }
+    if let Some(root) = &element.shadow_root {
+      self.shadow_root(root);
+    }
+  }
+
+  fn node(&mut self, node: &'a AstNode) {
+    match node {
+      AstNode::Document {
+        content_type,
+        scripting_enabled,
+        children,
+      } => {
+        self.nodes.push(DOCUMENT);
+        self.string(content_type);
+        self.nodes.push(if *scripting_enabled {
+          SCRIPTING_ENABLED
+        } else {
+          0
+        });
+        self.children(children);
+      }
+      AstNode::DocumentType {
+        name,
+        public_id,
+        system_id,
+      } => {
+        self.nodes.push(DOCUMENT_TYPE);
+        self.string(name);
+        self.string(public_id);
+        self.string(system_id);
+      }
+      AstNode::Element(element) => {
+        self.nodes.push(ELEMENT);
+        self.element(element);
+      }
+      AstNode::Text { value } => {
+        self.nodes.push(TEXT);
+        self.string(value);
+      }
+      AstNode::Comment { value } => {
+        self.nodes.push(COMMENT);
+        self.string(value);
+      }
+      AstNode::ProcessingInstruction { target, data } => {
+        self.nodes.push(PROCESSING_INSTRUCTION);
+        self.string(target);
+        self.string(data);
+      }
+      AstNode::DocumentFragment { children } => {
+        self.nodes.push(DOCUMENT_FRAGMENT);
+        self.children(children);
+      }
+      AstNode::ShadowRoot(root) => {
+        self.nodes.push(SHADOW_ROOT);
+        self.shadow_root(root);
+      }
+    }
+  }
+
+  fn finish(self) -> Vec<u8> {
+    let table_len: usize = self.strings.iter().map(|s| s.len() + 2).sum();
+    let mut out = Vec::with_capacity(8 + table_len + self.nodes.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&MAJOR_VERSION.to_le_bytes());
+    out.extend_from_slice(&MINOR_VERSION.to_le_bytes());
+    write_varint(&mut out, self.strings.len());
+    for string in &self.strings {
+      write_varint(&mut out, string.len());
+      out.extend_from_slice(string.as_bytes());
+    }
+    out.extend_from_slice(&self.nodes);
+    out
+  }
+}
+
+/// Encodes an [`AstNode`] as a snapshot.
+pub fn encode(ast: &AstNode) -> Vec<u8> {
+  let mut writer = Writer::default();
+  writer.node(ast);
+  writer.finish()
+}
+
+fn truncated() -> String {
+  "Invalid snapshot: unexpected end of data.".to_string()
+}
+
+struct Reader<'a> {
+  bytes: &'a [u8],
+  pos: usize,
+  strings: Vec<&'a str>,
+}
+
+impl<'a> Reader<'a> {
+  fn byte(&mut self) -> Result<u8, String> {
+    let byte = *self.bytes.get(self.pos).ok_or_else(truncated)?;
+    self.pos += 1;
+    Ok(byte)
+  }
+
+  fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+    let end = self.pos.checked_add(len).ok_or_else(truncated)?;
+    let bytes = self.bytes.get(self.pos..end).ok_or_else(truncated)?;
+    self.pos = end;
+    Ok(bytes)
+  }
+
+  fn varint(&mut self) -> Result<usize, String> {
+    let mut value: usize = 0;
+    let mut shift = 0;
+    loop {
+      let byte = self.byte()?;
+      if shift >= usize::BITS {
+        return Err("Invalid snapshot: a number is too large.".to_string());
+      }
+      value |= ((byte & 0x7f) as usize) << shift;
+      if byte & 0x80 == 0 {
+        return Ok(value);
+      }
+      shift += 7;
+    }
+  }
+
+  /// Reads a count of items that each take at least one byte, so a corrupt
+  /// count cannot make us allocate more than the input could hold.
+  fn count(&mut self) -> Result<usize, String> {
+    let count = self.varint()?;
+    if count > self.bytes.len() - self.pos {
+      return Err(truncated());
+    }
+    Ok(count)
+  }
+
+  fn lookup(&self, index: usize) -> Result<String, String> {
+    self
+      .strings
+      .get(index)
+      .map(|s| s.to_string())
+      .ok_or_else(|| format!("Invalid snapshot: string {} is out of range.", index))
+  }
+
+  fn string(&mut self) -> Result<String, String> {
+    let index = self.varint()?;
+    self.lookup(index)
+  }
+
+  fn optional_string(&mut self) -> Result<Option<String>, String> {
+    match self.varint()? {
+      0 => Ok(None),
+      index => self.lookup(index - 1).map(Some),
+    }
+  }
+
+  fn strings(&mut self) -> Result<(), String> {
+    let count = self.count()?;
+    self.strings.reserve(count);
+    for _ in 0..count {
+      let len = self.varint()?;
+      let bytes = self.take(len)?;
+      let string = std::str::from_utf8(bytes)
+        .map_err(|_| "Invalid snapshot: a string is not valid UTF-8.".to_string())?;
+      self.strings.push(string);
+    }
+    Ok(())
+  }
+
+  fn children(&mut self) -> Result<Vec<AstNode>, String> {
+    let count = self.count()?;
+    let mut children = Vec::with_capacity(count);
+    for _ in 0..count {
+      children.push(self.node()?);
+    }
+    Ok(children)
+  }
+
+  fn shadow_root(&mut self) -> Result<AstShadowRoot, String> {
+    let mode = self.string()?;
+    let slot_assignment = self.string()?;
+    let flags = self.byte()?;
+    Ok(AstShadowRoot {
+      mode,
+      delegates_focus: flags & DELEGATES_FOCUS != 0,
+      slot_assignment,
+      clonable: flags & CLONABLE != 0,
+      serializable: flags & SERIALIZABLE != 0,
+      children: self.children()?,
+    })
+  }
+
+  fn element(&mut self) -> Result<AstElement, String> {
+    let name = self.string()?;
+    let namespace = self.optional_string()?;
+    let prefix = self.optional_string()?;
+    let flags = self.byte()?;
+    let count = self.count()?;
+    let mut attributes = Vec::with_capacity(count);
+    for _ in 0..count {
+      attributes.push(AstAttribute {
+        name: self.string()?,
+        namespace: self.optional_string()?,
+        prefix: self.optional_string()?,
+        value: self.string()?,
+      });
+    }
+    let children = self.children()?;
+    let content = if flags & HAS_CONTENT != 0 {
+      Some(self.children()?)
+    } else {
+      None
+    };
+    let shadow_root = if flags & HAS_SHADOW_ROOT != 0 {
+      Some(self.shadow_root()?)
+    } else {
+      None
+    };
+    Ok(AstElement {
+      name,
+      namespace,
+      prefix,
+      attributes,
+      children,
+      content,
+      shadow_root,
+      mathml_annotation_xml_integration_point: flags & ANNOTATION_XML_INTEGRATION_POINT != 0,
+      location: None,
+    })
+  }
+
+  fn node(&mut self) -> Result<AstNode, String> {
+    let node = match self.byte()? {
+      DOCUMENT => {
+        let content_type = self.string()?;
+        let flags = self.byte()?;
+        AstNode::Document {
+          content_type,
+          scripting_enabled: flags & SCRIPTING_ENABLED != 0,
+          children: self.children()?,
+        }
+      }
+      DOCUMENT_TYPE => AstNode::DocumentType {
+        name: self.string()?,
+        public_id: self.string()?,
+        system_id: self.string()?,
+      },
+      ELEMENT => AstNode::Element(self.element()?),
+      TEXT => AstNode::Text {
+        value: self.string()?,
+      },
+      COMMENT => AstNode::Comment {
+        value: self.string()?,
+      },
+      PROCESSING_INSTRUCTION => AstNode::ProcessingInstruction {
+        target: self.string()?,
+        data: self.string()?,
+      },
+      DOCUMENT_FRAGMENT => AstNode::DocumentFragment {
+        children: self.children()?,
+      },
+      SHADOW_ROOT => AstNode::ShadowRoot(self.shadow_root()?),
+      kind => return Err(format!("Invalid snapshot: unknown node kind {}.", kind)),
+    };
+    Ok(node)
+  }
+}
+
+/// Decodes a snapshot written by [`encode`] with the same major version and a
+/// minor version no newer than this one.
+pub fn decode(bytes: &[u8]) -> Result<AstNode, String> {
+  if bytes.len() < 8 || &bytes[..4] != MAGIC {
+    return Err("Invalid snapshot: missing header.".to_string());
+  }
+  let major = u16::from_le_bytes([bytes[4], bytes[5]]);
+  if major != MAJOR_VERSION {
+    return Err(format!(
+      "Unsupported snapshot version {}; expected version {}.",
+      major, MAJOR_VERSION
+    ));
+  }
+  let minor = u16::from_le_bytes([bytes[6], bytes[7]]);
+  if minor > MINOR_VERSION {
+    return Err(format!(
+      "Unsupported snapshot version {}.{}; expected version {}.{} or older.",
+      major, minor, MAJOR_VERSION, MINOR_VERSION
+    ));
+  }
+  let mut reader = Reader {
+    bytes,
+    pos: 8,
+    strings: vec![],
+  };
+  reader.strings()?;
+  reader.node()
+}
+
+impl DomNode {
+  /// Serializes this node and its descendants, including template contents and
+  /// shadow roots, to the binary snapshot format.
+  pub fn to_snapshot(&self) -> Vec<u8> {
+    encode(&self.to_ast())
+  }
+
+  /// Builds a new tree from a snapshot written by [`DomNode::to_snapshot`].
+  pub fn from_snapshot(bytes: &[u8]) -> Result<DomNode, String> {
+    DomNode::from_ast(&decode(bytes)?)
+  }
+}
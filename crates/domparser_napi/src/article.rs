@@ -0,0 +1,56 @@
+use domparser::article::{Article as CoreArticle, ArticleOptions as CoreArticleOptions};
+
+use crate::node_repr::NodeRepr;
+
+/// Options for `extractArticle`.
+#[napi(object)]
+pub struct ArticleOptions {
+  /// The number of characters an article needs before the heuristics are relaxed. Defaults to 500.
+  pub char_threshold: Option<u32>,
+  /// Keep `class` attributes in the article. Defaults to false.
+  pub keep_classes: Option<bool>,
+}
+
+impl From<ArticleOptions> for CoreArticleOptions {
+  fn from(options: ArticleOptions) -> Self {
+    let defaults = CoreArticleOptions::default();
+    CoreArticleOptions {
+      char_threshold: options
+        .char_threshold
+        .map_or(defaults.char_threshold, |threshold| threshold as usize),
+      keep_classes: options.keep_classes.unwrap_or(defaults.keep_classes),
+    }
+  }
+}
+
+/// The main article of a page, returned by `extractArticle`.
+#[napi(object, object_from_js = false)]
+pub struct Article {
+  pub title: Option<String>,
+  pub byline: Option<String>,
+  /// The page description, or the first paragraph of the article.
+  pub excerpt: Option<String>,
+  pub site_name: Option<String>,
+  pub language: Option<String>,
+  /// A detached `<div>` holding the cleaned article, with absolute URLs.
+  pub content: NodeRepr,
+  /// The text of the article with white space collapsed.
+  pub text_content: String,
+  /// The length of `textContent` in characters.
+  pub length: u32,
+}
+
+impl From<CoreArticle> for Article {
+  fn from(article: CoreArticle) -> Self {
+    Article {
+      title: article.title,
+      byline: article.byline,
+      excerpt: article.excerpt,
+      site_name: article.site_name,
+      language: article.language,
+      content: NodeRepr(article.content),
+      text_content: article.text_content,
+      length: article.length as u32,
+    }
+  }
+}
@@ -0,0 +1,66 @@
+use domparser::css::{
+  CssCoverage as CoreCssCoverage, InlineOptions, RuleCoverage as CoreRuleCoverage,
+};
+
+/// Options for `inlineCSS`.
+#[napi(object)]
+pub struct InlineCssOptions {
+  /// Keep `!important` on declarations inlined from stylesheets. Defaults to false.
+  pub preserve_important: Option<bool>,
+  /// Remove class names that the remaining rules do not use. Defaults to false.
+  pub remove_classes: Option<bool>,
+  /// Remove ids that the remaining rules do not use. Defaults to false.
+  pub remove_ids: Option<bool>,
+}
+
+impl From<InlineCssOptions> for InlineOptions {
+  fn from(options: InlineCssOptions) -> Self {
+    InlineOptions {
+      preserve_important: options.preserve_important.unwrap_or_default(),
+      remove_classes: options.remove_classes.unwrap_or_default(),
+      remove_ids: options.remove_ids.unwrap_or_default(),
+    }
+  }
+}
+
+/// The usage of a style rule, returned by `cssCoverage`.
+#[napi(object)]
+pub struct RuleCoverage {
+  pub selector_text: String,
+  /// The at-rules the rule is nested in, such as `@media print`, outermost first.
+  pub conditions: Vec<String>,
+  /// Whether a selector of the rule matches an element.
+  pub used: bool,
+  /// The selectors of the list that match an element.
+  pub used_selectors: Vec<String>,
+}
+
+impl From<CoreRuleCoverage> for RuleCoverage {
+  fn from(rule: CoreRuleCoverage) -> Self {
+    RuleCoverage {
+      used: rule.is_used(),
+      selector_text: rule.selector_text,
+      conditions: rule.conditions,
+      used_selectors: rule.used_selectors,
+    }
+  }
+}
+
+/// The result of `cssCoverage`.
+#[napi(object)]
+pub struct CssCoverage {
+  /// The style rules of the stylesheet in source order, including those in at-rules.
+  pub rules: Vec<RuleCoverage>,
+  /// The stylesheet without unused style rules and selectors.
+  #[napi(js_name = "prunedCSS")]
+  pub pruned_css: String,
+}
+
+impl From<CoreCssCoverage> for CssCoverage {
+  fn from(coverage: CoreCssCoverage) -> Self {
+    CssCoverage {
+      pruned_css: coverage.pruned.css_text(),
+      rules: coverage.rules.into_iter().map(RuleCoverage::from).collect(),
+    }
+  }
+}
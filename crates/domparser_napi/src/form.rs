@@ -0,0 +1,64 @@
+use domparser::form::{FormEntry as CoreFormEntry, FormSubmission as CoreFormSubmission};
+
+/// The point clicked on an image button.
+#[napi(object)]
+pub struct Coordinates {
+  pub x: i32,
+  pub y: i32,
+}
+
+/// Options for `submitForm`.
+#[napi(object)]
+pub struct SubmitFormOptions {
+  /// The point clicked on an image button submitter. Defaults to (0, 0).
+  pub coordinates: Option<Coordinates>,
+  /// The boundary of a `multipart/form-data` body. Defaults to a random one.
+  pub boundary: Option<String>,
+}
+
+/// An entry of a form's entry list, returned by `formEntries`.
+#[napi(object)]
+pub struct FormEntry {
+  pub name: String,
+  pub value: String,
+  /// The file name of a file input's entry, which is always empty.
+  pub filename: Option<String>,
+}
+
+impl From<CoreFormEntry> for FormEntry {
+  fn from(entry: CoreFormEntry) -> Self {
+    FormEntry {
+      name: entry.name,
+      value: entry.value,
+      filename: entry.filename,
+    }
+  }
+}
+
+/// The request a browser would make to submit a form, returned by `submitForm`.
+#[napi(object)]
+pub struct FormSubmission {
+  /// The URL to submit to. For the "get" method, the entries are its query.
+  pub url: String,
+  /// "get", "post" or "dialog".
+  pub method: String,
+  pub enctype: String,
+  pub entries: Vec<FormEntry>,
+  /// The request body, for the "post" method.
+  pub body: Option<String>,
+  /// The `Content-Type` of the body, with its boundary for `multipart/form-data`.
+  pub content_type: Option<String>,
+}
+
+impl From<CoreFormSubmission> for FormSubmission {
+  fn from(submission: CoreFormSubmission) -> Self {
+    FormSubmission {
+      url: submission.url,
+      method: submission.method,
+      enctype: submission.enctype,
+      entries: submission.entries.into_iter().map(Into::into).collect(),
+      body: submission.body,
+      content_type: submission.content_type,
+    }
+  }
+}
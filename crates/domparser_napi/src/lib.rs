@@ -1,14 +1,237 @@
 #[macro_use]
 extern crate napi_derive;
 
-use domparser::parse as parse_core;
+use domparser::article::extract_article as extract_article_core;
+use domparser::css::{
+  css_coverage as css_coverage_core, inline_css as inline_css_core, StyleResolver, StyleSheet,
+};
+use domparser::form::{
+  form_entries as form_entries_core, submit_form as submit_form_core, SubmitOptions,
+};
+use domparser::metadata::extract_metadata as extract_metadata_core;
+use domparser::patch::{apply_patch as apply_patch_core, dom_diff as dom_diff_core, DiffOptions};
+use domparser::resources::extract_resources as extract_resources_core;
+use domparser::url::Url;
+use domparser::DomNode;
+use domparser::{parse_with_options, ParseOptions as CoreParseOptions};
+use napi::bindgen_prelude::{Buffer, Env, Object};
 use node_repr::NodeRepr;
 
+mod article;
+mod css;
+mod form;
+mod metadata;
 mod node_repr;
+mod resources;
+mod sanitizer;
+
+/// Options for `parse`.
+#[napi(object)]
+pub struct ParseOptions {
+  /// Record where each node came from in the input, read back with `sourceLocation`. Defaults to false.
+  pub source_locations: Option<bool>,
+  /// The URL of the document, used to resolve relative URLs. Defaults to "about:blank".
+  pub url: Option<String>,
+}
 
 /// Parse string input to a html tree, return the root node.
 ///
 #[napi]
-pub fn parse(html: String) -> NodeRepr {
-  NodeRepr(parse_core(html))
+pub fn parse(html: String, options: Option<ParseOptions>) -> napi::Result<NodeRepr> {
+  let options = options.unwrap_or(ParseOptions {
+    source_locations: None,
+    url: None,
+  });
+  let url = options
+    .url
+    .map(|url| {
+      Url::parse(&url).map_err(|e| {
+        napi::Error::new(
+          napi::Status::InvalidArg,
+          format!("Invalid document URL '{}': {}", url, e),
+        )
+      })
+    })
+    .transpose()?;
+  let options = CoreParseOptions {
+    source_locations: options.source_locations.unwrap_or(false),
+    url,
+  };
+  Ok(NodeRepr(parse_with_options(html, &options)))
+}
+
+/// Creates a new empty XML document, backing the `Document` constructor.
+#[napi(js_name = "createEmptyDocument")]
+pub fn create_empty_document() -> NodeRepr {
+  NodeRepr(DomNode::new_document())
+}
+
+/// Options for `domDiff`.
+#[napi(object)]
+pub struct DomDiffOptions {
+  /// Match elements by the value of this attribute, such as "id", before falling back to their position.
+  pub key: Option<String>,
+}
+
+/// Computes the patch that turns `oldNode` into a tree equal to `newNode`, as plain objects.
+#[napi(js_name = "domDiff", ts_return_type = "Patch")]
+pub fn dom_diff<'env>(
+  env: &'env Env,
+  old_node: &NodeRepr,
+  new_node: &NodeRepr,
+  options: Option<DomDiffOptions>,
+) -> napi::Result<Object<'env>> {
+  let options = DiffOptions {
+    key_attribute: options.and_then(|options| options.key),
+  };
+  node_repr::patch::patch_to_js(env, &dom_diff_core(&old_node.0, &new_node.0, &options))
+}
+
+/// Applies a patch returned by `domDiff` to the node, in order.
+#[napi(js_name = "applyPatch", ts_args_type = "node: NodeRepr, patch: Patch")]
+pub fn apply_patch(node: &NodeRepr, patch: Object) -> napi::Result<()> {
+  let patch = node_repr::patch::patch_from_js(&patch)?;
+  apply_patch_core(&node.0, &patch).map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+}
+
+/// Returns the URLs referenced by the node and its descendants, in tree order, in one call.
+#[napi(js_name = "extractResources")]
+pub fn extract_resources(node: &NodeRepr) -> Vec<resources::Resource> {
+  extract_resources_core(&node.0)
+    .into_iter()
+    .map(resources::Resource::from)
+    .collect()
+}
+
+/// Extracts the main article of a document, or null if it has no text. The document is not changed.
+#[napi(js_name = "extractArticle")]
+pub fn extract_article(
+  document: &NodeRepr,
+  options: Option<article::ArticleOptions>,
+) -> Option<article::Article> {
+  let options = options.map(Into::into).unwrap_or_default();
+  extract_article_core(&document.0, &options).map(article::Article::from)
+}
+
+/// Extracts the page metadata of a document in one call, with JSON-LD blocks as text.
+#[napi(js_name = "extractMetadata", ts_return_type = "Metadata")]
+pub fn extract_metadata<'env>(env: &'env Env, node: &NodeRepr) -> napi::Result<Object<'env>> {
+  metadata::metadata_to_js(env, &extract_metadata_core(&node.0))
+}
+
+/// Computes the style of an element from the cascade of the default stylesheet, the `<style>`
+/// elements of its document or shadow tree, the given stylesheets and its `style` attribute,
+/// without layout. The values are keyed by property name in alphabetical order.
+#[napi(
+  js_name = "getComputedStyle",
+  ts_return_type = "Record<string, string>"
+)]
+pub fn get_computed_style<'env>(
+  env: &'env Env,
+  element: &NodeRepr,
+  stylesheets: Option<Vec<String>>,
+) -> napi::Result<Object<'env>> {
+  let mut resolver = StyleResolver::new();
+  for css in stylesheets.unwrap_or_default() {
+    resolver.add_stylesheet(StyleSheet::parse(&css));
+  }
+  let mut object = Object::new(env)?;
+  for (name, value) in resolver.compute_style(&element.0).iter() {
+    object.set(name, value)?;
+  }
+  Ok(object)
+}
+
+/// Moves the style rules of a document's `<style>` elements into the `style` attributes of the
+/// elements they match, keeping the rules that cannot be inlined.
+#[napi(js_name = "inlineCSS")]
+pub fn inline_css(document: &NodeRepr, options: Option<css::InlineCssOptions>) {
+  let options = options.map(Into::into).unwrap_or_default();
+  inline_css_core(&document.0, &options);
+}
+
+/// Reports which style rules of the CSS match an element of the tree rooted at `root`, ignoring
+/// state pseudo-classes, and returns the CSS pruned of the others.
+#[napi(js_name = "cssCoverage")]
+pub fn css_coverage(root: &NodeRepr, css: String) -> css::CssCoverage {
+  css_coverage_core(&root.0, &css).into()
+}
+
+/// Constructs the entry list a form element would submit with `submitter`, one of its submit
+/// buttons, as the `FormData` constructor does.
+#[napi(js_name = "formEntries")]
+pub fn form_entries(
+  form: &NodeRepr,
+  submitter: Option<&NodeRepr>,
+  coordinates: Option<form::Coordinates>,
+) -> napi::Result<Vec<form::FormEntry>> {
+  let coordinates = coordinates.map_or((0, 0), |point| (point.x, point.y));
+  form_entries_core(&form.0, submitter.map(|n| &n.0), coordinates)
+    .map(|entries| entries.into_iter().map(Into::into).collect())
+    .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+}
+
+/// Builds the request a browser would make to submit a form element with `submitter`, its
+/// default button if omitted, encoding the entries by the resolved method and enctype.
+#[napi(js_name = "submitForm")]
+pub fn submit_form(
+  form: &NodeRepr,
+  submitter: Option<&NodeRepr>,
+  options: Option<form::SubmitFormOptions>,
+) -> napi::Result<form::FormSubmission> {
+  let (coordinates, boundary) = options.map_or((None, None), |options| {
+    (options.coordinates, options.boundary)
+  });
+  let options = SubmitOptions {
+    submitter: submitter.map(|n| n.0.clone()),
+    coordinates: coordinates.map_or((0, 0), |point| (point.x, point.y)),
+    boundary,
+  };
+  submit_form_core(&form.0, &options)
+    .map(Into::into)
+    .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+}
+
+/// Builds a new tree from a JSON AST of plain objects, as produced by `toJSON`.
+#[napi(js_name = "fromJSON", ts_args_type = "ast: AstNode")]
+pub fn from_json(ast: Object) -> napi::Result<NodeRepr> {
+  node_repr::ast::node_from_js(&ast)
+}
+
+/// Builds a new tree from a snapshot returned by `toSnapshot`.
+#[napi(js_name = "fromSnapshot")]
+pub fn from_snapshot(snapshot: Buffer) -> napi::Result<NodeRepr> {
+  DomNode::from_snapshot(&snapshot)
+    .map(NodeRepr)
+    .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+}
+
+/// Creates a new HTML document with a doctype, html, head, optional title and body.
+#[napi(js_name = "createHTMLDocument")]
+pub fn create_html_document(title: Option<String>) -> NodeRepr {
+  NodeRepr(DomNode::create_html_document(title))
+}
+
+/// Creates a new XML document with an optional doctype and document element.
+#[napi(js_name = "createDocument")]
+pub fn create_document(
+  namespace: Option<String>,
+  qualified_name: String,
+  doctype: Option<&NodeRepr>,
+) -> napi::Result<NodeRepr> {
+  DomNode::create_document(namespace, qualified_name, doctype.map(|n| &n.0))
+    .map(NodeRepr)
+    .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+}
+
+/// Creates a new DocumentType node.
+#[napi(js_name = "createDocumentType")]
+pub fn create_document_type(
+  qualified_name: String,
+  public_id: String,
+  system_id: String,
+) -> napi::Result<NodeRepr> {
+  DomNode::create_document_type(qualified_name, public_id, system_id)
+    .map(NodeRepr)
+    .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
 }
@@ -0,0 +1,76 @@
+use domparser::metadata::{Item, ItemValue, Metadata};
+use napi::bindgen_prelude::{Env, Object};
+
+fn set_optional(object: &mut Object, name: &str, value: &Option<String>) -> napi::Result<()> {
+  match value {
+    Some(value) => object.set(name, value.as_str()),
+    None => Ok(()),
+  }
+}
+
+fn values_to_js<'env, 'a>(
+  env: &'env Env,
+  values: impl Iterator<Item = (&'a String, &'a Vec<String>)>,
+) -> napi::Result<Object<'env>> {
+  let mut object = Object::new(env)?;
+  for (name, values) in values {
+    object.set(name, values.clone())?;
+  }
+  Ok(object)
+}
+
+fn item_to_js<'env>(env: &'env Env, item: &Item) -> napi::Result<Object<'env>> {
+  let mut object = Object::new(env)?;
+  object.set("types", item.types.clone())?;
+  set_optional(&mut object, "id", &item.id)?;
+  let mut properties = Object::new(env)?;
+  for (name, values) in &item.properties {
+    let mut array = env.create_array(values.len() as u32)?;
+    for (index, value) in values.iter().enumerate() {
+      match value {
+        ItemValue::Text(text) => array.set(index as u32, text.as_str())?,
+        ItemValue::Item(item) => array.set(index as u32, item_to_js(env, item)?)?,
+      }
+    }
+    properties.set(name, array)?;
+  }
+  object.set("properties", properties)?;
+  Ok(object)
+}
+
+/// Builds the plain object returned by `extractMetadata`, with JSON-LD blocks left as text.
+pub(crate) fn metadata_to_js<'env>(
+  env: &'env Env,
+  metadata: &Metadata,
+) -> napi::Result<Object<'env>> {
+  let mut object = Object::new(env)?;
+  set_optional(&mut object, "title", &metadata.title)?;
+  set_optional(&mut object, "description", &metadata.description)?;
+  set_optional(&mut object, "canonical", &metadata.canonical)?;
+  set_optional(&mut object, "language", &metadata.language)?;
+  let icons = metadata
+    .icons
+    .iter()
+    .map(|icon| {
+      let mut object = Object::new(env)?;
+      object.set("rel", icon.rel.as_str())?;
+      object.set("href", icon.href.as_str())?;
+      set_optional(&mut object, "sizes", &icon.sizes)?;
+      set_optional(&mut object, "type", &icon.media_type)?;
+      Ok(object)
+    })
+    .collect::<napi::Result<Vec<_>>>()?;
+  object.set("icons", icons)?;
+  object.set("openGraph", values_to_js(env, metadata.open_graph.iter())?)?;
+  object.set("twitter", values_to_js(env, metadata.twitter.iter())?)?;
+  object.set("jsonLd", metadata.json_ld.clone())?;
+  let items = |items: &[Item]| {
+    items
+      .iter()
+      .map(|item| item_to_js(env, item))
+      .collect::<napi::Result<Vec<_>>>()
+  };
+  object.set("microdata", items(&metadata.microdata)?)?;
+  object.set("rdfa", items(&metadata.rdfa)?)?;
+  Ok(object)
+}
@@ -0,0 +1,239 @@
+use domparser::ast::{AstAttribute, AstElement, AstNode, AstShadowRoot};
+use domparser::DomNode;
+use napi::bindgen_prelude::{Env, Object};
+
+use super::NodeRepr;
+
+fn set_optional(object: &mut Object, name: &str, value: &Option<String>) -> napi::Result<()> {
+  match value {
+    Some(value) => object.set(name, value.as_str()),
+    None => Ok(()),
+  }
+}
+
+fn children_to_js<'env>(env: &'env Env, children: &[AstNode]) -> napi::Result<Vec<Object<'env>>> {
+  children.iter().map(|child| ast_to_js(env, child)).collect()
+}
+
+fn set_shadow_root(env: &Env, object: &mut Object, root: &AstShadowRoot) -> napi::Result<()> {
+  object.set("mode", root.mode.as_str())?;
+  object.set("delegatesFocus", root.delegates_focus)?;
+  object.set("slotAssignment", root.slot_assignment.as_str())?;
+  object.set("clonable", root.clonable)?;
+  object.set("serializable", root.serializable)?;
+  object.set("children", children_to_js(env, &root.children)?)
+}
+
+/// Builds the plain object form of the JSON AST directly, without going through a JSON string.
+pub(crate) fn ast_to_js<'env>(env: &'env Env, ast: &AstNode) -> napi::Result<Object<'env>> {
+  let mut object = Object::new(env)?;
+  match ast {
+    AstNode::Document {
+      content_type,
+      scripting_enabled,
+      children,
+    } => {
+      object.set("type", "document")?;
+      object.set("contentType", content_type.as_str())?;
+      object.set("scriptingEnabled", *scripting_enabled)?;
+      object.set("children", children_to_js(env, children)?)?;
+    }
+    AstNode::DocumentType {
+      name,
+      public_id,
+      system_id,
+    } => {
+      object.set("type", "documentType")?;
+      object.set("name", name.as_str())?;
+      object.set("publicId", public_id.as_str())?;
+      object.set("systemId", system_id.as_str())?;
+    }
+    AstNode::Element(element) => {
+      object.set("type", "element")?;
+      object.set("name", element.name.as_str())?;
+      set_optional(&mut object, "namespace", &element.namespace)?;
+      set_optional(&mut object, "prefix", &element.prefix)?;
+      let attributes = element
+        .attributes
+        .iter()
+        .map(|attr| {
+          let mut attribute = Object::new(env)?;
+          attribute.set("name", attr.name.as_str())?;
+          set_optional(&mut attribute, "namespace", &attr.namespace)?;
+          set_optional(&mut attribute, "prefix", &attr.prefix)?;
+          attribute.set("value", attr.value.as_str())?;
+          Ok(attribute)
+        })
+        .collect::<napi::Result<Vec<_>>>()?;
+      object.set("attributes", attributes)?;
+      object.set("children", children_to_js(env, &element.children)?)?;
+      if let Some(content) = &element.content {
+        object.set("content", children_to_js(env, content)?)?;
+      }
+      if let Some(root) = &element.shadow_root {
+        let mut shadow_root = Object::new(env)?;
+        set_shadow_root(env, &mut shadow_root, root)?;
+        object.set("shadowRoot", shadow_root)?;
+      }
+      if element.mathml_annotation_xml_integration_point {
+        object.set("mathmlAnnotationXmlIntegrationPoint", true)?;
+      }
+      if let Some(location) = &element.location {
+        let mut span = Object::new(env)?;
+        span.set("startOffset", location.start_offset as u32)?;
+        span.set("endOffset", location.end_offset as u32)?;
+        span.set("startLine", location.start_line as u32)?;
+        span.set("startColumn", location.start_column as u32)?;
+        span.set("endLine", location.end_line as u32)?;
+        span.set("endColumn", location.end_column as u32)?;
+        object.set("location", span)?;
+      }
+    }
+    AstNode::Text { value } => {
+      object.set("type", "text")?;
+      object.set("value", value.as_str())?;
+    }
+    AstNode::Comment { value } => {
+      object.set("type", "comment")?;
+      object.set("value", value.as_str())?;
+    }
+    AstNode::ProcessingInstruction { target, data } => {
+      object.set("type", "processingInstruction")?;
+      object.set("target", target.as_str())?;
+      object.set("data", data.as_str())?;
+    }
+    AstNode::DocumentFragment { children } => {
+      object.set("type", "documentFragment")?;
+      object.set("children", children_to_js(env, children)?)?;
+    }
+    AstNode::ShadowRoot(root) => {
+      object.set("type", "shadowRoot")?;
+      set_shadow_root(env, &mut object, root)?;
+    }
+  }
+  Ok(object)
+}
+
+pub(crate) fn required<T: napi::bindgen_prelude::FromNapiValue>(
+  object: &Object,
+  object_type: &str,
+  name: &str,
+) -> napi::Result<T> {
+  object.get(name)?.ok_or_else(|| {
+    napi::Error::new(
+      napi::Status::InvalidArg,
+      format!(
+        "A '{}' object requires the '{}' property.",
+        object_type, name
+      ),
+    )
+  })
+}
+
+fn children_from_js(object: &Object) -> napi::Result<Vec<AstNode>> {
+  object
+    .get::<Vec<Object>>("children")?
+    .unwrap_or_default()
+    .iter()
+    .map(ast_from_js)
+    .collect()
+}
+
+fn shadow_root_from_js(object: &Object) -> napi::Result<AstShadowRoot> {
+  Ok(AstShadowRoot {
+    mode: required(object, "shadowRoot", "mode")?,
+    delegates_focus: object.get("delegatesFocus")?.unwrap_or(false),
+    slot_assignment: object
+      .get("slotAssignment")?
+      .unwrap_or_else(|| "named".to_string()),
+    clonable: object.get("clonable")?.unwrap_or(false),
+    serializable: object.get("serializable")?.unwrap_or(false),
+    children: children_from_js(object)?,
+  })
+}
+
+/// Reads the plain object form of the JSON AST.
+pub(crate) fn ast_from_js(object: &Object) -> napi::Result<AstNode> {
+  let node_type: String = required(object, "node", "type")?;
+  let ast = match node_type.as_str() {
+    "document" => AstNode::Document {
+      content_type: required(object, &node_type, "contentType")?,
+      scripting_enabled: object.get("scriptingEnabled")?.unwrap_or(true),
+      children: children_from_js(object)?,
+    },
+    "documentType" => AstNode::DocumentType {
+      name: required(object, &node_type, "name")?,
+      public_id: object.get("publicId")?.unwrap_or_default(),
+      system_id: object.get("systemId")?.unwrap_or_default(),
+    },
+    "element" => AstNode::Element(AstElement {
+      name: required(object, &node_type, "name")?,
+      namespace: object.get("namespace")?,
+      prefix: object.get("prefix")?,
+      attributes: object
+        .get::<Vec<Object>>("attributes")?
+        .unwrap_or_default()
+        .iter()
+        .map(|attr| {
+          Ok(AstAttribute {
+            name: required(attr, "attribute", "name")?,
+            namespace: attr.get("namespace")?,
+            prefix: attr.get("prefix")?,
+            value: required(attr, "attribute", "value")?,
+          })
+        })
+        .collect::<napi::Result<Vec<_>>>()?,
+      children: children_from_js(object)?,
+      content: object
+        .get::<Vec<Object>>("content")?
+        .map(|content| content.iter().map(ast_from_js).collect())
+        .transpose()?,
+      shadow_root: object
+        .get::<Object>("shadowRoot")?
+        .map(|root| shadow_root_from_js(&root))
+        .transpose()?,
+      mathml_annotation_xml_integration_point: object
+        .get("mathmlAnnotationXmlIntegrationPoint")?
+        .unwrap_or(false),
+      // Locations describe the parsed input, which a rebuilt tree has none of.
+      location: None,
+    }),
+    "text" => AstNode::Text {
+      value: required(object, &node_type, "value")?,
+    },
+    "comment" => AstNode::Comment {
+      value: required(object, &node_type, "value")?,
+    },
+    "processingInstruction" => AstNode::ProcessingInstruction {
+      target: required(object, &node_type, "target")?,
+      data: object.get("data")?.unwrap_or_default(),
+    },
+    "documentFragment" => AstNode::DocumentFragment {
+      children: children_from_js(object)?,
+    },
+    "shadowRoot" => AstNode::ShadowRoot(shadow_root_from_js(object)?),
+    other => {
+      return Err(napi::Error::new(
+        napi::Status::InvalidArg,
+        format!("'{}' is not a valid node type.", other),
+      ))
+    }
+  };
+  Ok(ast)
+}
+
+/// Builds a new tree from the plain object form of the JSON AST.
+pub(crate) fn node_from_js(object: &Object) -> napi::Result<NodeRepr> {
+  DomNode::from_ast(&ast_from_js(object)?)
+    .map(NodeRepr)
+    .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+}
+
+#[napi]
+impl NodeRepr {
+  /// Returns the node and its descendants as a JSON AST of plain objects, built in one call.
+  #[napi(js_name = "toJSON", ts_return_type = "AstNode")]
+  pub fn to_json<'env>(&self, env: &'env Env) -> napi::Result<Object<'env>> {
+    ast_to_js(env, &self.0.to_ast())
+  }
+}
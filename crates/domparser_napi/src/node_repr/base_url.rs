@@ -0,0 +1,37 @@
+use domparser::url::Url;
+
+use super::NodeRepr;
+
+#[napi]
+impl NodeRepr {
+  /// Returns the URL of a document, "about:blank" if it was parsed without one, or null for other nodes.
+  #[napi(getter, js_name = "URL")]
+  pub fn url(&self) -> Option<String> {
+    self.0.url()
+  }
+
+  /// Returns the absolute base URL of the node, from the first `<base href>` of its document
+  /// or the document's URL.
+  #[napi(getter, js_name = "baseURI")]
+  pub fn base_uri(&self) -> String {
+    self.0.base_uri()
+  }
+
+  /// Rewrites relative URLs in URL attributes, `srcset`s and CSS `url()`s of this node and its
+  /// descendants to absolute ones, resolved against `base` or the node's base URL.
+  #[napi(js_name = "absolutizeURLs")]
+  pub fn absolutize_urls(&self, base: Option<String>) -> napi::Result<()> {
+    let base = base
+      .map(|base| {
+        Url::parse(&base).map_err(|e| {
+          napi::Error::new(
+            napi::Status::InvalidArg,
+            format!("Invalid base URL '{}': {}", base, e),
+          )
+        })
+      })
+      .transpose()?;
+    self.0.absolutize_urls(base.as_ref());
+    Ok(())
+  }
+}
@@ -0,0 +1,21 @@
+use super::NodeRepr;
+
+#[napi]
+impl NodeRepr {
+  /// Returns the form owner of a form control: the form its `form` attribute names, or its
+  /// nearest ancestor form.
+  #[napi(getter)]
+  pub fn form(&self) -> Option<NodeRepr> {
+    self.0.form().map(NodeRepr)
+  }
+
+  /// Returns the controls of a form element in tree order, including those associated with
+  /// it by their `form` attribute, but not image buttons.
+  #[napi(getter)]
+  pub fn elements(&self) -> Option<Vec<NodeRepr>> {
+    self
+      .0
+      .elements()
+      .map(|elements| elements.into_iter().map(NodeRepr).collect())
+  }
+}
@@ -0,0 +1,68 @@
+use super::NodeRepr;
+use std::collections::HashMap;
+
+/// A range of the input, with 0-based byte offsets and 1-based lines and columns.
+#[napi(object)]
+pub struct SourceSpan {
+  pub start_offset: u32,
+  pub end_offset: u32,
+  pub start_line: u32,
+  pub start_column: u32,
+  pub end_line: u32,
+  pub end_column: u32,
+}
+
+/// Where a node came from in the input, returned by `sourceLocation`.
+#[napi(object)]
+pub struct SourceLocation {
+  pub start_offset: u32,
+  pub end_offset: u32,
+  pub start_line: u32,
+  pub start_column: u32,
+  pub end_line: u32,
+  pub end_column: u32,
+  /// The start tag of an element.
+  pub start_tag: Option<SourceSpan>,
+  /// The end tag of an element, if it had one in the input.
+  pub end_tag: Option<SourceSpan>,
+  /// The attributes of an element's start tag by qualified name.
+  pub attrs: Option<HashMap<String, SourceSpan>>,
+}
+
+fn span_to_js(span: &domparser::node::SourceSpan) -> SourceSpan {
+  SourceSpan {
+    start_offset: span.start.offset as u32,
+    end_offset: span.end.offset as u32,
+    start_line: span.start.line as u32,
+    start_column: span.start.column as u32,
+    end_line: span.end.line as u32,
+    end_column: span.end.column as u32,
+  }
+}
+
+#[napi]
+impl NodeRepr {
+  /// Returns where the node came from in the input, if it was parsed with
+  /// `sourceLocations` enabled, or null.
+  #[napi(getter)]
+  pub fn source_location(&self) -> Option<SourceLocation> {
+    let location = self.0.source_location()?;
+    Some(SourceLocation {
+      start_offset: location.start.offset as u32,
+      end_offset: location.end.offset as u32,
+      start_line: location.start.line as u32,
+      start_column: location.start.column as u32,
+      end_line: location.end.line as u32,
+      end_column: location.end.column as u32,
+      start_tag: location.start_tag.as_ref().map(span_to_js),
+      end_tag: location.end_tag.as_ref().map(span_to_js),
+      attrs: location.start_tag.is_some().then(|| {
+        location
+          .attributes
+          .iter()
+          .map(|(name, span)| (name.clone(), span_to_js(span)))
+          .collect()
+      }),
+    })
+  }
+}
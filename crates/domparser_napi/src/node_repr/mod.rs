@@ -1,8 +1,17 @@
 use domparser::DomNode;
 
+pub(crate) mod ast;
+mod base_url;
+mod form;
+mod location;
 mod modify;
+pub(crate) mod patch;
 mod properties;
 mod query;
+mod reflect;
+mod serialize;
+mod shadow;
+mod style;
 
 #[napi]
 #[derive(Clone)]
@@ -1,5 +1,29 @@
 use super::NodeRepr;
 
+/// A namespaced attribute as returned by `createAttributeNS`.
+#[napi(object)]
+pub struct Attr {
+  #[napi(js_name = "namespaceURI")]
+  pub namespace_uri: Option<String>,
+  pub prefix: Option<String>,
+  pub local_name: String,
+  /// The qualified name.
+  pub name: String,
+  pub value: String,
+}
+
+impl From<domparser::node::Attr> for Attr {
+  fn from(attr: domparser::node::Attr) -> Self {
+    Attr {
+      name: attr.name(),
+      namespace_uri: attr.namespace_uri,
+      prefix: attr.prefix,
+      local_name: attr.local_name,
+      value: attr.value,
+    }
+  }
+}
+
 #[napi]
 impl NodeRepr {
   /// Inserts a set of Node objects or DOMString objects after the last child of the Element.
@@ -98,6 +122,49 @@ impl NodeRepr {
     NodeRepr(self.0.create_element(tag_name))
   }
 
+  /// Creates an element with the specified namespace URI and qualified name.
+  #[napi(js_name = "createElementNS")]
+  pub fn create_element_ns(
+    &self,
+    namespace: Option<String>,
+    qualified_name: String,
+  ) -> napi::Result<NodeRepr> {
+    self
+      .0
+      .create_element_ns(namespace, qualified_name)
+      .map(NodeRepr)
+      .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+  }
+
+  /// Creates an attribute with the specified namespace URI and qualified name, to be added with `setAttributeNodeNS`.
+  #[napi(js_name = "createAttributeNS")]
+  pub fn create_attribute_ns(
+    &self,
+    namespace: Option<String>,
+    qualified_name: String,
+  ) -> napi::Result<Attr> {
+    self
+      .0
+      .create_attribute_ns(namespace, qualified_name)
+      .map(Attr::from)
+      .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+  }
+
+  /// Adds an attribute to the element, returning the attribute with the same namespace and local name it replaces.
+  #[napi(js_name = "setAttributeNodeNS")]
+  pub fn set_attribute_node_ns(&self, attr: Attr) -> napi::Result<Option<Attr>> {
+    self
+      .0
+      .set_attribute_node_ns(domparser::node::Attr {
+        namespace_uri: attr.namespace_uri,
+        prefix: attr.prefix,
+        local_name: attr.local_name,
+        value: attr.value,
+      })
+      .map(|old| old.map(Attr::from))
+      .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+  }
+
   /// Creates a new Text node.
   #[napi(js_name = "createTextNode")]
   pub fn create_text_node(&self, data: String) -> NodeRepr {
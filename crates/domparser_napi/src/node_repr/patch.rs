@@ -0,0 +1,146 @@
+use domparser::patch::{Patch, PatchOperation};
+use napi::bindgen_prelude::{Env, Object};
+
+use super::ast::{ast_from_js, ast_to_js, required};
+
+fn path_to_js(path: &[usize]) -> Vec<u32> {
+  path.iter().map(|&index| index as u32).collect()
+}
+
+fn path_from_js(object: &Object, node_type: &str, name: &str) -> napi::Result<Vec<usize>> {
+  let path: Vec<u32> = required(object, node_type, name)?;
+  Ok(path.into_iter().map(|index| index as usize).collect())
+}
+
+fn operation_to_js<'env>(env: &'env Env, operation: &PatchOperation) -> napi::Result<Object<'env>> {
+  let mut object = Object::new(env)?;
+  match operation {
+    PatchOperation::Insert {
+      parent,
+      index,
+      node,
+    } => {
+      object.set("type", "insert")?;
+      object.set("parent", path_to_js(parent))?;
+      object.set("index", *index as u32)?;
+      object.set("node", ast_to_js(env, node)?)?;
+    }
+    PatchOperation::Remove { path } => {
+      object.set("type", "remove")?;
+      object.set("path", path_to_js(path))?;
+    }
+    PatchOperation::Move { parent, from, to } => {
+      object.set("type", "move")?;
+      object.set("parent", path_to_js(parent))?;
+      object.set("from", *from as u32)?;
+      object.set("to", *to as u32)?;
+    }
+    PatchOperation::Replace { path, node } => {
+      object.set("type", "replace")?;
+      object.set("path", path_to_js(path))?;
+      object.set("node", ast_to_js(env, node)?)?;
+    }
+    PatchOperation::SetAttribute {
+      path,
+      namespace,
+      name,
+      value,
+    } => {
+      object.set("type", "setAttribute")?;
+      object.set("path", path_to_js(path))?;
+      if let Some(namespace) = namespace {
+        object.set("namespace", namespace.as_str())?;
+      }
+      object.set("name", name.as_str())?;
+      object.set("value", value.as_str())?;
+    }
+    PatchOperation::RemoveAttribute {
+      path,
+      namespace,
+      name,
+    } => {
+      object.set("type", "removeAttribute")?;
+      object.set("path", path_to_js(path))?;
+      if let Some(namespace) = namespace {
+        object.set("namespace", namespace.as_str())?;
+      }
+      object.set("name", name.as_str())?;
+    }
+    PatchOperation::SetText { path, value } => {
+      object.set("type", "setText")?;
+      object.set("path", path_to_js(path))?;
+      object.set("value", value.as_str())?;
+    }
+  }
+  Ok(object)
+}
+
+/// Builds the plain object form of a patch, matching its JSON format.
+pub(crate) fn patch_to_js<'env>(env: &'env Env, patch: &Patch) -> napi::Result<Object<'env>> {
+  let operations = patch
+    .operations
+    .iter()
+    .map(|operation| operation_to_js(env, operation))
+    .collect::<napi::Result<Vec<_>>>()?;
+  let mut object = Object::new(env)?;
+  object.set("operations", operations)?;
+  Ok(object)
+}
+
+fn operation_from_js(object: &Object) -> napi::Result<PatchOperation> {
+  let operation_type: String = required(object, "operation", "type")?;
+  let ty = operation_type.as_str();
+  let node = |name| required::<Object>(object, ty, name).and_then(|node| ast_from_js(&node));
+  let operation = match ty {
+    "insert" => PatchOperation::Insert {
+      parent: path_from_js(object, ty, "parent")?,
+      index: required::<u32>(object, ty, "index")? as usize,
+      node: node("node")?,
+    },
+    "remove" => PatchOperation::Remove {
+      path: path_from_js(object, ty, "path")?,
+    },
+    "move" => PatchOperation::Move {
+      parent: path_from_js(object, ty, "parent")?,
+      from: required::<u32>(object, ty, "from")? as usize,
+      to: required::<u32>(object, ty, "to")? as usize,
+    },
+    "replace" => PatchOperation::Replace {
+      path: path_from_js(object, ty, "path")?,
+      node: node("node")?,
+    },
+    "setAttribute" => PatchOperation::SetAttribute {
+      path: path_from_js(object, ty, "path")?,
+      namespace: object.get("namespace")?,
+      name: required(object, ty, "name")?,
+      value: required(object, ty, "value")?,
+    },
+    "removeAttribute" => PatchOperation::RemoveAttribute {
+      path: path_from_js(object, ty, "path")?,
+      namespace: object.get("namespace")?,
+      name: required(object, ty, "name")?,
+    },
+    "setText" => PatchOperation::SetText {
+      path: path_from_js(object, ty, "path")?,
+      value: required(object, ty, "value")?,
+    },
+    other => {
+      return Err(napi::Error::new(
+        napi::Status::InvalidArg,
+        format!("'{}' is not a valid patch operation.", other),
+      ))
+    }
+  };
+  Ok(operation)
+}
+
+/// Reads the plain object form of a patch.
+pub(crate) fn patch_from_js(object: &Object) -> napi::Result<Patch> {
+  let operations: Vec<Object> = required(object, "patch", "operations")?;
+  Ok(Patch {
+    operations: operations
+      .iter()
+      .map(operation_from_js)
+      .collect::<napi::Result<_>>()?,
+  })
+}
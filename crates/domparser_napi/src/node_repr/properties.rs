@@ -177,6 +177,12 @@ impl NodeRepr {
     self.0.system_id()
   }
 
+  /// Returns the MIME type of the document.
+  #[napi(getter)]
+  pub fn content_type(&self) -> Option<String> {
+    self.0.content_type()
+  }
+
   /// Returns the Document Type Declaration (DTD) associated with current document.
   #[napi(getter)]
   pub fn doctype(&self) -> Option<NodeRepr> {
@@ -207,6 +213,33 @@ impl NodeRepr {
     self.0.set_text_content(text);
   }
 
+  /// Returns the text of the element as rendered, with line breaks between blocks and hidden content skipped.
+  #[napi(getter, js_name = "innerText")]
+  pub fn inner_text(&self) -> Option<String> {
+    self.0.inner_text()
+  }
+
+  /// Replaces the children of the element with the given text, converting line breaks to `<br>` elements.
+  #[napi(setter, js_name = "innerText")]
+  pub fn set_inner_text(&self, text: String) {
+    self.0.set_inner_text(text);
+  }
+
+  /// Returns the text of the element as rendered; same as `innerText`.
+  #[napi(getter, js_name = "outerText")]
+  pub fn outer_text(&self) -> Option<String> {
+    self.0.outer_text()
+  }
+
+  /// Replaces the element with the given text, converting line breaks to `<br>` elements.
+  #[napi(setter, js_name = "outerText")]
+  pub fn set_outer_text(&self, text: String) -> napi::Result<()> {
+    self
+      .0
+      .set_outer_text(text)
+      .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+  }
+
   /// Returns a boolean value indicating whether the two nodes are the same (that is, they reference the same object).
   #[napi(js_name = "isSameNode")]
   pub fn is_same_node(&self, other_node: &NodeRepr) -> bool {
@@ -249,6 +282,12 @@ impl NodeRepr {
     self.0.owner_document().map(NodeRepr)
   }
 
+  /// Returns the contents of a template element as a DocumentFragment.
+  #[napi(getter)]
+  pub fn content(&self) -> Option<NodeRepr> {
+    self.0.content().map(NodeRepr)
+  }
+
   /// Returns a string representation of the object.
   #[napi(js_name = "toString")]
   pub fn to_string_js(&self) -> String {
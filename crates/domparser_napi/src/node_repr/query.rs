@@ -1,5 +1,18 @@
 use super::NodeRepr;
 
+/// The first place where two trees are not equal, returned by `diff`.
+#[napi(object)]
+pub struct NodeDifference {
+  /// Child indices leading from the compared nodes down to the nodes that differ.
+  pub path: Vec<u32>,
+  /// The property that differs, such as "localName", "attributes", "data" or "childNodes.length".
+  pub property: String,
+  /// The value on this node.
+  pub value: Option<String>,
+  /// The value on the other node.
+  pub other_value: Option<String>,
+}
+
 #[napi]
 impl NodeRepr {
   /// Returns the value of a specified attribute on the element.
@@ -95,6 +108,21 @@ impl NodeRepr {
       .collect()
   }
 
+  /// Returns an HTMLCollection of elements with the given local name and namespace.
+  #[napi(js_name = "getElementsByTagNameNS")]
+  pub fn get_elements_by_tag_name_ns(
+    &self,
+    namespace: Option<String>,
+    local_name: String,
+  ) -> Vec<NodeRepr> {
+    self
+      .0
+      .get_elements_by_tag_name_ns(namespace, local_name)
+      .into_iter()
+      .map(NodeRepr)
+      .collect()
+  }
+
   /// Returns a boolean value indicating whether a node is a descendant of a given node, that is the node itself, one of its direct children (childNodes), one of the children's direct children, and so on.
   #[napi]
   pub fn contains(&self, other_node: &NodeRepr) -> bool {
@@ -103,8 +131,23 @@ impl NodeRepr {
 
   /// Returns a boolean value indicating whether the node is equal to the specified node.
   #[napi(js_name = "isEqualNode")]
-  pub fn is_equal_node(&self, other_node: &NodeRepr) -> bool {
-    self.0.is_equal_node(&other_node.0)
+  pub fn is_equal_node(&self, other_node: Option<&NodeRepr>) -> bool {
+    other_node.is_some_and(|other| self.0.is_equal_node(&other.0))
+  }
+
+  /// Returns the first difference that makes `isEqualNode` false, or null if the nodes are equal.
+  #[napi]
+  pub fn diff(&self, other_node: &NodeRepr) -> Option<NodeDifference> {
+    self.0.diff(&other_node.0).map(|difference| NodeDifference {
+      path: difference
+        .path
+        .into_iter()
+        .map(|index| index as u32)
+        .collect(),
+      property: difference.property.to_string(),
+      value: difference.value,
+      other_value: difference.other_value,
+    })
   }
 
   /// Returns the head element of the document.
@@ -119,12 +162,18 @@ impl NodeRepr {
     self.0.body().map(NodeRepr)
   }
 
-  /// Returns the title of the document.
+  /// Returns the title of the document, or the title attribute of an element.
   #[napi(getter)]
   pub fn title(&self) -> String {
     self.0.title()
   }
 
+  /// Sets the title of the document, or the title attribute of an element.
+  #[napi(setter)]
+  pub fn set_title(&self, title: String) {
+    self.0.set_title(title);
+  }
+
   /// Returns the Element that is the root element of the document (for example, the <html> element for HTML documents).
   #[napi(getter)]
   pub fn document_element(&self) -> Option<NodeRepr> {
@@ -0,0 +1,191 @@
+use super::NodeRepr;
+
+#[napi]
+impl NodeRepr {
+  /// Returns or sets the URL of an `a`, `area`, `base` or `link` element.
+  #[napi(getter)]
+  pub fn href(&self) -> Option<String> {
+    self.0.href()
+  }
+
+  #[napi(setter)]
+  pub fn set_href(&self, value: String) {
+    self.0.set_href(value);
+  }
+
+  /// Returns or sets the URL a `form` element submits to.
+  #[napi(getter)]
+  pub fn action(&self) -> Option<String> {
+    self.0.action()
+  }
+
+  #[napi(setter)]
+  pub fn set_action(&self, value: String) {
+    self.0.set_action(value);
+  }
+
+  /// Returns or sets the HTTP method a `form` element submits with: "get", "post" or "dialog".
+  #[napi(getter)]
+  pub fn method(&self) -> Option<String> {
+    self.0.method()
+  }
+
+  #[napi(setter)]
+  pub fn set_method(&self, value: String) {
+    self.0.set_method(value);
+  }
+
+  /// Returns or sets the encoding a `form` element submits its entries with.
+  #[napi(getter)]
+  pub fn enctype(&self) -> Option<String> {
+    self.0.enctype()
+  }
+
+  #[napi(setter)]
+  pub fn set_enctype(&self, value: String) {
+    self.0.set_enctype(value);
+  }
+
+  /// Returns or sets the URL a submit button overrides its form's action with.
+  #[napi(getter, js_name = "formAction")]
+  pub fn form_action(&self) -> Option<String> {
+    self.0.form_action()
+  }
+
+  #[napi(setter, js_name = "formAction")]
+  pub fn set_form_action(&self, value: String) {
+    self.0.set_form_action(value);
+  }
+
+  /// Returns or sets the method a submit button overrides its form's method with.
+  #[napi(getter, js_name = "formMethod")]
+  pub fn form_method(&self) -> Option<String> {
+    self.0.form_method()
+  }
+
+  #[napi(setter, js_name = "formMethod")]
+  pub fn set_form_method(&self, value: String) {
+    self.0.set_form_method(value);
+  }
+
+  /// Returns or sets the encoding a submit button overrides its form's enctype with.
+  #[napi(getter, js_name = "formEnctype")]
+  pub fn form_enctype(&self) -> Option<String> {
+    self.0.form_enctype()
+  }
+
+  #[napi(setter, js_name = "formEnctype")]
+  pub fn set_form_enctype(&self, value: String) {
+    self.0.set_form_enctype(value);
+  }
+
+  /// Returns or sets the URL of an embedding element such as `img`, `script` or `iframe`.
+  #[napi(getter)]
+  pub fn src(&self) -> Option<String> {
+    self.0.src()
+  }
+
+  #[napi(setter)]
+  pub fn set_src(&self, value: String) {
+    self.0.set_src(value);
+  }
+
+  /// Returns or sets whether the element is hidden.
+  #[napi(getter)]
+  pub fn hidden(&self) -> Option<bool> {
+    self.0.hidden()
+  }
+
+  #[napi(setter)]
+  pub fn set_hidden(&self, value: bool) {
+    self.0.set_hidden(value);
+  }
+
+  /// Returns or sets the language of the element.
+  #[napi(getter)]
+  pub fn lang(&self) -> Option<String> {
+    self.0.lang()
+  }
+
+  #[napi(setter)]
+  pub fn set_lang(&self, value: String) {
+    self.0.set_lang(value);
+  }
+
+  /// Returns or sets the text direction of the element: "ltr", "rtl", "auto" or "".
+  #[napi(getter)]
+  pub fn dir(&self) -> Option<String> {
+    self.0.dir()
+  }
+
+  #[napi(setter)]
+  pub fn set_dir(&self, value: String) {
+    self.0.set_dir(value);
+  }
+
+  /// Returns or sets the tab order of the element.
+  #[napi(getter, js_name = "tabIndex")]
+  pub fn tab_index(&self) -> Option<i32> {
+    self.0.tab_index()
+  }
+
+  #[napi(setter, js_name = "tabIndex")]
+  pub fn set_tab_index(&self, value: i32) {
+    self.0.set_tab_index(value);
+  }
+
+  /// Returns or sets whether an input element is checked.
+  #[napi(getter)]
+  pub fn checked(&self) -> Option<bool> {
+    self.0.checked()
+  }
+
+  #[napi(setter)]
+  pub fn set_checked(&self, value: bool) {
+    self.0.set_checked(value);
+  }
+
+  /// Returns or sets the value of a form control.
+  #[napi(getter)]
+  pub fn value(&self) -> Option<String> {
+    self.0.value()
+  }
+
+  #[napi(setter)]
+  pub fn set_value(&self, value: String) {
+    self.0.set_value(value);
+  }
+
+  /// Returns or sets the default value of an input or textarea element.
+  #[napi(getter, js_name = "defaultValue")]
+  pub fn default_value(&self) -> Option<String> {
+    self.0.default_value()
+  }
+
+  #[napi(setter, js_name = "defaultValue")]
+  pub fn set_default_value(&self, value: String) {
+    self.0.set_default_value(value);
+  }
+
+  /// Returns or sets the index of the first selected option of a select element.
+  #[napi(getter, js_name = "selectedIndex")]
+  pub fn selected_index(&self) -> Option<i32> {
+    self.0.selected_index()
+  }
+
+  #[napi(setter, js_name = "selectedIndex")]
+  pub fn set_selected_index(&self, value: i32) {
+    self.0.set_selected_index(value);
+  }
+
+  /// Returns or sets whether an option element is selected.
+  #[napi(getter)]
+  pub fn selected(&self) -> Option<bool> {
+    self.0.selected()
+  }
+
+  #[napi(setter)]
+  pub fn set_selected(&self, value: bool) {
+    self.0.set_selected(value);
+  }
+}
@@ -0,0 +1,225 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use domparser::markdown::{
+  CodeBlockStyle, HeadingStyle, MarkdownOptions as CoreMarkdownOptions, MarkdownRule,
+};
+use domparser::serializer::{AttributeQuote, SerializeOptions as CoreSerializeOptions};
+use napi::bindgen_prelude::{Buffer, FnArgs, Function};
+
+use super::NodeRepr;
+
+/// Options for `serialize`.
+#[napi(object)]
+pub struct SerializeOptions {
+  /// Put block-level elements on their own lines. Defaults to false.
+  pub pretty: Option<bool>,
+  /// The indentation used when pretty-printing. Defaults to two spaces.
+  pub indent: Option<String>,
+  /// Collapse whitespace, remove comments and omit optional tags. Defaults to false.
+  pub minify: Option<bool>,
+  /// "double", "single" or "minimal". Defaults to "minimal" when minifying and "double" otherwise.
+  pub quote: Option<String>,
+  /// Write attributes in alphabetical order. Defaults to false.
+  pub sort_attributes: Option<bool>,
+  /// Write void elements as `<br />`. Defaults to false.
+  pub self_closing_void: Option<bool>,
+}
+
+/// Options for `toMarkdown`.
+#[napi(object)]
+pub struct MarkdownOptions {
+  /// "atx" or "setext". Defaults to "atx".
+  pub heading_style: Option<String>,
+  /// "-", "*" or "+". Defaults to "-".
+  pub bullet_list_marker: Option<String>,
+  /// "fenced" or "indented". Defaults to "fenced".
+  pub code_block_style: Option<String>,
+  /// "```" or "~~~". Defaults to "```".
+  pub fence: Option<String>,
+  /// "_" or "*". Defaults to "_".
+  pub em_delimiter: Option<String>,
+  /// "**" or "__". Defaults to "**".
+  pub strong_delimiter: Option<String>,
+  /// Write GFM tables, strikethrough and task lists. Defaults to true.
+  pub gfm: Option<bool>,
+  /// Per-tag rules: "remove", "keep", "content" or "custom".
+  pub rules: Option<HashMap<String, String>>,
+}
+
+/// Called with an element and its converted contents for tags with a "custom" rule.
+type MarkdownHandler<'scope> = Function<'scope, FnArgs<(NodeRepr, String)>, Option<String>>;
+
+fn invalid_option(name: &str, value: &str, expected: &str) -> napi::Error {
+  napi::Error::new(
+    napi::Status::InvalidArg,
+    format!(
+      "'{}' is not a valid {}, expected {}.",
+      value, name, expected
+    ),
+  )
+}
+
+fn markdown_options(options: MarkdownOptions) -> napi::Result<CoreMarkdownOptions> {
+  let heading_style = match options.heading_style.as_deref() {
+    None | Some("atx") => HeadingStyle::Atx,
+    Some("setext") => HeadingStyle::Setext,
+    Some(other) => return Err(invalid_option("heading style", other, "'atx' or 'setext'")),
+  };
+  let bullet_list_marker = match options.bullet_list_marker.as_deref() {
+    None => '-',
+    Some(marker @ ("-" | "*" | "+")) => marker.chars().next().unwrap(),
+    Some(other) => {
+      return Err(invalid_option(
+        "bullet list marker",
+        other,
+        "'-', '*' or '+'",
+      ))
+    }
+  };
+  let code_block_style = match options.code_block_style.as_deref() {
+    None | Some("fenced") => CodeBlockStyle::Fenced,
+    Some("indented") => CodeBlockStyle::Indented,
+    Some(other) => {
+      return Err(invalid_option(
+        "code block style",
+        other,
+        "'fenced' or 'indented'",
+      ))
+    }
+  };
+  let fence = match options.fence.as_deref() {
+    None | Some("```") => '`',
+    Some("~~~") => '~',
+    Some(other) => return Err(invalid_option("fence", other, "'```' or '~~~'")),
+  };
+  let em_delimiter = match options.em_delimiter.as_deref() {
+    None | Some("_") => '_',
+    Some("*") => '*',
+    Some(other) => return Err(invalid_option("emphasis delimiter", other, "'_' or '*'")),
+  };
+  let strong_delimiter = match options.strong_delimiter.as_deref() {
+    None | Some("**") => '*',
+    Some("__") => '_',
+    Some(other) => return Err(invalid_option("strong delimiter", other, "'**' or '__'")),
+  };
+  let gfm = options.gfm.unwrap_or(true);
+  let mut rules = HashMap::new();
+  for (tag, rule) in options.rules.unwrap_or_default() {
+    let rule = match rule.as_str() {
+      "remove" => MarkdownRule::Remove,
+      "keep" => MarkdownRule::Keep,
+      "content" => MarkdownRule::Content,
+      "custom" => MarkdownRule::Custom,
+      other => {
+        return Err(invalid_option(
+          "markdown rule",
+          other,
+          "'remove', 'keep', 'content' or 'custom'",
+        ))
+      }
+    };
+    rules.insert(tag.to_ascii_lowercase(), rule);
+  }
+  Ok(CoreMarkdownOptions {
+    heading_style,
+    bullet_list_marker,
+    code_block_style,
+    fence,
+    em_delimiter,
+    strong_delimiter,
+    gfm,
+    rules,
+  })
+}
+
+#[napi]
+impl NodeRepr {
+  /// Returns the HTML serialization of the node, formatted according to the options.
+  #[napi]
+  pub fn serialize(&self, options: Option<SerializeOptions>) -> napi::Result<String> {
+    let mut core_options = CoreSerializeOptions::default();
+    if let Some(options) = options {
+      if options.pretty == Some(true) && options.minify == Some(true) {
+        return Err(napi::Error::new(
+          napi::Status::InvalidArg,
+          "The pretty and minify options cannot be combined.",
+        ));
+      }
+      core_options.pretty = options.pretty.unwrap_or(false);
+      core_options.minify = options.minify.unwrap_or(false);
+      if let Some(indent) = options.indent {
+        core_options.indent = indent;
+      }
+      core_options.attribute_quote = match options.quote.as_deref() {
+        None => None,
+        Some("double") => Some(AttributeQuote::Double),
+        Some("single") => Some(AttributeQuote::Single),
+        Some("minimal") => Some(AttributeQuote::Minimal),
+        Some(other) => {
+          return Err(napi::Error::new(
+            napi::Status::InvalidArg,
+            format!(
+              "'{}' is not a valid quote style, expected 'double', 'single' or 'minimal'.",
+              other
+            ),
+          ))
+        }
+      };
+      core_options.sort_attributes = options.sort_attributes.unwrap_or(false);
+      core_options.self_closing_void = options.self_closing_void.unwrap_or(false);
+    }
+    Ok(self.0.serialize(&core_options))
+  }
+
+  /// Converts the node and its descendants to Markdown.
+  /// Elements with a "custom" rule are passed to `handler` with their converted contents;
+  /// returning null or undefined keeps the default conversion.
+  #[napi(js_name = "_toMarkdown")]
+  pub fn to_markdown(
+    &self,
+    options: Option<MarkdownOptions>,
+    handler: Option<MarkdownHandler>,
+  ) -> napi::Result<String> {
+    let options = match options {
+      Some(options) => markdown_options(options)?,
+      None => CoreMarkdownOptions::default(),
+    };
+    let Some(handler) = handler else {
+      return Ok(self.0.to_markdown(&options));
+    };
+    let error = RefCell::new(None);
+    let markdown = self.0.to_markdown_with(&options, &|node, content| {
+      if error.borrow().is_some() {
+        return None;
+      }
+      match handler.call((NodeRepr(node.clone()), content.to_string()).into()) {
+        Ok(markdown) => markdown,
+        Err(e) => {
+          *error.borrow_mut() = Some(e);
+          None
+        }
+      }
+    });
+    match error.into_inner() {
+      Some(e) => Err(e),
+      None => Ok(markdown),
+    }
+  }
+
+  /// Returns the XML serialization of the node.
+  /// With `requireWellFormed`, throws if the node cannot be written as well-formed XML.
+  #[napi(js_name = "_serializeToXml")]
+  pub fn serialize_to_xml(&self, require_well_formed: Option<bool>) -> napi::Result<String> {
+    self
+      .0
+      .serialize_to_xml(require_well_formed.unwrap_or(false))
+      .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+  }
+
+  /// Returns the node and its descendants in the binary snapshot format, loadable with `fromSnapshot`.
+  #[napi(js_name = "toSnapshot")]
+  pub fn to_snapshot(&self) -> Buffer {
+    self.0.to_snapshot().into()
+  }
+}
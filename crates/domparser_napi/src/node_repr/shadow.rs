@@ -0,0 +1,142 @@
+use napi::bindgen_prelude::ClassInstance;
+
+use super::NodeRepr;
+use crate::sanitizer::Sanitizer;
+
+/// Options for `attachShadow`.
+#[napi(object)]
+pub struct ShadowRootInit {
+  pub mode: String,
+  pub delegates_focus: Option<bool>,
+  pub slot_assignment: Option<String>,
+  pub clonable: Option<bool>,
+  pub serializable: Option<bool>,
+}
+
+#[napi]
+impl NodeRepr {
+  /// Attaches a shadow DOM tree to the element and returns its ShadowRoot.
+  #[napi(js_name = "attachShadow")]
+  pub fn attach_shadow(&self, init: ShadowRootInit) -> napi::Result<NodeRepr> {
+    self
+      .0
+      .attach_shadow(domparser::node::ShadowRootInit {
+        mode: init.mode,
+        delegates_focus: init.delegates_focus.unwrap_or(false),
+        slot_assignment: init.slot_assignment,
+        clonable: init.clonable.unwrap_or(false),
+        serializable: init.serializable.unwrap_or(false),
+      })
+      .map(NodeRepr)
+      .map_err(|e| napi::Error::new(napi::Status::InvalidArg, e))
+  }
+
+  /// Returns the open shadow root attached to the element, or null.
+  #[napi(getter, js_name = "shadowRoot")]
+  pub fn shadow_root(&self) -> Option<NodeRepr> {
+    self.0.shadow_root().map(NodeRepr)
+  }
+
+  /// Returns the host element of a ShadowRoot.
+  #[napi(getter)]
+  pub fn host(&self) -> Option<NodeRepr> {
+    self.0.host().map(NodeRepr)
+  }
+
+  /// Returns the mode of a ShadowRoot, either "open" or "closed".
+  #[napi(getter)]
+  pub fn mode(&self) -> Option<String> {
+    self.0.mode()
+  }
+
+  /// Returns the slot assignment mode of a ShadowRoot, either "named" or "manual".
+  #[napi(getter, js_name = "slotAssignment")]
+  pub fn slot_assignment(&self) -> Option<String> {
+    self.0.slot_assignment()
+  }
+
+  /// Returns whether a ShadowRoot delegates focus.
+  #[napi(getter, js_name = "delegatesFocus")]
+  pub fn delegates_focus(&self) -> bool {
+    self.0.delegates_focus()
+  }
+
+  /// Returns whether a ShadowRoot is cloned along with its host.
+  #[napi(getter)]
+  pub fn clonable(&self) -> bool {
+    self.0.clonable()
+  }
+
+  /// Returns whether a ShadowRoot is serialized by getHTML with `serializableShadowRoots`.
+  #[napi(getter)]
+  pub fn serializable(&self) -> bool {
+    self.0.serializable()
+  }
+
+  /// Returns the nodes assigned to a slot element.
+  #[napi(js_name = "_assignedNodes")]
+  pub fn assigned_nodes(&self, flatten: Option<bool>) -> Vec<NodeRepr> {
+    self
+      .0
+      .assigned_nodes(flatten)
+      .into_iter()
+      .map(NodeRepr)
+      .collect()
+  }
+
+  /// Returns the elements assigned to a slot element.
+  #[napi(js_name = "_assignedElements")]
+  pub fn assigned_elements(&self, flatten: Option<bool>) -> Vec<NodeRepr> {
+    self
+      .0
+      .assigned_elements(flatten)
+      .into_iter()
+      .map(NodeRepr)
+      .collect()
+  }
+
+  /// Returns the slot element the node is assigned to.
+  #[napi(getter, js_name = "assignedSlot")]
+  pub fn assigned_slot(&self) -> Option<NodeRepr> {
+    self.0.assigned_slot().map(NodeRepr)
+  }
+
+  /// Parses the html, including declarative shadow roots, and replaces the children with the result.
+  #[napi(js_name = "setHTMLUnsafe")]
+  pub fn set_html_unsafe(&self, html: String) {
+    self.0.set_html_unsafe(html)
+  }
+
+  /// Parses the html, removes what the sanitizer (or the default one) does not allow,
+  /// and replaces the children with the result.
+  #[napi(js_name = "_setHTML")]
+  pub fn set_html(&self, html: String, sanitizer: Option<&Sanitizer>) {
+    self
+      .0
+      .set_html_safe(html, sanitizer.map(|sanitizer| &sanitizer.0))
+  }
+
+  /// Returns the HTML serialization of the children, optionally including shadow roots.
+  #[napi(js_name = "_getHTML")]
+  pub fn get_html(
+    &self,
+    serializable_shadow_roots: Option<bool>,
+    shadow_roots: Option<Vec<ClassInstance<'_, NodeRepr>>>,
+  ) -> String {
+    self.0.get_html(
+      serializable_shadow_roots.unwrap_or(false),
+      shadow_roots
+        .unwrap_or_default()
+        .iter()
+        .map(|root| root.0.clone())
+        .collect(),
+    )
+  }
+
+  /// Returns whether the element matches the selectors, evaluated inside the shadow tree of `host`,
+  /// so that `:host`, `::slotted()` and `::part()` can match.
+  #[napi(js_name = "matchesInShadowTree")]
+  pub fn matches_in_shadow_tree(&self, selectors: String, host: &NodeRepr) -> bool {
+    self.0.matches_in_shadow_tree(selectors, &host.0)
+  }
+}
@@ -0,0 +1,90 @@
+use super::NodeRepr;
+use domparser::css::{CssStyleDeclaration, StyleResolver, VisibilityOptions};
+
+/// Options for `checkVisibility`, as in the DOM.
+#[napi(object)]
+pub struct CheckVisibilityOptions {
+  /// Also return false for elements with an `opacity` of 0, or inside one.
+  pub check_opacity: Option<bool>,
+  /// Also return false for elements whose `visibility` is not `visible`.
+  #[napi(js_name = "checkVisibilityCSS")]
+  pub check_visibility_css: Option<bool>,
+  /// An alias of `checkOpacity`.
+  pub opacity_property: Option<bool>,
+  /// An alias of `checkVisibilityCSS`.
+  pub visibility_property: Option<bool>,
+}
+
+impl From<CheckVisibilityOptions> for VisibilityOptions {
+  fn from(options: CheckVisibilityOptions) -> Self {
+    VisibilityOptions {
+      check_opacity: options.check_opacity.or(options.opacity_property) == Some(true),
+      check_visibility_css: options.check_visibility_css.or(options.visibility_property)
+        == Some(true),
+    }
+  }
+}
+
+impl NodeRepr {
+  /// Applies `change` to the parsed `style` attribute and writes it back if it changed.
+  fn update_style<T>(&self, change: impl FnOnce(&mut CssStyleDeclaration) -> T) -> T {
+    let mut style = self.0.style();
+    let before = style.clone();
+    let result = change(&mut style);
+    if style != before {
+      self.0.set_style(&style);
+    }
+    result
+  }
+}
+
+#[napi]
+impl NodeRepr {
+  #[napi(js_name = "_styleGetPropertyValue")]
+  pub fn _style_get_property_value(&self, name: String) -> String {
+    self.0.style().get_property_value(&name)
+  }
+
+  #[napi(js_name = "_styleGetPropertyPriority")]
+  pub fn _style_get_property_priority(&self, name: String) -> String {
+    self.0.style().get_property_priority(&name)
+  }
+
+  #[napi(js_name = "_styleSetProperty")]
+  pub fn _style_set_property(&self, name: String, value: String, priority: String) {
+    self.update_style(|style| style.set_property(&name, &value, &priority));
+  }
+
+  #[napi(js_name = "_styleRemoveProperty")]
+  pub fn _style_remove_property(&self, name: String) -> String {
+    self.update_style(|style| style.remove_property(&name))
+  }
+
+  #[napi(js_name = "_styleGetCssText")]
+  pub fn _style_get_css_text(&self) -> String {
+    self.0.style().css_text()
+  }
+
+  #[napi(js_name = "_styleSetCssText")]
+  pub fn _style_set_css_text(&self, css_text: String) {
+    self.0.set_style(&CssStyleDeclaration::parse(&css_text));
+  }
+
+  #[napi(js_name = "_styleProperties")]
+  pub fn _style_properties(&self) -> Vec<String> {
+    let style = self.0.style();
+    style
+      .declarations()
+      .iter()
+      .map(|declaration| declaration.name.clone())
+      .collect()
+  }
+
+  /// Returns whether the element is rendered, from the cascade of the default stylesheet, the
+  /// `<style>` elements of its tree and its `style` attribute.
+  #[napi(js_name = "checkVisibility")]
+  pub fn check_visibility(&self, options: Option<CheckVisibilityOptions>) -> bool {
+    let options = options.map(Into::into).unwrap_or_default();
+    StyleResolver::new().check_visibility(&self.0, &options)
+  }
+}
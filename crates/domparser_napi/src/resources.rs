@@ -0,0 +1,63 @@
+use domparser::resources::{Resource as CoreResource, SrcsetCandidate as CoreSrcsetCandidate};
+
+use crate::node_repr::NodeRepr;
+
+/// One candidate of a `srcset` attribute.
+#[napi(object, object_from_js = false)]
+pub struct SrcsetCandidate {
+  pub url: String,
+  pub resolved_url: Option<String>,
+  /// The width or density descriptor, such as "480w" or "2x".
+  pub descriptor: Option<String>,
+}
+
+/// A URL referenced by an element, returned by `extractResources`.
+#[napi(object, object_from_js = false)]
+pub struct Resource {
+  /// "hyperlink", "stylesheet", "link", "script", "image", "media", "frame", "embed", "form" or "css".
+  #[napi(
+    ts_type = "'hyperlink' | 'stylesheet' | 'link' | 'script' | 'image' | 'media' | 'frame' | 'embed' | 'form' | 'css'"
+  )]
+  pub kind: String,
+  pub element: NodeRepr,
+  /// The attribute holding the URL, or undefined for the text of a `<style>` element.
+  pub attribute: Option<String>,
+  /// The URL as written; the whole attribute value for `srcset` and `imagesrcset`.
+  pub url: String,
+  /// The URL resolved against the base URL, if it can be resolved. Undefined for `srcset`s.
+  pub resolved_url: Option<String>,
+  pub rel: Option<String>,
+  pub srcset: Option<Vec<SrcsetCandidate>>,
+  pub integrity: Option<String>,
+  pub crossorigin: Option<String>,
+  pub media: Option<String>,
+}
+
+impl From<CoreSrcsetCandidate> for SrcsetCandidate {
+  fn from(candidate: CoreSrcsetCandidate) -> Self {
+    SrcsetCandidate {
+      url: candidate.url,
+      resolved_url: candidate.resolved_url,
+      descriptor: candidate.descriptor,
+    }
+  }
+}
+
+impl From<CoreResource> for Resource {
+  fn from(resource: CoreResource) -> Self {
+    Resource {
+      kind: resource.kind.as_str().to_string(),
+      element: NodeRepr(resource.element),
+      attribute: resource.attribute,
+      url: resource.url,
+      resolved_url: resource.resolved_url,
+      rel: resource.rel,
+      srcset: resource
+        .srcset
+        .map(|srcset| srcset.into_iter().map(SrcsetCandidate::from).collect()),
+      integrity: resource.integrity,
+      crossorigin: resource.crossorigin,
+      media: resource.media,
+    }
+  }
+}
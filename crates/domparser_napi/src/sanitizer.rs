@@ -0,0 +1,130 @@
+use domparser::sanitizer::{
+  Sanitizer as CoreSanitizer, SanitizerConfig as CoreSanitizerConfig,
+  SanitizerElement as CoreSanitizerElement, SanitizerName as CoreSanitizerName,
+};
+use napi::bindgen_prelude::Either;
+
+use crate::node_repr::NodeRepr;
+
+const HTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// An element or attribute name with its namespace.
+#[napi(object)]
+pub struct SanitizerName {
+  pub name: String,
+  /// Defaults to the HTML namespace for elements and to no namespace for attributes.
+  pub namespace: Option<String>,
+}
+
+/// An allowed element with the attributes allowed or removed on it.
+#[napi(object)]
+pub struct SanitizerElement {
+  pub name: String,
+  pub namespace: Option<String>,
+  pub attributes: Option<Vec<Either<String, SanitizerName>>>,
+  pub remove_attributes: Option<Vec<Either<String, SanitizerName>>>,
+}
+
+/// Options for `new Sanitizer`. Lists that are left out allow everything not removed.
+#[napi(object)]
+pub struct SanitizerConfig {
+  /// Elements to keep; others are removed with their contents.
+  pub elements: Option<Vec<Either<String, SanitizerElement>>>,
+  /// Elements removed with their contents.
+  pub remove_elements: Option<Vec<Either<String, SanitizerName>>>,
+  /// Elements replaced by their children.
+  pub replace_with_children_elements: Option<Vec<Either<String, SanitizerName>>>,
+  /// Attributes allowed on every element.
+  pub attributes: Option<Vec<Either<String, SanitizerName>>>,
+  /// Attributes removed from every element.
+  pub remove_attributes: Option<Vec<Either<String, SanitizerName>>>,
+  /// Keep comments. Defaults to false.
+  pub comments: Option<bool>,
+  /// Keep `data-*` attributes. Defaults to true.
+  pub data_attributes: Option<bool>,
+  /// URL schemes allowed in `href`, `src`, `srcset`, `action` and similar attributes.
+  /// Defaults to "http", "https", "mailto" and "tel".
+  pub url_schemes: Option<Vec<String>>,
+}
+
+fn name_from_js(name: Either<String, SanitizerName>, default_namespace: &str) -> CoreSanitizerName {
+  match name {
+    Either::A(name) => CoreSanitizerName::new(default_namespace, &name),
+    Either::B(SanitizerName { name, namespace }) => {
+      CoreSanitizerName::new(namespace.as_deref().unwrap_or(default_namespace), &name)
+    }
+  }
+}
+
+fn names_from_js(
+  names: Option<Vec<Either<String, SanitizerName>>>,
+  default_namespace: &str,
+) -> Option<Vec<CoreSanitizerName>> {
+  names.map(|names| {
+    names
+      .into_iter()
+      .map(|name| name_from_js(name, default_namespace))
+      .collect()
+  })
+}
+
+fn element_from_js(element: Either<String, SanitizerElement>) -> CoreSanitizerElement {
+  match element {
+    Either::A(name) => CoreSanitizerName::element(&name).into(),
+    Either::B(element) => CoreSanitizerElement {
+      name: CoreSanitizerName::new(
+        element.namespace.as_deref().unwrap_or(HTML_NAMESPACE),
+        &element.name,
+      ),
+      attributes: names_from_js(element.attributes, ""),
+      remove_attributes: names_from_js(element.remove_attributes, "").unwrap_or_default(),
+    },
+  }
+}
+
+fn config_from_js(config: SanitizerConfig) -> CoreSanitizerConfig {
+  CoreSanitizerConfig {
+    elements: config
+      .elements
+      .map(|elements| elements.into_iter().map(element_from_js).collect()),
+    remove_elements: names_from_js(config.remove_elements, HTML_NAMESPACE).unwrap_or_default(),
+    replace_with_children_elements: names_from_js(
+      config.replace_with_children_elements,
+      HTML_NAMESPACE,
+    )
+    .unwrap_or_default(),
+    attributes: names_from_js(config.attributes, ""),
+    remove_attributes: names_from_js(config.remove_attributes, "").unwrap_or_default(),
+    comments: config.comments.unwrap_or(false),
+    data_attributes: config.data_attributes.unwrap_or(true),
+    url_schemes: match config.url_schemes {
+      Some(schemes) => schemes
+        .iter()
+        .map(|scheme| scheme.trim_end_matches(':').to_ascii_lowercase())
+        .collect(),
+      None => CoreSanitizerConfig::default().url_schemes,
+    },
+  }
+}
+
+/// Removes disallowed elements and attributes from DOM trees.
+#[napi]
+pub struct Sanitizer(pub(crate) CoreSanitizer);
+
+#[napi]
+impl Sanitizer {
+  /// Creates a sanitizer from the configuration, or with the default allowlists if there is none.
+  #[napi(constructor)]
+  pub fn new(config: Option<SanitizerConfig>) -> Sanitizer {
+    Sanitizer(match config {
+      Some(config) => CoreSanitizer::new(config_from_js(config)),
+      None => CoreSanitizer::default(),
+    })
+  }
+
+  /// Sanitizes the node's attributes and descendants in place.
+  #[napi]
+  pub fn sanitize(&self, node: &NodeRepr) {
+    self.0.sanitize(&node.0)
+  }
+}
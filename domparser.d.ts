@@ -28,6 +28,36 @@ export interface DOMTokenList {
   [Symbol.iterator](): Iterator<string>;
 }
 
+/**
+ * The declarations of a `style` attribute. Properties can also be read and written as
+ * camelCase fields, such as `style.backgroundColor`.
+ */
+export interface CSSStyleDeclaration {
+  /** Returns or sets the serialization of the declarations. */
+  cssText: string;
+  readonly length: number;
+  /** Returns the name of the property at `index`, or an empty string. */
+  item(index: number): string;
+  getPropertyValue(property: string): string;
+  /** Returns "important" if the property is set with `!important`, or an empty string. */
+  getPropertyPriority(property: string): string;
+  setProperty(property: string, value: string | null, priority?: string): void;
+  /** Removes the property and returns its previous value. */
+  removeProperty(property: string): string;
+  [Symbol.iterator](): Iterator<string>;
+  [index: number]: string;
+  [property: string]: any;
+}
+
+export interface CheckVisibilityOptions {
+  /** Also return false for elements with an `opacity` of 0, or inside one. */
+  checkOpacity?: boolean;
+  /** Also return false for elements whose `visibility` is not `visible`. */
+  checkVisibilityCSS?: boolean;
+  opacityProperty?: boolean;
+  visibilityProperty?: boolean;
+}
+
 // ---------------------------------------------------------------------------
 // Node
 // ---------------------------------------------------------------------------
@@ -58,6 +88,10 @@ export interface Node {
   readonly childNodes: Node[];
   /** Returns the top-level document object for this node. */
   readonly ownerDocument: Document | null;
+  /** Returns where the node came from in the input, if it was parsed with `sourceLocations` enabled. */
+  readonly sourceLocation: SourceLocation | null;
+  /** Returns the absolute base URL of the node: the first `<base href>` of its document resolved against the document's URL, or "about:blank". */
+  readonly baseURI: string;
 
   /** Adds a node to the end of the list of children of a specified parent node. */
   appendChild<T extends Node>(node: T): T;
@@ -75,8 +109,10 @@ export interface Node {
   hasChildNodes(): boolean;
   /** Returns a boolean value indicating whether the two nodes are the same. */
   isSameNode(otherNode: Node | null): boolean;
-  /** Returns a boolean value indicating whether the node is equal to the specified node. */
+  /** Returns whether the node is equal to the specified node: same type, names, data, attributes in any order and children. */
   isEqualNode(otherNode: Node | null): boolean;
+  /** Returns the first difference that makes `isEqualNode` false, or null if the nodes are equal. */
+  diff(otherNode: Node): NodeDifference | null;
   /** Returns a boolean value indicating whether a node is a descendant of a given node. */
   contains(other: Node | null): boolean;
   /** Puts the specified node and all of its subtree into a "normalized" form. */
@@ -93,6 +129,16 @@ export interface Node {
 
   /** Returns a string representation of the object. */
   toString(): string;
+  /** Returns the HTML serialization of the node, formatted according to the options. */
+  serialize(options?: SerializeOptions): string;
+  /** Converts the node and its descendants to CommonMark with GFM tables, strikethrough and task lists. */
+  toMarkdown(options?: MarkdownOptions): string;
+  /** Returns the node and its descendants as a plain-object AST, so `JSON.stringify(node)` serializes the tree. */
+  toJSON(): AstNode;
+  /** Returns the node and its descendants in a compact, versioned binary format that loads faster than parsing. */
+  toSnapshot(): Buffer;
+  /** Rewrites relative URLs in URL attributes, `srcset`s and CSS `url()`s of the node and its descendants to absolute ones, resolved against `base` or `baseURI`. */
+  absolutizeURLs(base?: string): void;
 
   // Node type constants
   readonly ELEMENT_NODE: 1;
@@ -117,10 +163,150 @@ export interface Node {
   readonly DOCUMENT_POSITION_IMPLEMENTATION_SPECIFIC: 0x20;
 }
 
+/** Where a node came from in the input. Offsets are 0-based UTF-8 byte offsets; lines and columns are 1-based. */
+export interface SourceLocation extends SourceSpan {
+  /** The start tag of an element. */
+  startTag?: SourceSpan;
+  /** The end tag of an element, if it had one in the input. */
+  endTag?: SourceSpan;
+  /** The attributes of an element's start tag by qualified name. */
+  attrs?: Record<string, SourceSpan>;
+}
+
+export interface SourceSpan {
+  startOffset: number;
+  endOffset: number;
+  startLine: number;
+  startColumn: number;
+  endLine: number;
+  endColumn: number;
+}
+
+export interface NodeDifference {
+  /** Child indices leading from the compared nodes down to the nodes that differ. */
+  path: number[];
+  /** The property that differs. */
+  property:
+    | 'nodeType'
+    | 'name'
+    | 'publicId'
+    | 'systemId'
+    | 'namespaceURI'
+    | 'prefix'
+    | 'localName'
+    | 'attributes'
+    | 'target'
+    | 'data'
+    | 'childNodes.length';
+  /** The value on this node; for attributes, the differing `name="value"`. */
+  value?: string;
+  /** The value on the other node. */
+  otherValue?: string;
+}
+
+/**
+ * A node of the JSON AST. Template contents are kept in `content` and shadow
+ * roots in `shadowRoot`; `namespace` and `prefix` are left out when null.
+ */
+export type AstNode =
+  | { type: 'document'; contentType: DOMParserSupportedType; scriptingEnabled?: boolean; children: AstNode[] }
+  | { type: 'documentType'; name: string; publicId: string; systemId: string }
+  | AstElement
+  | { type: 'text'; value: string }
+  | { type: 'comment'; value: string }
+  | { type: 'processingInstruction'; target: string; data: string }
+  | { type: 'documentFragment'; children: AstNode[] }
+  | ({ type: 'shadowRoot' } & AstShadowRoot);
+
+export interface AstElement {
+  type: 'element';
+  name: string;
+  namespace?: string;
+  prefix?: string;
+  attributes: AstAttribute[];
+  children: AstNode[];
+  /** The contents of a `<template>` element. */
+  content?: AstNode[];
+  shadowRoot?: AstShadowRoot;
+  mathmlAnnotationXmlIntegrationPoint?: boolean;
+  /** Where the element was in the input, if it was parsed with `sourceLocations`. Ignored by `fromJSON`. */
+  location?: AstLocation;
+}
+
+export interface AstLocation {
+  startOffset: number;
+  endOffset: number;
+  startLine: number;
+  startColumn: number;
+  endLine: number;
+  endColumn: number;
+}
+
+export interface AstAttribute {
+  name: string;
+  namespace?: string;
+  prefix?: string;
+  value: string;
+}
+
+export interface AstShadowRoot {
+  mode: ShadowRootMode;
+  delegatesFocus?: boolean;
+  slotAssignment?: SlotAssignmentMode;
+  clonable?: boolean;
+  serializable?: boolean;
+  children: AstNode[];
+}
+
 // ---------------------------------------------------------------------------
 // CharacterData
 // ---------------------------------------------------------------------------
 
+export interface SerializeOptions {
+  /** Put block-level elements on their own lines. Whitespace in `pre`, `textarea` and inline content is kept. */
+  pretty?: boolean;
+  /** The indentation used when pretty-printing. Defaults to two spaces. */
+  indent?: string;
+  /** Collapse whitespace, remove comments and omit optional tags. Cannot be combined with `pretty`. */
+  minify?: boolean;
+  /** Attribute quoting. Defaults to "minimal" when minifying and "double" otherwise. */
+  quote?: 'double' | 'single' | 'minimal';
+  /** Write attributes in alphabetical order. */
+  sortAttributes?: boolean;
+  /** Write void elements as `<br />`. */
+  selfClosingVoid?: boolean;
+}
+
+/**
+ * How a tag is converted to Markdown: dropped with its contents, kept as HTML,
+ * reduced to its contents, or written by a function that receives the element
+ * and its converted contents and may return null to use the default.
+ */
+export type MarkdownRule =
+  | 'remove'
+  | 'keep'
+  | 'content'
+  | ((node: Element, content: string) => string | null | undefined);
+
+export interface MarkdownOptions {
+  /** Defaults to "atx"; "setext" underlines `h1` and `h2`. */
+  headingStyle?: 'atx' | 'setext';
+  /** Defaults to "-". */
+  bulletListMarker?: '-' | '*' | '+';
+  /** Defaults to "fenced"; fences carry the language of a `language-*` or `lang-*` class. */
+  codeBlockStyle?: 'fenced' | 'indented';
+  /** Defaults to "```". */
+  fence?: '```' | '~~~';
+  /** Defaults to "_". */
+  emDelimiter?: '_' | '*';
+  /** Defaults to "**". */
+  strongDelimiter?: '**' | '__';
+  /** Write GFM tables, strikethrough and task lists; tables are kept as HTML otherwise. Defaults to true. */
+  gfm?: boolean;
+  /** Per-tag overrides keyed by tag name. */
+  rules?: Record<string, MarkdownRule>;
+}
+
 export interface CharacterData extends Node {
   /** Returns the character data of the node. */
   data: string;
@@ -224,6 +410,9 @@ export interface Element extends Node {
   get classList(): DOMTokenList;
   /** Returns the dataset of the element. */
   readonly dataset: Record<string, string>;
+  /** Returns a live declaration block backed by the `style` attribute. Assigning a string sets its `cssText`. */
+  get style(): CSSStyleDeclaration;
+  set style(cssText: string);
 
   /** Returns or sets the HTML serialization of the element's descendants. */
   innerHTML: string;
@@ -285,6 +474,8 @@ export interface Element extends Node {
   getAttributeNS(namespace: string | null, localName: string): string | null;
   /** Sets the value of an attribute on the specified element with the specified namespace. */
   setAttributeNS(namespace: string | null, qualifiedName: string, value: string): void;
+  /** Adds an attribute created by `createAttributeNS`, returning the attribute with the same namespace and local name it replaces. */
+  setAttributeNodeNS(attr: Attr): Attr | null;
   /** Removes an attribute from the specified element with the specified namespace. */
   removeAttributeNS(namespace: string | null, localName: string): void;
   /** Returns a boolean value indicating whether the current element has the specified attribute. */
@@ -295,10 +486,14 @@ export interface Element extends Node {
   getElementsByClassName(classNames: string): Element[];
   /** Returns an HTMLCollection of elements with the given tag name. */
   getElementsByTagName(qualifiedName: string): Element[];
+  /** Returns an HTMLCollection of elements with the given local name and namespace. */
+  getElementsByTagNameNS(namespace: string | null, localName: string): Element[];
   /** Returns the closest ancestor of the current element which matches the selectors. */
   closest(selectors: string): Element | null;
   /** Returns a boolean value indicating whether the element would be selected by the specified selector string. */
   matches(selectors: string): boolean;
+  /** Returns whether the element is rendered, from the default stylesheet, the `<style>` elements of its tree and its `style` attribute. */
+  checkVisibility(options?: CheckVisibilityOptions): boolean;
 
   // Insertion methods
   /** Parses the specified text as HTML and inserts the resulting nodes at a specified position. */
@@ -310,6 +505,179 @@ export interface Element extends Node {
 
   /** Returns an Element object representing the element whose id property matches the specified string. */
   getElementById(id: string): Element | null;
+
+  // Shadow DOM
+  /** Attaches a shadow DOM tree to the element and returns its ShadowRoot. */
+  attachShadow(init: ShadowRootInit): ShadowRoot;
+  /** Returns the open shadow root attached to the element, or null. */
+  readonly shadowRoot: ShadowRoot | null;
+  /** Returns the slot the element is assigned to, or null. */
+  readonly assignedSlot: HTMLSlotElement | null;
+  /** Parses the HTML, removes what the sanitizer does not allow, and replaces the element's children. */
+  setHTML(html: string, options?: SetHTMLOptions): void;
+  /** Parses the HTML, including declarative shadow roots, and replaces the element's children. */
+  setHTMLUnsafe(html: string): void;
+  /** Returns the HTML serialization of the element's children, optionally including shadow roots. */
+  getHTML(options?: GetHTMLOptions): string;
+  /**
+   * Returns whether the element matches the selectors evaluated inside the shadow tree of `host`,
+   * so that `:host`, `::slotted()` and `::part()` can match.
+   */
+  matchesInShadowTree(selectors: string, host: Element): boolean;
+}
+
+/** A namespaced attribute that is not on an element, as created by `createAttributeNS`. */
+export interface Attr {
+  namespaceURI?: string;
+  prefix?: string;
+  localName: string;
+  /** The qualified name, `prefix:localName` or `localName`. */
+  readonly name: string;
+  value: string;
+}
+
+export interface ShadowRootInit {
+  mode: ShadowRootMode;
+  delegatesFocus?: boolean;
+  slotAssignment?: SlotAssignmentMode;
+  clonable?: boolean;
+  serializable?: boolean;
+}
+
+export type ShadowRootMode = 'open' | 'closed';
+export type SlotAssignmentMode = 'named' | 'manual';
+
+export interface GetHTMLOptions {
+  /** Include shadow roots marked as serializable. */
+  serializableShadowRoots?: boolean;
+  /** Shadow roots to include regardless of their serializable flag. */
+  shadowRoots?: ShadowRoot[];
+}
+
+export interface SetHTMLOptions {
+  /** The sanitizer or its configuration. Defaults to `new Sanitizer()`. */
+  sanitizer?: Sanitizer | SanitizerConfig;
+}
+
+export interface AssignedNodesOptions {
+  flatten?: boolean;
+}
+
+export interface HTMLElement extends Element {
+  /** Reflects the `title` attribute. */
+  title: string;
+  /** Reflects the `lang` attribute. */
+  lang: string;
+  /** Reflects the `dir` attribute, limited to "ltr", "rtl", "auto" or "". */
+  dir: string;
+  /** Reflects the `hidden` attribute. */
+  hidden: boolean;
+  /** Reflects the `tabindex` attribute; defaults to 0 for focusable elements and -1 otherwise. */
+  tabIndex: number;
+  /** The rendered text: blocks on their own lines, table cells separated by tabs, hidden content skipped. Setting it inserts `<br>` for line breaks. */
+  innerText: string;
+  /** Same as `innerText` when read; setting it replaces the element itself with the text. */
+  outerText: string;
+}
+
+export interface HTMLAnchorElement extends HTMLElement {
+  /** The `href` attribute resolved against the base URL when possible. */
+  href: string;
+}
+
+export interface HTMLLinkElement extends HTMLElement {
+  /** The `href` attribute resolved against the base URL when possible. */
+  href: string;
+}
+
+export interface HTMLFormElement extends HTMLElement {
+  /** The `action` attribute resolved to an absolute URL, or the document's URL if it is missing or empty. */
+  action: string;
+  /** Reflects the `method` attribute: "get", "post" or "dialog". Defaults to "get". */
+  method: string;
+  /** Reflects the `enctype` attribute. Defaults to "application/x-www-form-urlencoded". */
+  enctype: string;
+  /** The controls whose form owner is this form, in tree order, except image buttons. */
+  readonly elements: Element[];
+}
+
+export interface HTMLImageElement extends HTMLElement {
+  /** The `src` attribute resolved against the base URL when possible. */
+  src: string;
+}
+
+export interface HTMLScriptElement extends HTMLElement {
+  /** The `src` attribute resolved against the base URL when possible. */
+  src: string;
+}
+
+export interface HTMLInputElement extends HTMLElement {
+  /** Reflects the `checked` attribute; checking a radio button unchecks its group. */
+  checked: boolean;
+  /** The value of the control, from the `value` attribute. */
+  value: string;
+  /** Reflects the `value` attribute. */
+  defaultValue: string;
+  /** The `src` attribute resolved against the base URL when possible. */
+  src: string;
+  /** The form named by the `form` attribute, or the nearest ancestor form. */
+  readonly form: HTMLFormElement | null;
+  /** The `formaction` attribute resolved to an absolute URL, overriding the form's `action`. */
+  formAction: string;
+  /** Reflects the `formmethod` attribute, or "" if it is missing. */
+  formMethod: string;
+  /** Reflects the `formenctype` attribute, or "" if it is missing. */
+  formEnctype: string;
+}
+
+export interface HTMLButtonElement extends HTMLElement {
+  /** Reflects the `value` attribute. */
+  value: string;
+  /** The form named by the `form` attribute, or the nearest ancestor form. */
+  readonly form: HTMLFormElement | null;
+  /** The `formaction` attribute resolved to an absolute URL, overriding the form's `action`. */
+  formAction: string;
+  /** Reflects the `formmethod` attribute, or "" if it is missing. */
+  formMethod: string;
+  /** Reflects the `formenctype` attribute, or "" if it is missing. */
+  formEnctype: string;
+}
+
+export interface HTMLTextAreaElement extends HTMLElement {
+  /** The text of the textarea. */
+  value: string;
+  /** The text of the textarea. */
+  defaultValue: string;
+  /** The form named by the `form` attribute, or the nearest ancestor form. */
+  readonly form: HTMLFormElement | null;
+}
+
+export interface HTMLSelectElement extends HTMLElement {
+  /** The value of the first selected option. */
+  value: string;
+  /** The index of the first selected option, or -1. */
+  selectedIndex: number;
+  /** The form named by the `form` attribute, or the nearest ancestor form. */
+  readonly form: HTMLFormElement | null;
+}
+
+export interface HTMLOptionElement extends HTMLElement {
+  /** The `value` attribute, or the option's text. */
+  value: string;
+  /** Whether the option is selected in its select element. */
+  selected: boolean;
+}
+
+export interface HTMLTemplateElement extends HTMLElement {
+  /** Returns the template contents, which `innerHTML` reads and replaces. */
+  readonly content: DocumentFragment;
+}
+
+export interface HTMLSlotElement extends HTMLElement {
+  /** Returns the nodes assigned to the slot, or its fallback content when `flatten` is set. */
+  assignedNodes(options?: AssignedNodesOptions): Node[];
+  /** Returns the elements assigned to the slot, or its fallback elements when `flatten` is set. */
+  assignedElements(options?: AssignedNodesOptions): Element[];
 }
 
 // ---------------------------------------------------------------------------
@@ -338,6 +706,33 @@ export interface DocumentFragment extends Node {
   getElementById(elementId: string): Element | null;
 }
 
+// ---------------------------------------------------------------------------
+// ShadowRoot
+// ---------------------------------------------------------------------------
+
+export interface ShadowRoot extends DocumentFragment {
+  /** Returns the element the shadow root is attached to. */
+  readonly host: Element;
+  /** Returns the mode of the shadow root. */
+  readonly mode: ShadowRootMode;
+  /** Returns the slot assignment mode of the shadow root. */
+  readonly slotAssignment: SlotAssignmentMode;
+  /** Returns whether the shadow root delegates focus. */
+  readonly delegatesFocus: boolean;
+  /** Returns whether the shadow root is cloned along with its host. */
+  readonly clonable: boolean;
+  /** Returns whether the shadow root is serialized by `getHTML({ serializableShadowRoots: true })`. */
+  readonly serializable: boolean;
+  /** Returns or sets the HTML serialization of the shadow root's children. */
+  innerHTML: string;
+  /** Parses the HTML, removes what the sanitizer does not allow, and replaces the shadow root's children. */
+  setHTML(html: string, options?: SetHTMLOptions): void;
+  /** Parses the HTML, including declarative shadow roots, and replaces the shadow root's children. */
+  setHTMLUnsafe(html: string): void;
+  /** Returns the HTML serialization of the shadow root's children, optionally including nested shadow roots. */
+  getHTML(options?: GetHTMLOptions): string;
+}
+
 // ---------------------------------------------------------------------------
 // Document
 // ---------------------------------------------------------------------------
@@ -353,10 +748,20 @@ export interface Document extends Node {
   readonly body: Element | null;
   /** Returns or sets the title of the document. */
   title: string;
+  /** Returns the DOMImplementation object associated with the document. */
+  readonly implementation: DOMImplementation;
+  /** Returns the MIME type of the document. */
+  readonly contentType: string;
+  /** Returns the URL the document was parsed with, or "about:blank". */
+  readonly URL: string;
 
   // Factory methods
   /** Creates the HTML element specified by tagName. */
   createElement(tagName: string): Element;
+  /** Creates an element with the specified namespace URI and qualified name. */
+  createElementNS(namespace: string | null, qualifiedName: string): Element;
+  /** Creates an attribute with the specified namespace URI and qualified name and an empty value. */
+  createAttributeNS(namespace: string | null, qualifiedName: string): Attr;
   /** Creates a new Text node. */
   createTextNode(data: string): Text;
   /** Creates a new Comment node. */
@@ -398,17 +803,335 @@ export interface Document extends Node {
   getElementsByClassName(classNames: string): Element[];
   /** Returns an HTMLCollection of elements with the given tag name. */
   getElementsByTagName(qualifiedName: string): Element[];
+  /** Returns an HTMLCollection of elements with the given local name and namespace. */
+  getElementsByTagNameNS(namespace: string | null, localName: string): Element[];
 }
 
+// ---------------------------------------------------------------------------
+// DOMImplementation
+// ---------------------------------------------------------------------------
+
+export interface DOMImplementation {
+  /** Creates a new HTML document with a doctype, html, head, optional title and body. */
+  createHTMLDocument(title?: string): Document;
+  /** Creates a new XML document with an optional doctype and document element. */
+  createDocument(namespace: string | null, qualifiedName: string | null, doctype?: DocumentType | null): Document;
+  /** Creates a new DocumentType node. */
+  createDocumentType(qualifiedName: string, publicId: string, systemId: string): DocumentType;
+  /** Always returns true, kept for compatibility. */
+  hasFeature(): true;
+}
+
+export declare var DOMImplementation: {
+  prototype: DOMImplementation;
+};
+
+/** Creates a new empty XML document. */
+export declare var Document: {
+  prototype: Document;
+  new (): Document;
+};
+
 // ---------------------------------------------------------------------------
 // DOMParser
 // ---------------------------------------------------------------------------
 
 export class DOMParser {
+  constructor(options?: DOMParserOptions);
   /** Parses a string containing HTML, returning a Document. */
   parseFromString(string: string, type: DOMParserSupportedType): Document;
 }
 
+// ---------------------------------------------------------------------------
+// XMLSerializer
+// ---------------------------------------------------------------------------
+
+export class XMLSerializer {
+  /** Returns the namespace-aware XML serialization of the node and its subtree. */
+  serializeToString(root: Node, options?: { requireWellFormed?: boolean }): string;
+}
+
+// ---------------------------------------------------------------------------
+// Sanitizer
+// ---------------------------------------------------------------------------
+
+/**
+ * Removes disallowed elements and attributes. Script elements, frames, plugins,
+ * `on*` handlers, `javascript:` URLs and misnested SVG or MathML are always removed.
+ */
+export class Sanitizer {
+  /** Creates a sanitizer from the configuration, or with the default allowlists if there is none. */
+  constructor(config?: SanitizerConfig);
+  /** Sanitizes the node's attributes and descendants in place. */
+  sanitize(node: Node): void;
+}
+
+/** Lists that are left out allow everything not removed. */
+export interface SanitizerConfig {
+  /** Elements to keep; others are removed with their contents. */
+  elements?: (string | SanitizerElement)[];
+  /** Elements removed with their contents. */
+  removeElements?: (string | SanitizerName)[];
+  /** Elements replaced by their children. */
+  replaceWithChildrenElements?: (string | SanitizerName)[];
+  /** Attributes allowed on every element. */
+  attributes?: (string | SanitizerName)[];
+  /** Attributes removed from every element. */
+  removeAttributes?: (string | SanitizerName)[];
+  /** Keep comments. Defaults to false. */
+  comments?: boolean;
+  /** Keep `data-*` attributes. Defaults to true. */
+  dataAttributes?: boolean;
+  /** URL schemes allowed in `href`, `src`, `srcset`, `action` and similar attributes. Defaults to http, https, mailto and tel. */
+  urlSchemes?: string[];
+}
+
+export interface SanitizerElement extends SanitizerName {
+  attributes?: (string | SanitizerName)[];
+  removeAttributes?: (string | SanitizerName)[];
+}
+
+export interface SanitizerName {
+  name: string;
+  /** Defaults to the HTML namespace for elements and to no namespace for attributes. */
+  namespace?: string;
+}
+
+/**
+ * An edit script returned by `domDiff`. Operations run in order and address
+ * nodes by child indices from the patched node, as the tree is when each runs.
+ */
+export interface Patch {
+  operations: PatchOperation[];
+}
+
+export type PatchOperation =
+  | { type: 'insert'; parent: number[]; index: number; node: AstNode }
+  | { type: 'remove'; path: number[] }
+  | { type: 'move'; parent: number[]; from: number; to: number }
+  | { type: 'replace'; path: number[]; node: AstNode }
+  | { type: 'setAttribute'; path: number[]; namespace?: string; name: string; value: string }
+  | { type: 'removeAttribute'; path: number[]; namespace?: string; name: string }
+  | { type: 'setText'; path: number[]; value: string };
+
+/** Computes the patch that turns `oldNode` into a tree equal to `newNode`, optionally matching elements by a key attribute. */
+export function domDiff(oldNode: Node, newNode: Node, options?: { key?: string }): Patch;
+
+/** Applies a patch to a tree equal to the one it was computed from. The patch may be given as JSON. */
+export function applyPatch(node: Node, patch: Patch | string): void;
+
+/** Builds a new tree from a JSON AST, given as an object or as a JSON string. */
+export function fromJSON(ast: AstNode | string): Node;
+
+/** Builds a new tree from a snapshot returned by `toSnapshot`. */
+export function fromSnapshot(snapshot: Uint8Array | ArrayBuffer): Node;
+
+/** A URL referenced by an element, returned by `extractResources`. */
+export interface Resource {
+  /** What the URL is used for; "css" for `url()`s and `@import`s in `style` attributes and `<style>` elements. */
+  kind: 'hyperlink' | 'stylesheet' | 'link' | 'script' | 'image' | 'media' | 'frame' | 'embed' | 'form' | 'css';
+  element: Element;
+  /** The attribute holding the URL, or undefined for the text of a `<style>` element. */
+  attribute?: string;
+  /** The URL as written; the whole attribute value for `srcset` and `imagesrcset`. */
+  url: string;
+  /** The URL resolved against the element's `baseURI`, if it can be resolved. Undefined for `srcset`s. */
+  resolvedUrl?: string;
+  rel?: string;
+  /** The candidates of a `srcset` or `imagesrcset` attribute. */
+  srcset?: { url: string; resolvedUrl?: string; descriptor?: string }[];
+  integrity?: string;
+  crossorigin?: string;
+  media?: string;
+}
+
+/** Returns the links and subresources referenced by the node and its descendants, including template contents and shadow trees, in tree order. */
+export function extractResources(node: Node): Resource[];
+
+/** An item described by microdata or RDFa Lite. */
+export interface MetadataItem {
+  /** The `itemtype` or `typeof` types. */
+  types: string[];
+  /** The `itemid`, or the RDFa `resource`. */
+  id?: string;
+  /** The values of each property in document order. RDFa property names are expanded to IRIs. */
+  properties: Record<string, (string | MetadataItem)[]>;
+}
+
+/** Page metadata returned by `extractMetadata`. */
+export interface Metadata {
+  title?: string;
+  /** The `<meta name="description">` content. */
+  description?: string;
+  /** The resolved `<link rel="canonical">` URL. */
+  canonical?: string;
+  /** The root element's `lang`, or the `Content-Language` pragma. */
+  language?: string;
+  /** `icon`, `apple-touch-icon` and other icon links, with resolved URLs. */
+  icons: { rel: string; href: string; sizes?: string; type?: string }[];
+  /** OpenGraph `<meta property>` values such as `og:title` or `article:author`, by property. */
+  openGraph: Record<string, string[]>;
+  /** `twitter:` card values, by name. */
+  twitter: Record<string, string[]>;
+  /** The parsed `<script type="application/ld+json">` blocks; invalid ones are skipped. */
+  jsonLd: unknown[];
+  /** The top-level microdata items. */
+  microdata: MetadataItem[];
+  /** The RDFa Lite items declared with `typeof`. */
+  rdfa: MetadataItem[];
+}
+
+/** Extracts a page's title, description, canonical URL, language, icons, OpenGraph and Twitter properties, JSON-LD, microdata and RDFa Lite in one call. */
+export function extractMetadata(node: Node): Metadata;
+
+/** Options for `extractArticle`. */
+export interface ArticleOptions {
+  /** The number of characters an article needs before the heuristics are relaxed. Defaults to 500. */
+  charThreshold?: number;
+  /** Keep `class` attributes in the article. Defaults to false. */
+  keepClasses?: boolean;
+}
+
+/** The main article of a page, returned by `extractArticle`. */
+export interface Article {
+  title?: string;
+  byline?: string;
+  /** The page description, or the first paragraph of the article. */
+  excerpt?: string;
+  siteName?: string;
+  language?: string;
+  /** A detached `<div>` holding the cleaned article, with absolute URLs. */
+  content: HTMLElement;
+  /** The text of the article with white space collapsed. */
+  textContent: string;
+  /** The length of `textContent` in characters. */
+  length: number;
+}
+
+/** Extracts the main article of a document with a Readability-style algorithm, or null if it has no text. The document is not changed. */
+export function extractArticle(document: Document, options?: ArticleOptions): Article | null;
+
+export interface GetComputedStyleOptions {
+  /** Author stylesheets applied to the document after its `<style>` elements. */
+  stylesheets?: string[];
+}
+
+/**
+ * Returns the computed style of an element from the cascade of the default stylesheet, the
+ * `<style>` elements of its document or shadow tree, the given stylesheets and its `style`
+ * attribute. There is no layout: lengths in absolute and font-relative units are converted to
+ * pixels, but percentages and `auto` are kept. The declaration is read-only, and empty for
+ * elements that are not in a document.
+ */
+export function getComputedStyle(element: Element, options?: GetComputedStyleOptions): CSSStyleDeclaration;
+
+export interface InlineCSSOptions {
+  /** Keep `!important` on declarations inlined from stylesheets. Defaults to false. */
+  preserveImportant?: boolean;
+  /** Remove class names that the remaining rules do not use. Defaults to false. */
+  removeClasses?: boolean;
+  /** Remove ids that the remaining rules do not use. Defaults to false, since ids may also be link targets. */
+  removeIds?: boolean;
+}
+
+/**
+ * Moves the style rules of the document's `<style>` elements into the `style` attributes of the
+ * elements they match, for mail clients that ignore stylesheets. Rules that cannot be inlined,
+ * such as `@media` rules and `:hover` selectors, are kept in their `<style>` element.
+ */
+export function inlineCSS(document: Document, options?: InlineCSSOptions): void;
+
+/** The usage of a style rule, returned by `cssCoverage`. */
+export interface RuleCoverage {
+  selectorText: string;
+  /** The at-rules the rule is nested in, such as `@media print`, outermost first. */
+  conditions: string[];
+  /** Whether a selector of the rule matches an element. */
+  used: boolean;
+  /** The selectors of the list that match an element. */
+  usedSelectors: string[];
+}
+
+export interface CSSCoverage {
+  /** The style rules of the stylesheet in source order, including those in at-rules. */
+  rules: RuleCoverage[];
+  /** The stylesheet without unused style rules and selectors, and without at-rules left empty. */
+  prunedCSS: string;
+}
+
+/**
+ * Reports which style rules of `css` match an element of the tree rooted at `root`, and returns
+ * the CSS pruned of the others. Pass a document to find unused CSS, or the above-the-fold part
+ * of a page to extract its critical CSS. State pseudo-classes such as `:hover` are ignored.
+ */
+export function cssCoverage(root: Node, css: string): CSSCoverage;
+
+/**
+ * The entries a form submits, built with the HTML "constructing the entry list" algorithm:
+ * disabled controls, unchecked checkboxes and radio buttons, buttons other than the submitter
+ * and unnamed controls are skipped. A file input has no selected file and submits an empty
+ * `File`.
+ */
+export declare class FormData implements Iterable<[string, FormDataEntryValue]> {
+  constructor(form?: HTMLFormElement, submitter?: HTMLElement | null);
+  append(name: string, value: FormDataEntryValue): void;
+  delete(name: string): void;
+  get(name: string): FormDataEntryValue | null;
+  getAll(name: string): FormDataEntryValue[];
+  has(name: string): boolean;
+  set(name: string, value: FormDataEntryValue): void;
+  forEach(callback: (value: FormDataEntryValue, name: string, parent: FormData) => void, thisArg?: any): void;
+  entries(): IterableIterator<[string, FormDataEntryValue]>;
+  keys(): IterableIterator<string>;
+  values(): IterableIterator<FormDataEntryValue>;
+  [Symbol.iterator](): IterableIterator<[string, FormDataEntryValue]>;
+}
+
+type FormDataEntryValue = string | File;
+
+export interface FormEntry {
+  name: string;
+  value: string;
+  /** The file name of a file input's entry, which is always empty. */
+  filename?: string;
+}
+
+export interface SubmitFormOptions {
+  /** The point clicked on an image button submitter. Defaults to `{ x: 0, y: 0 }`. */
+  coordinates?: { x: number; y: number };
+  /** The boundary of a `multipart/form-data` body. Defaults to a random one. */
+  boundary?: string;
+}
+
+export interface FormSubmission {
+  /** The URL to submit to, with the entries as its query for the "get" method. */
+  url: string;
+  /** "get", "post" or "dialog", from the submitter's `formmethod` or the form's `method`. */
+  method: string;
+  /** The form's `enctype`, or the submitter's `formenctype`. */
+  enctype: string;
+  entries: FormEntry[];
+  /** The encoded entries, for the "post" method. */
+  body?: string;
+  /** The `Content-Type` of the body, with its boundary for `multipart/form-data`. */
+  contentType?: string;
+}
+
+/**
+ * Builds the request a browser would make to submit `form` with `submitter`, its default
+ * button if omitted. The submitter's `formaction`, `formmethod` and `formenctype` override
+ * the form's, and the entries are encoded as `application/x-www-form-urlencoded`,
+ * `multipart/form-data` or `text/plain`. Throws if the action is not a valid URL.
+ */
+export function submitForm(form: HTMLFormElement, submitter?: HTMLElement | null, options?: SubmitFormOptions): FormSubmission;
+
+export interface DOMParserOptions {
+  /** Record where each node came from in the input, read back with `node.sourceLocation`. Defaults to false. */
+  sourceLocations?: boolean;
+  /** The URL of the document, used to resolve relative URLs. Defaults to "about:blank". */
+  url?: string;
+}
+
 type DOMParserSupportedType =
   | 'text/html'
   | 'text/xml'
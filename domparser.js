@@ -1,4 +1,24 @@
-const { parse, NodeRepr } = require('./index.js');
+const {
+  parse,
+  NodeRepr,
+  Sanitizer,
+  createDocument,
+  createDocumentType,
+  createEmptyDocument,
+  createHTMLDocument,
+  applyPatch: applyPatchObject,
+  cssCoverage,
+  domDiff,
+  extractArticle,
+  extractMetadata: extractMetadataObject,
+  extractResources,
+  formEntries,
+  fromJSON: fromAst,
+  fromSnapshot: fromSnapshotBuffer,
+  getComputedStyle: getComputedStyleObject,
+  inlineCSS,
+  submitForm,
+} = require('./index.js');
 
 // Implement classList and dataset wrappers
 Object.defineProperty(NodeRepr.prototype, 'classList', {
@@ -83,16 +103,378 @@ Object.defineProperty(NodeRepr.prototype, 'dataset', {
   configurable: true
 });
 
+// Converts a camelCase field of a style object to a property name.
+const cssPropertyName = (field) => {
+  if (field === 'cssFloat') return 'float';
+  if (field.includes('-')) return field;
+  return field
+    .replace(/^(webkit|moz|ms|o)(?=[A-Z])/, '-$1')
+    .replace(/[A-Z]/g, (c) => `-${c.toLowerCase()}`);
+};
+
+Object.defineProperty(NodeRepr.prototype, 'style', {
+  get() {
+    const node = this;
+    const declaration = {
+      get cssText() {
+        return node._styleGetCssText();
+      },
+      set cssText(value) {
+        node._styleSetCssText(String(value));
+      },
+      get length() {
+        return node._styleProperties().length;
+      },
+      get parentRule() {
+        return null;
+      },
+      item(index) {
+        return node._styleProperties()[index] || '';
+      },
+      getPropertyValue(property) {
+        return node._styleGetPropertyValue(String(property));
+      },
+      getPropertyPriority(property) {
+        return node._styleGetPropertyPriority(String(property));
+      },
+      setProperty(property, value, priority = '') {
+        node._styleSetProperty(String(property), value == null ? '' : String(value), String(priority));
+      },
+      removeProperty(property) {
+        return node._styleRemoveProperty(String(property));
+      },
+      toString() {
+        return '[object CSSStyleDeclaration]';
+      },
+      [Symbol.iterator]() {
+        return node._styleProperties()[Symbol.iterator]();
+      },
+    };
+    return new Proxy(declaration, {
+      get(target, prop, receiver) {
+        if (typeof prop !== 'string' || prop in target) {
+          return Reflect.get(target, prop, receiver);
+        }
+        if (/^\d+$/.test(prop)) {
+          return node._styleProperties()[Number(prop)];
+        }
+        return node._styleGetPropertyValue(cssPropertyName(prop));
+      },
+      set(target, prop, value, receiver) {
+        if (typeof prop !== 'string' || prop in target) {
+          return Reflect.set(target, prop, value, receiver);
+        }
+        node._styleSetProperty(cssPropertyName(prop), value == null ? '' : String(value), '');
+        return true;
+      },
+      has(target, prop) {
+        return prop in target || (typeof prop === 'string' && node._styleProperties().includes(prop));
+      },
+      ownKeys(target) {
+        return [...Reflect.ownKeys(target), ...node._styleProperties().map((_, index) => String(index))];
+      },
+      getOwnPropertyDescriptor(target, prop) {
+        if (typeof prop === 'string' && /^\d+$/.test(prop)) {
+          const name = node._styleProperties()[Number(prop)];
+          if (name !== undefined) {
+            return { value: name, enumerable: true, configurable: true, writable: false };
+          }
+        }
+        return Reflect.getOwnPropertyDescriptor(target, prop);
+      },
+    });
+  },
+  set(value) {
+    this._styleSetCssText(String(value));
+  },
+  configurable: true
+});
+
+NodeRepr.prototype.getHTML = function getHTML(options = {}) {
+  return this._getHTML(!!options.serializableShadowRoots, options.shadowRoots || []);
+};
+
+NodeRepr.prototype.setHTML = function setHTML(html, options = {}) {
+  const { sanitizer } = options;
+  if (sanitizer === undefined || sanitizer instanceof Sanitizer) {
+    this._setHTML(String(html), sanitizer);
+  } else {
+    this._setHTML(String(html), new Sanitizer(sanitizer));
+  }
+};
+
+NodeRepr.prototype.assignedNodes = function assignedNodes(options = {}) {
+  return this._assignedNodes(!!options.flatten);
+};
+
+NodeRepr.prototype.assignedElements = function assignedElements(options = {}) {
+  return this._assignedElements(!!options.flatten);
+};
+
+NodeRepr.prototype.toMarkdown = function toMarkdown(options = {}) {
+  const { rules = {}, ...rest } = options;
+  const ruleNames = {};
+  const handlers = {};
+  for (const [tag, rule] of Object.entries(rules)) {
+    const name = tag.toLowerCase();
+    if (typeof rule === 'function') {
+      ruleNames[name] = 'custom';
+      handlers[name] = rule;
+    } else {
+      ruleNames[name] = rule;
+    }
+  }
+  const handler = (node, content) => {
+    const result = handlers[node.localName.toLowerCase()](node, content);
+    return result == null ? null : String(result);
+  };
+  return this._toMarkdown({ ...rest, rules: ruleNames }, Object.keys(handlers).length ? handler : null);
+};
+
+class DOMImplementation {
+  createHTMLDocument(title) {
+    return createHTMLDocument(title === undefined ? null : String(title));
+  }
+  createDocument(namespace, qualifiedName, doctype) {
+    return createDocument(namespace, qualifiedName === null ? '' : String(qualifiedName), doctype);
+  }
+  createDocumentType(qualifiedName, publicId, systemId) {
+    return createDocumentType(String(qualifiedName), String(publicId), String(systemId));
+  }
+  hasFeature() {
+    return true;
+  }
+}
+
+const implementation = new DOMImplementation();
+
+Object.defineProperty(NodeRepr.prototype, 'implementation', {
+  get() {
+    return this.nodeType === 9 ? implementation : undefined;
+  },
+  configurable: true
+});
+
+class Document {
+  constructor() {
+    return createEmptyDocument();
+  }
+}
+
+class XMLSerializer {
+  serializeToString(root, options = {}) {
+    if (!(root instanceof NodeRepr)) {
+      throw new TypeError('XMLSerializer.serializeToString: Argument 1 is not a Node.');
+    }
+    return root._serializeToXml(!!options.requireWellFormed);
+  }
+}
+
 class DOMParser {
+  #options;
+
+  constructor(options = {}) {
+    this.#options = { sourceLocations: Boolean(options.sourceLocations), url: options.url };
+  }
+
   parseFromString(string, mimeType) {
     if (mimeType === 'text/html') {
-      return parse(string);
+      return parse(string, this.#options);
     }
     throw new Error(`Unsupported mime type: ${mimeType}`);
   }
 }
 
+function fromJSON(ast) {
+  return fromAst(typeof ast === 'string' ? JSON.parse(ast) : ast);
+}
+
+function applyPatch(node, patch) {
+  applyPatchObject(node, typeof patch === 'string' ? JSON.parse(patch) : patch);
+}
+
+function extractMetadata(node) {
+  const metadata = extractMetadataObject(node);
+  // Blocks that are not valid JSON are skipped, as search engines do.
+  metadata.jsonLd = metadata.jsonLd.flatMap((text) => {
+    try {
+      return [JSON.parse(text)];
+    } catch {
+      return [];
+    }
+  });
+  return metadata;
+}
+
+// A read-only CSSStyleDeclaration over the computed values, like the one browsers return.
+function getComputedStyle(element, options = {}) {
+  if (!(element instanceof NodeRepr) || element.nodeType !== 1) {
+    throw new TypeError('getComputedStyle: Argument 1 is not an Element.');
+  }
+  const values = getComputedStyleObject(element, options.stylesheets || []);
+  const names = Object.keys(values);
+  const readOnly = () => {
+    throw new Error('getComputedStyle: the declaration is read-only.');
+  };
+  const declaration = {
+    get cssText() {
+      return '';
+    },
+    get length() {
+      return names.length;
+    },
+    get parentRule() {
+      return null;
+    },
+    item(index) {
+      return names[index] || '';
+    },
+    getPropertyValue(property) {
+      const name = String(property);
+      return values[name.startsWith('--') ? name : name.toLowerCase()] || '';
+    },
+    getPropertyPriority() {
+      return '';
+    },
+    setProperty: readOnly,
+    removeProperty: readOnly,
+    toString() {
+      return '[object CSSStyleDeclaration]';
+    },
+    [Symbol.iterator]() {
+      return names[Symbol.iterator]();
+    },
+  };
+  return new Proxy(declaration, {
+    get(target, prop, receiver) {
+      if (typeof prop !== 'string' || prop in target) {
+        return Reflect.get(target, prop, receiver);
+      }
+      if (/^\d+$/.test(prop)) {
+        return names[Number(prop)];
+      }
+      return values[cssPropertyName(prop)] || '';
+    },
+    set: readOnly,
+    has(target, prop) {
+      return prop in target || (typeof prop === 'string' && names.includes(prop));
+    },
+    ownKeys(target) {
+      return [...Reflect.ownKeys(target), ...names.map((_, index) => String(index))];
+    },
+    getOwnPropertyDescriptor(target, prop) {
+      if (typeof prop === 'string' && /^\d+$/.test(prop) && Number(prop) < names.length) {
+        return { value: names[Number(prop)], enumerable: true, configurable: true, writable: false };
+      }
+      return Reflect.getOwnPropertyDescriptor(target, prop);
+    },
+  });
+}
+
+// The entries of a form as the FormData constructor builds them. A file input has no selected
+// file, so it submits an empty File, or its empty file name where File is not available.
+class FormData {
+  #entries = [];
+
+  constructor(form, submitter) {
+    if (form === undefined) return;
+    if (!(form instanceof NodeRepr) || form.nodeType !== 1) {
+      throw new TypeError('FormData: Argument 1 is not an HTMLFormElement.');
+    }
+    for (const { name, value, filename } of formEntries(form, submitter)) {
+      this.#entries.push([name, filename == null ? value : FormData.#file(filename)]);
+    }
+  }
+
+  static #file(filename) {
+    return typeof File === 'function' ? new File([], filename, { type: 'application/octet-stream' }) : filename;
+  }
+
+  append(name, value) {
+    this.#entries.push([String(name), typeof value === 'object' && value !== null ? value : String(value)]);
+  }
+
+  delete(name) {
+    this.#entries = this.#entries.filter(([entryName]) => entryName !== String(name));
+  }
+
+  get(name) {
+    const entry = this.#entries.find(([entryName]) => entryName === String(name));
+    return entry ? entry[1] : null;
+  }
+
+  getAll(name) {
+    return this.#entries.filter(([entryName]) => entryName === String(name)).map(([, value]) => value);
+  }
+
+  has(name) {
+    return this.#entries.some(([entryName]) => entryName === String(name));
+  }
+
+  set(name, value) {
+    name = String(name);
+    const index = this.#entries.findIndex(([entryName]) => entryName === name);
+    if (index === -1) {
+      this.append(name, value);
+      return;
+    }
+    this.#entries[index] = [name, typeof value === 'object' && value !== null ? value : String(value)];
+    this.#entries = this.#entries.filter(([entryName], i) => i <= index || entryName !== name);
+  }
+
+  forEach(callback, thisArg) {
+    for (const [name, value] of this.#entries) {
+      callback.call(thisArg, value, name, this);
+    }
+  }
+
+  *entries() {
+    for (const [name, value] of this.#entries) yield [name, value];
+  }
+
+  *keys() {
+    for (const [name] of this.#entries) yield name;
+  }
+
+  *values() {
+    for (const [, value] of this.#entries) yield value;
+  }
+
+  [Symbol.iterator]() {
+    return this.entries();
+  }
+}
+
+function fromSnapshot(snapshot) {
+  if (Buffer.isBuffer(snapshot)) {
+    return fromSnapshotBuffer(snapshot);
+  }
+  if (ArrayBuffer.isView(snapshot)) {
+    return fromSnapshotBuffer(Buffer.from(snapshot.buffer, snapshot.byteOffset, snapshot.byteLength));
+  }
+  if (snapshot instanceof ArrayBuffer) {
+    return fromSnapshotBuffer(Buffer.from(snapshot));
+  }
+  throw new TypeError('fromSnapshot: Argument 1 is not a Buffer, typed array or ArrayBuffer.');
+}
+
 module.exports = {
   DOMParser,
+  DOMImplementation,
+  Document,
+  XMLSerializer,
+  Sanitizer,
+  fromJSON,
+  fromSnapshot,
+  domDiff,
+  applyPatch,
+  extractResources,
+  extractMetadata,
+  extractArticle,
+  getComputedStyle,
+  inlineCSS,
+  cssCoverage,
+  FormData,
+  submitForm,
 };
 
@@ -1,6 +1,35 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
 export declare class NodeRepr {
+  /** Returns the node and its descendants as a JSON AST of plain objects, built in one call. */
+  toJSON(): AstNode
+  /** Returns the URL of a document, "about:blank" if it was parsed without one, or null for other nodes. */
+  get URL(): string | null
+  /**
+   * Returns the absolute base URL of the node, from the first `<base href>` of its document
+   * or the document's URL.
+   */
+  get baseURI(): string
+  /**
+   * Rewrites relative URLs in URL attributes, `srcset`s and CSS `url()`s of this node and its
+   * descendants to absolute ones, resolved against `base` or the node's base URL.
+   */
+  absolutizeURLs(base?: string | undefined | null): void
+  /**
+   * Returns the form owner of a form control: the form its `form` attribute names, or its
+   * nearest ancestor form.
+   */
+  get form(): NodeRepr | null
+  /**
+   * Returns the controls of a form element in tree order, including those associated with
+   * it by their `form` attribute, but not image buttons.
+   */
+  get elements(): Array<NodeRepr> | null
+  /**
+   * Returns where the node came from in the input, if it was parsed with
+   * `sourceLocations` enabled, or null.
+   */
+  get sourceLocation(): SourceLocation | null
   /** Inserts a set of Node objects or DOMString objects after the last child of the Element. */
   append(newChild: NodeRepr): void
   /** Adds a node to the end of the list of children of a specified parent node. */
@@ -29,6 +58,12 @@ export declare class NodeRepr {
   removeAttributeNS(namespace: string | undefined | null, localName: string): void
   /** Creates the HTML element specified by tagName. */
   createElement(tagName: string): NodeRepr
+  /** Creates an element with the specified namespace URI and qualified name. */
+  createElementNS(namespace: string | undefined | null, qualifiedName: string): NodeRepr
+  /** Creates an attribute with the specified namespace URI and qualified name, to be added with `setAttributeNodeNS`. */
+  createAttributeNS(namespace: string | undefined | null, qualifiedName: string): Attr
+  /** Adds an attribute to the element, returning the attribute with the same namespace and local name it replaces. */
+  setAttributeNodeNS(attr: Attr): Attr | null
   /** Creates a new Text node. */
   createTextNode(data: string): NodeRepr
   /** Creates a new Comment node. */
@@ -98,6 +133,8 @@ export declare class NodeRepr {
   get publicId(): string | null
   /** Returns the system identifier of the document type. */
   get systemId(): string | null
+  /** Returns the MIME type of the document. */
+  get contentType(): string | null
   /** Returns the Document Type Declaration (DTD) associated with current document. */
   get doctype(): NodeRepr | null
   /** Returns the character data of the node. */
@@ -108,6 +145,14 @@ export declare class NodeRepr {
   get textContent(): string
   /** Sets the text content of the node and its descendants. */
   set textContent(text: string)
+  /** Returns the text of the element as rendered, with line breaks between blocks and hidden content skipped. */
+  get innerText(): string | null
+  /** Replaces the children of the element with the given text, converting line breaks to `<br>` elements. */
+  set innerText(text: string)
+  /** Returns the text of the element as rendered; same as `innerText`. */
+  get outerText(): string | null
+  /** Replaces the element with the given text, converting line breaks to `<br>` elements. */
+  set outerText(text: string)
   /** Returns a boolean value indicating whether the two nodes are the same (that is, they reference the same object). */
   isSameNode(otherNode: NodeRepr): boolean
   /** Returns the HTML serialization of the element's descendants. */
@@ -122,6 +167,8 @@ export declare class NodeRepr {
   set outerHTML(html: string)
   /** Returns the top-level document object for this node. */
   get ownerDocument(): NodeRepr | null
+  /** Returns the contents of a template element as a DocumentFragment. */
+  get content(): NodeRepr | null
   /** Returns a string representation of the object. */
   toString(): string
   /** Returns a live NodeList containing all the children of this node. */
@@ -185,22 +232,139 @@ export declare class NodeRepr {
   getElementsByClassName(classNames: string): Array<NodeRepr>
   /** Returns an HTMLCollection of elements with the given tag name. */
   getElementsByTagName(tagName: string): Array<NodeRepr>
+  /** Returns an HTMLCollection of elements with the given local name and namespace. */
+  getElementsByTagNameNS(namespace: string | undefined | null, localName: string): Array<NodeRepr>
   /** Returns a boolean value indicating whether a node is a descendant of a given node, that is the node itself, one of its direct children (childNodes), one of the children's direct children, and so on. */
   contains(otherNode: NodeRepr): boolean
   /** Returns a boolean value indicating whether the node is equal to the specified node. */
-  isEqualNode(otherNode: NodeRepr): boolean
+  isEqualNode(otherNode?: NodeRepr | undefined | null): boolean
+  /** Returns the first difference that makes `isEqualNode` false, or null if the nodes are equal. */
+  diff(otherNode: NodeRepr): NodeDifference | null
   /** Returns the head element of the document. */
   get head(): NodeRepr | null
   /** Returns the body element of the document. */
   get body(): NodeRepr | null
-  /** Returns the title of the document. */
+  /** Returns the title of the document, or the title attribute of an element. */
   get title(): string
+  /** Sets the title of the document, or the title attribute of an element. */
+  set title(value: string)
   /** Returns the Element that is the root element of the document (for example, the <html> element for HTML documents). */
   get documentElement(): NodeRepr | null
   /** Returns a boolean value indicating whether the element would be selected by the specified selector string. */
   matches(selectors: string): boolean
   /** Returns the closest ancestor of the current element (or the current element itself) which matches the selectors given in parameter. */
   closest(selectors: string): NodeRepr | null
+  /** Returns or sets the URL of an `a`, `area`, `base` or `link` element. */
+  get href(): string | null
+  set href(value: string)
+  /** Returns or sets the URL a `form` element submits to. */
+  get action(): string | null
+  set action(value: string)
+  /** Returns or sets the HTTP method a `form` element submits with: "get", "post" or "dialog". */
+  get method(): string | null
+  set method(value: string)
+  /** Returns or sets the encoding a `form` element submits its entries with. */
+  get enctype(): string | null
+  set enctype(value: string)
+  /** Returns or sets the URL a submit button overrides its form's action with. */
+  get formAction(): string | null
+  set formAction(value: string)
+  /** Returns or sets the method a submit button overrides its form's method with. */
+  get formMethod(): string | null
+  set formMethod(value: string)
+  /** Returns or sets the encoding a submit button overrides its form's enctype with. */
+  get formEnctype(): string | null
+  set formEnctype(value: string)
+  /** Returns or sets the URL of an embedding element such as `img`, `script` or `iframe`. */
+  get src(): string | null
+  set src(value: string)
+  /** Returns or sets whether the element is hidden. */
+  get hidden(): boolean | null
+  set hidden(value: boolean)
+  /** Returns or sets the language of the element. */
+  get lang(): string | null
+  set lang(value: string)
+  /** Returns or sets the text direction of the element: "ltr", "rtl", "auto" or "". */
+  get dir(): string | null
+  set dir(value: string)
+  /** Returns or sets the tab order of the element. */
+  get tabIndex(): number | null
+  set tabIndex(value: number)
+  /** Returns or sets whether an input element is checked. */
+  get checked(): boolean | null
+  set checked(value: boolean)
+  /** Returns or sets the value of a form control. */
+  get value(): string | null
+  set value(value: string)
+  /** Returns or sets the default value of an input or textarea element. */
+  get defaultValue(): string | null
+  set defaultValue(value: string)
+  /** Returns or sets the index of the first selected option of a select element. */
+  get selectedIndex(): number | null
+  set selectedIndex(value: number)
+  /** Returns or sets whether an option element is selected. */
+  get selected(): boolean | null
+  set selected(value: boolean)
+  /** Returns the HTML serialization of the node, formatted according to the options. */
+  serialize(options?: SerializeOptions | undefined | null): string
+  /**
+   * Converts the node and its descendants to Markdown.
+   * Elements with a "custom" rule are passed to `handler` with their converted contents;
+   * returning null or undefined keeps the default conversion.
+   */
+  _toMarkdown(options?: MarkdownOptions | undefined | null, handler?: ((arg0: NodeRepr, arg1: string) => string | null) | undefined | null): string
+  /** Returns the XML serialization of the node, throwing if it is not well-formed and that is required. */
+  _serializeToXml(requireWellFormed?: boolean | undefined | null): string
+  /** Returns the node and its descendants in the binary snapshot format, loadable with `fromSnapshot`. */
+  toSnapshot(): Buffer
+  /** Attaches a shadow DOM tree to the element and returns its ShadowRoot. */
+  attachShadow(init: ShadowRootInit): NodeRepr
+  /** Returns the open shadow root attached to the element, or null. */
+  get shadowRoot(): NodeRepr | null
+  /** Returns the host element of a ShadowRoot. */
+  get host(): NodeRepr | null
+  /** Returns the mode of a ShadowRoot, either "open" or "closed". */
+  get mode(): string | null
+  /** Returns the slot assignment mode of a ShadowRoot, either "named" or "manual". */
+  get slotAssignment(): string | null
+  /** Returns whether a ShadowRoot delegates focus. */
+  get delegatesFocus(): boolean
+  /** Returns whether a ShadowRoot is cloned along with its host. */
+  get clonable(): boolean
+  /** Returns whether a ShadowRoot is serialized by getHTML with `serializableShadowRoots`. */
+  get serializable(): boolean
+  /** Returns the nodes assigned to a slot element. */
+  _assignedNodes(flatten?: boolean | undefined | null): Array<NodeRepr>
+  /** Returns the elements assigned to a slot element. */
+  _assignedElements(flatten?: boolean | undefined | null): Array<NodeRepr>
+  /** Returns the slot element the node is assigned to. */
+  get assignedSlot(): NodeRepr | null
+  /** Parses the html, including declarative shadow roots, and replaces the children with the result. */
+  setHTMLUnsafe(html: string): void
+  /**
+   * Parses the html, removes what the sanitizer (or the default one) does not allow,
+   * and replaces the children with the result.
+   */
+  _setHTML(html: string, sanitizer?: Sanitizer | undefined | null): void
+  /** Returns the HTML serialization of the children, optionally including shadow roots. */
+  _getHTML(serializableShadowRoots?: boolean | undefined | null, shadowRoots?: Array<NodeRepr> | undefined | null): string
+  /**
+   * Returns whether the element matches the selectors, evaluated inside the shadow tree of `host`,
+   * so that `:host`, `::slotted()` and `::part()` can match.
+   */
+  matchesInShadowTree(selectors: string, host: NodeRepr): boolean
+  _styleGetPropertyValue(name: string): string
+  _styleGetPropertyPriority(name: string): string
+  _styleSetProperty(name: string, value: string, priority: string): void
+  _styleRemoveProperty(name: string): string
+  _styleGetCssText(): string
+  _styleSetCssText(cssText: string): void
+  _styleProperties(): Array<string>
+  /**
+   * Returns whether the element is rendered, from the cascade of the default stylesheet, the
+   * `<style>` elements of its tree and its `style` attribute.
+   */
+  checkVisibility(options?: CheckVisibilityOptions | undefined | null): boolean
   /** The node object, cann't be instantiated in javascript. So call the constructor will throw an error. */
   constructor(): void
   /**
@@ -211,5 +375,436 @@ export declare class NodeRepr {
   cloneNode(deep?: boolean | undefined | null): NodeRepr
 }
 
+/** Applies a patch returned by `domDiff` to the node, in order. */
+/** Removes disallowed elements and attributes from DOM trees. */
+export declare class Sanitizer {
+  /** Creates a sanitizer from the configuration, or with the default allowlists if there is none. */
+  constructor(config?: SanitizerConfig | undefined | null)
+  /** Sanitizes the node's attributes and descendants in place. */
+  sanitize(node: NodeRepr): void
+}
+
+export declare function applyPatch(node: NodeRepr, patch: Patch): void
+
+/** Creates a new XML document with an optional doctype and document element. */
+export declare function createDocument(namespace: string | undefined | null, qualifiedName: string, doctype?: NodeRepr | undefined | null): NodeRepr
+
+/** Creates a new DocumentType node. */
+export declare function createDocumentType(qualifiedName: string, publicId: string, systemId: string): NodeRepr
+
+/** Creates a new empty XML document, backing the `Document` constructor. */
+export declare function createEmptyDocument(): NodeRepr
+
+/** Computes the patch that turns `oldNode` into a tree equal to `newNode`, as plain objects. */
+export declare function domDiff(oldNode: NodeRepr, newNode: NodeRepr, options?: DomDiffOptions | undefined | null): Patch
+
+/** Extracts the main article of a document, or null if it has no text. The document is not changed. */
+export declare function extractArticle(document: NodeRepr, options?: ArticleOptions | undefined | null): Article | null
+
+/** Extracts the page metadata of a document in one call, with JSON-LD blocks as text. */
+export declare function extractMetadata(node: NodeRepr): Metadata
+
+/** Returns the URLs referenced by the node and its descendants, in tree order, in one call. */
+export declare function extractResources(node: NodeRepr): Array<Resource>
+
+/**
+ * Computes the style of an element from the cascade of the default stylesheet, the `<style>`
+ * elements of its document or shadow tree, the given stylesheets and its `style` attribute,
+ * without layout. The values are keyed by property name in alphabetical order, and there are
+ * none for elements that are not in a document.
+ */
+export declare function getComputedStyle(element: NodeRepr, stylesheets?: Array<string> | undefined | null): Record<string, string>
+
+/**
+ * Moves the style rules of a document's `<style>` elements into the `style` attributes of the
+ * elements they match, keeping the rules that cannot be inlined.
+ */
+export declare function inlineCSS(document: NodeRepr, options?: InlineCssOptions | undefined | null): void
+
+/**
+ * Reports which style rules of the CSS match an element of the tree rooted at `root`, ignoring
+ * state pseudo-classes, and returns the CSS pruned of the others.
+ */
+export declare function cssCoverage(root: NodeRepr, css: string): CssCoverage
+
+/**
+ * Constructs the entry list a form element would submit with `submitter`, one of its submit
+ * buttons, as the `FormData` constructor does.
+ */
+export declare function formEntries(form: NodeRepr, submitter?: NodeRepr | undefined | null, coordinates?: Coordinates | undefined | null): Array<FormEntry>
+
+/**
+ * Builds the request a browser would make to submit a form element with `submitter`, its
+ * default button if omitted, encoding the entries by the resolved method and enctype.
+ * Throws if the action is not a valid URL.
+ */
+export declare function submitForm(form: NodeRepr, submitter?: NodeRepr | undefined | null, options?: SubmitFormOptions | undefined | null): FormSubmission
+
+/** Builds a new tree from a JSON AST of plain objects, as produced by `toJSON`. */
+export declare function fromJSON(ast: AstNode): NodeRepr
+
+/** Builds a new tree from a snapshot returned by `toSnapshot`. */
+export declare function fromSnapshot(snapshot: Buffer): NodeRepr
+
+/** Creates a new HTML document with a doctype, html, head, optional title and body. */
+export declare function createHTMLDocument(title?: string | undefined | null): NodeRepr
+
 /** Parse string input to a html tree, return the root node. */
-export declare function parse(html: string): NodeRepr
+export declare function parse(html: string, options?: ParseOptions | undefined | null): NodeRepr
+
+/** The main article of a page, returned by `extractArticle`. */
+export interface Article {
+  title?: string
+  byline?: string
+  /** The page description, or the first paragraph of the article. */
+  excerpt?: string
+  siteName?: string
+  language?: string
+  /** A detached `<div>` holding the cleaned article, with absolute URLs. */
+  content: NodeRepr
+  /** The text of the article with white space collapsed. */
+  textContent: string
+  /** The length of `textContent` in characters. */
+  length: number
+}
+
+/** Options for `extractArticle`. */
+export interface ArticleOptions {
+  /** The number of characters an article needs before the heuristics are relaxed. Defaults to 500. */
+  charThreshold?: number
+  /** Keep `class` attributes in the article. Defaults to false. */
+  keepClasses?: boolean
+}
+
+/** A namespaced attribute as returned by `createAttributeNS`. */
+export interface Attr {
+  namespaceURI?: string
+  prefix?: string
+  localName: string
+  /** The qualified name. */
+  name: string
+  value: string
+}
+
+/** Options for `inlineCSS`. */
+export interface InlineCssOptions {
+  /** Keep `!important` on declarations inlined from stylesheets. Defaults to false. */
+  preserveImportant?: boolean
+  /** Remove class names that the remaining rules do not use. Defaults to false. */
+  removeClasses?: boolean
+  /** Remove ids that the remaining rules do not use. Defaults to false. */
+  removeIds?: boolean
+}
+
+/** A node of the JSON AST returned by `toJSON`. */
+export type AstNode =
+  | { type: 'document'; contentType: string; scriptingEnabled?: boolean; children: Array<AstNode> }
+  | { type: 'documentType'; name: string; publicId: string; systemId: string }
+  | AstElement
+  | { type: 'text'; value: string }
+  | { type: 'comment'; value: string }
+  | { type: 'processingInstruction'; target: string; data: string }
+  | { type: 'documentFragment'; children: Array<AstNode> }
+  | ({ type: 'shadowRoot' } & AstShadowRoot)
+
+export interface AstElement {
+  type: 'element'
+  name: string
+  namespace?: string
+  prefix?: string
+  attributes: Array<AstAttribute>
+  children: Array<AstNode>
+  content?: Array<AstNode>
+  shadowRoot?: AstShadowRoot
+  mathmlAnnotationXmlIntegrationPoint?: boolean
+  location?: AstLocation
+}
+
+export interface AstLocation {
+  startOffset: number
+  endOffset: number
+  startLine: number
+  startColumn: number
+  endLine: number
+  endColumn: number
+}
+
+export interface AstAttribute {
+  name: string
+  namespace?: string
+  prefix?: string
+  value: string
+}
+
+export interface AstShadowRoot {
+  mode: string
+  delegatesFocus?: boolean
+  slotAssignment?: string
+  clonable?: boolean
+  serializable?: boolean
+  children: Array<AstNode>
+}
+
+/** Options for `checkVisibility`, as in the DOM. */
+export interface CheckVisibilityOptions {
+  /** Also return false for elements with an `opacity` of 0, or inside one. */
+  checkOpacity?: boolean
+  /** Also return false for elements whose `visibility` is not `visible`. */
+  checkVisibilityCSS?: boolean
+  /** An alias of `checkOpacity`. */
+  opacityProperty?: boolean
+  /** An alias of `checkVisibilityCSS`. */
+  visibilityProperty?: boolean
+}
+
+/** The point clicked on an image button. */
+export interface Coordinates {
+  x: number
+  y: number
+}
+
+/** The result of `cssCoverage`. */
+export interface CssCoverage {
+  /** The style rules of the stylesheet in source order, including those in at-rules. */
+  rules: Array<RuleCoverage>
+  /** The stylesheet without unused style rules and selectors. */
+  prunedCSS: string
+}
+
+/** Options for `domDiff`. */
+export interface DomDiffOptions {
+  /** Match elements by the value of this attribute, such as "id", before falling back to their position. */
+  key?: string
+}
+
+/** An edit script returned by `domDiff`. */
+export interface Patch {
+  operations: Array<PatchOperation>
+}
+
+/** A single edit of a `Patch`; paths are child indices from the patched node. */
+export type PatchOperation =
+  | { type: 'insert'; parent: Array<number>; index: number; node: AstNode }
+  | { type: 'remove'; path: Array<number> }
+  | { type: 'move'; parent: Array<number>; from: number; to: number }
+  | { type: 'replace'; path: Array<number>; node: AstNode }
+  | { type: 'setAttribute'; path: Array<number>; namespace?: string; name: string; value: string }
+  | { type: 'removeAttribute'; path: Array<number>; namespace?: string; name: string }
+  | { type: 'setText'; path: Array<number>; value: string }
+
+/** Options for `toMarkdown`. */
+export interface MarkdownOptions {
+  /** "atx" or "setext". Defaults to "atx". */
+  headingStyle?: string
+  /** "-", "*" or "+". Defaults to "-". */
+  bulletListMarker?: string
+  /** "fenced" or "indented". Defaults to "fenced". */
+  codeBlockStyle?: string
+  /** "```" or "~~~". Defaults to "```". */
+  fence?: string
+  /** "_" or "*". Defaults to "_". */
+  emDelimiter?: string
+  /** "**" or "__". Defaults to "**". */
+  strongDelimiter?: string
+  /** Write GFM tables, strikethrough and task lists. Defaults to true. */
+  gfm?: boolean
+  /** Per-tag rules: "remove", "keep", "content" or "custom". */
+  rules?: Record<string, string>
+}
+
+/** The first place where two trees are not equal, returned by `diff`. */
+export interface NodeDifference {
+  /** Child indices leading from the compared nodes down to the nodes that differ. */
+  path: Array<number>
+  /** The property that differs, such as "localName", "attributes", "data" or "childNodes.length". */
+  property: string
+  /** The value on this node. */
+  value?: string
+  /** The value on the other node. */
+  otherValue?: string
+}
+
+/** An entry of a form's entry list, returned by `formEntries`. */
+export interface FormEntry {
+  name: string
+  value: string
+  /** The file name of a file input's entry, which is always empty. */
+  filename?: string
+}
+
+/** The request a browser would make to submit a form, returned by `submitForm`. */
+export interface FormSubmission {
+  /** The URL to submit to. For the "get" method, the entries are its query. */
+  url: string
+  /** "get", "post" or "dialog". */
+  method: string
+  enctype: string
+  entries: Array<FormEntry>
+  /** The request body, for the "post" method. */
+  body?: string
+  /** The `Content-Type` of the body, with its boundary for `multipart/form-data`. */
+  contentType?: string
+}
+
+/** An item described by microdata or RDFa Lite. */
+export interface MetadataItem {
+  types: Array<string>
+  id?: string
+  properties: Record<string, Array<string | MetadataItem>>
+}
+
+/** Page metadata returned by `extractMetadata`. */
+export interface Metadata {
+  title?: string
+  description?: string
+  canonical?: string
+  language?: string
+  icons: Array<{ rel: string; href: string; sizes?: string; type?: string }>
+  openGraph: Record<string, Array<string>>
+  twitter: Record<string, Array<string>>
+  jsonLd: Array<string>
+  microdata: Array<MetadataItem>
+  rdfa: Array<MetadataItem>
+}
+
+/** Options for `parse`. */
+export interface ParseOptions {
+  /** Record where each node came from in the input, read back with `sourceLocation`. Defaults to false. */
+  sourceLocations?: boolean
+  /** The URL of the document, used to resolve relative URLs. Defaults to "about:blank". */
+  url?: string
+}
+
+/** A URL referenced by an element, returned by `extractResources`. */
+export interface Resource {
+  /** "hyperlink", "stylesheet", "link", "script", "image", "media", "frame", "embed", "form" or "css". */
+  kind: 'hyperlink' | 'stylesheet' | 'link' | 'script' | 'image' | 'media' | 'frame' | 'embed' | 'form' | 'css'
+  element: NodeRepr
+  /** The attribute holding the URL, or undefined for the text of a `<style>` element. */
+  attribute?: string
+  /** The URL as written; the whole attribute value for `srcset` and `imagesrcset`. */
+  url: string
+  /** The URL resolved against the base URL, if it can be resolved. Undefined for `srcset`s. */
+  resolvedUrl?: string
+  rel?: string
+  srcset?: Array<SrcsetCandidate>
+  integrity?: string
+  crossorigin?: string
+  media?: string
+}
+
+/** Options for `new Sanitizer`. Lists that are left out allow everything not removed. */
+/** The usage of a style rule, returned by `cssCoverage`. */
+export interface RuleCoverage {
+  selectorText: string
+  /** The at-rules the rule is nested in, such as `@media print`, outermost first. */
+  conditions: Array<string>
+  /** Whether a selector of the rule matches an element. */
+  used: boolean
+  /** The selectors of the list that match an element. */
+  usedSelectors: Array<string>
+}
+
+export interface SanitizerConfig {
+  /** Elements to keep; others are removed with their contents. */
+  elements?: Array<string | SanitizerElement>
+  /** Elements removed with their contents. */
+  removeElements?: Array<string | SanitizerName>
+  /** Elements replaced by their children. */
+  replaceWithChildrenElements?: Array<string | SanitizerName>
+  /** Attributes allowed on every element. */
+  attributes?: Array<string | SanitizerName>
+  /** Attributes removed from every element. */
+  removeAttributes?: Array<string | SanitizerName>
+  /** Keep comments. Defaults to false. */
+  comments?: boolean
+  /** Keep `data-*` attributes. Defaults to true. */
+  dataAttributes?: boolean
+  /**
+   * URL schemes allowed in `href`, `src`, `srcset`, `action` and similar attributes.
+   * Defaults to "http", "https", "mailto" and "tel".
+   */
+  urlSchemes?: Array<string>
+}
+
+/** An allowed element with the attributes allowed or removed on it. */
+export interface SanitizerElement {
+  name: string
+  namespace?: string
+  attributes?: Array<string | SanitizerName>
+  removeAttributes?: Array<string | SanitizerName>
+}
+
+/** An element or attribute name with its namespace. */
+export interface SanitizerName {
+  name: string
+  /** Defaults to the HTML namespace for elements and to no namespace for attributes. */
+  namespace?: string
+}
+
+/** Options for `serialize`. */
+export interface SerializeOptions {
+  /** Put block-level elements on their own lines. Defaults to false. */
+  pretty?: boolean
+  /** The indentation used when pretty-printing. Defaults to two spaces. */
+  indent?: string
+  /** Collapse whitespace, remove comments and omit optional tags. Defaults to false. */
+  minify?: boolean
+  /** "double", "single" or "minimal". Defaults to "minimal" when minifying and "double" otherwise. */
+  quote?: string
+  /** Write attributes in alphabetical order. Defaults to false. */
+  sortAttributes?: boolean
+  /** Write void elements as `<br />`. Defaults to false. */
+  selfClosingVoid?: boolean
+}
+
+/** Where a node came from in the input, returned by `sourceLocation`. */
+export interface SourceLocation {
+  startOffset: number
+  endOffset: number
+  startLine: number
+  startColumn: number
+  endLine: number
+  endColumn: number
+  /** The start tag of an element. */
+  startTag?: SourceSpan
+  /** The end tag of an element, if it had one in the input. */
+  endTag?: SourceSpan
+  /** The attributes of an element's start tag by qualified name. */
+  attrs?: Record<string, SourceSpan>
+}
+
+/** A range of the input, with 0-based byte offsets and 1-based lines and columns. */
+export interface SourceSpan {
+  startOffset: number
+  endOffset: number
+  startLine: number
+  startColumn: number
+  endLine: number
+  endColumn: number
+}
+
+/** Options for `submitForm`. */
+export interface SubmitFormOptions {
+  /** The point clicked on an image button submitter. Defaults to (0, 0). */
+  coordinates?: Coordinates
+  /** The boundary of a `multipart/form-data` body. Defaults to a random one. */
+  boundary?: string
+}
+
+/** One candidate of a `srcset` attribute. */
+export interface SrcsetCandidate {
+  url: string
+  resolvedUrl?: string
+  /** The width or density descriptor, such as "480w" or "2x". */
+  descriptor?: string
+}
+
+/** Options for `attachShadow`. */
+export interface ShadowRootInit {
+  mode: string
+  delegatesFocus?: boolean
+  slotAssignment?: string
+  clonable?: boolean
+  serializable?: boolean
+}
@@ -573,4 +573,21 @@ if (!nativeBinding) {
 
 module.exports = nativeBinding
 module.exports.NodeRepr = nativeBinding.NodeRepr
+module.exports.Sanitizer = nativeBinding.Sanitizer
+module.exports.applyPatch = nativeBinding.applyPatch
+module.exports.createDocument = nativeBinding.createDocument
+module.exports.createDocumentType = nativeBinding.createDocumentType
+module.exports.createEmptyDocument = nativeBinding.createEmptyDocument
+module.exports.createHTMLDocument = nativeBinding.createHTMLDocument
+module.exports.cssCoverage = nativeBinding.cssCoverage
+module.exports.domDiff = nativeBinding.domDiff
+module.exports.extractArticle = nativeBinding.extractArticle
+module.exports.extractMetadata = nativeBinding.extractMetadata
+module.exports.extractResources = nativeBinding.extractResources
+module.exports.formEntries = nativeBinding.formEntries
+module.exports.fromJSON = nativeBinding.fromJSON
+module.exports.fromSnapshot = nativeBinding.fromSnapshot
+module.exports.getComputedStyle = nativeBinding.getComputedStyle
+module.exports.inlineCSS = nativeBinding.inlineCSS
 module.exports.parse = nativeBinding.parse
+module.exports.submitForm = nativeBinding.submitForm
@@ -0,0 +1,80 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, extractArticle } = pkg;
+
+const parse = (html, url) => new DOMParser({ url }).parseFromString(html, 'text/html');
+
+const paragraph = (n) => `<p>Paragraph ${n} of the story, which goes on for a while, with commas, clauses, and enough words to count as real prose for the scorer.</p>`;
+
+const page = (body) => parse(
+  '<html lang="en"><head><title>The Big Story | Example News</title>'
+    + '<meta property="og:site_name" content="Example News"></head>'
+    + `<body>${body}</body></html>`,
+  'https://news.example/2024/story.html',
+);
+
+test('finds the main content and drops boilerplate', () => {
+  const doc = page(
+    '<header class="site-header"><nav><a href="/">Home</a> <a href="/world">World</a></nav></header>'
+      + '<div class="sidebar"><a href="/a">Related one</a>, <a href="/b">Related two</a></div>'
+      + '<div id="main" class="article-body"><h1>The Big Story</h1><p class="byline">By Jane Doe</p>'
+      + [1, 2, 3, 4, 5].map(paragraph).join('')
+      + '<p><img src="photo.jpg" class="wide" style="width: 100px" onclick="zoom()"></p>'
+      + '<p></p><script>track()</script></div>'
+      + '<div class="comments">' + '<a href="/c">comment</a> '.repeat(20) + '</div>'
+      + '<footer class="footer">Copyright</footer>',
+  );
+  const article = extractArticle(doc);
+  assert.equal(article.title, 'The Big Story | Example News');
+  assert.equal(article.byline, 'By Jane Doe');
+  assert.equal(article.siteName, 'Example News');
+  assert.equal(article.language, 'en');
+  assert.match(article.excerpt, /^Paragraph 1 of the story/);
+
+  const { content } = article;
+  assert.equal(content.tagName, 'DIV');
+  assert.equal(content.querySelectorAll('p').length, 6);
+  assert.equal(content.querySelector('h1, script, nav, footer, .byline'), null);
+  assert.doesNotMatch(content.textContent, /Home|Related|comment|Copyright/);
+  const img = content.querySelector('img');
+  assert.equal(img.getAttribute('src'), 'https://news.example/2024/photo.jpg');
+  assert.equal(img.hasAttribute('class'), false);
+  assert.equal(img.hasAttribute('style'), false);
+  assert.equal(img.hasAttribute('onclick'), false);
+  assert.equal(article.length, article.textContent.length);
+  assert.match(article.textContent, /^Paragraph 1 .*scorer\.Paragraph 5 /);
+
+  // The document itself is left alone.
+  assert.notEqual(doc.querySelector('nav'), null);
+  assert.notEqual(doc.querySelector('script'), null);
+});
+
+test('prefers metadata for the title, byline and excerpt', () => {
+  const doc = parse(
+    '<head><title>Site name - A very long and descriptive title here</title>'
+      + '<meta name="author" content="Sam"><meta name="description" content="Summary.">'
+      + '<meta name="twitter:title" content="Card title"></head>'
+      + `<body><article>${[1, 2, 3].map(paragraph).join('')}</article></body>`,
+  );
+  const article = extractArticle(doc, { keepClasses: true });
+  assert.equal(article.title, 'Card title');
+  assert.equal(article.byline, 'Sam');
+  assert.equal(article.excerpt, 'Summary.');
+  assert.equal(article.siteName, undefined);
+});
+
+test('cleans the document title', () => {
+  const title = (html) => extractArticle(parse(`<title>${html}</title><p>${'word '.repeat(50)}</p>`)).title;
+  assert.equal(title('Site name - A very long and descriptive title here'), 'A very long and descriptive title here');
+  assert.equal(title('A very long and descriptive title here | Site'), 'A very long and descriptive title here');
+  assert.equal(title('Short | Site'), 'Short | Site');
+  assert.equal(title('Home » Install'), 'Install');
+});
+
+test('keeps classes on request and returns null without text', () => {
+  const doc = page(`<div class="content">${[1, 2, 3].map((n) => paragraph(n).replace('<p>', '<p class="lead">')).join('')}</div>`);
+  assert.equal(extractArticle(doc, { keepClasses: true }).content.querySelectorAll('p.lead').length, 3);
+  assert.equal(extractArticle(doc).content.querySelector('.lead'), null);
+  assert.equal(extractArticle(parse('<div><img src="a.png"></div>')), null);
+});
@@ -0,0 +1,96 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser } = pkg;
+
+const parse = (html, url) => new DOMParser({ url }).parseFromString(html, 'text/html');
+
+test('documents default to about:blank', () => {
+  const doc = parse('<a href="/x">x</a><img src="a.png">');
+  assert.equal(doc.URL, 'about:blank');
+  assert.equal(doc.baseURI, 'about:blank');
+  assert.equal(doc.querySelector('a').href, '/x');
+  assert.equal(doc.querySelector('img').src, 'a.png');
+});
+
+test('resolves reflected URLs against the document URL', () => {
+  const doc = parse('<a href="../b?q#f">b</a><img src="img/a.png"><link href="//cdn.example.com/s.css"><form></form><form action="post">', 'https://example.com/docs/page.html');
+  assert.equal(doc.URL, 'https://example.com/docs/page.html');
+  assert.equal(doc.querySelector('a').href, 'https://example.com/b?q#f');
+  assert.equal(doc.querySelector('img').src, 'https://example.com/docs/img/a.png');
+  assert.equal(doc.querySelector('link').href, 'https://cdn.example.com/s.css');
+  const [empty, form] = doc.querySelectorAll('form');
+  assert.equal(empty.action, 'https://example.com/docs/page.html');
+  assert.equal(form.action, 'https://example.com/docs/post');
+  form.action = '/submit';
+  assert.equal(form.getAttribute('action'), '/submit');
+  assert.equal(form.action, 'https://example.com/submit');
+});
+
+test('the first base element with an href sets the base URL', () => {
+  const doc = parse('<head><base target="_blank"><base href="/assets/"><base href="/ignored/"></head><a href="x">x</a>', 'https://example.com/docs/page.html');
+  assert.equal(doc.URL, 'https://example.com/docs/page.html');
+  assert.equal(doc.baseURI, 'https://example.com/assets/');
+  const a = doc.querySelector('a');
+  assert.equal(a.baseURI, 'https://example.com/assets/');
+  assert.equal(a.href, 'https://example.com/assets/x');
+  assert.equal(doc.createElement('a').baseURI, 'about:blank');
+  assert.equal(parse('<base href="https://other.example/">', undefined).baseURI, 'https://other.example/');
+});
+
+test('rejects invalid document URLs', () => {
+  assert.throws(() => parse('', 'not a url'), /Invalid document URL/);
+});
+
+test('absolutizeURLs rewrites URL attributes, srcset and CSS', () => {
+  const doc = parse(
+    '<a href="a.html">a</a><a href="mailto:x@example.com">m</a><a href="#top">t</a>'
+      + '<img src="i.png" srcset="s.png 1x, /l.png 2x, https://cdn.example/h.png 3x">'
+      + '<form action="go"><button formaction="alt">b</button></form>'
+      + '<div style="background: url(bg.png), url(\'#frag\'); mask: url( &quot;m.svg&quot; )"></div>'
+      + '<style>@import "base.css"; .x { background: url(x.png) }</style>'
+      + '<svg><use href="#icon"></use><image href="pic.png"></image></svg>'
+      + '<template><img src="t.png"></template>',
+    'https://example.com/dir/page.html',
+  );
+  doc.absolutizeURLs();
+  const body = doc.body.innerHTML;
+  assert.equal(
+    body,
+    '<a href="https://example.com/dir/a.html">a</a><a href="mailto:x@example.com">m</a><a href="https://example.com/dir/page.html#top">t</a>'
+      + '<img src="https://example.com/dir/i.png" srcset="https://example.com/dir/s.png 1x, https://example.com/l.png 2x, https://cdn.example/h.png 3x">'
+      + '<form action="https://example.com/dir/go"><button formaction="https://example.com/dir/alt">b</button></form>'
+      + '<div style="background: url(&quot;https://example.com/dir/bg.png&quot;), url(\'#frag\'); mask: url( &quot;https://example.com/dir/m.svg&quot; )"></div>'
+      + '<style>@import "https://example.com/dir/base.css"; .x { background: url("https://example.com/dir/x.png") }</style>'
+      + '<svg><use href="#icon"></use><image href="https://example.com/dir/pic.png"></image></svg>'
+      + '<template><img src="https://example.com/dir/t.png"></template>',
+  );
+});
+
+test('absolutizeURLs points empty form actions at the document URL', () => {
+  const doc = parse(
+    '<base href="/base/"><form action=""><button formaction="">b</button></form><form action="go"></form>',
+    'https://example.com/dir/page.html?q=1',
+  );
+  const [empty, relative] = doc.querySelectorAll('form');
+  assert.equal(empty.action, 'https://example.com/dir/page.html?q=1');
+  doc.absolutizeURLs();
+  assert.equal(empty.getAttribute('action'), 'https://example.com/dir/page.html?q=1');
+  assert.equal(doc.querySelector('button').getAttribute('formaction'), 'https://example.com/dir/page.html?q=1');
+  assert.equal(relative.getAttribute('action'), 'https://example.com/base/go');
+  assert.equal(empty.action, 'https://example.com/dir/page.html?q=1');
+
+  const blank = parse('<base href="https://example.com/base/"><form action=""></form>');
+  blank.absolutizeURLs();
+  assert.equal(blank.querySelector('form').getAttribute('action'), '');
+});
+
+test('absolutizeURLs takes an explicit base', () => {
+  const doc = parse('<div><img src="a.png"></div>');
+  const div = doc.querySelector('div');
+  div.absolutizeURLs();
+  assert.equal(div.innerHTML, '<img src="a.png">');
+  div.absolutizeURLs('https://example.org/x/');
+  assert.equal(div.innerHTML, '<img src="https://example.org/x/a.png">');
+  assert.throws(() => div.absolutizeURLs('nope'), /Invalid base URL/);
+});
@@ -0,0 +1,157 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, getComputedStyle } = pkg;
+
+const parser = new DOMParser();
+
+describe('getComputedStyle', () => {
+  it('applies the default stylesheet', () => {
+    const doc = parser.parseFromString('<p>Text <b>bold</b> <span>x</span></p><h1>Title</h1>', 'text/html');
+    assert.equal(getComputedStyle(doc.querySelector('p')).display, 'block');
+    assert.equal(getComputedStyle(doc.querySelector('p')).marginTop, '16px');
+    assert.equal(getComputedStyle(doc.querySelector('span')).display, 'inline');
+    assert.equal(getComputedStyle(doc.querySelector('b')).fontWeight, '700');
+    assert.equal(getComputedStyle(doc.querySelector('h1')).fontSize, '32px');
+    assert.equal(getComputedStyle(doc.querySelector('head')).display, 'none');
+    assert.equal(getComputedStyle(doc.body).getPropertyValue('margin-left'), '8px');
+  });
+
+  it('cascades by specificity, order and importance', () => {
+    const doc = parser.parseFromString(
+      `<style>
+        #a { color: red }
+        .b { color: blue; background-color: #0f0 !important }
+        p { color: green; margin-top: 0 }
+        p { margin-top: 4px }
+      </style>
+      <p id="a" class="b" style="background-color: white">x</p>`,
+      'text/html',
+    );
+    const style = getComputedStyle(doc.querySelector('p'));
+    assert.equal(style.color, 'rgb(255, 0, 0)');
+    assert.equal(style.backgroundColor, 'rgb(0, 255, 0)');
+    assert.equal(style.marginTop, '4px');
+
+    const inline = getComputedStyle(doc.querySelector('p'), { stylesheets: ['p { color: purple !important }'] });
+    assert.equal(inline.color, 'rgb(128, 0, 128)');
+  });
+
+  it('inherits and resolves relative values', () => {
+    const doc = parser.parseFromString(
+      `<style>
+        :root { --accent: teal; font-size: 20px }
+        div { font-size: 1.5em; color: var(--accent); border: 2px solid currentcolor; line-height: 150% }
+        span { font-size: 0.5rem; padding: 1em; width: 50% }
+        em { color: inherit; font-weight: bolder }
+      </style>
+      <div><span><em>x</em></span></div>`,
+      'text/html',
+    );
+    const div = getComputedStyle(doc.querySelector('div'));
+    assert.equal(div.fontSize, '30px');
+    assert.equal(div.color, 'rgb(0, 128, 128)');
+    assert.equal(div.borderTopColor, 'rgb(0, 128, 128)');
+    assert.equal(div.borderTopWidth, '2px');
+    assert.equal(div.borderLeftWidth, '2px');
+    assert.equal(div.lineHeight, '45px');
+    assert.equal(div.getPropertyValue('--accent'), 'teal');
+
+    const span = getComputedStyle(doc.querySelector('span'));
+    assert.equal(span.fontSize, '10px');
+    assert.equal(span.paddingLeft, '10px');
+    assert.equal(span.width, '50%');
+    assert.equal(span.color, 'rgb(0, 128, 128)');
+    assert.equal(span.borderTopWidth, '0px');
+    assert.equal(span.lineHeight, '45px');
+
+    const em = getComputedStyle(doc.querySelector('em'));
+    assert.equal(em.fontStyle, 'italic');
+    assert.equal(em.fontWeight, '700');
+  });
+
+  it('uses the initial font for system font keywords', () => {
+    const doc = parser.parseFromString('<div style="font: bold 20px Arial"><p style="font: menu">x</p></div>', 'text/html');
+    const p = getComputedStyle(doc.querySelector('p'));
+    assert.deepEqual([p.fontSize, p.fontWeight, p.fontFamily], ['16px', '400', 'serif']);
+  });
+
+  it('applies media and supports rules', () => {
+    const doc = parser.parseFromString(
+      `<style>
+        @media print { p { color: red } }
+        @media screen { p { margin-left: 3px } }
+        @media (min-width: 600px) { p { margin-right: 3px } }
+        @supports (display: grid) { p { display: grid } }
+      </style>
+      <style media="print">p { padding-top: 3px }</style>
+      <p>x</p>`,
+      'text/html',
+    );
+    const style = getComputedStyle(doc.querySelector('p'));
+    assert.equal(style.color, 'rgb(0, 0, 0)');
+    assert.equal(style.marginLeft, '3px');
+    assert.equal(style.marginRight, '0px');
+    assert.equal(style.display, 'grid');
+    assert.equal(style.paddingTop, '0px');
+  });
+
+  it('returns a read-only declaration', () => {
+    const doc = parser.parseFromString('<p style="color: red">x</p>', 'text/html');
+    const style = getComputedStyle(doc.querySelector('p'));
+    assert.ok(style.length > 50);
+    assert.equal(style.item(0), 'align-items');
+    assert.equal([...style][0], 'align-items');
+    assert.equal(style.getPropertyPriority('color'), '');
+    assert.throws(() => style.setProperty('color', 'blue'));
+    assert.throws(() => {
+      style.color = 'blue';
+    });
+    assert.throws(() => getComputedStyle(doc.querySelector('p').firstChild), TypeError);
+  });
+
+  it('is empty for elements that are not in a document', () => {
+    const doc = parser.parseFromString('<div><p style="color: red">x</p></div>', 'text/html');
+    const div = doc.querySelector('div');
+    div.remove();
+    for (const element of [div, div.firstChild, doc.createElement('p')]) {
+      const style = getComputedStyle(element);
+      assert.equal(style.length, 0);
+      assert.equal(style.display, '');
+      assert.equal(style.color, '');
+      assert.equal(element.checkVisibility(), false);
+    }
+  });
+});
+
+describe('checkVisibility', () => {
+  const doc = parser.parseFromString(
+    `<style>.gone { display: none } .ghost { visibility: hidden } .clear { opacity: 0 }</style>
+    <div id="shown">x</div>
+    <div class="gone"><p id="inside">x</p></div>
+    <p id="hidden" hidden>x</p>
+    <p id="ghost" class="ghost">x</p>
+    <div class="clear"><p id="faded">x</p></div>
+    <div style="display: contents" id="contents">x</div>
+    <div id="host"><template shadowrootmode="open"><slot name="a"></slot></template><p id="unslotted">x</p><p slot="a" id="slotted">x</p></div>`,
+    'text/html',
+  );
+
+  it('hides elements without a box', () => {
+    assert.equal(doc.getElementById('shown').checkVisibility(), true);
+    assert.equal(doc.getElementById('inside').checkVisibility(), false);
+    assert.equal(doc.getElementById('hidden').checkVisibility(), false);
+    assert.equal(doc.getElementById('contents').checkVisibility(), false);
+    assert.equal(doc.getElementById('slotted').checkVisibility(), true);
+    assert.equal(doc.getElementById('unslotted').checkVisibility(), false);
+    assert.equal(doc.createElement('p').checkVisibility(), false);
+  });
+
+  it('checks opacity and visibility on request', () => {
+    assert.equal(doc.getElementById('ghost').checkVisibility(), true);
+    assert.equal(doc.getElementById('ghost').checkVisibility({ checkVisibilityCSS: true }), false);
+    assert.equal(doc.getElementById('ghost').checkVisibility({ visibilityProperty: true }), false);
+    assert.equal(doc.getElementById('faded').checkVisibility(), true);
+    assert.equal(doc.getElementById('faded').checkVisibility({ checkOpacity: true }), false);
+  });
+});
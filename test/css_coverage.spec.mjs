@@ -0,0 +1,96 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, cssCoverage } = pkg;
+
+const parser = new DOMParser();
+
+const doc = parser.parseFromString(
+  `<header class="hero"><h1>Title</h1><a href="/" class="cta">Go</a></header>
+  <main><p class="intro">Hello</p><input type="checkbox"></main>
+  <footer><p>Bye</p></footer>`,
+  'text/html',
+);
+
+describe('cssCoverage', () => {
+  it('reports used and unused rules', () => {
+    const { rules } = cssCoverage(
+      doc,
+      `h1 { margin: 0 }
+      .missing, p.intro { color: red }
+      .sidebar { float: left }
+      @media (max-width: 600px) { .hero .cta { display: block } nav { display: none } }`,
+    );
+    assert.deepEqual(
+      rules.map(({ selectorText, used }) => [selectorText, used]),
+      [
+        ['h1', true],
+        ['.missing, p.intro', true],
+        ['.sidebar', false],
+        ['.hero .cta', true],
+        ['nav', false],
+      ],
+    );
+    assert.deepEqual(rules[1].usedSelectors, ['p.intro']);
+    assert.deepEqual(rules[0].conditions, []);
+    assert.deepEqual(rules[3].conditions, ['@media (max-width: 600px)']);
+  });
+
+  it('ignores state pseudo-classes and pseudo-elements', () => {
+    const { rules } = cssCoverage(
+      doc,
+      `a:hover { color: red }
+      .cta:focus-visible, .cta::after, p:first-line { outline: 0 }
+      input:checked { margin: 0 }
+      :hover { cursor: pointer }
+      video:hover { opacity: 1 }
+      p:first-child { margin: 0 }
+      h1:nth-child(2) { margin: 0 }`,
+    );
+    assert.deepEqual(
+      rules.map(({ used }) => used),
+      [true, true, true, true, false, true, false],
+    );
+    assert.deepEqual(rules[1].usedSelectors, ['.cta:focus-visible', '.cta::after', 'p:first-line']);
+  });
+
+  it('keeps negated states matching', () => {
+    const { rules } = cssCoverage(
+      doc,
+      `p:not(:hover) { color: red }
+      a:not(.x:focus) { color: red }
+      :not(:checked) { margin: 0 }
+      p:not(p) { margin: 0 }
+      video:not(:hover) { margin: 0 }`,
+    );
+    assert.deepEqual(
+      rules.map(({ used }) => used),
+      [true, true, true, false, false],
+    );
+  });
+
+  it('prunes the stylesheet', () => {
+    const { prunedCSS } = cssCoverage(
+      doc,
+      `@font-face { font-family: Brand; src: url(brand.woff) }
+      h1, h2 { margin: 0 }
+      .sidebar { float: left }
+      @media print { nav { display: none } }
+      @media screen { p { color: red } }`,
+    );
+    assert.equal(
+      prunedCSS,
+      '@font-face { font-family: Brand; src: url(brand.woff) }\nh1 { margin: 0; }\n@media screen { p { color: red; } }',
+    );
+  });
+
+  it('extracts critical CSS for a subtree', () => {
+    const css = 'body h1 { font-size: 3em } .cta { color: red } footer p { color: gray } main p { margin: 0 }';
+    const { prunedCSS, rules } = cssCoverage(doc.querySelector('header'), css);
+    assert.equal(prunedCSS, 'body h1 { font-size: 3em; }\n.cta { color: red; }');
+    assert.deepEqual(
+      rules.map(({ used }) => used),
+      [true, true, false, false],
+    );
+  });
+});
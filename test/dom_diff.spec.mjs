@@ -0,0 +1,114 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, domDiff, applyPatch } = pkg;
+
+const parse = (html) => new DOMParser().parseFromString(html, 'text/html');
+
+const roundTrip = (before, after, options) => {
+  const oldDoc = parse(before);
+  const newDoc = parse(after);
+  const patch = domDiff(oldDoc, newDoc, options);
+  applyPatch(oldDoc, JSON.stringify(patch));
+  assert.equal(oldDoc.diff(newDoc), null, `${before} -> ${after}`);
+  assert.equal(oldDoc.outerHTML, newDoc.outerHTML);
+  return patch.operations;
+};
+
+test('equal trees produce an empty patch', () => {
+  const html = '<div id="a" class="x"><p>text</p><!--c--></div>';
+  assert.deepEqual(domDiff(parse(html), parse(html)), { operations: [] });
+});
+
+test('keyed reordering becomes moves', () => {
+  const before = parse('<ul><li id="a">A</li><li id="b">B</li></ul>');
+  const after = parse('<ul><li id="b">B!</li><li id="a" class="on">A</li></ul>');
+  const patch = domDiff(before, after, { key: 'id' });
+  assert.deepEqual(patch, {
+    operations: [
+      { type: 'move', parent: [0, 1, 0], from: 1, to: 0 },
+      { type: 'setText', path: [0, 1, 0, 0, 0], value: 'B!' },
+      { type: 'setAttribute', path: [0, 1, 0, 1], name: 'class', value: 'on' },
+    ],
+  });
+  applyPatch(before, JSON.parse(JSON.stringify(patch)));
+  assert.equal(before.isEqualNode(after), true);
+});
+
+test('custom key attributes', () => {
+  const ops = roundTrip(
+    '<ul><li data-key="1">one</li><li data-key="2">two</li><li data-key="3">three</li></ul>',
+    '<ul><li data-key="3">three</li><li data-key="1">one</li><li data-key="4">four</li></ul>',
+    { key: 'data-key' },
+  );
+  assert.deepEqual(ops.map((op) => op.type), ['remove', 'move', 'insert']);
+});
+
+test('attribute changes', () => {
+  const before = parse('<p a="1" b="2" c="3">x</p>');
+  const after = parse('<p c="3" a="one" d="4">x</p>');
+  const patch = domDiff(before, after);
+  applyPatch(before, patch);
+  assert.equal(before.isEqualNode(after), true);
+  assert.deepEqual(patch.operations, [
+    { type: 'removeAttribute', path: [0, 1, 0], name: 'b' },
+    { type: 'setAttribute', path: [0, 1, 0], name: 'a', value: 'one' },
+    { type: 'setAttribute', path: [0, 1, 0], name: 'd', value: '4' },
+  ]);
+  roundTrip('<svg><use xlink:href="#a"/></svg>', '<svg><use xlink:href="#b"/></svg>');
+  roundTrip('<svg><use xlink:href="#a"/></svg>', '<svg><use/></svg>');
+});
+
+test('inserts, removes and replaces', () => {
+  const ops = roundTrip('<p>a</p><div>b</div><p>c</p>', '<p>a</p><span>b</span><p>c</p><p>d</p>');
+  assert.deepEqual(ops.map((op) => op.type), ['replace', 'insert']);
+  assert.deepEqual(ops[0].path, [0, 1, 1]);
+  assert.equal(ops[0].node.name, 'span');
+
+  roundTrip('<p>a</p><p>b</p><p>c</p>', '<p>a</p><p>c</p>');
+  roundTrip('<p>a</p>', '<h1>t</h1><p>a</p>');
+  roundTrip('<p>a<!--x--></p>', '<p>a<!--y--></p>');
+  roundTrip('<p>a</p>', '<p></p>');
+  roundTrip('<table><tr><td>1</td></tr></table>', '<table><tr><td>1</td><td>2</td></tr><tr><td>3</td></tr></table>');
+  roundTrip('<template id="t"><b>x</b></template>', '<template id="t"><i>x</i></template>');
+  roundTrip('<div id="h"><template shadowrootmode="open">a</template></div>', '<div id="h"><template shadowrootmode="open">b</template></div>');
+  roundTrip('<!DOCTYPE html><p>a</p>', '<p>a</p>');
+});
+
+test('mixed keyed and unkeyed children', () => {
+  const pages = [
+    '<div id="a">A</div>text<p>1</p><div id="b">B</div><p>2</p>',
+    '<p>1</p><div id="b">B</div>text<div id="c">C</div><div id="a">A2</div>',
+    '<div id="c">C</div>',
+    '',
+    '<section><div id="a">A</div><div id="b"><span id="a">nested</span></div></section>',
+  ];
+  for (const before of pages) {
+    for (const after of pages) {
+      roundTrip(before, after, { key: 'id' });
+      roundTrip(before, after);
+    }
+  }
+});
+
+test('diffs elements and fragments', () => {
+  const before = parse('<ul><li>a</li></ul>').querySelector('ul');
+  const after = parse('<ul class="x"><li>b</li><li>c</li></ul>').querySelector('ul');
+  applyPatch(before, domDiff(before, after));
+  assert.equal(before.outerHTML, '<ul class="x"><li>b</li><li>c</li></ul>');
+
+  const doc = parse('<p>a</p>');
+  const root = domDiff(doc.body.firstChild, parse('<div>a</div>').body.firstChild);
+  assert.deepEqual(root.operations.map((op) => [op.type, op.path]), [['replace', []]]);
+  applyPatch(doc.body.firstChild, root);
+  assert.equal(doc.body.innerHTML, '<div>a</div>');
+});
+
+test('applyPatch rejects patches that do not fit', () => {
+  const doc = parse('<p>a</p>');
+  assert.throws(() => applyPatch(doc, { operations: [{ type: 'remove', path: [5] }] }), /no node at \[5\]/);
+  assert.throws(() => applyPatch(doc, { operations: [{ type: 'setText', path: [0] }] }), /requires the 'value' property/);
+  assert.throws(() => applyPatch(doc, { operations: [{ type: 'setText', path: [0], value: 'x' }] }), /not a text node/);
+  assert.throws(() => applyPatch(doc, { operations: [{ type: 'bogus' }] }), /not a valid patch operation/);
+  assert.throws(() => applyPatch(doc, { operations: [{ type: 'replace', path: [], node: { type: 'text', value: '' } }] }), /root without a parent cannot be replaced/);
+});
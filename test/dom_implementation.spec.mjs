@@ -0,0 +1,75 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, Document } = pkg;
+
+const SVG_NS = 'http://www.w3.org/2000/svg';
+
+test('should create an HTML document skeleton', () => {
+  const source = new DOMParser().parseFromString('', 'text/html');
+  const doc = source.implementation.createHTMLDocument('Hello');
+
+  assert.strictEqual(doc.nodeType, 9);
+  assert.strictEqual(doc.contentType, 'text/html');
+  assert.strictEqual(doc.doctype.name, 'html');
+  assert.strictEqual(doc.title, 'Hello');
+  assert.strictEqual(doc.body.tagName, 'BODY');
+  assert.strictEqual(
+    doc.outerHTML,
+    '<!DOCTYPE html><html><head><title>Hello</title></head><body></body></html>',
+  );
+
+  const untitled = source.implementation.createHTMLDocument();
+  assert.strictEqual(untitled.head.childNodes.length, 0);
+
+  const div = doc.createElement('DIV');
+  doc.body.appendChild(div);
+  assert.strictEqual(doc.querySelector('div').tagName, 'DIV');
+});
+
+test('should create XML documents and doctypes', () => {
+  const { implementation } = new DOMParser().parseFromString('', 'text/html');
+  const doctype = implementation.createDocumentType(
+    'svg',
+    '-//W3C//DTD SVG 1.1//EN',
+    'http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd',
+  );
+  assert.strictEqual(doctype.nodeType, 10);
+  assert.strictEqual(doctype.publicId, '-//W3C//DTD SVG 1.1//EN');
+
+  const doc = implementation.createDocument(SVG_NS, 'svg', doctype);
+  assert.strictEqual(doc.contentType, 'image/svg+xml');
+  assert.strictEqual(doc.doctype.isSameNode(doctype), true);
+  assert.strictEqual(doc.documentElement.namespaceURI, SVG_NS);
+
+  const bare = implementation.createDocument(null, null);
+  assert.strictEqual(bare.contentType, 'application/xml');
+  assert.strictEqual(bare.childNodes.length, 0);
+
+  assert.throws(() => implementation.createDocumentType('bad name', '', ''));
+  assert.throws(() => implementation.createDocument(null, 'a:b'));
+});
+
+test('should keep element names as-is in XML documents', () => {
+  const doc = new DOMParser()
+    .parseFromString('', 'text/html')
+    .implementation.createDocument('http://www.w3.org/1999/xhtml', 'html');
+  const el = doc.createElement('Section');
+  doc.documentElement.appendChild(el);
+  assert.strictEqual(el.localName, 'Section');
+  assert.strictEqual(el.tagName, 'Section');
+  assert.strictEqual(doc.documentElement.tagName, 'html');
+});
+
+test('should support the Document constructor', () => {
+  const doc = new Document();
+  assert.strictEqual(doc.nodeType, 9);
+  assert.strictEqual(doc.contentType, 'application/xml');
+  assert.strictEqual(doc.childNodes.length, 0);
+
+  const root = doc.createElement('feed');
+  doc.appendChild(root);
+  assert.strictEqual(doc.documentElement.localName, 'feed');
+  assert.strictEqual(root.namespaceURI, '');
+  assert.ok(doc.implementation.createHTMLDocument('x').body);
+});
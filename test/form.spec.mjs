@@ -0,0 +1,213 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, FormData, submitForm } = pkg;
+
+const parser = new DOMParser();
+
+const doc = parser.parseFromString(
+  `<form id="search" action="/search?old=1">
+    <input name="q" value="caf&eacute; &amp; tea">
+    <input type="checkbox" name="opt" value="a" checked>
+    <input type="checkbox" name="opt" value="b">
+    <input type="checkbox" name="agree" checked>
+    <input type="radio" name="size" value="s">
+    <input type="radio" name="size" value="m" checked>
+    <select name="tags" multiple>
+      <option selected>x</option><option value="y" selected disabled>Y</option>
+      <optgroup disabled><option selected>z</option></optgroup><option value="w" selected>W</option>
+    </select>
+    <select name="one"><option value="1">One</option><option value="2">Two</option></select>
+    <input name="off" value="1" disabled>
+    <fieldset disabled><legend><input name="in-legend" value="1"></legend><input name="in-fieldset" value="1"></fieldset>
+    <datalist><input name="in-datalist" value="1"></datalist>
+    <input value="unnamed">
+    <textarea name="note">line 1
+line 2</textarea>
+    <input type="file" name="upload">
+    <input type="hidden" name="_charset_">
+    <input name="title" value="שלום" dirname="title.dir" dir="auto">
+    <button name="go" value="1">Go</button>
+    <button name="other" value="2" formmethod="post" formenctype="multipart/form-data" formaction="/upload">Upload</button>
+    <input type="image" name="map" src="map.png">
+  </form>
+  <input name="outside" value="1" form="search">
+  <input name="stray" value="1">`,
+  'text/html',
+);
+const form = doc.getElementById('search');
+
+describe('form', () => {
+  it('associates controls', () => {
+    const names = form.elements.map((element) => element.getAttribute('name'));
+    assert.ok(names.includes('outside'));
+    assert.ok(!names.includes('stray'));
+    assert.ok(!names.includes('map'));
+    assert.ok(doc.querySelector('[name=outside]').form.isSameNode(form));
+    assert.equal(doc.querySelector('[name=stray]').form, null);
+    assert.ok(doc.querySelector('textarea').form.isSameNode(form));
+    assert.equal(form.form, null);
+  });
+
+  it('reflects method and enctype', () => {
+    const f = parser.parseFromString('<form method="POST" enctype="bogus"></form>', 'text/html').querySelector('form');
+    assert.equal(f.method, 'post');
+    assert.equal(f.enctype, 'application/x-www-form-urlencoded');
+    f.method = 'dialog';
+    assert.equal(f.getAttribute('method'), 'dialog');
+    assert.equal(form.method, 'get');
+    const other = doc.querySelector('[name=other]');
+    assert.equal(other.formMethod, 'post');
+    assert.equal(other.formEnctype, 'multipart/form-data');
+    assert.equal(other.formAction, '/upload');
+    assert.equal(doc.querySelector('[name=go]').formMethod, '');
+  });
+});
+
+describe('FormData', () => {
+  it('constructs the entry list', () => {
+    const data = new FormData(form);
+    const entries = [...data].filter(([, value]) => typeof value === 'string');
+    assert.deepEqual(entries, [
+      ['q', 'café & tea'],
+      ['opt', 'a'],
+      ['agree', 'on'],
+      ['size', 'm'],
+      ['tags', 'x'],
+      ['tags', 'w'],
+      ['one', '1'],
+      ['in-legend', '1'],
+      ['note', 'line 1\nline 2'],
+      ['_charset_', 'UTF-8'],
+      ['title', 'שלום'],
+      ['title.dir', 'rtl'],
+      ['outside', '1'],
+    ]);
+    const upload = data.get('upload');
+    assert.equal(upload.name, '');
+    assert.equal(upload.size, 0);
+    assert.deepEqual(data.getAll('tags'), ['x', 'w']);
+    assert.equal(data.has('go'), false);
+  });
+
+  it('includes the submitter', () => {
+    const data = new FormData(form, doc.querySelector('[name=go]'));
+    assert.equal(data.get('go'), '1');
+    const image = new FormData(form, doc.querySelector('[name=map]'));
+    assert.equal(image.get('map.x'), '0');
+    assert.equal(image.get('map.y'), '0');
+    assert.throws(() => new FormData(form, doc.querySelector('[name=q]')));
+  });
+
+  it('sanitizes input values by type', () => {
+    const f = parser.parseFromString(
+      `<form>
+        <input name="text" value="a&#10;b&#13;c">
+        <input type="search" name="search" value="a&#10;b">
+        <input type="password" name="password" value="a&#13;&#10;b">
+        <input type="url" name="url" value=" https://example.com/&#10; ">
+        <input type="email" name="email" value=" a@example.com ">
+        <input type="email" name="emails" value=" a@example.com , b@example.com" multiple>
+        <input type="hidden" name="hidden" value="a&#10;b">
+      </form>`,
+      'text/html',
+    ).querySelector('form');
+    assert.deepEqual([...new FormData(f)], [
+      ['text', 'abc'],
+      ['search', 'ab'],
+      ['password', 'ab'],
+      ['url', 'https://example.com/'],
+      ['email', 'a@example.com'],
+      ['emails', 'a@example.com,b@example.com'],
+      ['hidden', 'a\nb'],
+    ]);
+    assert.equal(f.querySelector('[name=text]').value, 'abc');
+  });
+
+  it('can be edited', () => {
+    const data = new FormData();
+    data.append('a', 1);
+    data.append('b', 'x');
+    data.append('a', 2);
+    data.set('a', 3);
+    assert.deepEqual([...data.entries()], [['a', '3'], ['b', 'x']]);
+    data.delete('b');
+    assert.deepEqual([...data.keys()], ['a']);
+  });
+});
+
+describe('submitForm', () => {
+  const page = new DOMParser({ url: 'https://example.com/a/b' }).parseFromString(
+    `<form action="search?page=2" accept-charset="utf-8">
+      <input name="q" value="a b">
+      <textarea name="t">x
+y</textarea>
+      <input type="image" name="pos" src="go.png">
+      <input type="submit" name="send" value="Send" formmethod="post">
+      <button name="plain" formmethod="post" formenctype="text/plain">Plain</button>
+      <button name="multi" formmethod="post" formenctype="multipart/form-data" formaction="/upload">Multi</button>
+      <input type="file" name="f">
+    </form>`,
+    'text/html',
+  );
+  const pageForm = page.querySelector('form');
+
+  it('submits with the default button', () => {
+    const submission = submitForm(pageForm, null, { coordinates: { x: 3, y: 7 } });
+    assert.equal(submission.method, 'get');
+    assert.equal(submission.url, 'https://example.com/a/search?q=a+b&t=x%0D%0Ay&pos.x=3&pos.y=7&f=');
+    assert.equal(submission.body, undefined);
+  });
+
+  it('encodes the body by enctype', () => {
+    const urlencoded = submitForm(pageForm, page.querySelector('[name=send]'));
+    assert.equal(urlencoded.method, 'post');
+    assert.equal(urlencoded.contentType, 'application/x-www-form-urlencoded');
+    assert.equal(urlencoded.body, 'q=a+b&t=x%0D%0Ay&send=Send&f=');
+
+    const plain = submitForm(pageForm, page.querySelector('[name=plain]'));
+    assert.equal(plain.contentType, 'text/plain');
+    assert.equal(plain.body, 'q=a b\r\nt=x\r\ny\r\nplain=\r\nf=\r\n');
+
+    const multi = submitForm(pageForm, page.querySelector('[name=multi]'), { boundary: 'XyZ' });
+    assert.equal(multi.url, 'https://example.com/upload');
+    assert.equal(multi.contentType, 'multipart/form-data; boundary=XyZ');
+    assert.equal(
+      multi.body,
+      '--XyZ\r\nContent-Disposition: form-data; name="q"\r\n\r\na b\r\n' +
+        '--XyZ\r\nContent-Disposition: form-data; name="t"\r\n\r\nx\r\ny\r\n' +
+        '--XyZ\r\nContent-Disposition: form-data; name="multi"\r\n\r\n\r\n' +
+        '--XyZ\r\nContent-Disposition: form-data; name="f"; filename=""\r\nContent-Type: application/octet-stream\r\n\r\n\r\n' +
+        '--XyZ--\r\n',
+    );
+  });
+
+  it('resolves the action against the document URL', () => {
+    const withUrl = new DOMParser({ url: 'https://example.com/a/b' }).parseFromString(
+      '<form action="search?old=1"><input name="q" value="x"></form><form method="post"><input name="q" value="x"></form>',
+      'text/html',
+    );
+    const [get, post] = withUrl.querySelectorAll('form');
+    assert.equal(submitForm(get).url, 'https://example.com/a/search?q=x');
+    assert.equal(submitForm(post).url, 'https://example.com/a/b');
+    assert.equal(submitForm(post).body, 'q=x');
+  });
+
+  it('keeps the query of other schemes and rejects invalid actions', () => {
+    const forms = parser.parseFromString(
+      `<form action="javascript:go()"><input name="q" value="a b"></form>
+      <form action="mailto:a@example.com?subject=x"><input name="q" value="a b"></form>
+      <form action="data:text/plain,hi"><input name="q" value="x"></form>
+      <form action="search"><input name="q" value="x"></form>
+      <form action="https://[bad"><input name="q" value="x"></form>`,
+      'text/html',
+    );
+    const [script, mail, data, relative, invalid] = forms.querySelectorAll('form');
+    assert.equal(submitForm(script).url, 'javascript:go()');
+    assert.equal(submitForm(mail).url, 'mailto:a@example.com?q=a%20b');
+    assert.equal(submitForm(data).url, 'data:text/plain,hi?q=x');
+    // Without a document URL, relative actions can't be resolved.
+    assert.throws(() => submitForm(relative), /not a valid form action URL/);
+    assert.throws(() => submitForm(invalid), /not a valid form action URL/);
+  });
+});
@@ -0,0 +1,84 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, inlineCSS } = pkg;
+
+const parser = new DOMParser();
+
+describe('inlineCSS', () => {
+  it('inlines rules by specificity and source order', () => {
+    const doc = parser.parseFromString(
+      `<html><head><style>
+        p { color: red; margin: 0 }
+        .note { color: blue }
+        #intro { padding: 4px 8px }
+        p { font-size: 14px }
+      </style></head>
+      <body><p id="intro" class="note" style="margin-top: 2px">Hi</p><span>x</span></body></html>`,
+      'text/html',
+    );
+    inlineCSS(doc);
+    assert.equal(doc.querySelector('style'), null);
+    assert.equal(doc.querySelector('span').getAttribute('style'), null);
+    const p = doc.querySelector('p');
+    assert.equal(p.style.color, 'blue');
+    assert.equal(p.style.marginTop, '2px');
+    assert.equal(p.style.marginBottom, '0');
+    assert.equal(p.style.padding, '4px 8px');
+    assert.equal(p.style.fontSize, '14px');
+  });
+
+  it('honors !important', () => {
+    const doc = parser.parseFromString(
+      `<style>p { color: red !important } #a { color: blue }</style>
+      <p id="a" style="background: white">x</p><p style="color: green !important">y</p>`,
+      'text/html',
+    );
+    inlineCSS(doc);
+    const [first, second] = doc.querySelectorAll('p');
+    assert.equal(first.style.color, 'red');
+    assert.equal(first.style.getPropertyPriority('color'), '');
+    assert.equal(second.style.color, 'green');
+    assert.equal(second.style.getPropertyPriority('color'), 'important');
+
+    const kept = parser.parseFromString('<style>p { color: red !important }</style><p>x</p>', 'text/html');
+    inlineCSS(kept, { preserveImportant: true });
+    assert.equal(kept.querySelector('p').getAttribute('style'), 'color: red !important;');
+  });
+
+  it('keeps the rules it cannot inline', () => {
+    const doc = parser.parseFromString(
+      `<style>
+        @font-face { font-family: Brand; src: url(brand.woff) }
+        a { color: red }
+        a:hover { color: blue }
+        @media (max-width: 600px) { .wide { width: 100% } }
+      </style>
+      <style media="print">.wide { display: none }</style>
+      <a class="wide" href="#">x</a>`,
+      'text/html',
+    );
+    inlineCSS(doc);
+    const [screen, print] = doc.querySelectorAll('style');
+    assert.equal(
+      screen.textContent,
+      '@font-face { font-family: Brand; src: url(brand.woff) }\na:hover { color: blue; }\n@media (max-width: 600px) { .wide { width: 100%; } }',
+    );
+    assert.equal(print.textContent, '.wide { display: none }');
+    assert.equal(doc.querySelector('a').getAttribute('style'), 'color: red;');
+  });
+
+  it('removes classes and ids no longer needed', () => {
+    const doc = parser.parseFromString(
+      `<style>.a { color: red } #b { color: blue } @media (max-width: 600px) { .c, #d { margin: 0 } }</style>
+      <p class="a c" id="b">x</p><p class="a" id="d">y</p>`,
+      'text/html',
+    );
+    inlineCSS(doc, { removeClasses: true, removeIds: true });
+    const [first, second] = doc.querySelectorAll('p');
+    assert.equal(first.getAttribute('class'), 'c');
+    assert.equal(first.getAttribute('id'), null);
+    assert.equal(second.getAttribute('class'), null);
+    assert.equal(second.getAttribute('id'), 'd');
+  });
+});
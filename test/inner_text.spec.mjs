@@ -0,0 +1,87 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser } = pkg;
+
+const parse = (html) => new DOMParser().parseFromString(html, 'text/html');
+
+test('innerText separates blocks and paragraphs', () => {
+  const doc = parse(
+    '<div id="root"><h1>Title</h1><p>First  paragraph\n with <b>bold</b> text.</p><p>Second</p><div>a<br>b</div><ul><li>one</li><li>two</li></ul></div>',
+  );
+  assert.equal(
+    doc.getElementById('root').innerText,
+    'Title\n\nFirst paragraph with bold text.\n\nSecond\n\na\nb\none\ntwo',
+  );
+});
+
+test('innerText collapses white space around inline boundaries', () => {
+  const doc = parse('<div id="d">  a <span> b </span>  c <br> d  </div>');
+  assert.equal(doc.getElementById('d').innerText, 'a b c\nd');
+});
+
+test('innerText skips hidden content', () => {
+  const doc = parse(
+    '<div id="d">shown<script>var x = 1;</script><style>p{}</style><span hidden>hidden</span><span style="display: none">none</span><span style="visibility:hidden">invisible</span><input type="hidden" value="x"> end</div>',
+  );
+  assert.equal(doc.getElementById('d').innerText, 'shown end');
+});
+
+test('innerText of elements that are not rendered is their text content', () => {
+  const doc = parse('<script>let a = 1;\nlet b = 2;</script><div hidden id="h"><p>x</p>  y</div>');
+  assert.equal(doc.querySelector('script').innerText, 'let a = 1;\nlet b = 2;');
+  assert.equal(doc.getElementById('h').innerText, 'x  y');
+});
+
+test('innerText preserves white space in pre', () => {
+  const doc = parse('<div id="d">before<pre>  line 1\n    line 2</pre>after</div>');
+  assert.equal(doc.getElementById('d').innerText, 'before\n  line 1\n    line 2\nafter');
+});
+
+test('innerText lays out tables with tabs and newlines', () => {
+  const doc = parse(
+    '<table><thead><tr><th>a</th><th>b</th></tr></thead><tbody><tr><td>1</td><td> 2 </td></tr><tr><td>3</td><td>4</td></tr></tbody></table>',
+  );
+  assert.equal(doc.querySelector('table').innerText, 'a\tb\n1\t2\n3\t4');
+});
+
+test('innerText shows only the summary of closed details', () => {
+  const doc = parse('<details><summary>More</summary><p>secret</p></details><details open><summary>Open</summary>body</details>');
+  const [closed, open] = doc.querySelectorAll('details');
+  assert.equal(closed.innerText, 'More');
+  assert.equal(open.innerText, 'Open\nbody');
+});
+
+test('innerText is null for non-element nodes', () => {
+  const doc = parse('<p>x</p>');
+  assert.equal(doc.querySelector('p').firstChild.innerText, null);
+});
+
+test('setting innerText inserts br elements for line breaks', () => {
+  const doc = parse('<div id="d"><b>old</b></div>');
+  const div = doc.getElementById('d');
+  div.innerText = 'a < b\nc\r\n\nd';
+  assert.equal(div.innerHTML, 'a &lt; b<br>c<br><br>d');
+  assert.equal(div.innerText, 'a < b\nc\n\nd');
+  div.innerText = '';
+  assert.equal(div.childNodes.length, 0);
+});
+
+test('setting outerText replaces the element and merges adjacent text', () => {
+  const doc = parse('<p id="p">before <b>bold</b> after</p>');
+  const p = doc.getElementById('p');
+  const b = p.querySelector('b');
+  assert.equal(b.outerText, 'bold');
+  b.outerText = 'x\ny';
+  assert.equal(p.innerHTML, 'before x<br>y after');
+  assert.equal(p.childNodes.length, 3);
+  assert.equal(p.firstChild.data, 'before x');
+
+  const span = doc.createElement('span');
+  p.appendChild(span);
+  span.outerText = '';
+  assert.equal(p.lastChild.data, 'y after');
+  assert.throws(() => {
+    doc.createElement('i').outerText = 'z';
+  });
+});
@@ -0,0 +1,68 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, Document } = pkg;
+
+const parse = (html) => new DOMParser().parseFromString(html, 'text/html');
+const body = (html) => parse(html).body;
+
+test('attribute order does not matter', () => {
+  assert.equal(body('<p a="1" b="2">x</p>').isEqualNode(body('<p b="2" a="1">x</p>')), true);
+  assert.equal(body('<p a="1" b="2">x</p>').diff(body('<p b="2" a="1">x</p>')), null);
+  assert.equal(body('<p a="1">x</p>').isEqualNode(body('<p a="2">x</p>')), false);
+});
+
+test('compares namespaces that serialize identically', () => {
+  const doc = new Document();
+  const a = doc.createElementNS('urn:a', 'item');
+  const b = doc.createElementNS('urn:b', 'item');
+  assert.equal(a.outerHTML, b.outerHTML);
+  assert.equal(a.isEqualNode(b), false);
+  assert.deepEqual(a.diff(b), { path: [], property: 'namespaceURI', value: 'urn:a', otherValue: 'urn:b' });
+
+  const x = doc.createElement('x');
+  const y = doc.createElement('x');
+  x.setAttributeNS('urn:a', 'p:k', 'v');
+  y.setAttributeNS('urn:b', 'p:k', 'v');
+  assert.equal(x.isEqualNode(y), false);
+});
+
+test('compares doctypes, comments and processing instructions', () => {
+  const doc = new Document();
+  const { implementation } = doc;
+  const html5 = implementation.createDocumentType('html', '', '');
+  const legacy = implementation.createDocumentType('html', '-//W3C//DTD HTML 4.01//EN', '');
+  assert.equal(html5.isEqualNode(implementation.createDocumentType('html', '', '')), true);
+  assert.deepEqual(html5.diff(legacy), { path: [], property: 'publicId', value: '', otherValue: '-//W3C//DTD HTML 4.01//EN' });
+
+  assert.equal(doc.createComment('a').isEqualNode(doc.createComment('a')), true);
+  assert.equal(doc.createComment('a').isEqualNode(doc.createTextNode('a')), false);
+  assert.deepEqual(doc.createComment('a').diff(doc.createTextNode('a')), { path: [], property: 'nodeType', value: '8', otherValue: '3' });
+  assert.deepEqual(
+    doc.createProcessingInstruction('t', 'x').diff(doc.createProcessingInstruction('t', 'y')),
+    { path: [], property: 'data', value: 'x', otherValue: 'y' },
+  );
+});
+
+test('compares whole documents and fragments', () => {
+  const html = '<!DOCTYPE html><ul><li>a</li><li class="x">b</li></ul>';
+  assert.equal(parse(html).isEqualNode(parse(html)), true);
+  assert.equal(parse(html).isEqualNode(parse('<ul><li>a</li><li class="x">b</li></ul>')), false);
+  const doc = parse('');
+  const fragment = doc.createDocumentFragment();
+  fragment.appendChild(doc.createTextNode('t'));
+  const other = doc.createDocumentFragment();
+  other.appendChild(doc.createTextNode('t'));
+  assert.equal(fragment.isEqualNode(other), true);
+  assert.equal(fragment.isEqualNode(null), false);
+});
+
+test('diff reports the path to the first difference', () => {
+  const a = body('<ul><li>a</li><li class="x">b</li></ul><p>same</p>');
+  const b = body('<ul><li>a</li><li class="y">b</li></ul><p>other</p>');
+  assert.deepEqual(a.diff(b), { path: [0, 1], property: 'attributes', value: 'class="x"', otherValue: 'class="y"' });
+  assert.deepEqual(body('<p>x</p>').diff(body('<p>y</p>')), { path: [0, 0], property: 'data', value: 'x', otherValue: 'y' });
+  assert.deepEqual(body('<p title="t">x</p>').diff(body('<p>x</p>')), { path: [0], property: 'attributes', value: 'title="t"' });
+  assert.deepEqual(body('<p>x</p>').diff(body('<p>x</p><p>y</p>')), { path: [], property: 'childNodes.length', value: '1', otherValue: '2' });
+  assert.deepEqual(body('<p>x</p>').diff(body('<div>x</div>')), { path: [0], property: 'localName', value: 'p', otherValue: 'div' });
+});
@@ -0,0 +1,125 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, Document, fromJSON } = pkg;
+
+const parse = (html) => new DOMParser().parseFromString(html, 'text/html');
+
+test('toJSON describes the tree as plain objects', () => {
+  const doc = parse('<!DOCTYPE html><p class="note">Hi<!--c--></p>');
+  const ast = doc.toJSON();
+  assert.equal(ast.type, 'document');
+  assert.equal(ast.contentType, 'text/html');
+  assert.deepEqual(ast.children[0], { type: 'documentType', name: 'html', publicId: '', systemId: '' });
+  const p = doc.querySelector('p').toJSON();
+  assert.deepEqual(p, {
+    type: 'element',
+    name: 'p',
+    namespace: 'http://www.w3.org/1999/xhtml',
+    attributes: [{ name: 'class', value: 'note' }],
+    children: [
+      { type: 'text', value: 'Hi' },
+      { type: 'comment', value: 'c' },
+    ],
+  });
+});
+
+test('JSON.stringify and fromJSON round-trip a document', () => {
+  const doc = parse('<!DOCTYPE html><html lang="en"><head><title>T</title></head><body><p id="a">x &amp; y</p><noscript><b>n</b></noscript></body></html>');
+  const json = JSON.stringify(doc);
+  const copy = fromJSON(json);
+  assert.equal(copy.nodeType, 9);
+  assert.equal(copy.contentType, 'text/html');
+  assert.equal(copy.outerHTML, doc.outerHTML);
+  assert.equal(copy.getElementById('a').textContent, 'x & y');
+  assert.notEqual(copy.body, doc.body);
+});
+
+test('fromJSON accepts structured clones of the AST', () => {
+  const fragment = parse('<ul><li>one</li><li>two</li></ul>').querySelector('ul');
+  const copy = fromJSON(structuredClone(fragment.toJSON()));
+  assert.equal(copy.outerHTML, '<ul><li>one</li><li>two</li></ul>');
+  assert.equal(copy.parentNode, null);
+});
+
+test('keeps template contents and shadow roots', () => {
+  const doc = parse('<div id="host"><template shadowrootmode="open" shadowrootclonable><slot></slot></template><span>light</span></div><template id="t"><b>tpl</b></template>');
+  const ast = doc.toJSON();
+  const copy = fromJSON(ast);
+
+  const template = copy.getElementById('t');
+  assert.equal(template.childNodes.length, 0);
+  assert.equal(template.content.firstChild.outerHTML, '<b>tpl</b>');
+
+  const host = copy.getElementById('host');
+  assert.ok(host.shadowRoot);
+  assert.equal(host.shadowRoot.mode, 'open');
+  assert.equal(host.shadowRoot.clonable, true);
+  assert.equal(host.shadowRoot.innerHTML, '<slot></slot>');
+  assert.equal(host.innerHTML, '<span>light</span>');
+
+  const hostAst = doc.getElementById('host').toJSON();
+  assert.equal(hostAst.shadowRoot.mode, 'open');
+  assert.equal(hostAst.shadowRoot.type, undefined);
+});
+
+test('keeps namespaces and prefixed attributes', () => {
+  const doc = parse('<svg viewBox="0 0 1 1"><use xlink:href="#a"/></svg><math><mi>x</mi></math>');
+  const copy = fromJSON(JSON.parse(JSON.stringify(doc)));
+  const use = copy.querySelector('use');
+  assert.equal(use.namespaceURI, 'http://www.w3.org/2000/svg');
+  assert.equal(use.getAttributeNS('http://www.w3.org/1999/xlink', 'href'), '#a');
+  assert.equal(copy.querySelector('svg').getAttribute('viewBox'), '0 0 1 1');
+  assert.equal(copy.querySelector('mi').namespaceURI, 'http://www.w3.org/1998/Math/MathML');
+
+  const attr = doc.querySelector('use').toJSON().attributes[0];
+  assert.deepEqual(attr, { name: 'href', namespace: 'http://www.w3.org/1999/xlink', prefix: 'xlink', value: '#a' });
+});
+
+test('round-trips XML documents with processing instructions', () => {
+  const doc = new Document();
+  doc.appendChild(doc.createProcessingInstruction('xml-stylesheet', 'href="a.css"'));
+  doc.appendChild(doc.createElementNS('urn:x', 'x:root'));
+  const copy = fromJSON(doc.toJSON());
+  assert.equal(copy.contentType, 'application/xml');
+  assert.equal(copy.firstChild.target, 'xml-stylesheet');
+  assert.equal(copy.documentElement.prefix, 'x');
+  assert.equal(copy.documentElement.namespaceURI, 'urn:x');
+});
+
+test('elements carry their source location when parsed with one', () => {
+  const html = '<p class="a">one</p>';
+  assert.equal(parse(html).querySelector('p').toJSON().location, undefined);
+
+  const doc = new DOMParser({ sourceLocations: true }).parseFromString(html, 'text/html');
+  const ast = doc.querySelector('p').toJSON();
+  assert.deepEqual(ast.location, { startOffset: 0, endOffset: 20, startLine: 1, startColumn: 1, endLine: 1, endColumn: 21 });
+  assert.equal(doc.querySelector('body').toJSON().location, undefined);
+
+  const copy = fromJSON(JSON.parse(JSON.stringify(ast)));
+  assert.equal(copy.outerHTML, html);
+  assert.equal(copy.sourceLocation, null);
+  assert.equal(copy.toJSON().location, undefined);
+});
+
+test('fromJSON rejects invalid input', () => {
+  assert.throws(() => fromJSON({ type: 'bogus' }), /not a valid node type/);
+  assert.throws(() => fromJSON({ type: 'document', contentType: 'text/plain', children: [] }), /not a supported document type/);
+  assert.throws(() => fromJSON({ type: 'element', attributes: [], children: [] }), /requires the 'name' property/);
+  assert.throws(() => fromJSON('{'), SyntaxError);
+});
+
+test('fromJSON rejects names that would not serialize as a single name', () => {
+  const element = (name, attributes = []) => ({ type: 'element', name, attributes, children: [] });
+  for (const name of ['', '1bad', 'a b', 'a>b', '#shadow-root']) {
+    assert.throws(() => fromJSON(element(name)), /not a valid element name/, name);
+  }
+  assert.throws(() => fromJSON({ ...element('svg'), prefix: 'a b' }), /not a valid element name/);
+  for (const name of ['', 'a b', 'a=b', 'x>', 'a/b']) {
+    assert.throws(() => fromJSON(element('p', [{ name, value: '' }])), /not a valid attribute name/, name);
+  }
+  assert.throws(() => fromJSON(element('p', [{ name: 'href', prefix: 'x y', value: '' }])), /not a valid attribute name/);
+
+  const doc = parse('<foo-bar @click="x" :id="y" x.y="z"></foo-bar>');
+  assert.equal(fromJSON(doc.toJSON()).outerHTML, doc.outerHTML);
+});
@@ -0,0 +1,120 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser } = pkg;
+
+const parse = (html) => new DOMParser().parseFromString(html, 'text/html');
+const md = (html, options) => parse(html).toMarkdown(options);
+
+test('converts headings, paragraphs and inline formatting', () => {
+  assert.equal(
+    md('<h1>Title</h1><p>Hello <strong>bold</strong>, <em> spaced </em>text and <code>a `b` c</code>.</p><h3>Sub #</h3>'),
+    '# Title\n\nHello **bold**, _spaced_ text and ``a `b` c``.\n\n### Sub \\#',
+  );
+});
+
+test('converts links and images', () => {
+  assert.equal(
+    md('<p><a href="https://example.com/a b" title="T">link</a> <a href="https://e.com">https://e.com</a> <a>plain</a> <img src="/i.png" alt="pic"></p>'),
+    '[link](https://example.com/a%20b "T") <https://e.com> plain ![pic](/i.png)',
+  );
+});
+
+test('converts nested, ordered and loose lists', () => {
+  assert.equal(
+    md('<ul><li>one</li><li>two<ul><li>nested</li></ul></li></ul><ol start="9"><li><p>a</p><p>b</p></li><li>ten</li></ol>'),
+    '- one\n- two\n  - nested\n\n9. a\n\n   b\n\n10. ten',
+  );
+});
+
+test('converts task lists', () => {
+  assert.equal(
+    md('<ul><li><input type="checkbox" checked> done</li><li><input type="checkbox"> todo</li></ul>'),
+    '- [x] done\n- [ ] todo',
+  );
+  assert.equal(md('<ul><li><input type="checkbox"> todo</li></ul>', { gfm: false }), '- todo');
+});
+
+test('converts code blocks with a language', () => {
+  assert.equal(
+    md('<pre><code class="language-js">const a = 1;\nlog(```);\n</code></pre>'),
+    '````js\nconst a = 1;\nlog(```);\n````',
+  );
+  assert.equal(md('<pre>x\n  y</pre>', { codeBlockStyle: 'indented' }), '    x\n      y');
+  assert.equal(md('<pre class="lang-rs">fn</pre>', { fence: '~~~' }), '~~~rs\nfn\n~~~');
+});
+
+test('converts blockquotes and horizontal rules', () => {
+  assert.equal(
+    md('<blockquote><p>quote</p><blockquote>inner</blockquote></blockquote><hr>'),
+    '> quote\n>\n> > inner\n\n---',
+  );
+});
+
+test('converts GFM tables', () => {
+  assert.equal(
+    md('<table><caption>Data</caption><thead><tr><th align="right">A</th><th style="text-align: center">B|C</th></tr></thead><tbody><tr><td>1</td><td>2<br>3</td></tr><tr><td colspan="2">wide</td></tr></tbody></table>'),
+    'Data\n\n| A    | B\\|C   |\n| ---: | :----: |\n| 1    | 2<br>3 |\n| wide |        |',
+  );
+  assert.equal(md('<table><tr><td>x</td></tr></table>', { gfm: false }), '<table><tbody><tr><td>x</td></tr></tbody></table>');
+});
+
+test('converts strikethrough only with GFM', () => {
+  assert.equal(md('<p><del>gone</del> <s>old</s></p>'), '~~gone~~ ~~old~~');
+  assert.equal(md('<p><del>gone</del></p>', { gfm: false }), 'gone');
+});
+
+test('escapes Markdown syntax in text', () => {
+  assert.equal(
+    md('<p># not heading</p><p>1. not list</p><p>- dash</p><p>snake_case _x_ *y* [z] &lt;div&gt; a|b</p>'),
+    '\\# not heading\n\n1\\. not list\n\n\\- dash\n\nsnake_case \\_x\\_ \\*y\\* \\[z\\] \\<div> a|b',
+  );
+});
+
+test('writes hard line breaks and skips non-content elements', () => {
+  assert.equal(
+    md('<p>a<br>b</p><script>x()</script><style>p{}</style><p hidden>secret</p><template>t</template>'),
+    'a  \nb',
+  );
+});
+
+test('supports heading and delimiter styles', () => {
+  assert.equal(
+    md('<h1>One</h1><h2>Two</h2><h3>Three</h3><ul><li><i>a</i> <b>b</b></li></ul>', {
+      headingStyle: 'setext',
+      bulletListMarker: '*',
+      emDelimiter: '*',
+      strongDelimiter: '__',
+    }),
+    'One\n===\n\nTwo\n---\n\n### Three\n\n* *a* __b__',
+  );
+  assert.throws(() => md('<p>x</p>', { headingStyle: 'underline' }));
+});
+
+test('applies per-tag rules', () => {
+  const html = '<div><mark>hi</mark> <aside>side</aside><span class="x">kept</span><figure><img src="a.png"><figcaption>cap</figcaption></figure></div>';
+  assert.equal(
+    md(html, {
+      rules: {
+        mark: (node, content) => `==${content}==`,
+        aside: 'remove',
+        span: 'keep',
+        FIGURE: (node) => (node.querySelector('img') ? null : 'never'),
+        figcaption: 'content',
+      },
+    }),
+    '==hi== <span class="x">kept</span>\n\n![](a.png)\n\ncap',
+  );
+  assert.throws(() => md('<p>x</p>', { rules: { p: 'drop' } }));
+  assert.throws(
+    () => md('<p>x</p>', { rules: { p: () => { throw new Error('boom'); } } }),
+    /boom/,
+  );
+});
+
+test('converts a single element or a fragment', () => {
+  const doc = parse('<main><h2>Part</h2><p>body</p></main>');
+  assert.equal(doc.querySelector('h2').toMarkdown(), '## Part');
+  assert.equal(doc.querySelector('main').toMarkdown(), '## Part\n\nbody');
+  assert.equal(doc.querySelector('p').firstChild.toMarkdown(), 'body');
+});
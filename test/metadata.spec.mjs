@@ -0,0 +1,103 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, extractMetadata } = pkg;
+
+const parse = (html, url) => new DOMParser({ url }).parseFromString(html, 'text/html');
+
+test('extracts head metadata, OpenGraph and Twitter cards', () => {
+  const doc = parse(
+    '<html lang="en-GB"><head><title>  My\n  Page </title>'
+      + '<meta name="Description" content="About things">'
+      + '<link rel="canonical" href="/post">'
+      + '<link rel="icon" href="/favicon.ico" sizes="32x32" type="image/x-icon"><link rel="apple-touch-icon" href="touch.png"><link rel="stylesheet" href="s.css">'
+      + '<meta property="og:title" content="OG title"><meta property="og:image" content="a.png"><meta property="og:image" content="b.png">'
+      + '<meta property="article:author" content="Ann"><meta name="twitter:card" content="summary"><meta property="twitter:site" content="@site">'
+      + '</head><body></body></html>',
+    'https://example.com/blog/',
+  );
+  const metadata = extractMetadata(doc);
+  assert.equal(metadata.title, 'My Page');
+  assert.equal(metadata.description, 'About things');
+  assert.equal(metadata.canonical, 'https://example.com/post');
+  assert.equal(metadata.language, 'en-GB');
+  assert.deepEqual(metadata.icons, [
+    { rel: 'icon', href: 'https://example.com/favicon.ico', sizes: '32x32', type: 'image/x-icon' },
+    { rel: 'apple-touch-icon', href: 'https://example.com/blog/touch.png' },
+  ]);
+  assert.deepEqual(metadata.openGraph, {
+    'og:title': ['OG title'],
+    'og:image': ['a.png', 'b.png'],
+    'article:author': ['Ann'],
+  });
+  assert.deepEqual(metadata.twitter, { 'twitter:card': ['summary'], 'twitter:site': ['@site'] });
+});
+
+test('falls back to the Content-Language pragma and leaves missing fields out', () => {
+  const metadata = extractMetadata(parse('<meta http-equiv="content-language" content="de, en">'));
+  assert.equal(metadata.language, 'de');
+  assert.equal(metadata.title, undefined);
+  assert.equal(metadata.description, undefined);
+  assert.deepEqual(metadata.icons, []);
+  assert.deepEqual(metadata.microdata, []);
+});
+
+test('parses JSON-LD blocks and skips invalid ones', () => {
+  const metadata = extractMetadata(parse(
+    '<script type="application/ld+json">{"@context":"https://schema.org","@type":"Article","headline":"Hi"}</script>'
+      + '<script type="application/ld+json">{ not json</script>'
+      + '<script type="Application/LD+JSON; charset=utf-8">[1, 2]</script><script>var x = 1;</script>',
+  ));
+  assert.deepEqual(metadata.jsonLd, [{ '@context': 'https://schema.org', '@type': 'Article', headline: 'Hi' }, [1, 2]]);
+});
+
+test('runs the microdata algorithm', () => {
+  const doc = parse(
+    '<div itemscope itemtype="https://schema.org/Movie" itemid="/movies/1" itemref="rating">'
+      + '<h1 itemprop="name">Avatar</h1><a itemprop="url sameAs" href="avatar">site</a>'
+      + '<time itemprop="datePublished" datetime="2009-12-18">Dec 2009</time><meta itemprop="duration" content="PT2H42M">'
+      + '<div itemprop="director" itemscope itemtype="https://schema.org/Person"><span itemprop="name">James</span></div>'
+      + '</div><p id="rating">Rating: <data itemprop="rating" value="8">eight</data></p>'
+      + '<span itemscope><span itemprop="loop" itemscope itemref="self"></span></span><b id="self" itemprop="x">y</b>',
+    'https://example.com/',
+  );
+  const [movie, loop] = extractMetadata(doc).microdata;
+  assert.deepEqual(movie, {
+    types: ['https://schema.org/Movie'],
+    id: 'https://example.com/movies/1',
+    properties: {
+      name: ['Avatar'],
+      url: ['https://example.com/avatar'],
+      sameAs: ['https://example.com/avatar'],
+      datePublished: ['2009-12-18'],
+      duration: ['PT2H42M'],
+      director: [{ types: ['https://schema.org/Person'], properties: { name: ['James'] } }],
+      rating: ['8'],
+    },
+  });
+  assert.deepEqual(loop, { types: [], properties: { loop: [{ types: [], properties: { x: ['y'] } }] } });
+});
+
+test('extracts RDFa Lite items', () => {
+  const doc = parse(
+    '<div vocab="http://schema.org/" typeof="Person" resource="#me">'
+      + '<span property="name">Ann</span><a property="url" href="https://ann.example">home</a>'
+      + '<div property="address" typeof="PostalAddress"><span property="addressLocality">Oslo</span></div>'
+      + '<span prefix="foaf: http://xmlns.com/foaf/0.1/" property="foaf:nick" content="annie">A</span>'
+      + '</div><p property="name">orphan</p>',
+    'https://example.com/about',
+  );
+  assert.deepEqual(extractMetadata(doc).rdfa, [{
+    types: ['http://schema.org/Person'],
+    id: 'https://example.com/about#me',
+    properties: {
+      'http://schema.org/name': ['Ann'],
+      'http://schema.org/url': ['https://ann.example/'],
+      'http://schema.org/address': [{
+        types: ['http://schema.org/PostalAddress'],
+        properties: { 'http://schema.org/addressLocality': ['Oslo'] },
+      }],
+      'http://xmlns.com/foaf/0.1/nick': ['annie'],
+    },
+  }]);
+});
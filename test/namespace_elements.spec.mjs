@@ -0,0 +1,99 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser } = pkg;
+
+const SVG_NS = 'http://www.w3.org/2000/svg';
+const MATHML_NS = 'http://www.w3.org/1998/Math/MathML';
+const HTML_NS = 'http://www.w3.org/1999/xhtml';
+
+test('should support createElementNS', () => {
+  const doc = new DOMParser().parseFromString('<div></div>', 'text/html');
+
+  const svg = doc.createElementNS(SVG_NS, 'svg');
+  assert.strictEqual(svg.namespaceURI, SVG_NS);
+  assert.strictEqual(svg.localName, 'svg');
+  assert.strictEqual(svg.tagName, 'svg');
+
+  const fo = doc.createElementNS(SVG_NS, 'foreignObject');
+  assert.strictEqual(fo.localName, 'foreignObject');
+  assert.strictEqual(fo.tagName, 'foreignObject');
+
+  const prefixed = doc.createElementNS(MATHML_NS, 'm:math');
+  assert.strictEqual(prefixed.prefix, 'm');
+  assert.strictEqual(prefixed.localName, 'math');
+  assert.strictEqual(prefixed.tagName, 'm:math');
+
+  const div = doc.createElementNS(HTML_NS, 'div');
+  assert.strictEqual(div.tagName, 'DIV');
+
+  svg.appendChild(fo);
+  doc.querySelector('div').appendChild(svg);
+  assert.strictEqual(doc.querySelector('foreignObject').isSameNode(fo), true);
+});
+
+test('createElementNS should validate the qualified name', () => {
+  const doc = new DOMParser().parseFromString('', 'text/html');
+
+  assert.throws(() => doc.createElementNS(SVG_NS, '1svg'));
+  assert.throws(() => doc.createElementNS(SVG_NS, 'a:b:c'));
+  assert.throws(() => doc.createElementNS(null, 'svg:rect'));
+  assert.throws(() => doc.createElementNS(SVG_NS, 'xml:rect'));
+  assert.throws(() => doc.createElementNS(SVG_NS, 'xmlns'));
+  assert.throws(() => doc.createElementNS('http://www.w3.org/2000/xmlns/', 'foo'));
+  assert.strictEqual(doc.createElementNS('', 'foo').namespaceURI, '');
+});
+
+test('should keep the case of foreign element tag names', () => {
+  const doc = new DOMParser().parseFromString(
+    '<svg><foreignObject><p>hi</p></foreignObject><linearGradient/></svg>',
+    'text/html',
+  );
+  assert.strictEqual(doc.querySelector('svg').tagName, 'svg');
+  assert.strictEqual(doc.querySelector('foreignObject').nodeName, 'foreignObject');
+  assert.strictEqual(doc.querySelector('p').tagName, 'P');
+  assert.strictEqual(doc.getElementsByTagName('linearGradient').length, 1);
+  assert.strictEqual(doc.getElementsByTagName('lineargradient').length, 0);
+  assert.strictEqual(doc.getElementsByTagName('P').length, 1);
+});
+
+test('should support getElementsByTagNameNS', () => {
+  const doc = new DOMParser().parseFromString(
+    '<div><svg><circle/><rect/></svg><math><mi>x</mi></math><p>text</p></div>',
+    'text/html',
+  );
+  assert.strictEqual(doc.getElementsByTagNameNS(SVG_NS, 'circle').length, 1);
+  assert.strictEqual(doc.getElementsByTagNameNS(SVG_NS, '*').length, 3);
+  assert.strictEqual(doc.getElementsByTagNameNS(MATHML_NS, 'mi').length, 1);
+  assert.strictEqual(doc.getElementsByTagNameNS('*', 'p').length, 1);
+  assert.strictEqual(doc.getElementsByTagNameNS(HTML_NS, 'circle').length, 0);
+});
+
+test('should support createAttributeNS and setAttributeNodeNS', () => {
+  const doc = new DOMParser().parseFromString('<svg></svg>', 'text/html');
+  const XLINK_NS = 'http://www.w3.org/1999/xlink';
+
+  const attr = doc.createAttributeNS(XLINK_NS, 'xlink:href');
+  assert.strictEqual(attr.namespaceURI, XLINK_NS);
+  assert.strictEqual(attr.prefix, 'xlink');
+  assert.strictEqual(attr.localName, 'href');
+  assert.strictEqual(attr.name, 'xlink:href');
+  assert.strictEqual(attr.value, '');
+
+  const svg = doc.querySelector('svg');
+  attr.value = '#a';
+  assert.strictEqual(svg.setAttributeNodeNS(attr), null);
+  assert.strictEqual(svg.getAttributeNS(XLINK_NS, 'href'), '#a');
+
+  const replacement = doc.createAttributeNS(XLINK_NS, 'x:href');
+  replacement.value = '#b';
+  const old = svg.setAttributeNodeNS(replacement);
+  assert.strictEqual(old.name, 'xlink:href');
+  assert.strictEqual(old.value, '#a');
+  assert.strictEqual(svg.getAttributeNS(XLINK_NS, 'href'), '#b');
+  assert.deepStrictEqual(svg.getAttributeNames(), ['x:href']);
+
+  assert.throws(() => doc.createAttributeNS(null, 'x:href'));
+  assert.throws(() => doc.createAttributeNS(XLINK_NS, 'a b'));
+  assert.throws(() => svg.setAttributeNodeNS({ localName: 'a b', value: '' }));
+});
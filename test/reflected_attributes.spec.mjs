@@ -0,0 +1,124 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser } = pkg;
+
+const parser = new DOMParser();
+const parse = (html) => parser.parseFromString(html, 'text/html');
+
+test('string and enumerated attributes', () => {
+  const doc = parse('<div title="tip" lang="en" dir="RTL"></div><p dir="sideways"></p>');
+  const div = doc.querySelector('div');
+  assert.equal(div.title, 'tip');
+  assert.equal(div.lang, 'en');
+  assert.equal(div.dir, 'rtl');
+  assert.equal(doc.querySelector('p').dir, '');
+  assert.equal(doc.querySelector('p').title, '');
+
+  div.title = 'new';
+  div.dir = 'auto';
+  assert.equal(div.getAttribute('title'), 'new');
+  assert.equal(div.getAttribute('dir'), 'auto');
+});
+
+test('document title is still the title element text', () => {
+  const doc = parse('<title>Old</title><div title="x"></div>');
+  assert.equal(doc.title, 'Old');
+  doc.title = 'New';
+  assert.equal(doc.querySelector('title').textContent, 'New');
+
+  const empty = parse('');
+  empty.title = 'Created';
+  assert.equal(empty.head.innerHTML, '<title>Created</title>');
+});
+
+test('boolean attributes', () => {
+  const doc = parse('<div hidden></div><input type="checkbox">');
+  const div = doc.querySelector('div');
+  assert.equal(div.hidden, true);
+  div.hidden = false;
+  assert.equal(div.hasAttribute('hidden'), false);
+
+  const input = doc.querySelector('input');
+  assert.equal(input.checked, false);
+  input.checked = true;
+  assert.equal(input.getAttribute('checked'), '');
+  assert.equal(div.checked, null);
+});
+
+test('checking a radio button unchecks its group', () => {
+  const doc = parse(
+    '<input type="radio" name="g" id="a" checked><input type="radio" name="g" id="b"><input type="radio" name="h" id="c" checked>',
+  );
+  doc.getElementById('b').checked = true;
+  assert.equal(doc.getElementById('a').checked, false);
+  assert.equal(doc.getElementById('b').checked, true);
+  assert.equal(doc.getElementById('c').checked, true);
+});
+
+test('tabIndex uses integer parsing and focusable defaults', () => {
+  const doc = parse('<div tabindex=" 3px"></div><span></span><button></button><p tabindex="x"></p>');
+  assert.equal(doc.querySelector('div').tabIndex, 3);
+  assert.equal(doc.querySelector('span').tabIndex, -1);
+  assert.equal(doc.querySelector('button').tabIndex, 0);
+  assert.equal(doc.querySelector('p').tabIndex, -1);
+  doc.querySelector('span').tabIndex = 2;
+  assert.equal(doc.querySelector('span').getAttribute('tabindex'), '2');
+});
+
+test('URL attributes', () => {
+  const doc = parse('<a href="HTTPS://Example.com/a/../b?q"></a><a id="rel" href="/path"></a><img><div></div>');
+  assert.equal(doc.querySelector('a').href, 'https://example.com/b?q');
+  assert.equal(doc.getElementById('rel').href, '/path');
+  assert.equal(doc.querySelector('img').src, '');
+  assert.equal(doc.querySelector('div').href, null);
+  doc.querySelector('img').src = 'https://example.com/x.png';
+  assert.equal(doc.querySelector('img').getAttribute('src'), 'https://example.com/x.png');
+});
+
+test('input and textarea values', () => {
+  const doc = parse(
+    '<input value="a"><input type="checkbox"><input type="file" value="x"><textarea>text</textarea>',
+  );
+  const [text, checkbox, file] = doc.querySelectorAll('input');
+  assert.equal(text.value, 'a');
+  assert.equal(text.defaultValue, 'a');
+  assert.equal(checkbox.value, 'on');
+  assert.equal(file.value, '');
+  text.value = 'b';
+  assert.equal(text.getAttribute('value'), 'b');
+
+  const textarea = doc.querySelector('textarea');
+  assert.equal(textarea.value, 'text');
+  textarea.defaultValue = 'changed';
+  assert.equal(textarea.textContent, 'changed');
+  assert.equal(textarea.value, 'changed');
+});
+
+test('select, option and selectedIndex', () => {
+  const doc = parse(
+    '<select><option>  One  </option><optgroup><option value="2" selected>Two</option></optgroup><option value="3">Three</option></select>' +
+      '<select id="plain"><option disabled>A</option><option>B</option></select>',
+  );
+  const select = doc.querySelector('select');
+  const options = select.querySelectorAll('option');
+  assert.equal(options[0].value, 'One');
+  assert.equal(select.selectedIndex, 1);
+  assert.equal(select.value, '2');
+  assert.equal(options[1].selected, true);
+
+  select.selectedIndex = 2;
+  assert.equal(options[1].selected, false);
+  assert.equal(options[2].selected, true);
+  assert.equal(select.value, '3');
+
+  select.value = 'One';
+  assert.equal(select.selectedIndex, 0);
+  options[2].selected = true;
+  assert.equal(select.selectedIndex, 2);
+
+  const plain = doc.getElementById('plain');
+  assert.equal(plain.selectedIndex, 1);
+  assert.equal(plain.value, 'B');
+  assert.equal(doc.querySelector('div'), null);
+});
@@ -0,0 +1,112 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, extractResources } = pkg;
+
+const parse = (html, url) => new DOMParser({ url }).parseFromString(html, 'text/html');
+
+const summary = (resources) => resources.map(({ kind, attribute, url, resolvedUrl }) => [kind, attribute, url, resolvedUrl]);
+
+test('extracts links and subresources with resolved URLs', () => {
+  const doc = parse(
+    '<head><base href="/site/">'
+      + '<link rel="stylesheet" href="main.css" integrity="sha384-abc" crossorigin="anonymous" media="screen">'
+      + '<link rel="icon" href="/favicon.ico">'
+      + '<script src="app.js" crossorigin></script></head>'
+      + '<body><a href="page.html" rel="nofollow">p</a><a href="mailto:x@example.com">m</a><a>none</a>'
+      + '<iframe src="https://other.example/embed"></iframe><form action="send"><button formaction="alt">b</button></form>'
+      + '<video poster="poster.jpg"><source src="movie.mp4"><track src="subs.vtt"></video>'
+      + '<object data="file.swf"></object><input type="image" src="go.png"><input src="ignored.png"></body>',
+    'https://example.com/index.html',
+  );
+  const resources = extractResources(doc);
+  assert.deepEqual(summary(resources), [
+    ['stylesheet', 'href', 'main.css', 'https://example.com/site/main.css'],
+    ['link', 'href', '/favicon.ico', 'https://example.com/favicon.ico'],
+    ['script', 'src', 'app.js', 'https://example.com/site/app.js'],
+    ['hyperlink', 'href', 'page.html', 'https://example.com/site/page.html'],
+    ['hyperlink', 'href', 'mailto:x@example.com', 'mailto:x@example.com'],
+    ['frame', 'src', 'https://other.example/embed', 'https://other.example/embed'],
+    ['form', 'action', 'send', 'https://example.com/site/send'],
+    ['form', 'formaction', 'alt', 'https://example.com/site/alt'],
+    ['image', 'poster', 'poster.jpg', 'https://example.com/site/poster.jpg'],
+    ['media', 'src', 'movie.mp4', 'https://example.com/site/movie.mp4'],
+    ['media', 'src', 'subs.vtt', 'https://example.com/site/subs.vtt'],
+    ['embed', 'data', 'file.swf', 'https://example.com/site/file.swf'],
+    ['image', 'src', 'go.png', 'https://example.com/site/go.png'],
+  ]);
+  const stylesheet = resources[0];
+  assert.equal(stylesheet.element.tagName, 'LINK');
+  assert.equal(stylesheet.rel, 'stylesheet');
+  assert.equal(stylesheet.integrity, 'sha384-abc');
+  assert.equal(stylesheet.crossorigin, 'anonymous');
+  assert.equal(stylesheet.media, 'screen');
+  assert.equal(resources[2].crossorigin, '');
+  assert.equal(resources[3].rel, 'nofollow');
+  assert.equal(resources[3].integrity, undefined);
+});
+
+test('empty form actions resolve to the document URL', () => {
+  const doc = parse(
+    '<base href="/base/"><form action=""><button formaction="">b</button></form>',
+    'https://example.com/dir/page.html?q=1',
+  );
+  const form = doc.querySelector('form');
+  assert.deepEqual(summary(extractResources(doc.body)), [
+    ['form', 'action', '', 'https://example.com/dir/page.html?q=1'],
+    ['form', 'formaction', '', 'https://example.com/dir/page.html?q=1'],
+  ]);
+  assert.equal(form.action, 'https://example.com/dir/page.html?q=1');
+});
+
+test('splits srcset candidates', () => {
+  const doc = parse(
+    '<picture><source srcset="wide.webp 1200w, narrow.webp 600w" media="(min-width: 800px)"><img src="a.png" srcset="a.png, a@2x.png 2x"></picture>',
+    'https://example.com/img/',
+  );
+  const resources = extractResources(doc);
+  assert.deepEqual(summary(resources), [
+    ['image', 'srcset', 'wide.webp 1200w, narrow.webp 600w', undefined],
+    ['image', 'src', 'a.png', 'https://example.com/img/a.png'],
+    ['image', 'srcset', 'a.png, a@2x.png 2x', undefined],
+  ]);
+  assert.equal(resources[0].media, '(min-width: 800px)');
+  assert.deepEqual(resources[0].srcset, [
+    { url: 'wide.webp', resolvedUrl: 'https://example.com/img/wide.webp', descriptor: '1200w' },
+    { url: 'narrow.webp', resolvedUrl: 'https://example.com/img/narrow.webp', descriptor: '600w' },
+  ]);
+  assert.deepEqual(resources[2].srcset, [
+    { url: 'a.png', resolvedUrl: 'https://example.com/img/a.png' },
+    { url: 'a@2x.png', resolvedUrl: 'https://example.com/img/a@2x.png', descriptor: '2x' },
+  ]);
+});
+
+test('finds CSS and SVG references', () => {
+  const doc = parse(
+    '<style>@import url(base.css); @import "print.css" print; .a { background: url("bg.png") } .b { mask: url(#m) }</style>'
+      + '<div style="background-image: url(hero.jpg)"></div>'
+      + '<svg><image href="pic.svg"></image><use xlink:href="sprite.svg#icon"></use><use href="#local"></use></svg>'
+      + '<template><img src="inert.png"></template>',
+    'https://example.com/',
+  );
+  const resources = extractResources(doc);
+  assert.deepEqual(summary(resources), [
+    ['css', undefined, 'base.css', 'https://example.com/base.css'],
+    ['css', undefined, 'print.css', 'https://example.com/print.css'],
+    ['css', undefined, 'bg.png', 'https://example.com/bg.png'],
+    ['css', undefined, '#m', 'https://example.com/#m'],
+    ['css', 'style', 'hero.jpg', 'https://example.com/hero.jpg'],
+    ['image', 'href', 'pic.svg', 'https://example.com/pic.svg'],
+    ['image', 'xlink:href', 'sprite.svg#icon', 'https://example.com/sprite.svg#icon'],
+    ['image', 'src', 'inert.png', 'https://example.com/inert.png'],
+  ]);
+  assert.equal(resources[0].element.tagName, 'STYLE');
+});
+
+test('relative URLs stay unresolved without a base URL', () => {
+  const doc = parse('<a href="x.html">x</a><img src="https://cdn.example/i.png">');
+  assert.deepEqual(summary(extractResources(doc.body)), [
+    ['hyperlink', 'href', 'x.html', undefined],
+    ['image', 'src', 'https://cdn.example/i.png', 'https://cdn.example/i.png'],
+  ]);
+});
@@ -0,0 +1,125 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, Sanitizer } = pkg;
+
+const parse = (html) => new DOMParser().parseFromString(html, 'text/html');
+
+const clean = (html, sanitizer) => {
+  const div = parse('').createElement('div');
+  div.setHTML(html, { sanitizer });
+  return div.innerHTML;
+};
+
+test('keeps safe markup and removes handlers and scripts', () => {
+  assert.equal(
+    clean('<p onclick="x()" ONMOUSEOVER="y()" class="a" style="color:red" data-x="1">hi<script>alert(1)</script><!--c--></p>'),
+    '<p class="a" data-x="1">hi</p>',
+  );
+  assert.equal(clean('<custom>gone</custom><iframe src="x"></iframe><object data="x"></object><b>kept</b>'), '<b>kept</b>');
+});
+
+test('checks URL schemes', () => {
+  assert.equal(
+    clean('<a href="javascript:alert(1)">a</a><a href=" JaVa&#9;Script:alert(1)">b</a><a href="data:text/html,x">c</a>'),
+    '<a>a</a><a>b</a><a>c</a>',
+  );
+  assert.equal(
+    clean('<a href="/rel">a</a><a href="https://example.com">b</a><a href="mailto:x@example.com">c</a>'),
+    '<a href="/rel">a</a><a href="https://example.com">b</a><a href="mailto:x@example.com">c</a>',
+  );
+  assert.equal(clean('<img srcset="a.png 1x, javascript:x 2x"><img srcset="a.png 1x, b.png 2x">'), '<img><img srcset="a.png 1x, b.png 2x">');
+  assert.equal(clean('<form action="javascript:1"><button formaction="javascript:2">b</button></form>'), '<form><button>b</button></form>');
+  assert.equal(clean('<img src="data:image/png;base64,AA==">', { urlSchemes: ['data'] }), '<img src="data:image/png;base64,AA==">');
+  assert.equal(clean('<a href="javascript:1">x</a>', { urlSchemes: ['javascript'] }), '<a>x</a>');
+});
+
+test('sanitizes template contents and foreign content', () => {
+  assert.equal(clean('<template><script>1</script><b onclick="1">t</b></template>'), '<template><b>t</b></template>');
+  assert.equal(
+    clean('<svg><script>alert(1)</script><a xlink:href="javascript:1"><circle r="1" onload="x"/></a><foreignObject><img src="x"></foreignObject><animate attributeName="href" to="javascript:1"/></svg>'),
+    '<svg><a><circle r="1"></circle></a></svg>',
+  );
+  assert.equal(clean('<math><mi>x</mi><annotation-xml encoding="text/html"><img src="x"></annotation-xml></math>'), '<math><mi>x</mi></math>');
+});
+
+test('removes elements that would change namespace when parsed again', () => {
+  const doc = parse('');
+  const div = doc.createElement('div');
+  const svg = doc.createElementNS('http://www.w3.org/2000/svg', 'svg');
+  svg.appendChild(doc.createElement('p'));
+  svg.appendChild(doc.createElementNS('http://www.w3.org/2000/svg', 'style'));
+  div.appendChild(svg);
+  div.appendChild(doc.createElementNS('http://www.w3.org/2000/svg', 'circle'));
+  new Sanitizer({}).sanitize(div);
+  assert.equal(div.innerHTML, '<svg><style></style></svg>');
+});
+
+test('output is stable when parsed and sanitized again', () => {
+  const inputs = [
+    '<svg></p><style><a id="</style><img src=1 onerror=alert(1)>">',
+    '<math><mtext><table><mglyph><style><img src=x onerror=alert(1)>',
+    '<form><math><mtext></form><form><mglyph><style></math><img src onerror=alert(1)>',
+    '<noscript><p title="</noscript><img src=x onerror=alert(1)>">',
+  ];
+  for (const input of inputs) {
+    const once = clean(input);
+    assert.equal(clean(once), once, input);
+    assert.doesNotMatch(once, /onerror/);
+  }
+});
+
+test('does not keep HTML mglyph or malignmark in MathML text integration points', () => {
+  for (const tag of ['mglyph', 'malignmark']) {
+    const input = `<math><mtext><table><${tag}><style><img src=x onerror=alert(1)>`;
+    const once = clean(input, new Sanitizer({}));
+    const reparsed = parse('').createElement('div');
+    reparsed.innerHTML = once;
+    assert.equal(reparsed.innerHTML, once, input);
+    assert.equal(reparsed.querySelector('img'), null);
+  }
+});
+
+test('custom configurations', () => {
+  assert.equal(
+    clean('<b>x</b><i>y</i><custom>c</custom><!--note-->', { replaceWithChildrenElements: ['b'], removeElements: ['i'], comments: true }),
+    'x<custom>c</custom><!--note-->',
+  );
+  const sanitizer = new Sanitizer({ elements: [{ name: 'b', attributes: ['class'] }], attributes: ['id'], dataAttributes: false });
+  assert.equal(clean('<b class="x" id="y" title="z" data-a="1">x</b><i>y</i>', sanitizer), '<b class="x" id="y">x</b>');
+  assert.equal(clean('<p class="a" id="b">x</p>', { removeAttributes: ['class'] }), '<p id="b">x</p>');
+  assert.equal(
+    clean('<svg><circle r="1" fill="red"/></svg>', { elements: [{ name: 'svg', namespace: 'http://www.w3.org/2000/svg' }, { name: 'circle', namespace: 'http://www.w3.org/2000/svg' }], attributes: [] }),
+    '<svg><circle></circle></svg>',
+  );
+});
+
+test('sanitize works in place on documents', () => {
+  const doc = parse('<!DOCTYPE html><html><head><script>x</script><title>T</title><base href="http://evil"></head><body onload="x"><p>a</p></body></html>');
+  new Sanitizer().sanitize(doc);
+  assert.equal(doc.outerHTML, '<!DOCTYPE html><html><head><title>T</title></head><body><p>a</p></body></html>');
+
+  const host = parse('<div id="h"><template shadowrootmode="open"><script>1</script><b>s</b></template></div>').getElementById('h');
+  new Sanitizer().sanitize(host);
+  assert.equal(host.getHTML({ serializableShadowRoots: false, shadowRoots: [host.shadowRoot] }), '<template shadowrootmode="open"><b>s</b></template>');
+});
+
+test('setHTML leaves script elements empty and ignores declarative shadow roots', () => {
+  const doc = parse('');
+  const script = doc.createElement('script');
+  script.setHTML('alert(1)');
+  assert.equal(script.innerHTML, '');
+  const div = doc.createElement('div');
+  div.setHTML('<template shadowrootmode="open"><b>x</b></template>');
+  assert.equal(div.shadowRoot, null);
+  assert.equal(div.innerHTML, '<template><b>x</b></template>');
+});
+
+test('setHTML detaches the children it replaces', () => {
+  const doc = parse('<div><b>old</b></div><script>old</script>');
+  for (const element of [doc.querySelector('div'), doc.querySelector('script')]) {
+    const old = element.firstChild;
+    element.setHTML('<i>new</i>');
+    assert.equal(old.parentNode, null);
+  }
+});
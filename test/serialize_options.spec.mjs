@@ -0,0 +1,77 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser } = pkg;
+
+const parser = new DOMParser();
+const parse = (html) => parser.parseFromString(html, 'text/html');
+
+test('default options match outerHTML', () => {
+  const doc = parse('<div id="a" title="x &quot;y&quot;">a &amp; b<br><!--c--></div><template><i>t</i></template>');
+  assert.equal(doc.serialize(), doc.outerHTML);
+  const div = doc.querySelector('div');
+  assert.equal(div.serialize({}), div.outerHTML);
+});
+
+test('pretty-prints block elements and keeps inline content', () => {
+  const doc = parse('<div><p>Hello <b>big</b>  world</p><ul><li>One</li><li>Two</li></ul>text <i>x</i></div>');
+  assert.equal(
+    doc.querySelector('div').serialize({ pretty: true }),
+    [
+      '<div>',
+      '  <p>Hello <b>big</b>  world</p>',
+      '  <ul>',
+      '    <li>One</li>',
+      '    <li>Two</li>',
+      '  </ul>',
+      '  text <i>x</i>',
+      '</div>',
+    ].join('\n'),
+  );
+});
+
+test('pretty-printing respects pre, textarea and custom indent', () => {
+  const doc = parse('<section><pre>\n  a\n <b> b </b></pre><textarea> x\n y</textarea><div></div></section>');
+  assert.equal(
+    doc.querySelector('section').serialize({ pretty: true, indent: '\t' }),
+    '<section>\n\t<pre>  a\n <b> b </b></pre>\n\t<textarea> x\n y</textarea>\n\t<div></div>\n</section>',
+  );
+});
+
+test('minify collapses whitespace, drops comments and optional tags', () => {
+  const doc = parse(
+    '<!DOCTYPE html><html><head><title>T</title></head><body>\n  <!-- note -->\n  <p class="a b" id="x">Hello   <b>world</b> </p>\n  <ul>\n    <li>One</li>\n    <li>Two</li>\n  </ul>\n  <pre>  keep  </pre>\n</body></html>',
+  );
+  assert.equal(
+    doc.serialize({ minify: true }),
+    '<!DOCTYPE html><title>T</title><p class="a b" id=x>Hello <b>world</b><ul><li>One<li>Two</ul><pre>  keep  </pre>',
+  );
+});
+
+test('minify keeps end tags that are required', () => {
+  const doc = parse('<div><a href="/"><p>x</p></a><p>y</p>z</div>');
+  assert.equal(doc.querySelector('div').serialize({ minify: true }), '<div><a href=/><p>x</p></a><p>y</p>z</div>');
+});
+
+test('attribute quoting, sorting and void element style', () => {
+  const doc = parse(`<div><img src="a.png" alt="" data-x="it's"><input disabled></div>`);
+  const div = doc.querySelector('div');
+  assert.equal(
+    div.serialize({ quote: 'single', sortAttributes: true, selfClosingVoid: true }),
+    `<div><img alt='' data-x='it&#39;s' src='a.png' /><input disabled='' /></div>`,
+  );
+  assert.equal(
+    div.serialize({ quote: 'minimal' }),
+    `<div><img src=a.png alt data-x="it's"><input disabled></div>`,
+  );
+  assert.equal(
+    div.serialize({ minify: true, quote: 'double' }),
+    `<div><img src="a.png" alt="" data-x="it's"><input disabled=""></div>`,
+  );
+});
+
+test('invalid option combinations throw', () => {
+  const doc = parse('<p></p>');
+  assert.throws(() => doc.serialize({ pretty: true, minify: true }));
+  assert.throws(() => doc.serialize({ quote: 'backtick' }));
+});
@@ -0,0 +1,43 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, DOMImplementation } = pkg;
+
+const parser = new DOMParser();
+
+test('noscript round-trips in parsed documents', () => {
+  const doc = parser.parseFromString('<body><noscript><p>a &amp; b</p></noscript></body>', 'text/html');
+  const noscript = doc.querySelector('noscript');
+  // Parsed documents use scripting, so noscript holds raw text.
+  assert.equal(noscript.childNodes.length, 1);
+  assert.equal(noscript.firstChild.nodeType, 3);
+  assert.equal(noscript.innerHTML, '<p>a &amp; b</p>');
+  assert.equal(doc.body.innerHTML, '<noscript><p>a &amp; b</p></noscript>');
+  assert.equal(noscript.outerHTML, '<noscript><p>a &amp; b</p></noscript>');
+  assert.equal(doc.body.serialize(), '<body><noscript><p>a &amp; b</p></noscript></body>');
+  assert.equal(doc.body.serialize({ pretty: true }), '<body>\n  <noscript><p>a &amp; b</p></noscript>\n</body>');
+});
+
+test('innerHTML parses with the document scripting mode', () => {
+  const doc = parser.parseFromString('<div></div>', 'text/html');
+  const div = doc.querySelector('div');
+  div.innerHTML = '<noscript><b>x</b></noscript>';
+  assert.equal(div.querySelector('b'), null);
+  assert.equal(div.innerHTML, '<noscript><b>x</b></noscript>');
+});
+
+test('created documents serialize noscript content as markup', () => {
+  const doc = new DOMImplementation().createHTMLDocument('t');
+  doc.body.innerHTML = '<noscript><b>a &amp; b</b></noscript>';
+  assert.equal(doc.querySelector('noscript b').textContent, 'a & b');
+  assert.equal(doc.body.innerHTML, '<noscript><b>a &amp; b</b></noscript>');
+  assert.equal(doc.body.serialize({ minify: true }), '<body><noscript><b>a &amp; b</b></noscript>');
+});
+
+test('outerHTML of documents and fragments serializes their children', () => {
+  const doc = parser.parseFromString('<p>x</p>', 'text/html');
+  assert.equal(doc.outerHTML, doc.innerHTML);
+  const fragment = doc.createDocumentFragment();
+  fragment.appendChild(doc.createElement('i'));
+  assert.equal(fragment.outerHTML, '<i></i>');
+});
@@ -0,0 +1,163 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser } = pkg;
+
+const parser = new DOMParser();
+
+test('attachShadow creates an open shadow root', () => {
+  const doc = parser.parseFromString('<div id="host"></div>', 'text/html');
+  const host = doc.getElementById('host');
+  const root = host.attachShadow({ mode: 'open' });
+
+  assert.equal(root.nodeType, 11);
+  assert.equal(root.nodeName, '#document-fragment');
+  assert.equal(root.mode, 'open');
+  assert.equal(root.slotAssignment, 'named');
+  assert.ok(root.host.isSameNode(host));
+  assert.ok(host.shadowRoot.isSameNode(root));
+  assert.equal(root.parentNode, null);
+
+  root.innerHTML = '<span>shadow</span>';
+  assert.equal(root.querySelector('span').textContent, 'shadow');
+  assert.ok(root.querySelector('span').getRootNode().isSameNode(root));
+  assert.equal(doc.querySelector('span'), null);
+  assert.equal(host.innerHTML, '');
+});
+
+test('shadow roots do not expose element accessors', () => {
+  const doc = parser.parseFromString('<div id="host"><p>light</p></div>', 'text/html');
+  const host = doc.getElementById('host');
+  const root = host.attachShadow({ mode: 'open', clonable: true, delegatesFocus: true });
+  root.innerHTML = '<span>x</span>';
+
+  assert.equal(root.tagName, null);
+  assert.equal(root.localName, null);
+  assert.equal(root.getAttribute('mode'), null);
+  assert.equal(root.hasAttributes(), false);
+  assert.equal(root.matches('*'), false);
+  assert.equal(root.querySelector('span').closest('*').localName, 'span');
+  assert.equal(root.querySelector('span').closest('div'), null);
+  root.setAttribute('mode', 'closed');
+  assert.equal(root.hasAttributes(), false);
+  assert.equal(root.mode, 'open');
+  assert.equal(root.delegatesFocus, true);
+
+  const clone = host.cloneNode(true);
+  assert.equal(clone.shadowRoot.mode, 'open');
+  assert.equal(clone.shadowRoot.clonable, true);
+  assert.equal(clone.shadowRoot.delegatesFocus, true);
+});
+
+test('closed shadow roots are hidden from shadowRoot', () => {
+  const doc = parser.parseFromString('<div id="host"></div>', 'text/html');
+  const host = doc.getElementById('host');
+  const root = host.attachShadow({ mode: 'closed' });
+  assert.equal(host.shadowRoot, null);
+  assert.equal(root.mode, 'closed');
+});
+
+test('attachShadow rejects invalid hosts and double attachment', () => {
+  const doc = parser.parseFromString('<div id="host"></div><img>', 'text/html');
+  const host = doc.getElementById('host');
+  assert.throws(() => doc.querySelector('img').attachShadow({ mode: 'open' }));
+  assert.throws(() => host.attachShadow({ mode: 'sideways' }));
+  host.attachShadow({ mode: 'open' });
+  assert.throws(() => host.attachShadow({ mode: 'open' }));
+  assert.ok(doc.createElement('my-element').attachShadow({ mode: 'open' }));
+});
+
+test('declarative shadow roots are attached while parsing', () => {
+  const doc = parser.parseFromString(
+    '<div id="host"><template shadowrootmode="open" shadowrootserializable><p>inside</p><slot></slot></template><b>light</b></div>',
+    'text/html',
+  );
+  const host = doc.getElementById('host');
+  assert.ok(host.shadowRoot);
+  assert.equal(host.shadowRoot.serializable, true);
+  assert.equal(host.querySelector('template'), null);
+  assert.equal(host.shadowRoot.querySelector('p').textContent, 'inside');
+  assert.equal(host.innerHTML, '<b>light</b>');
+  assert.equal(
+    host.getHTML({ serializableShadowRoots: true }),
+    '<template shadowrootmode="open" shadowrootserializable=""><p>inside</p><slot></slot></template><b>light</b>',
+  );
+  assert.equal(host.getHTML(), '<b>light</b>');
+});
+
+test('declarative shadow root attributes are case-insensitive', () => {
+  for (const sourceLocations of [false, true]) {
+    const doc = new DOMParser({ sourceLocations }).parseFromString(
+      '<div id="host"><template SHADOWROOTMODE="Open"><p>inside</p></template></div>',
+      'text/html',
+    );
+    const host = doc.getElementById('host');
+    assert.equal(host.shadowRoot.mode, 'open');
+    assert.equal(host.shadowRoot.querySelector('p').textContent, 'inside');
+  }
+});
+
+test('setHTMLUnsafe parses declarative shadow roots', () => {
+  const doc = parser.parseFromString('<div id="host"></div>', 'text/html');
+  const host = doc.getElementById('host');
+  host.setHTMLUnsafe('<section><template shadowrootmode="closed"><i>x</i></template></section>');
+  const section = host.querySelector('section');
+  assert.equal(section.shadowRoot, null);
+  assert.equal(section.getHTML({ shadowRoots: [] }), '');
+});
+
+test('slots are assigned by name', () => {
+  const doc = parser.parseFromString(
+    '<div id="host"><span slot="title">Title</span><p>Body</p></div>',
+    'text/html',
+  );
+  const host = doc.getElementById('host');
+  const root = host.attachShadow({ mode: 'open' });
+  root.innerHTML = '<slot name="title"></slot><slot id="default"><em>fallback</em></slot>';
+
+  const titleSlot = root.querySelector('slot[name="title"]');
+  const defaultSlot = root.querySelector('#default');
+  assert.deepEqual(titleSlot.assignedNodes().map((n) => n.textContent), ['Title']);
+  assert.deepEqual(defaultSlot.assignedElements().map((n) => n.tagName), ['P']);
+  assert.ok(host.querySelector('span').assignedSlot.isSameNode(titleSlot));
+
+  const empty = doc.createElement('div');
+  const emptyRoot = empty.attachShadow({ mode: 'open' });
+  emptyRoot.innerHTML = '<slot></slot>';
+  const slot = emptyRoot.querySelector('slot');
+  assert.equal(slot.assignedNodes().length, 0);
+  slot.innerHTML = '<em>fallback</em>';
+  assert.deepEqual(slot.assignedNodes({ flatten: true }).map((n) => n.tagName), ['EM']);
+});
+
+test(':host, ::slotted and ::part match inside shadow trees', () => {
+  const doc = parser.parseFromString(
+    '<div id="host" class="card"><span>light</span></div>',
+    'text/html',
+  );
+  const host = doc.getElementById('host');
+  const root = host.attachShadow({ mode: 'open' });
+  root.innerHTML = '<header part="title"></header><slot></slot>';
+
+  assert.ok(host.matchesInShadowTree(':host', host));
+  assert.ok(host.matchesInShadowTree(':host(.card)', host));
+  assert.ok(!host.matchesInShadowTree(':host(.other)', host));
+  assert.ok(host.querySelector('span').matchesInShadowTree('::slotted(span)', host));
+  assert.ok(root.querySelector('header').matchesInShadowTree('::part(title)', host));
+  assert.equal(root.querySelectorAll('header').length, 1);
+});
+
+test('cloneNode copies only clonable shadow roots', () => {
+  const doc = parser.parseFromString('<div id="a"></div><div id="b"></div>', 'text/html');
+  const a = doc.getElementById('a');
+  const b = doc.getElementById('b');
+  a.attachShadow({ mode: 'open', clonable: true }).innerHTML = '<i>a</i>';
+  b.attachShadow({ mode: 'open' }).innerHTML = '<i>b</i>';
+
+  const aClone = a.cloneNode(true);
+  assert.ok(aClone.shadowRoot);
+  assert.ok(!aClone.shadowRoot.isSameNode(a.shadowRoot));
+  assert.ok(aClone.shadowRoot.host.isSameNode(aClone));
+  assert.equal(aClone.shadowRoot.innerHTML, '<i>a</i>');
+  assert.equal(b.cloneNode(true).shadowRoot, null);
+});
@@ -0,0 +1,87 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, Document, fromSnapshot } = pkg;
+
+const parse = (html) => new DOMParser().parseFromString(html, 'text/html');
+
+test('toSnapshot returns a versioned Buffer', () => {
+  const snapshot = parse('<p>hi</p>').toSnapshot();
+  assert.ok(Buffer.isBuffer(snapshot));
+  assert.equal(snapshot.subarray(0, 4).toString('latin1'), 'DPSN');
+  assert.equal(snapshot.readUInt16LE(4), 1);
+});
+
+test('round-trips a document', () => {
+  const doc = parse('<!DOCTYPE html><html lang="en"><head><title>T</title></head><body><p id="a" class="x">x &amp; y</p><!--c--><noscript><b>n</b></noscript></body></html>');
+  const copy = fromSnapshot(doc.toSnapshot());
+  assert.equal(copy.nodeType, 9);
+  assert.equal(copy.contentType, 'text/html');
+  assert.equal(copy.doctype.name, 'html');
+  assert.equal(copy.outerHTML, doc.outerHTML);
+  assert.equal(copy.getElementById('a').textContent, 'x & y');
+});
+
+test('interns repeated strings', () => {
+  const snapshot = parse(`<ul>${'<li class="item">repeated text</li>'.repeat(200)}</ul>`).toSnapshot();
+  const occurrences = (needle) => snapshot.toString('latin1').split(needle).length - 1;
+  assert.equal(occurrences('repeated text'), 1);
+  assert.equal(occurrences('item'), 1);
+});
+
+test('keeps template contents, shadow roots and namespaces', () => {
+  const doc = parse('<div id="host"><template shadowrootmode="closed" shadowrootdelegatesfocus><slot></slot></template>light</div><template id="t"><b>tpl</b></template><svg><use xlink:href="#a"/></svg>');
+  const copy = fromSnapshot(doc.toSnapshot());
+  assert.equal(copy.getElementById('t').content.firstChild.outerHTML, '<b>tpl</b>');
+  const root = copy.getElementById('host').toJSON().shadowRoot;
+  assert.equal(root.mode, 'closed');
+  assert.equal(root.delegatesFocus, true);
+  const use = copy.querySelector('use');
+  assert.equal(use.namespaceURI, 'http://www.w3.org/2000/svg');
+  assert.equal(use.getAttributeNS('http://www.w3.org/1999/xlink', 'href'), '#a');
+});
+
+test('snapshots elements and XML documents', () => {
+  const element = parse('<ul><li>one</li></ul>').querySelector('ul');
+  const copy = fromSnapshot(element.toSnapshot());
+  assert.equal(copy.outerHTML, '<ul><li>one</li></ul>');
+  assert.equal(copy.parentNode, null);
+
+  const xml = new Document();
+  xml.appendChild(xml.createElementNS('urn:x', 'x:root'));
+  const xmlCopy = fromSnapshot(xml.toSnapshot());
+  assert.equal(xmlCopy.contentType, 'application/xml');
+  assert.equal(xmlCopy.documentElement.prefix, 'x');
+});
+
+test('accepts typed arrays and ArrayBuffers', () => {
+  const snapshot = parse('<p>hi</p>').toSnapshot();
+  const bytes = new Uint8Array(snapshot);
+  assert.equal(fromSnapshot(bytes).body.innerHTML, '<p>hi</p>');
+  assert.equal(fromSnapshot(bytes.buffer).body.innerHTML, '<p>hi</p>');
+  assert.throws(() => fromSnapshot('DPSN'), TypeError);
+});
+
+test('rejects corrupt and incompatible snapshots', () => {
+  const snapshot = parse('<p>hi</p>').toSnapshot();
+  assert.throws(() => fromSnapshot(Buffer.from('nope')), /missing header/);
+  const future = Buffer.from(snapshot);
+  future.writeUInt16LE(2, 4);
+  assert.throws(() => fromSnapshot(future), /Unsupported snapshot version 2/);
+  const newerMinor = Buffer.from(snapshot);
+  newerMinor.writeUInt16LE(1, 6);
+  assert.throws(() => fromSnapshot(newerMinor), /Unsupported snapshot version 1\.1/);
+  assert.throws(() => fromSnapshot(snapshot.subarray(0, snapshot.length - 3)), /unexpected end of data/);
+});
+
+test('loads faster than parsing', () => {
+  const html = `<table>${'<tr><td class="c">cell <b>bold</b></td><td><a href="/x">link</a></td></tr>'.repeat(2000)}</table>`;
+  const snapshot = parse(html).toSnapshot();
+  const time = (fn) => {
+    const start = process.hrtime.bigint();
+    for (let i = 0; i < 5; i++) fn();
+    return Number(process.hrtime.bigint() - start);
+  };
+  time(() => fromSnapshot(snapshot));
+  assert.ok(time(() => fromSnapshot(snapshot)) < time(() => parse(html)));
+});
@@ -0,0 +1,99 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser } = pkg;
+
+const parse = (html) => new DOMParser({ sourceLocations: true }).parseFromString(html, 'text/html');
+
+const source = (html, span) => Buffer.from(html).subarray(span.startOffset, span.endOffset).toString();
+
+test('is off by default', () => {
+  const doc = new DOMParser().parseFromString('<p>a</p>', 'text/html');
+  assert.equal(doc.querySelector('p').sourceLocation, null);
+});
+
+test('locates elements, tags and attributes', () => {
+  const html = '<p class="note" hidden>Hello</p>';
+  const location = parse(html).querySelector('p').sourceLocation;
+  assert.equal(source(html, location), html);
+  assert.equal(source(html, location.startTag), '<p class="note" hidden>');
+  assert.equal(source(html, location.endTag), '</p>');
+  assert.equal(source(html, location.attrs.class), 'class="note"');
+  assert.equal(source(html, location.attrs.hidden), 'hidden');
+  assert.deepEqual(location.attrs.class, {
+    startOffset: 3,
+    endOffset: 15,
+    startLine: 1,
+    startColumn: 4,
+    endLine: 1,
+    endColumn: 16,
+  });
+});
+
+test('locates text, comments and doctypes', () => {
+  const html = '<!DOCTYPE html>\n<div>one <b>two</b><!--note--></div>';
+  const doc = parse(html);
+  const div = doc.querySelector('div');
+  assert.equal(source(html, doc.doctype.sourceLocation), '<!DOCTYPE html>');
+  assert.equal(source(html, div.firstChild.sourceLocation), 'one ');
+  assert.equal(source(html, div.lastChild.sourceLocation), '<!--note-->');
+  const text = div.querySelector('b').firstChild.sourceLocation;
+  assert.deepEqual([text.startLine, text.startColumn, text.endLine, text.endColumn], [2, 13, 2, 16]);
+  assert.equal(text.startTag, undefined);
+});
+
+test('counts columns in characters and offsets in bytes', () => {
+  const html = '<p>héllo</p>\r\n<p>wörld</p>';
+  const [first, second] = parse(html).querySelectorAll('p');
+  assert.equal(source(html, first.sourceLocation), '<p>héllo</p>');
+  assert.equal(first.sourceLocation.endOffset, 13);
+  assert.equal(first.sourceLocation.endColumn, 13);
+  const location = second.sourceLocation;
+  assert.equal(source(html, location), '<p>wörld</p>');
+  assert.deepEqual([location.startLine, location.startColumn, location.endLine, location.endColumn], [2, 1, 2, 13]);
+});
+
+test('text spans end after a trailing CRLF', () => {
+  const html = '<pre>\r\na\r\n</pre><p>b\r\n</p>';
+  const doc = parse(html);
+  assert.equal(source(html, doc.querySelector('pre').firstChild.sourceLocation), 'a\r\n');
+  const text = doc.querySelector('p').firstChild.sourceLocation;
+  assert.equal(source(html, text), 'b\r\n');
+  assert.deepEqual([text.endLine, text.endColumn], [4, 1]);
+});
+
+test('elements closed implicitly end with their content', () => {
+  const html = '<ul><li>one<li>two</ul><p>open';
+  const doc = parse(html);
+  const [one, two] = doc.querySelectorAll('li');
+  assert.equal(source(html, one.sourceLocation), '<li>one');
+  assert.equal(one.sourceLocation.endTag, undefined);
+  assert.equal(source(html, two.sourceLocation), '<li>two');
+  assert.equal(source(html, doc.querySelector('ul').sourceLocation), '<ul><li>one<li>two</ul>');
+  assert.equal(source(html, doc.querySelector('p').sourceLocation), '<p>open');
+});
+
+test('handles misnested formatting, tables, templates and foreign content', () => {
+  const html = '<b>x<i>y</b>z</i><table><tr><td>c</td></tr></table><template><em>t</em></template><svg viewBox="0 0 1 1"><foreignObject>f</foreignObject></svg>';
+  const doc = parse(html);
+  assert.equal(source(html, doc.querySelector('b').sourceLocation), '<b>x<i>y</b>');
+  assert.equal(source(html, doc.querySelector('table').sourceLocation), '<table><tr><td>c</td></tr></table>');
+  assert.equal(doc.querySelector('tbody').sourceLocation, null);
+  assert.equal(source(html, doc.querySelector('template').content.firstChild.sourceLocation), '<em>t</em>');
+  const svg = doc.querySelector('svg').sourceLocation;
+  assert.equal(source(html, svg.attrs.viewBox), 'viewBox="0 0 1 1"');
+  assert.equal(source(html, doc.querySelector('foreignObject').sourceLocation), '<foreignObject>f</foreignObject>');
+});
+
+test('implied and created nodes have no location', () => {
+  const doc = parse('<div id="a"><p>x</p></div>');
+  assert.equal(doc.sourceLocation, null);
+  assert.equal(doc.documentElement.sourceLocation, null);
+  assert.equal(doc.body.sourceLocation, null);
+  const div = doc.getElementById('a');
+  assert.equal(div.cloneNode(true).sourceLocation, null);
+  assert.equal(doc.createElement('p').sourceLocation, null);
+  div.innerHTML = '<i>new</i>';
+  assert.equal(div.firstChild.sourceLocation, null);
+  assert.notEqual(div.sourceLocation, null);
+});
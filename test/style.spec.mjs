@@ -0,0 +1,157 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser } = pkg;
+
+const element = (style) => {
+  const doc = new DOMParser().parseFromString(`<div style="${style}"></div>`, 'text/html');
+  return doc.querySelector('div');
+};
+
+test('reads declarations from the style attribute', () => {
+  const div = element('COLOR: red; background-color : rgb( 1 , 2 , 3 ) /* note */ ; --Accent: 1px  2px; bogus; width: 10px !IMPORTANT');
+  const { style } = div;
+  assert.equal(style.getPropertyValue('color'), 'red');
+  assert.equal(style.getPropertyValue('background-color'), 'rgb(1, 2, 3)');
+  assert.equal(style.getPropertyValue('--Accent'), '1px  2px');
+  assert.equal(style.getPropertyValue('--accent'), '');
+  assert.equal(style.getPropertyPriority('width'), 'important');
+  assert.equal(style.getPropertyPriority('color'), '');
+  assert.equal(style.length, 4);
+  assert.equal(style.item(1), 'background-color');
+  assert.equal(style[0], 'color');
+  assert.equal(style.item(9), '');
+  assert.deepEqual([...style], ['color', 'background-color', '--Accent', 'width']);
+  assert.equal(style.cssText, 'color: red; background-color: rgb(1, 2, 3); --Accent: 1px  2px; width: 10px !important;');
+});
+
+test('writes changes back to the attribute', () => {
+  const div = element('color: red; margin-top: 1px');
+  div.style.setProperty('color', 'blue');
+  div.style.setProperty('display', 'flex', 'important');
+  div.style.setProperty('top', '1px', 'bogus');
+  assert.equal(div.getAttribute('style'), 'color: blue; margin-top: 1px; display: flex !important;');
+  assert.equal(div.style.removeProperty('margin-top'), '1px');
+  div.style.setProperty('color', '');
+  div.style.setProperty('display', 'grid)');
+  assert.equal(div.getAttribute('style'), 'display: flex !important;');
+  div.style.cssText = 'opacity: 0.5; opacity: 1';
+  assert.equal(div.getAttribute('style'), 'opacity: 1;');
+  div.style = 'color: red !important; color: blue';
+  assert.equal(div.getAttribute('style'), 'color: red !important;');
+
+  const plain = element('').ownerDocument.createElement('span');
+  plain.style.removeProperty('color');
+  assert.equal(plain.hasAttribute('style'), false);
+});
+
+test('ignores values that are more than a single declaration value', () => {
+  const div = element('color: red');
+  div.style.setProperty('color', 'blue; display: none');
+  div.style.color = 'green; display: none';
+  div.style.setProperty('width', '1px !important');
+  div.style.setProperty('--x', 'a; --y: b');
+  assert.equal(div.style.cssText, 'color: red;');
+  div.style.setProperty('content', '"a; b"');
+  div.style.setProperty('--x', '{ a; b }');
+  assert.equal(div.style.content, '"a; b"');
+  assert.equal(div.style.getPropertyValue('--x'), '{ a; b }');
+  assert.equal(div.style.display, '');
+});
+
+test('supports camelCase fields', () => {
+  const div = element('');
+  div.style.backgroundColor = 'red';
+  div.style.cssFloat = 'left';
+  div.style.webkitTransform = 'none';
+  div.style['font-size'] = '12px';
+  assert.equal(div.style.backgroundColor, 'red');
+  assert.equal(div.style.float, 'left');
+  assert.equal(div.getAttribute('style'), 'background-color: red; float: left; -webkit-transform: none; font-size: 12px;');
+  div.style.backgroundColor = null;
+  assert.equal(div.style.getPropertyValue('background-color'), '');
+  assert.equal(div.style.color, '');
+});
+
+test('expands and combines shorthands', () => {
+  const div = element('margin: 1px 2px; border: 2px solid; flex: 1; padding: 0 !important');
+  const { style } = div;
+  assert.equal(style.marginLeft, '2px');
+  assert.equal(style.marginBottom, '1px');
+  assert.equal(style.borderTopColor, 'currentcolor');
+  assert.equal(style.borderLeftStyle, 'solid');
+  assert.equal(style.flexBasis, '0%');
+  assert.equal(style.getPropertyPriority('padding'), 'important');
+  assert.equal(style.length, 23);
+  assert.equal(style.cssText, 'margin: 1px 2px; border: 2px solid; flex: 1 1 0%; padding: 0 !important;');
+
+  style.borderTopColor = 'red';
+  assert.equal(style.border, '');
+  assert.equal(style.borderColor, 'red currentcolor currentcolor');
+  assert.equal(style.cssText, 'margin: 1px 2px; border-width: 2px; border-style: solid; border-color: red currentcolor currentcolor; flex: 1 1 0%; padding: 0 !important;');
+
+  assert.equal(style.removeProperty('margin'), '1px 2px');
+  assert.equal(style.marginTop, '');
+  style.marginTop = '3px';
+  assert.equal(style.margin, '');
+});
+
+test('expands and combines font and background', () => {
+  const { style } = element('');
+  style.setProperty('font', 'italic bold 12px/30px Georgia, serif');
+  assert.deepEqual(
+    [style.fontStyle, style.fontVariant, style.fontWeight, style.fontSize, style.lineHeight, style.fontFamily],
+    ['italic', 'normal', 'bold', '12px', '30px', 'Georgia, serif'],
+  );
+  assert.equal(style.font, 'italic bold 12px / 30px Georgia, serif');
+  style.fontVariant = 'all-small-caps';
+  assert.equal(style.font, '');
+  style.font = '12px';
+  assert.equal(style.fontSize, '12px');
+
+  style.background = 'url(a.png) no-repeat center / cover, linear-gradient(red, blue) #fff';
+  assert.equal(style.backgroundImage, 'url(a.png), linear-gradient(red, blue)');
+  assert.equal(style.backgroundRepeat, 'no-repeat, repeat');
+  assert.equal(style.backgroundSize, 'cover, auto');
+  assert.equal(style.backgroundColor, '#fff');
+  assert.equal(style.background, 'url(a.png) center / cover no-repeat, linear-gradient(red, blue) #fff');
+  style.background = 'red, blue';
+  assert.equal(style.backgroundColor, '#fff');
+  style.background = 'none';
+  assert.equal(style.cssText, 'font-style: italic; font-variant: all-small-caps; font-weight: bold; font-stretch: normal; font-size: 12px; line-height: 30px; font-family: Georgia, serif; background: none;');
+});
+
+test('accepts system font keywords as a font value', () => {
+  for (const keyword of ['caption', 'icon', 'menu', 'message-box', 'small-caption', 'status-bar']) {
+    const { style } = element('');
+    style.cssText = `font: ${keyword.toUpperCase()}`;
+    assert.equal(style.font, keyword);
+    assert.equal(style.cssText, `font: ${keyword};`);
+  }
+  const { style } = element('font: menu');
+  style.fontSize = '12px';
+  assert.equal(style.font, '');
+  assert.equal(element('font: menu 12px').style.cssText, '');
+});
+
+test('keeps unknown properties', () => {
+  assert.equal(element('bar: baz; color: red').style.cssText, 'bar: baz; color: red;');
+});
+
+test('handles radius, flow, pairs and variables', () => {
+  const { style } = element('border-radius: 1px 2px / 3px; flex-flow: wrap column; overflow: hidden; gap: 1px 2px; outline: none; padding: var(--x); margin: 1px 2px 3px 4px 5px');
+  assert.equal(style.borderTopLeftRadius, '1px 3px');
+  assert.equal(style.borderTopRightRadius, '2px 3px');
+  assert.equal(style.borderRadius, '1px 2px / 3px');
+  assert.equal(style.flexDirection, 'column');
+  assert.equal(style.flexFlow, 'column wrap');
+  assert.equal(style.overflowY, 'hidden');
+  assert.equal(style.columnGap, '2px');
+  assert.equal(style.outline, 'none');
+  assert.equal(style.padding, 'var(--x)');
+  assert.equal(style.paddingTop, '');
+  assert.equal(style.margin, '');
+  style.margin = 'inherit';
+  assert.equal(style.marginRight, 'inherit');
+  assert.equal(style.margin, 'inherit');
+});
@@ -0,0 +1,71 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser } = pkg;
+
+const parser = new DOMParser();
+
+test('template.content is a DocumentFragment holding the parsed children', () => {
+  const doc = parser.parseFromString(
+    '<template id="t"><p class="a">Hello</p><span>World</span></template>',
+    'text/html',
+  );
+  const template = doc.getElementById('t');
+  const content = template.content;
+
+  assert.equal(content.nodeType, 11);
+  assert.equal(content.nodeName, '#document-fragment');
+  assert.equal(content.parentNode, null);
+  assert.equal(template.childNodes.length, 0);
+  assert.equal(content.childNodes.length, 2);
+  assert.equal(content.querySelector('.a').textContent, 'Hello');
+  assert.ok(content.firstChild.parentNode.isSameNode(content));
+  assert.ok(template.content.isSameNode(content));
+  assert.equal(doc.querySelector('.a'), null);
+});
+
+test('content is null for non-template elements', () => {
+  const doc = parser.parseFromString('<div></div>', 'text/html');
+  assert.equal(doc.querySelector('div').content, null);
+});
+
+test('innerHTML and outerHTML serialize template contents', () => {
+  const doc = parser.parseFromString('<template id="t"><b>x</b></template>', 'text/html');
+  const template = doc.getElementById('t');
+  assert.equal(template.innerHTML, '<b>x</b>');
+  assert.equal(template.outerHTML, '<template id="t"><b>x</b></template>');
+  assert.match(doc.documentElement.outerHTML, /<template id="t"><b>x<\/b><\/template>/);
+});
+
+test('setting innerHTML replaces template contents', () => {
+  const doc = parser.parseFromString('<template id="t"><b>x</b></template>', 'text/html');
+  const template = doc.getElementById('t');
+  template.innerHTML = '<tr><td>cell</td></tr><i>y</i>';
+
+  assert.equal(template.childNodes.length, 0);
+  assert.equal(template.content.firstChild.nodeName, 'TR');
+  assert.equal(template.innerHTML, '<tr><td>cell</td></tr><i>y</i>');
+});
+
+test('created templates get empty contents that can be edited', () => {
+  const doc = parser.parseFromString('', 'text/html');
+  const template = doc.createElement('template');
+  const content = template.content;
+  assert.equal(content.childNodes.length, 0);
+
+  content.appendChild(doc.createElement('li'));
+  assert.equal(template.innerHTML, '<li></li>');
+});
+
+test('cloneNode copies contents only for deep clones', () => {
+  const doc = parser.parseFromString('<template id="t"><b>x</b></template>', 'text/html');
+  const template = doc.getElementById('t');
+
+  const deep = template.cloneNode(true);
+  assert.equal(deep.innerHTML, '<b>x</b>');
+  assert.ok(!deep.content.isSameNode(template.content));
+  deep.content.firstChild.remove();
+  assert.equal(template.innerHTML, '<b>x</b>');
+
+  assert.equal(template.cloneNode(false).innerHTML, '');
+});
@@ -0,0 +1,96 @@
+import test from 'node:test';
+import assert from 'node:assert/strict';
+import pkg from '../domparser.js';
+const { DOMParser, DOMImplementation, XMLSerializer } = pkg;
+
+const parser = new DOMParser();
+const serializer = new XMLSerializer();
+const implementation = new DOMImplementation();
+
+test('serializes an HTML document as XHTML', () => {
+  const doc = parser.parseFromString('<!DOCTYPE html><p class="a">x &amp; y<br>z</p>', 'text/html');
+  assert.equal(
+    serializer.serializeToString(doc),
+    '<!DOCTYPE html><html xmlns="http://www.w3.org/1999/xhtml"><head></head><body><p class="a">x &amp; y<br />z</p></body></html>',
+  );
+  assert.equal(
+    serializer.serializeToString(doc.querySelector('p')),
+    '<p xmlns="http://www.w3.org/1999/xhtml" class="a">x &amp; y<br />z</p>',
+  );
+});
+
+test('serializes inline SVG with its namespace and xlink prefix', () => {
+  const doc = parser.parseFromString(
+    '<svg viewBox="0 0 10 10"><circle r="1"></circle><image xlink:href="a.png"></image></svg>',
+    'text/html',
+  );
+  assert.equal(
+    serializer.serializeToString(doc.querySelector('svg')),
+    '<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><circle r="1"/><image xmlns:xlink="http://www.w3.org/1999/xlink" xlink:href="a.png"/></svg>',
+  );
+});
+
+test('generates namespace declarations and prefixes', () => {
+  const doc = implementation.createDocument(null, 'root');
+  const root = doc.documentElement;
+  const child = doc.createElementNS('urn:x', 'x:item');
+  child.setAttributeNS('urn:a', 'a:one', '1');
+  child.setAttributeNS('urn:b', 'b:two', '2');
+  root.appendChild(child);
+  root.appendChild(doc.createElementNS('urn:x', 'x:other'));
+  assert.equal(
+    serializer.serializeToString(doc),
+    '<root><x:item xmlns:x="urn:x" xmlns:ns1="urn:a" ns1:one="1" xmlns:ns2="urn:b" ns2:two="2"/><x:other xmlns:x="urn:x"/></root>',
+  );
+});
+
+test('reuses declared prefixes and default namespaces', () => {
+  const doc = implementation.createDocument('urn:feed', 'feed');
+  const root = doc.documentElement;
+  root.setAttributeNS('http://www.w3.org/2000/xmlns/', 'xmlns:m', 'urn:media');
+  const entry = doc.createElementNS('urn:feed', 'entry');
+  const thumb = doc.createElementNS('urn:media', 'm:thumbnail');
+  thumb.setAttribute('url', 'a&b "c"\n');
+  entry.appendChild(thumb);
+  root.appendChild(entry);
+  assert.equal(
+    serializer.serializeToString(doc),
+    '<feed xmlns="urn:feed" xmlns:m="urn:media"><entry><m:thumbnail url="a&amp;b &quot;c&quot;&#xA;"/></entry></feed>',
+  );
+});
+
+test('escapes text and serializes other node types', () => {
+  const doc = implementation.createDocument(null, 'r', implementation.createDocumentType('r', '', 'r.dtd'));
+  const root = doc.documentElement;
+  root.appendChild(doc.createTextNode('1 < 2 > 0 & "q"'));
+  root.appendChild(doc.createComment(' c '));
+  root.appendChild(doc.createProcessingInstruction('pi', 'data'));
+  assert.equal(
+    serializer.serializeToString(doc),
+    '<!DOCTYPE r SYSTEM "r.dtd"><r>1 &lt; 2 &gt; 0 &amp; "q"<!-- c --><?pi data?></r>',
+  );
+});
+
+test('serializes template contents and fragments', () => {
+  const doc = parser.parseFromString('<template><b>t</b></template>', 'text/html');
+  assert.equal(
+    serializer.serializeToString(doc.querySelector('template')),
+    '<template xmlns="http://www.w3.org/1999/xhtml"><b>t</b></template>',
+  );
+  const fragment = doc.createDocumentFragment();
+  fragment.appendChild(doc.createElement('i'));
+  assert.equal(serializer.serializeToString(fragment), '<i xmlns="http://www.w3.org/1999/xhtml"></i>');
+});
+
+test('requireWellFormed reports nodes that are not well-formed XML', () => {
+  const doc = parser.parseFromString('', 'text/html');
+  const comment = doc.createComment('a -- b');
+  assert.equal(serializer.serializeToString(comment), '<!--a -- b-->');
+  assert.throws(() => serializer.serializeToString(comment, { requireWellFormed: true }));
+  assert.throws(() =>
+    serializer.serializeToString(doc.createProcessingInstruction('xml', 'x'), { requireWellFormed: true }),
+  );
+  assert.throws(() => serializer.serializeToString(doc.createTextNode('\u0001'), { requireWellFormed: true }));
+  assert.throws(() => serializer.serializeToString(implementation.createDocument(null, ''), { requireWellFormed: true }));
+  assert.throws(() => serializer.serializeToString('text'), TypeError);
+});